@@ -8,7 +8,7 @@ use analysis::{
     abstract_interpretation::FixpointEngine,
     domains::{
         DefinitelyAccessibleAnalysis, DefinitelyInitializedAnalysis, FramingAnalysis,
-        MaybeBorrowedAnalysis, ReachingDefsAnalysis,
+        IntervalsAnalysis, MaybeBorrowedAnalysis, ReachingDefsAnalysis,
     },
 };
 use prusti_rustc_interface::{
@@ -241,6 +241,16 @@ impl prusti_rustc_interface::driver::Callbacks for OurCompilerCalls {
                             Err(e) => eprintln!("{}", e.to_pretty_str(body)),
                         }
                     }
+                    "IntervalsAnalysis" => {
+                        let result = IntervalsAnalysis::new(tcx, local_def_id.to_def_id(), body)
+                            .run_fwd_analysis();
+                        match result {
+                            Ok(state) => {
+                                println!("{}", serde_json::to_string_pretty(&state).unwrap())
+                            }
+                            Err(e) => eprintln!("{}", e.to_pretty_str(body)),
+                        }
+                    }
                     "MaybeBorrowedAnalysis" => {
                         let analyzer = MaybeBorrowedAnalysis::new(tcx, &body_with_facts);
                         match analyzer.run_analysis() {
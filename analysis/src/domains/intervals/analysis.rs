@@ -0,0 +1,134 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::state::constant_value;
+use crate::{
+    abstract_interpretation::{AnalysisResult, FixpointEngine},
+    domains::{Interval, IntervalsState},
+};
+use prusti_rustc_interface::{
+    data_structures::fx::FxHashMap,
+    middle::{
+        mir::{self, visit::Visitor},
+        ty::TyCtxt,
+    },
+    span::def_id::DefId,
+};
+use std::rc::Rc;
+
+pub struct IntervalsAnalysis<'mir, 'tcx: 'mir> {
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    mir: &'mir mir::Body<'tcx>,
+    thresholds: Rc<[i128]>,
+}
+
+impl<'mir, 'tcx: 'mir> IntervalsAnalysis<'mir, 'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>, def_id: DefId, mir: &'mir mir::Body<'tcx>) -> Self {
+        let mut collector = ThresholdCollector {
+            tcx,
+            thresholds: Vec::new(),
+        };
+        collector.visit_body(mir);
+        for local_decl in mir.local_decls.iter() {
+            if let Some(interval) = Interval::of_type(tcx, local_decl.ty) {
+                collector.thresholds.extend(interval.lo);
+                collector.thresholds.extend(interval.hi);
+            }
+        }
+        let mut thresholds = collector.thresholds;
+        thresholds.sort_unstable();
+        thresholds.dedup();
+        IntervalsAnalysis {
+            tcx,
+            def_id,
+            mir,
+            thresholds: thresholds.into(),
+        }
+    }
+}
+
+/// Collects the bounds to which intervals are widened: the integer constants
+/// of the body, and their neighbours to take strict comparisons into account.
+struct ThresholdCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    thresholds: Vec<i128>,
+}
+
+impl<'tcx> Visitor<'tcx> for ThresholdCollector<'tcx> {
+    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, _location: mir::Location) {
+        if let Some(value) = constant_value(self.tcx, constant) {
+            self.thresholds.push(value);
+            self.thresholds.extend(value.checked_sub(1));
+            self.thresholds.extend(value.checked_add(1));
+        }
+    }
+}
+
+impl<'mir, 'tcx: 'mir> FixpointEngine<'mir, 'tcx> for IntervalsAnalysis<'mir, 'tcx> {
+    type State = IntervalsState<'mir, 'tcx>;
+
+    fn def_id(&self) -> DefId {
+        self.def_id
+    }
+
+    fn body(&self) -> &'mir mir::Body<'tcx> {
+        self.mir
+    }
+
+    /// The bottom element of the lattice represents an unreachable program point
+    fn new_bottom(&self) -> Self::State {
+        IntervalsState {
+            intervals: None,
+            comparisons: FxHashMap::default(),
+            copies: FxHashMap::default(),
+            thresholds: self.thresholds.clone(),
+            mir: self.mir,
+            tcx: self.tcx,
+        }
+    }
+
+    /// Integer arguments can have any value of their type
+    fn new_initial(&self) -> Self::State {
+        let intervals = self
+            .mir
+            .args_iter()
+            .filter_map(|local| {
+                Interval::of_type(self.tcx, self.mir.local_decls[local].ty)
+                    .map(|interval| (local, interval))
+            })
+            .collect();
+        IntervalsState {
+            intervals: Some(intervals),
+            comparisons: FxHashMap::default(),
+            copies: FxHashMap::default(),
+            thresholds: self.thresholds.clone(),
+            mir: self.mir,
+            tcx: self.tcx,
+        }
+    }
+
+    fn need_to_widen(counter: u32) -> bool {
+        // the bounds can grow for as long as a loop runs
+        counter > 2
+    }
+
+    fn apply_statement_effect(
+        &self,
+        state: &mut Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<()> {
+        state.apply_statement_effect(location)
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &Self::State,
+        location: mir::Location,
+    ) -> AnalysisResult<Vec<(mir::BasicBlock, Self::State)>> {
+        state.apply_terminator_effect(location)
+    }
+}
@@ -0,0 +1,666 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{abstract_interpretation::AbstractState, AnalysisError};
+use prusti_rustc_interface::{
+    data_structures::fx::FxHashMap,
+    middle::{
+        mir,
+        ty::{Ty, TyCtxt},
+    },
+};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::{collections::BTreeMap, fmt, rc::Rc};
+
+/// A range of integer values. A missing bound means that the range is
+/// unbounded in that direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interval {
+    pub lo: Option<i128>,
+    pub hi: Option<i128>,
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.lo {
+            Some(lo) => write!(f, "[{lo}, ")?,
+            None => write!(f, "[-inf, ")?,
+        }
+        match self.hi {
+            Some(hi) => write!(f, "{hi}]"),
+            None => write!(f, "+inf]"),
+        }
+    }
+}
+
+impl Interval {
+    pub const TOP: Interval = Interval { lo: None, hi: None };
+
+    pub fn singleton(value: i128) -> Self {
+        Interval {
+            lo: Some(value),
+            hi: Some(value),
+        }
+    }
+
+    /// The values of the integer type `ty`, or `None` if `ty` is not an
+    /// integer type.
+    pub fn of_type<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Self> {
+        if !ty.is_integral() {
+            return None;
+        }
+        let (size, signed) = ty.int_size_and_signed(tcx);
+        Some(if signed {
+            Interval {
+                lo: Some(size.signed_int_min()),
+                hi: Some(size.signed_int_max()),
+            }
+        } else {
+            Interval {
+                lo: Some(0),
+                hi: i128::try_from(size.unsigned_int_max()).ok(),
+            }
+        })
+    }
+
+    /// The smallest interval that contains both intervals.
+    pub fn join(self, other: Self) -> Self {
+        Interval {
+            lo: self.lo.zip(other.lo).map(|(a, b)| a.min(b)),
+            hi: self.hi.zip(other.hi).map(|(a, b)| a.max(b)),
+        }
+    }
+
+    /// The intersection of both intervals, or `None` if it is empty.
+    pub fn meet(self, other: Self) -> Option<Self> {
+        let lo = match (self.lo, other.lo) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (bound, None) | (None, bound) => bound,
+        };
+        let hi = match (self.hi, other.hi) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (bound, None) | (None, bound) => bound,
+        };
+        match (lo, hi) {
+            (Some(lo), Some(hi)) if lo > hi => None,
+            _ => Some(Interval { lo, hi }),
+        }
+    }
+
+    /// Whether all values of `other` are in this interval.
+    pub fn contains(self, other: Self) -> bool {
+        let lo_ok = match (self.lo, other.lo) {
+            (None, _) => true,
+            (Some(a), Some(b)) => a <= b,
+            (Some(_), None) => false,
+        };
+        let hi_ok = match (self.hi, other.hi) {
+            (None, _) => true,
+            (Some(a), Some(b)) => a >= b,
+            (Some(_), None) => false,
+        };
+        lo_ok && hi_ok
+    }
+
+    /// Moves the bounds that grew since `previous` to the next value in the
+    /// sorted `thresholds`, or drops them if there is none.
+    pub fn widen(self, previous: Self, thresholds: &[i128]) -> Self {
+        Interval {
+            lo: match (previous.lo, self.lo) {
+                (Some(p), Some(s)) if s >= p => Some(s),
+                (Some(_), Some(s)) => thresholds.iter().rev().find(|t| **t <= s).copied(),
+                _ => None,
+            },
+            hi: match (previous.hi, self.hi) {
+                (Some(p), Some(s)) if s <= p => Some(s),
+                (Some(_), Some(s)) => thresholds.iter().find(|t| **t >= s).copied(),
+                _ => None,
+            },
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Interval {
+            lo: self.lo.zip(other.lo).and_then(|(a, b)| a.checked_add(b)),
+            hi: self.hi.zip(other.hi).and_then(|(a, b)| a.checked_add(b)),
+        }
+    }
+
+    fn neg(self) -> Self {
+        Interval {
+            lo: self.hi.and_then(i128::checked_neg),
+            hi: self.lo.and_then(i128::checked_neg),
+        }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self.add(other.neg())
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let (Some(a), Some(b), Some(c), Some(d)) = (self.lo, self.hi, other.lo, other.hi) else {
+            return Interval::TOP;
+        };
+        let products = [
+            a.checked_mul(c),
+            a.checked_mul(d),
+            b.checked_mul(c),
+            b.checked_mul(d),
+        ];
+        if products.iter().any(Option::is_none) {
+            return Interval::TOP;
+        }
+        let products = products.map(Option::unwrap);
+        Interval {
+            lo: products.iter().min().copied(),
+            hi: products.iter().max().copied(),
+        }
+    }
+}
+
+/// The value of an integer constant, if it fits into an `i128`.
+pub(super) fn constant_value<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    constant: &mir::Constant<'tcx>,
+) -> Option<i128> {
+    let ty = constant.ty();
+    if !ty.is_integral() {
+        return None;
+    }
+    let scalar = constant.literal.try_to_scalar_int()?;
+    let (size, signed) = ty.int_size_and_signed(tcx);
+    if signed {
+        scalar.try_to_int(size).ok()
+    } else {
+        scalar
+            .try_to_uint(size)
+            .ok()
+            .and_then(|value| i128::try_from(value).ok())
+    }
+}
+
+/// An operand of a comparison.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Term {
+    Local(mir::Local),
+    Const(i128),
+}
+
+/// A comparison of two integers whose result is stored in a boolean local.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Comparison {
+    op: mir::BinOp,
+    left: Term,
+    right: Term,
+}
+
+impl Comparison {
+    fn mentions(&self, local: mir::Local) -> bool {
+        self.left == Term::Local(local) || self.right == Term::Local(local)
+    }
+}
+
+/// An interval of possible values for each integer local whose value is
+/// known to be bounded at a program point.
+///
+/// Locals that are not in the map can have any value, or are not initialized.
+/// Locals that are borrowed are removed from the map, because they might be
+/// changed through the reference.
+#[derive(Clone)]
+pub struct IntervalsState<'mir, 'tcx: 'mir> {
+    /// `None` if the program point is unreachable
+    pub(super) intervals: Option<FxHashMap<mir::Local, Interval>>,
+    /// The comparisons that were assigned to boolean locals, used to refine
+    /// the intervals on the branches of a `SwitchInt` or an `Assert`
+    pub(super) comparisons: FxHashMap<mir::Local, Comparison>,
+    /// Locals that hold a copy of the value of another local
+    pub(super) copies: FxHashMap<mir::Local, mir::Local>,
+    /// The sorted bounds to which the intervals are widened
+    pub(super) thresholds: Rc<[i128]>,
+    pub(super) mir: &'mir mir::Body<'tcx>,
+    pub(super) tcx: TyCtxt<'tcx>,
+}
+
+impl<'mir, 'tcx: 'mir> fmt::Debug for IntervalsState<'mir, 'tcx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // ignore mir
+        f.debug_struct("IntervalsState")
+            .field("intervals", &self.intervals)
+            .field("comparisons", &self.comparisons)
+            .field("copies", &self.copies)
+            .finish()
+    }
+}
+
+impl<'mir, 'tcx: 'mir> PartialEq for IntervalsState<'mir, 'tcx> {
+    fn eq(&self, other: &Self) -> bool {
+        // Ignore the `mir` field.
+        self.intervals == other.intervals
+            && self.comparisons == other.comparisons
+            && self.copies == other.copies
+    }
+}
+
+impl<'mir, 'tcx: 'mir> Eq for IntervalsState<'mir, 'tcx> {}
+
+impl<'mir, 'tcx: 'mir> Serialize for IntervalsState<'mir, 'tcx> {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        let Some(intervals) = &self.intervals else {
+            return serializer.serialize_none();
+        };
+        let mut map = serializer.serialize_map(Some(intervals.len()))?;
+        let ordered_map: BTreeMap<_, _> = intervals.iter().collect();
+        for (local, interval) in ordered_map {
+            map.serialize_entry(&format!("{local:?}"), &interval.to_string())?;
+        }
+        map.end()
+    }
+}
+
+impl<'mir, 'tcx: 'mir> IntervalsState<'mir, 'tcx> {
+    /// The interval of `local`, or `None` if nothing is known about it.
+    pub fn get_interval(&self, local: mir::Local) -> Option<Interval> {
+        self.intervals.as_ref()?.get(&local).copied()
+    }
+
+    /// The locals whose interval is smaller than the values of their type,
+    /// ordered by local. The bounds that are implied by the type are omitted.
+    pub fn get_bounded_locals(&self) -> Vec<(mir::Local, Interval)> {
+        let Some(intervals) = &self.intervals else {
+            return Vec::new();
+        };
+        let mut bounded: Vec<_> = intervals
+            .iter()
+            .filter_map(|(local, interval)| {
+                let type_interval = Interval::of_type(self.tcx, self.mir.local_decls[*local].ty)?;
+                let bounds = Interval {
+                    lo: interval.lo.filter(|lo| Some(*lo) != type_interval.lo),
+                    hi: interval.hi.filter(|hi| Some(*hi) != type_interval.hi),
+                };
+                (bounds != Interval::TOP).then_some((*local, bounds))
+            })
+            .collect();
+        bounded.sort_by_key(|(local, _)| *local);
+        bounded
+    }
+
+    fn set_bottom(&mut self) {
+        self.intervals = None;
+        self.comparisons.clear();
+        self.copies.clear();
+    }
+
+    /// Forgets everything that is known about `local`.
+    fn forget(&mut self, local: mir::Local) {
+        if let Some(intervals) = &mut self.intervals {
+            intervals.remove(&local);
+        }
+        self.comparisons
+            .retain(|target, comparison| *target != local && !comparison.mentions(local));
+        self.copies
+            .retain(|target, source| *target != local && *source != local);
+    }
+
+    /// Intersects the interval of `term` with `interval`, if `term` is a
+    /// local whose interval is known.
+    fn restrict(&mut self, term: Term, interval: Interval) {
+        let Term::Local(local) = term else {
+            return;
+        };
+        let Some(current) = self.get_interval(local) else {
+            return;
+        };
+        match current.meet(interval) {
+            Some(restricted) => {
+                self.intervals.as_mut().unwrap().insert(local, restricted);
+            }
+            None => self.set_bottom(),
+        }
+    }
+
+    fn operand_term(&self, operand: &mir::Operand<'tcx>) -> Option<Term> {
+        match operand {
+            mir::Operand::Constant(box constant) => {
+                constant_value(self.tcx, constant).map(Term::Const)
+            }
+            mir::Operand::Copy(place) | mir::Operand::Move(place) => {
+                let local = place.as_local()?;
+                Some(Term::Local(
+                    self.copies.get(&local).copied().unwrap_or(local),
+                ))
+            }
+        }
+    }
+
+    fn term_interval(&self, term: Term) -> Option<Interval> {
+        match term {
+            Term::Local(local) => self.get_interval(local),
+            Term::Const(value) => Some(Interval::singleton(value)),
+        }
+    }
+
+    fn operand_interval(&self, operand: &mir::Operand<'tcx>) -> Option<Interval> {
+        match operand {
+            mir::Operand::Constant(box constant) => {
+                constant_value(self.tcx, constant).map(Interval::singleton)
+            }
+            mir::Operand::Copy(place) | mir::Operand::Move(place) => {
+                match place.projection.as_slice() {
+                    [] => self.get_interval(place.local),
+                    // The result of a checked operation is stored in its first field.
+                    [mir::ProjectionElem::Field(field, _)] if field.index() == 0 => {
+                        self.get_interval(place.local)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// The interval of the value of `rvalue`. For a checked operation, this
+    /// is the interval of its result if it does not overflow.
+    fn rvalue_interval(&self, rvalue: &mir::Rvalue<'tcx>) -> Option<Interval> {
+        let arithmetic = |op: mir::BinOp, left, right| {
+            let left = self.operand_interval(left)?;
+            let right = self.operand_interval(right)?;
+            match op {
+                mir::BinOp::Add => Some(left.add(right)),
+                mir::BinOp::Sub => Some(left.sub(right)),
+                mir::BinOp::Mul => Some(left.mul(right)),
+                _ => None,
+            }
+        };
+        match rvalue {
+            mir::Rvalue::Use(operand) => self.operand_interval(operand),
+            mir::Rvalue::BinaryOp(op, box (left, right)) => {
+                let type_interval = Interval::of_type(self.tcx, left.ty(self.mir, self.tcx))?;
+                // Without overflow checks the operation wraps around.
+                arithmetic(*op, left, right).filter(|interval| type_interval.contains(*interval))
+            }
+            mir::Rvalue::CheckedBinaryOp(op, box (left, right)) => {
+                let type_interval = Interval::of_type(self.tcx, left.ty(self.mir, self.tcx))?;
+                // If the operation overflows, the following assertion fails.
+                arithmetic(*op, left, right).and_then(|interval| interval.meet(type_interval))
+            }
+            mir::Rvalue::UnaryOp(mir::UnOp::Neg, operand) => {
+                let type_interval = Interval::of_type(self.tcx, operand.ty(self.mir, self.tcx))?;
+                self.operand_interval(operand)
+                    .map(Interval::neg)
+                    .filter(|interval| type_interval.contains(*interval))
+            }
+            mir::Rvalue::Cast(mir::CastKind::IntToInt, operand, ty) => {
+                let type_interval = Interval::of_type(self.tcx, *ty)?;
+                self.operand_interval(operand)
+                    .filter(|interval| type_interval.contains(*interval))
+            }
+            _ => None,
+        }
+    }
+
+    fn rvalue_comparison(&self, rvalue: &mir::Rvalue<'tcx>) -> Option<Comparison> {
+        let mir::Rvalue::BinaryOp(op, box (left, right)) = rvalue else {
+            return None;
+        };
+        match op {
+            mir::BinOp::Lt
+            | mir::BinOp::Le
+            | mir::BinOp::Gt
+            | mir::BinOp::Ge
+            | mir::BinOp::Eq
+            | mir::BinOp::Ne
+                if left.ty(self.mir, self.tcx).is_integral() =>
+            {
+                Some(Comparison {
+                    op: *op,
+                    left: self.operand_term(left)?,
+                    right: self.operand_term(right)?,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Refines the intervals with the knowledge that `comparison` evaluated
+    /// to `holds`.
+    fn assume(&mut self, comparison: Comparison, holds: bool) {
+        let op = match (comparison.op, holds) {
+            (op, true) => op,
+            (mir::BinOp::Lt, false) => mir::BinOp::Ge,
+            (mir::BinOp::Le, false) => mir::BinOp::Gt,
+            (mir::BinOp::Gt, false) => mir::BinOp::Le,
+            (mir::BinOp::Ge, false) => mir::BinOp::Lt,
+            (mir::BinOp::Eq, false) => mir::BinOp::Ne,
+            (mir::BinOp::Ne, false) => mir::BinOp::Eq,
+            _ => unreachable!(),
+        };
+        let (left, right) = match op {
+            mir::BinOp::Gt | mir::BinOp::Ge => (comparison.right, comparison.left),
+            _ => (comparison.left, comparison.right),
+        };
+        let (Some(left_interval), Some(right_interval)) =
+            (self.term_interval(left), self.term_interval(right))
+        else {
+            return;
+        };
+        match op {
+            // left < right
+            mir::BinOp::Lt | mir::BinOp::Gt => {
+                let below = Interval {
+                    lo: None,
+                    hi: right_interval.hi.and_then(|hi| hi.checked_sub(1)),
+                };
+                let above = Interval {
+                    lo: left_interval.lo.and_then(|lo| lo.checked_add(1)),
+                    hi: None,
+                };
+                self.restrict(left, below);
+                self.restrict(right, above);
+            }
+            // left <= right
+            mir::BinOp::Le | mir::BinOp::Ge => {
+                self.restrict(
+                    left,
+                    Interval {
+                        lo: None,
+                        hi: right_interval.hi,
+                    },
+                );
+                self.restrict(
+                    right,
+                    Interval {
+                        lo: left_interval.lo,
+                        hi: None,
+                    },
+                );
+            }
+            mir::BinOp::Eq => {
+                self.restrict(left, right_interval);
+                self.restrict(right, left_interval);
+            }
+            _ => {}
+        }
+    }
+
+    pub(super) fn apply_statement_effect(
+        &mut self,
+        location: mir::Location,
+    ) -> Result<(), AnalysisError> {
+        if self.intervals.is_none() {
+            return Ok(());
+        }
+        let stmt = &self.mir[location.block].statements[location.statement_index];
+        match &stmt.kind {
+            mir::StatementKind::Assign(box (target, rvalue)) => {
+                if let mir::Rvalue::Ref(_, _, borrowed) | mir::Rvalue::AddressOf(_, borrowed) =
+                    rvalue
+                {
+                    self.forget(borrowed.local);
+                }
+                let interval = self.rvalue_interval(rvalue);
+                let comparison = self.rvalue_comparison(rvalue);
+                let copied = match rvalue {
+                    mir::Rvalue::Use(operand) => match self.operand_term(operand) {
+                        Some(Term::Local(source)) => Some(source),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                self.forget(target.local);
+                if let Some(local) = target.as_local() {
+                    if let Some(interval) = interval {
+                        self.intervals.as_mut().unwrap().insert(local, interval);
+                    }
+                    if let Some(comparison) = comparison {
+                        self.comparisons.insert(local, comparison);
+                    }
+                    let is_integral = self.mir.local_decls[local].ty.is_integral();
+                    if let Some(source) = copied.filter(|source| is_integral && *source != local) {
+                        self.copies.insert(local, source);
+                    }
+                }
+            }
+            mir::StatementKind::StorageLive(local) | mir::StatementKind::StorageDead(local) => {
+                self.forget(*local);
+            }
+            mir::StatementKind::Deinit(box place)
+            | mir::StatementKind::SetDiscriminant {
+                place: box place, ..
+            } => {
+                self.forget(place.local);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub(super) fn apply_terminator_effect(
+        &self,
+        location: mir::Location,
+    ) -> Result<Vec<(mir::BasicBlock, Self)>, AnalysisError> {
+        let terminator = self.mir[location.block].terminator();
+        let mut res_vec = Vec::new();
+        if self.intervals.is_none() {
+            for bb in terminator.successors() {
+                res_vec.push((bb, self.clone()));
+            }
+            return Ok(res_vec);
+        }
+        match &terminator.kind {
+            mir::TerminatorKind::SwitchInt { discr, targets } => {
+                let discr_local = discr.place().and_then(|place| place.as_local());
+                let comparison = discr_local.and_then(|local| self.comparisons.get(&local));
+                for (value, bb) in targets.iter() {
+                    let mut state = self.clone();
+                    if let Some(comparison) = comparison {
+                        state.assume(*comparison, value != 0);
+                    } else if let Some(local) = discr_local {
+                        let ty = self.mir.local_decls[local].ty;
+                        if ty.is_integral() {
+                            let (size, signed) = ty.int_size_and_signed(self.tcx);
+                            let value = if signed {
+                                Some(size.sign_extend(value) as i128)
+                            } else {
+                                i128::try_from(value).ok()
+                            };
+                            if let Some(value) = value {
+                                state.restrict(Term::Local(local), Interval::singleton(value));
+                            }
+                        }
+                    }
+                    res_vec.push((bb, state));
+                }
+                let mut otherwise_state = self.clone();
+                if let Some(comparison) = comparison {
+                    let values: Vec<_> = targets.iter().map(|(value, _)| value).collect();
+                    match values.as_slice() {
+                        [0] => otherwise_state.assume(*comparison, true),
+                        [1] => otherwise_state.assume(*comparison, false),
+                        _ => {}
+                    }
+                }
+                res_vec.push((targets.otherwise(), otherwise_state));
+            }
+            mir::TerminatorKind::Assert {
+                cond,
+                expected,
+                target,
+                unwind,
+                ..
+            } => {
+                let mut state = self.clone();
+                let cond_local = cond.place().and_then(|place| place.as_local());
+                if let Some(comparison) = cond_local.and_then(|local| self.comparisons.get(&local))
+                {
+                    state.assume(*comparison, *expected);
+                }
+                res_vec.push((*target, state));
+                if let mir::UnwindAction::Cleanup(bb) = unwind {
+                    res_vec.push((*bb, self.clone()));
+                }
+            }
+            mir::TerminatorKind::Call { destination, .. } => {
+                let mut state = self.clone();
+                state.forget(destination.local);
+                for bb in terminator.successors() {
+                    res_vec.push((bb, state.clone()));
+                }
+            }
+            mir::TerminatorKind::InlineAsm { .. } => {
+                return Err(AnalysisError::UnsupportedStatement(location));
+            }
+            _ => {
+                for bb in terminator.successors() {
+                    res_vec.push((bb, self.clone()));
+                }
+            }
+        }
+        Ok(res_vec)
+    }
+}
+
+impl<'mir, 'tcx: 'mir> AbstractState for IntervalsState<'mir, 'tcx> {
+    fn is_bottom(&self) -> bool {
+        self.intervals.is_none()
+    }
+
+    /// The lattice join keeps the locals that are bounded in both states,
+    /// with the union of their intervals
+    fn join(&mut self, other: &Self) {
+        let Some(other_intervals) = &other.intervals else {
+            return;
+        };
+        let Some(intervals) = &mut self.intervals else {
+            self.intervals = other.intervals.clone();
+            self.comparisons = other.comparisons.clone();
+            self.copies = other.copies.clone();
+            return;
+        };
+        intervals.retain(|local, interval| match other_intervals.get(local) {
+            Some(other_interval) => {
+                *interval = interval.join(*other_interval);
+                true
+            }
+            None => false,
+        });
+        self.comparisons
+            .retain(|local, comparison| other.comparisons.get(local) == Some(comparison));
+        self.copies
+            .retain(|local, source| other.copies.get(local) == Some(source));
+    }
+
+    fn widen(&mut self, previous: &Self) {
+        let (Some(intervals), Some(previous_intervals)) =
+            (&mut self.intervals, &previous.intervals)
+        else {
+            return;
+        };
+        for (local, interval) in intervals.iter_mut() {
+            if let Some(previous_interval) = previous_intervals.get(local) {
+                *interval = interval.widen(*previous_interval, &self.thresholds);
+            }
+        }
+    }
+}
@@ -8,6 +8,7 @@ mod definitely_accessible;
 mod definitely_allocated;
 mod definitely_initialized;
 mod framing;
+mod intervals;
 mod maybe_borrowed;
 mod reaching_definitions;
 
@@ -15,5 +16,6 @@ pub use definitely_accessible::*;
 pub use definitely_allocated::*;
 pub use definitely_initialized::*;
 pub use framing::*;
+pub use intervals::*;
 pub use maybe_borrowed::*;
 pub use reaching_definitions::*;
@@ -67,4 +67,9 @@ fn test_runner(_tests: &[&()]) {
         "tests/test_cases/framing",
         vec!["--analysis=FramingAnalysis".into()],
     );
+    run_tests(
+        "ui",
+        "tests/test_cases/intervals",
+        vec!["--analysis=IntervalsAnalysis".into()],
+    );
 }
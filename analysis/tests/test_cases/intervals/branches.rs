@@ -0,0 +1,16 @@
+#[analyzer::run]
+fn clamp(x: i32) -> i32 {
+    let res: i32;
+    if x < -5 {
+        res = -5;
+    } else if x > 5 {
+        res = 5;
+    } else {
+        res = x;
+    }
+    res
+}
+
+fn main() {
+    let _ = clamp(7);
+}
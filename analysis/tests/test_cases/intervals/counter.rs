@@ -0,0 +1,8 @@
+#[analyzer::run]
+fn main() {
+    let mut i = 0;
+    while i < 10 {
+        i += 1;
+    }
+    assert!(i == 10);
+}
@@ -0,0 +1,16 @@
+#[analyzer::run]
+fn sum(n: u32) -> u32 {
+    let mut i = 0;
+    let mut total = 0;
+    while i < n {
+        if total < 1000 {
+            total += 2;
+        }
+        i += 1;
+    }
+    total
+}
+
+fn main() {
+    let _ = sum(3);
+}
@@ -0,0 +1,24 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! An experimental verifier that translates programs of the low-level VIR
+//! directly to SMT-LIB and checks them with Z3, without Viper and the JVM.
+//!
+//! Only programs without permissions are supported, such as the purified
+//! programs that check specifications (see
+//! `config::verify_specifications_backend`). Loops must have been cut, i.e.
+//! the control flow graph of each procedure has to be acyclic. Other programs
+//! are rejected with `NativeError::Unsupported`.
+
+#![warn(clippy::disallowed_types)]
+
+mod smt_lib;
+mod verifier;
+
+pub use crate::{
+    smt_lib::smt_script,
+    verifier::{NativeError, NativeVerificationError, NativeVerificationResult, NativeVerifier},
+};
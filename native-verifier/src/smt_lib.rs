@@ -0,0 +1,1361 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Translation of low-level VIR programs to SMT-LIB scripts.
+//!
+//! Each procedure is translated to passive form: every assignment introduces
+//! a fresh constant, and the constants of the branches of the control flow
+//! graph are merged where the branches join. Each block and each branch of a
+//! conditional statement has a Boolean constant that holds if it is reached.
+//! An assertion `e` in a block reached under `pc` becomes the check that
+//! `pc && !e` is unsatisfiable, after which `pc ==> e` is assumed, as in
+//! Silicon.
+//!
+//! As in Viper, every expression is also checked to be well-defined where it
+//! is evaluated: the preconditions of the applied functions have to hold, the
+//! divisors have to be non-zero and the sequence indices have to be in bounds.
+//! These checks respect the short-circuiting of `&&`, `||`, `==>` and
+//! conditional expressions.
+
+use crate::verifier::NativeError;
+use rustc_hash::FxHashMap;
+use std::collections::{BTreeMap, BTreeSet};
+use vir::low::{
+    ast::statement,
+    expression::{self, QuantifierKind},
+    BinaryOpKind, ConstantValue, ContainerOpKind, Expression, FunctionDecl, MethodDecl, Position,
+    ProcedureDecl, Program, Statement, Successor, Type, UnaryOpKind,
+};
+
+type Result<T> = std::result::Result<T, NativeError>;
+
+/// The SMT constants that hold the current values of the variables, keyed
+/// by the names of the variables.
+type Incarnations = BTreeMap<String, String>;
+
+/// A proof obligation, which holds if the corresponding `check-sat` of the
+/// script returns `unsat`.
+#[derive(Debug, Clone)]
+pub(crate) struct Check {
+    pub(crate) full_id: String,
+    pub(crate) offending_pos: Position,
+    pub(crate) reason_pos: Position,
+    pub(crate) message: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SmtScript {
+    pub(crate) text: String,
+    /// The proof obligations in the order of their `check-sat` commands.
+    pub(crate) checks: Vec<Check>,
+}
+
+/// Translates `program` to SMT-LIB, e.g. to inspect what the native verifier
+/// checks.
+pub fn smt_script(program: &Program) -> std::result::Result<String, NativeError> {
+    translate_program(program).map(|script| script.text)
+}
+
+pub(crate) fn translate_program(program: &Program) -> Result<SmtScript> {
+    let mut translator = Translator {
+        methods: program
+            .methods
+            .iter()
+            .map(|method| (method.name.as_str(), method))
+            .collect(),
+        functions: program
+            .functions
+            .iter()
+            .map(|function| (function.name.as_str(), function))
+            .collect(),
+        text: String::new(),
+        checks: Vec::new(),
+        fresh_id: 0,
+        sorts: FxHashMap::default(),
+        labels: FxHashMap::default(),
+    };
+    translator.translate_declarations(program)?;
+    for method in &program.methods {
+        translator.translate_method(method)?;
+    }
+    for procedure in &program.procedures {
+        translator.translate_procedure(procedure)?;
+    }
+    Ok(SmtScript {
+        text: translator.text,
+        checks: translator.checks,
+    })
+}
+
+fn unsupported<T>(feature: impl ToString) -> Result<T> {
+    Err(NativeError::Unsupported(feature.to_string()))
+}
+
+/// Quotes `name` so that it can be used as an SMT-LIB symbol.
+fn symbol(name: &str) -> String {
+    format!("|{}|", name.replace(['|', '\\'], "_"))
+}
+
+fn conjunction(terms: &[String]) -> String {
+    match terms {
+        [] => "true".to_string(),
+        [term] => term.clone(),
+        _ => format!("(and {})", terms.join(" ")),
+    }
+}
+
+/// The conjuncts of `expression`, which are checked separately to report the
+/// one that fails.
+fn conjuncts(expression: &Expression) -> Vec<&Expression> {
+    match expression {
+        Expression::BinaryOp(expression::BinaryOp {
+            op_kind: BinaryOpKind::And,
+            left,
+            right,
+            ..
+        }) => {
+            let mut result = conjuncts(left);
+            result.extend(conjuncts(right));
+            result
+        }
+        _ => vec![expression],
+    }
+}
+
+fn sort(ty: &Type) -> Result<String> {
+    match ty {
+        Type::Int => Ok("Int".to_string()),
+        Type::Bool => Ok("Bool".to_string()),
+        Type::Ref => Ok(symbol("$Ref")),
+        Type::Domain(domain) => Ok(symbol(&domain.name)),
+        Type::Seq(seq) => Ok(format!("(Seq {})", sort(&seq.element_type)?)),
+        Type::Perm => unsupported("permissions"),
+        Type::Float(_) => unsupported("floating-point numbers"),
+        Type::BitVector(_) => unsupported("bit vectors"),
+        Type::Set(_) | Type::MultiSet(_) | Type::Map(_) => {
+            unsupported(format!("values of type {ty}"))
+        }
+    }
+}
+
+/// The state in which an expression is evaluated.
+struct Context<'a> {
+    variables: &'a Incarnations,
+    /// The state of `old(..)` expressions without a label.
+    old: &'a Incarnations,
+}
+
+/// The part of an expression in which a subexpression is evaluated: the
+/// variables bound by enclosing quantifiers and the conditions under which
+/// the subexpression is evaluated at all.
+#[derive(Clone, Default)]
+struct Scope {
+    bindings: Vec<String>,
+    guards: Vec<String>,
+}
+
+impl Scope {
+    fn guarded(&self, guard: String) -> Self {
+        let mut scope = self.clone();
+        scope.guards.push(guard);
+        scope
+    }
+
+    /// The term that states that `term` holds in this scope.
+    fn close(&self, term: String) -> String {
+        let term = if self.guards.is_empty() {
+            term
+        } else {
+            format!("(=> {} {term})", conjunction(&self.guards))
+        };
+        if self.bindings.is_empty() {
+            term
+        } else {
+            format!("(forall ({}) {term})", self.bindings.join(" "))
+        }
+    }
+}
+
+/// Where an expression that is checked for well-definedness is evaluated.
+#[derive(Clone, Copy)]
+struct Evaluation<'a> {
+    /// The identifier of the Viper error that is reported if the expression
+    /// is not well-defined, e.g. `assignment.failed`.
+    error_id: &'a str,
+    /// The position of the statement or contract that evaluates it.
+    position: Position,
+}
+
+struct Translator<'p> {
+    methods: FxHashMap<&'p str, &'p MethodDecl>,
+    functions: FxHashMap<&'p str, &'p FunctionDecl>,
+    text: String,
+    checks: Vec<Check>,
+    fresh_id: usize,
+    /// The sorts of the constants declared with `fresh_constant`.
+    sorts: FxHashMap<String, String>,
+    /// The state at the start of each block of the current procedure, for
+    /// `old[label](..)` expressions.
+    labels: FxHashMap<String, Incarnations>,
+}
+
+impl<'p> Translator<'p> {
+    fn emit(&mut self, command: impl AsRef<str>) {
+        self.text.push_str(command.as_ref());
+        self.text.push('\n');
+    }
+
+    /// Declares a fresh constant for a new value of the variable `name`.
+    fn fresh_constant(&mut self, name: &str, sort: &str) -> String {
+        self.fresh_id += 1;
+        let constant = symbol(&format!("{name}@{}", self.fresh_id));
+        self.emit(format!("(declare-const {constant} {sort})"));
+        self.sorts.insert(constant.clone(), sort.to_string());
+        constant
+    }
+
+    /// Declares a fresh Boolean constant that is equal to `term`, to keep
+    /// the path conditions small.
+    fn define_condition(&mut self, term: String) -> String {
+        let constant = self.fresh_constant("$pc", "Bool");
+        self.emit(format!("(assert (= {constant} {term}))"));
+        constant
+    }
+
+    fn assume(&mut self, path_condition: &str, term: &str) {
+        self.emit(format!("(assert (=> {path_condition} {term}))"));
+    }
+
+    /// Checks that `term` holds whenever `path_condition` does.
+    fn prove(&mut self, path_condition: &str, term: &str, check: Check) {
+        self.emit("(push 1)");
+        self.emit(format!("(assert (and {path_condition} (not {term})))"));
+        self.emit("(check-sat)");
+        self.emit("(pop 1)");
+        self.checks.push(check);
+    }
+
+    /// Checks that `term` holds whenever `path_condition` does, and then
+    /// assumes it.
+    fn check(&mut self, path_condition: &str, term: &str, check: Check) {
+        self.prove(path_condition, term, check);
+        self.assume(path_condition, term);
+    }
+
+    fn translate_declarations(&mut self, program: &Program) -> Result<()> {
+        self.emit(format!("; program {}", program.name));
+        self.emit(format!("(declare-sort {} 0)", symbol("$Ref")));
+        for domain in &program.domains {
+            self.emit(format!("(declare-sort {} 0)", symbol(&domain.name)));
+        }
+        let mut unique_constants: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for function in program.domains.iter().flat_map(|domain| &domain.functions) {
+            let parameter_sorts = function
+                .parameters
+                .iter()
+                .map(|parameter| sort(&parameter.ty))
+                .collect::<Result<Vec<_>>>()?;
+            let return_sort = sort(&function.return_type)?;
+            self.emit(format!(
+                "(declare-fun {} ({}) {return_sort})",
+                symbol(&function.name),
+                parameter_sorts.join(" ")
+            ));
+            if function.is_unique && function.parameters.is_empty() {
+                unique_constants
+                    .entry(return_sort)
+                    .or_default()
+                    .push(symbol(&function.name));
+            }
+        }
+        for constants in unique_constants.values() {
+            if constants.len() > 1 {
+                self.emit(format!("(assert (distinct {}))", constants.join(" ")));
+            }
+        }
+        for function in &program.functions {
+            let parameter_sorts = function
+                .parameters
+                .iter()
+                .map(|parameter| sort(&parameter.ty))
+                .collect::<Result<Vec<_>>>()?;
+            self.emit(format!(
+                "(declare-fun {} ({}) {})",
+                symbol(&function.name),
+                parameter_sorts.join(" "),
+                sort(&function.return_type)?
+            ));
+        }
+
+        let empty = Incarnations::new();
+        let context = Context {
+            variables: &empty,
+            old: &empty,
+        };
+        for axiom in program.domains.iter().flat_map(|domain| &domain.axioms) {
+            let body = self.expression(&axiom.body, &context)?;
+            self.emit(format!("; axiom {}", axiom.name));
+            self.emit(format!("(assert {body})"));
+        }
+        for function in &program.functions {
+            if function.body.is_none() && function.posts.is_empty() {
+                continue;
+            }
+            let mut variables = Incarnations::new();
+            let mut bindings = Vec::new();
+            for parameter in &function.parameters {
+                let name = symbol(&parameter.name);
+                bindings.push(format!("({name} {})", sort(&parameter.ty)?));
+                variables.insert(parameter.name.clone(), name);
+            }
+            let application = function_application(function);
+            variables.insert("__result".to_string(), application.clone());
+            let context = Context {
+                variables: &variables,
+                old: &variables,
+            };
+            let pres = function
+                .pres
+                .iter()
+                .map(|pre| self.expression(pre, &context))
+                .collect::<Result<Vec<_>>>()?;
+            let mut facts = function
+                .posts
+                .iter()
+                .map(|post| self.expression(post, &context))
+                .collect::<Result<Vec<_>>>()?;
+            if let Some(body) = &function.body {
+                facts.insert(
+                    0,
+                    format!("(= {application} {})", self.expression(body, &context)?),
+                );
+            }
+            let definition = format!("(=> {} {})", conjunction(&pres), conjunction(&facts));
+            self.emit(format!("; function {}", function.name));
+            if bindings.is_empty() {
+                self.emit(format!("(assert {definition})"));
+            } else {
+                self.emit(format!(
+                    "(assert (forall ({}) (! {definition} :pattern ({application}))))",
+                    bindings.join(" ")
+                ));
+            }
+        }
+        for function in &program.functions {
+            self.check_function(function)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the contract and the body of `function` are well-defined.
+    /// The postconditions are assumed to hold.
+    fn check_function(&mut self, function: &FunctionDecl) -> Result<()> {
+        let mut variables = Incarnations::new();
+        let mut scope = Scope::default();
+        for parameter in &function.parameters {
+            let name = symbol(&parameter.name);
+            scope
+                .bindings
+                .push(format!("({name} {})", sort(&parameter.ty)?));
+            variables.insert(parameter.name.clone(), name);
+        }
+        let application = function_application(function);
+        variables.insert("__result".to_string(), application.clone());
+        let context = Context {
+            variables: &variables,
+            old: &variables,
+        };
+        let evaluation = Evaluation {
+            error_id: "function.not.wellformed",
+            position: Position::default(),
+        };
+        for pre in &function.pres {
+            self.check_well_definedness(pre, "true", &context, &scope, evaluation)?;
+            let pre = self.expression(pre, &context)?;
+            scope.guards.push(pre);
+        }
+        if let Some(body) = &function.body {
+            self.check_well_definedness(body, "true", &context, &scope, evaluation)?;
+            let body = self.expression(body, &context)?;
+            scope.guards.push(format!("(= {application} {body})"));
+        }
+        for post in &function.posts {
+            self.check_well_definedness(post, "true", &context, &scope, evaluation)?;
+            let post = self.expression(post, &context)?;
+            scope.guards.push(post);
+        }
+        Ok(())
+    }
+
+    /// Verifies the body of `method`, if any, against its contract.
+    fn translate_method(&mut self, method: &MethodDecl) -> Result<()> {
+        let Some(body) = &method.body else {
+            return Ok(());
+        };
+        self.emit(format!("; method {}", method.name));
+        self.emit("(push 1)");
+        self.labels.clear();
+        let mut variables = Incarnations::new();
+        for variable in method.parameters.iter().chain(&method.targets) {
+            let constant = self.fresh_constant(&variable.name, &sort(&variable.ty)?);
+            variables.insert(variable.name.clone(), constant);
+        }
+        let initial = variables.clone();
+        for pre in &method.pres {
+            let context = Context {
+                variables: &variables,
+                old: &initial,
+            };
+            self.check_well_definedness(
+                pre,
+                "true",
+                &context,
+                &Scope::default(),
+                Evaluation {
+                    error_id: "contract.not.wellformed",
+                    position: expression_position(pre),
+                },
+            )?;
+            let pre = self.expression(pre, &context)?;
+            self.assume("true", &pre);
+        }
+        let variables = self.statements(body, "true", variables, &initial)?;
+        let context = Context {
+            variables: &variables,
+            old: &initial,
+        };
+        for post in &method.posts {
+            for conjunct in conjuncts(post) {
+                self.check_well_definedness(
+                    conjunct,
+                    "true",
+                    &context,
+                    &Scope::default(),
+                    Evaluation {
+                        error_id: "contract.not.wellformed",
+                        position: expression_position(post),
+                    },
+                )?;
+                let term = self.expression(conjunct, &context)?;
+                self.check(
+                    "true",
+                    &term,
+                    Check {
+                        full_id: "postcondition.violated:assertion.false".to_string(),
+                        offending_pos: expression_position(post),
+                        reason_pos: expression_position(conjunct),
+                        message: format!(
+                            "Postcondition of {} might not hold. Assertion {conjunct} might not hold.",
+                            method.name
+                        ),
+                    },
+                );
+            }
+        }
+        self.emit("(pop 1)");
+        Ok(())
+    }
+
+    fn translate_procedure(&mut self, procedure: &ProcedureDecl) -> Result<()> {
+        self.emit(format!("; procedure {}", procedure.name));
+        self.emit("(push 1)");
+        self.labels.clear();
+        let mut initial = Incarnations::new();
+        for local in &procedure.locals {
+            let constant = self.fresh_constant(&local.name, &sort(&local.ty)?);
+            initial.insert(local.name.clone(), constant);
+        }
+
+        // The incoming edges of each block, with the condition under which
+        // they are taken and the state at their end.
+        let mut incoming: FxHashMap<&str, Vec<(String, Incarnations)>> = FxHashMap::default();
+        for (index, block_index) in topological_order(procedure)?.into_iter().enumerate() {
+            let block = &procedure.basic_blocks[block_index];
+            let (path_condition, variables) = if index == 0 {
+                ("true".to_string(), initial.clone())
+            } else {
+                let edges = incoming
+                    .remove(block.label.name.as_str())
+                    .unwrap_or_default();
+                if edges.is_empty() {
+                    // The block is unreachable.
+                    continue;
+                }
+                let conditions: Vec<_> = edges
+                    .iter()
+                    .map(|(condition, _)| condition.clone())
+                    .collect();
+                let path_condition = if conditions.len() == 1 {
+                    conditions[0].clone()
+                } else {
+                    self.define_condition(format!("(or {})", conditions.join(" ")))
+                };
+                (path_condition, self.merge(&edges))
+            };
+            self.labels
+                .insert(block.label.name.clone(), variables.clone());
+            let variables =
+                self.statements(&block.statements, &path_condition, variables, &initial)?;
+            match &block.successor {
+                Successor::Return => {}
+                Successor::Goto(target) => {
+                    incoming
+                        .entry(target.name.as_str())
+                        .or_default()
+                        .push((path_condition, variables));
+                }
+                Successor::GotoSwitch(targets) => {
+                    let context = Context {
+                        variables: &variables,
+                        old: &initial,
+                    };
+                    // As in Viper, the first test that holds decides the
+                    // target, and it is an error if none holds.
+                    let mut previous_tests_fail = vec![path_condition.clone()];
+                    for (test, target) in targets {
+                        // The test is only evaluated if the previous tests fail.
+                        let scope = Scope {
+                            bindings: Vec::new(),
+                            guards: previous_tests_fail[1..].to_vec(),
+                        };
+                        self.check_well_definedness(
+                            test,
+                            &path_condition,
+                            &context,
+                            &scope,
+                            Evaluation {
+                                error_id: "if.failed",
+                                position: expression_position(test),
+                            },
+                        )?;
+                        let test = self.expression(test, &context)?;
+                        let mut condition = previous_tests_fail.clone();
+                        condition.push(test.clone());
+                        let condition = self.define_condition(conjunction(&condition));
+                        incoming
+                            .entry(target.name.as_str())
+                            .or_default()
+                            .push((condition, variables.clone()));
+                        previous_tests_fail.push(format!("(not {test})"));
+                    }
+                    let no_test_holds = conjunction(&previous_tests_fail[1..]);
+                    self.check(
+                        &path_condition,
+                        &format!("(not {no_test_holds})"),
+                        Check {
+                            full_id: "assert.failed:assertion.false".to_string(),
+                            offending_pos: Position::default(),
+                            reason_pos: Position::default(),
+                            message: "Assert might fail. Assertion false might not hold."
+                                .to_string(),
+                        },
+                    );
+                }
+            }
+        }
+        self.emit("(pop 1)");
+        Ok(())
+    }
+
+    /// Merges the states at the end of the given edges into the state at
+    /// their common target.
+    fn merge(&mut self, edges: &[(String, Incarnations)]) -> Incarnations {
+        let mut merged = Incarnations::new();
+        let names: BTreeSet<&String> = edges
+            .iter()
+            .flat_map(|(_, variables)| variables.keys())
+            .collect();
+        for name in names {
+            let values: Vec<_> = edges
+                .iter()
+                .filter_map(|(condition, variables)| {
+                    variables.get(name).map(|value| (condition, value))
+                })
+                .collect();
+            if values.iter().all(|(_, value)| *value == values[0].1) {
+                merged.insert(name.clone(), values[0].1.clone());
+                continue;
+            }
+            let sort = self.sorts[values[0].1].clone();
+            let constant = self.fresh_constant(name, &sort);
+            for (condition, value) in values {
+                self.assume(condition, &format!("(= {constant} {value})"));
+            }
+            merged.insert(name.clone(), constant);
+        }
+        merged
+    }
+
+    fn statements(
+        &mut self,
+        statements: &[Statement],
+        path_condition: &str,
+        mut variables: Incarnations,
+        initial: &Incarnations,
+    ) -> Result<Incarnations> {
+        for statement in statements {
+            variables = self.statement(statement, path_condition, variables, initial)?;
+        }
+        Ok(variables)
+    }
+
+    fn statement(
+        &mut self,
+        statement: &Statement,
+        path_condition: &str,
+        mut variables: Incarnations,
+        initial: &Incarnations,
+    ) -> Result<Incarnations> {
+        let context = Context {
+            variables: &variables,
+            old: initial,
+        };
+        match statement {
+            Statement::Comment(_) => {}
+            Statement::LogEvent(statement) => {
+                self.check_well_definedness(
+                    &statement.expression,
+                    path_condition,
+                    &context,
+                    &Scope::default(),
+                    Evaluation {
+                        error_id: "inhale.failed",
+                        position: Position::default(),
+                    },
+                )?;
+                let term = self.expression(&statement.expression, &context)?;
+                self.assume(path_condition, &term);
+            }
+            Statement::Assume(statement::Assume {
+                expression,
+                position,
+            })
+            | Statement::Inhale(statement::Inhale {
+                expression,
+                position,
+            }) => {
+                self.check_well_definedness(
+                    expression,
+                    path_condition,
+                    &context,
+                    &Scope::default(),
+                    Evaluation {
+                        error_id: "inhale.failed",
+                        position: *position,
+                    },
+                )?;
+                let term = self.expression(expression, &context)?;
+                self.assume(path_condition, &term);
+            }
+            Statement::Assert(statement::Assert {
+                expression,
+                position,
+            }) => {
+                self.check_conjuncts(
+                    path_condition,
+                    expression,
+                    *position,
+                    &context,
+                    "assert.failed",
+                    |conjunct| format!("Assert might fail. Assertion {conjunct} might not hold."),
+                )?;
+            }
+            Statement::Exhale(statement::Exhale {
+                expression,
+                position,
+            }) => {
+                self.check_conjuncts(
+                    path_condition,
+                    expression,
+                    *position,
+                    &context,
+                    "exhale.failed",
+                    |conjunct| format!("Exhale might fail. Assertion {conjunct} might not hold."),
+                )?;
+            }
+            Statement::Assign(statement) => {
+                self.check_well_definedness(
+                    &statement.value,
+                    path_condition,
+                    &context,
+                    &Scope::default(),
+                    Evaluation {
+                        error_id: "assignment.failed",
+                        position: statement.position,
+                    },
+                )?;
+                let value = self.expression(&statement.value, &context)?;
+                let constant =
+                    self.fresh_constant(&statement.target.name, &sort(&statement.target.ty)?);
+                self.emit(format!("(assert (= {constant} {value}))"));
+                variables.insert(statement.target.name.clone(), constant);
+            }
+            Statement::Conditional(statement) => {
+                self.check_well_definedness(
+                    &statement.guard,
+                    path_condition,
+                    &context,
+                    &Scope::default(),
+                    Evaluation {
+                        error_id: "if.failed",
+                        position: statement.position,
+                    },
+                )?;
+                let guard = self.expression(&statement.guard, &context)?;
+                let then_condition =
+                    self.define_condition(format!("(and {path_condition} {guard})"));
+                let else_condition =
+                    self.define_condition(format!("(and {path_condition} (not {guard}))"));
+                let then_variables = self.statements(
+                    &statement.then_branch,
+                    &then_condition,
+                    variables.clone(),
+                    initial,
+                )?;
+                let else_variables =
+                    self.statements(&statement.else_branch, &else_condition, variables, initial)?;
+                variables = self.merge(&[
+                    (then_condition, then_variables),
+                    (else_condition, else_variables),
+                ]);
+            }
+            Statement::MethodCall(statement) => {
+                variables = self.method_call(statement, path_condition, variables, initial)?;
+            }
+            Statement::Fold(_) | Statement::Unfold(_) => return unsupported("predicates"),
+            Statement::ApplyMagicWand(_) => return unsupported("magic wands"),
+        }
+        Ok(variables)
+    }
+
+    /// Checks each conjunct of `expression` separately, after checking that
+    /// it is well-defined. The earlier conjuncts are assumed when checking the
+    /// later ones.
+    fn check_conjuncts(
+        &mut self,
+        path_condition: &str,
+        expression: &Expression,
+        position: Position,
+        context: &Context,
+        error_id: &str,
+        describe: impl Fn(&Expression) -> String,
+    ) -> Result<()> {
+        for conjunct in conjuncts(expression) {
+            self.check_well_definedness(
+                conjunct,
+                path_condition,
+                context,
+                &Scope::default(),
+                Evaluation { error_id, position },
+            )?;
+            let term = self.expression(conjunct, context)?;
+            self.check(
+                path_condition,
+                &term,
+                Check {
+                    full_id: format!("{error_id}:assertion.false"),
+                    offending_pos: position,
+                    reason_pos: expression_position(conjunct),
+                    message: describe(conjunct),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that `expression` is well-defined whenever `path_condition`
+    /// holds, see the module documentation.
+    fn check_well_definedness(
+        &mut self,
+        expression: &Expression,
+        path_condition: &str,
+        context: &Context,
+        scope: &Scope,
+        evaluation: Evaluation,
+    ) -> Result<()> {
+        let mut obligations = Vec::new();
+        self.well_definedness(expression, context, scope, evaluation, &mut obligations)?;
+        for (term, check) in obligations {
+            self.prove(path_condition, &term, check);
+        }
+        Ok(())
+    }
+
+    /// Collects the terms that have to hold for `expression` to be
+    /// well-defined in `scope`.
+    fn well_definedness(
+        &mut self,
+        expression: &Expression,
+        context: &Context,
+        scope: &Scope,
+        evaluation: Evaluation,
+        obligations: &mut Vec<(String, Check)>,
+    ) -> Result<()> {
+        match expression {
+            Expression::LabelledOld(old) => {
+                let variables = if let Some(label) = &old.label {
+                    self.labels.get(label).cloned().ok_or_else(|| {
+                        NativeError::Unsupported(format!("old expressions of the label {label}"))
+                    })?
+                } else {
+                    context.old.clone()
+                };
+                let old_context = Context {
+                    variables: &variables,
+                    old: context.old,
+                };
+                self.well_definedness(&old.base, &old_context, scope, evaluation, obligations)?;
+            }
+            Expression::UnaryOp(unary_op) => {
+                self.well_definedness(&unary_op.argument, context, scope, evaluation, obligations)?;
+            }
+            Expression::BinaryOp(binary_op) => {
+                self.well_definedness(&binary_op.left, context, scope, evaluation, obligations)?;
+                let right_scope = match binary_op.op_kind {
+                    BinaryOpKind::And | BinaryOpKind::Implies => {
+                        scope.guarded(self.expression(&binary_op.left, context)?)
+                    }
+                    BinaryOpKind::Or => scope.guarded(format!(
+                        "(not {})",
+                        self.expression(&binary_op.left, context)?
+                    )),
+                    _ => scope.clone(),
+                };
+                self.well_definedness(
+                    &binary_op.right,
+                    context,
+                    &right_scope,
+                    evaluation,
+                    obligations,
+                )?;
+                if matches!(binary_op.op_kind, BinaryOpKind::Div | BinaryOpKind::Mod) {
+                    let divisor = self.expression(&binary_op.right, context)?;
+                    obligations.push((
+                        scope.close(format!("(not (= {divisor} 0))")),
+                        Check {
+                            full_id: format!("{}:division.by.zero", evaluation.error_id),
+                            offending_pos: evaluation.position,
+                            reason_pos: expression_position(&binary_op.right),
+                            message: format!("Divisor {} might be zero.", binary_op.right),
+                        },
+                    ));
+                }
+            }
+            Expression::ContainerOp(container_op) => {
+                for operand in &container_op.operands {
+                    self.well_definedness(operand, context, scope, evaluation, obligations)?;
+                }
+                if let (ContainerOpKind::SeqIndex, [sequence, index]) =
+                    (&container_op.kind, container_op.operands.as_slice())
+                {
+                    let sequence_term = self.expression(sequence, context)?;
+                    let index_term = self.expression(index, context)?;
+                    obligations.push((
+                        scope.close(format!("(>= {index_term} 0)")),
+                        Check {
+                            full_id: format!("{}:seq.index.negative", evaluation.error_id),
+                            offending_pos: evaluation.position,
+                            reason_pos: expression_position(index),
+                            message: format!("Index {index} into {sequence} might be negative."),
+                        },
+                    ));
+                    obligations.push((
+                        scope.close(format!("(< {index_term} (seq.len {sequence_term}))")),
+                        Check {
+                            full_id: format!("{}:seq.index.length", evaluation.error_id),
+                            offending_pos: evaluation.position,
+                            reason_pos: expression_position(index),
+                            message: format!(
+                                "Index {index} into {sequence} might exceed sequence length."
+                            ),
+                        },
+                    ));
+                }
+            }
+            Expression::Conditional(conditional) => {
+                self.well_definedness(&conditional.guard, context, scope, evaluation, obligations)?;
+                let guard = self.expression(&conditional.guard, context)?;
+                self.well_definedness(
+                    &conditional.then_expr,
+                    context,
+                    &scope.guarded(guard.clone()),
+                    evaluation,
+                    obligations,
+                )?;
+                self.well_definedness(
+                    &conditional.else_expr,
+                    context,
+                    &scope.guarded(format!("(not {guard})")),
+                    evaluation,
+                    obligations,
+                )?;
+            }
+            Expression::Quantifier(quantifier) => {
+                let mut variables = context.variables.clone();
+                let mut body_scope = Scope {
+                    bindings: scope.bindings.clone(),
+                    guards: Vec::new(),
+                };
+                for variable in &quantifier.variables {
+                    let name = symbol(&variable.name);
+                    body_scope
+                        .bindings
+                        .push(format!("({name} {})", sort(&variable.ty)?));
+                    variables.insert(variable.name.clone(), name);
+                }
+                // The guards do not depend on the bound variables, so they can
+                // be moved into the quantifier.
+                body_scope.guards.extend(scope.guards.iter().cloned());
+                let body_context = Context {
+                    variables: &variables,
+                    old: context.old,
+                };
+                self.well_definedness(
+                    &quantifier.body,
+                    &body_context,
+                    &body_scope,
+                    evaluation,
+                    obligations,
+                )?;
+            }
+            Expression::LetExpr(let_expr) => {
+                self.well_definedness(&let_expr.def, context, scope, evaluation, obligations)?;
+                // The bound variable is replaced by its definition.
+                let def = self.expression(&let_expr.def, context)?;
+                let mut variables = context.variables.clone();
+                variables.insert(let_expr.variable.name.clone(), def);
+                let body_context = Context {
+                    variables: &variables,
+                    old: context.old,
+                };
+                self.well_definedness(
+                    &let_expr.body,
+                    &body_context,
+                    scope,
+                    evaluation,
+                    obligations,
+                )?;
+            }
+            Expression::FuncApp(func_app) => {
+                let mut callee_variables = Incarnations::new();
+                for argument in &func_app.arguments {
+                    self.well_definedness(argument, context, scope, evaluation, obligations)?;
+                }
+                let Some(function) = self.functions.get(func_app.function_name.as_str()).copied()
+                else {
+                    return Ok(());
+                };
+                if function.parameters.len() != func_app.arguments.len() {
+                    return unsupported(format!("the function application {expression}"));
+                }
+                for (parameter, argument) in function.parameters.iter().zip(&func_app.arguments) {
+                    let argument = self.expression(argument, context)?;
+                    callee_variables.insert(parameter.name.clone(), argument);
+                }
+                let callee_context = Context {
+                    variables: &callee_variables,
+                    old: &callee_variables,
+                };
+                let mut pre_scope = scope.clone();
+                for conjunct in function.pres.iter().flat_map(conjuncts) {
+                    let term = self.expression(conjunct, &callee_context)?;
+                    obligations.push((
+                        pre_scope.close(term.clone()),
+                        Check {
+                            full_id: "application.precondition:assertion.false".to_string(),
+                            offending_pos: func_app.position,
+                            reason_pos: expression_position(conjunct),
+                            message: format!(
+                                "Precondition of function {} might not hold. \
+                                    Assertion {conjunct} might not hold.",
+                                function.name
+                            ),
+                        },
+                    ));
+                    pre_scope.guards.push(term);
+                }
+            }
+            Expression::DomainFuncApp(func_app) => {
+                for argument in &func_app.arguments {
+                    self.well_definedness(argument, context, scope, evaluation, obligations)?;
+                }
+            }
+            // The remaining expressions are either atomic or rejected by
+            // `expression`.
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Checks the preconditions of the called method, havocs the targets and
+    /// assumes the postconditions.
+    fn method_call(
+        &mut self,
+        call: &statement::MethodCall,
+        path_condition: &str,
+        mut variables: Incarnations,
+        initial: &Incarnations,
+    ) -> Result<Incarnations> {
+        let Some(method) = self.methods.get(call.method_name.as_str()).copied() else {
+            return unsupported(format!(
+                "calls of the undeclared method {}",
+                call.method_name
+            ));
+        };
+        if method.parameters.len() != call.arguments.len()
+            || method.targets.len() != call.targets.len()
+        {
+            return unsupported(format!("the call {call}"));
+        }
+        let caller_context = Context {
+            variables: &variables,
+            old: initial,
+        };
+        let mut callee_variables = Incarnations::new();
+        for (parameter, argument) in method.parameters.iter().zip(&call.arguments) {
+            self.check_well_definedness(
+                argument,
+                path_condition,
+                &caller_context,
+                &Scope::default(),
+                Evaluation {
+                    error_id: "call.failed",
+                    position: call.position,
+                },
+            )?;
+            let argument = self.expression(argument, &caller_context)?;
+            callee_variables.insert(parameter.name.clone(), argument);
+        }
+        let callee_initial = callee_variables.clone();
+        for pre in &method.pres {
+            let context = Context {
+                variables: &callee_variables,
+                old: &callee_initial,
+            };
+            let method_name = &method.name;
+            self.check_conjuncts(
+                path_condition,
+                pre,
+                call.position,
+                &context,
+                "call.precondition",
+                |conjunct| {
+                    format!(
+                        "The precondition of method {method_name} might not hold. \
+                            Assertion {conjunct} might not hold."
+                    )
+                },
+            )?;
+        }
+        for (target_decl, target) in method.targets.iter().zip(&call.targets) {
+            let Expression::Local(target) = target else {
+                return unsupported(format!("the call target {target}"));
+            };
+            let constant = self.fresh_constant(&target.variable.name, &sort(&target.variable.ty)?);
+            variables.insert(target.variable.name.clone(), constant.clone());
+            callee_variables.insert(target_decl.name.clone(), constant);
+        }
+        let context = Context {
+            variables: &callee_variables,
+            old: &callee_initial,
+        };
+        for post in &method.posts {
+            let term = self.expression(post, &context)?;
+            self.assume(path_condition, &term);
+        }
+        Ok(variables)
+    }
+
+    fn expressions(&mut self, expressions: &[Expression], context: &Context) -> Result<String> {
+        let terms = expressions
+            .iter()
+            .map(|expression| self.expression(expression, context))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(terms.join(" "))
+    }
+
+    fn application(
+        &mut self,
+        name: &str,
+        arguments: &[Expression],
+        context: &Context,
+    ) -> Result<String> {
+        if arguments.is_empty() {
+            Ok(symbol(name))
+        } else {
+            Ok(format!(
+                "({} {})",
+                symbol(name),
+                self.expressions(arguments, context)?
+            ))
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression, context: &Context) -> Result<String> {
+        match expression {
+            Expression::Local(local) => context
+                .variables
+                .get(&local.variable.name)
+                .cloned()
+                .ok_or_else(|| {
+                    NativeError::Unsupported(format!(
+                        "the undeclared variable {}",
+                        local.variable.name
+                    ))
+                }),
+            Expression::LabelledOld(old) => {
+                let variables = if let Some(label) = &old.label {
+                    self.labels.get(label).cloned().ok_or_else(|| {
+                        NativeError::Unsupported(format!("old expressions of the label {label}"))
+                    })?
+                } else {
+                    context.old.clone()
+                };
+                let old_context = Context {
+                    variables: &variables,
+                    old: context.old,
+                };
+                self.expression(&old.base, &old_context)
+            }
+            Expression::Constant(constant) => match (&constant.value, &constant.ty) {
+                (ConstantValue::Bool(value), _) => Ok(value.to_string()),
+                (ConstantValue::Int(value), Type::Int) => Ok(if *value < 0 {
+                    format!("(- {})", value.unsigned_abs())
+                } else {
+                    value.to_string()
+                }),
+                (ConstantValue::BigInt(value), Type::Int) => Ok(match value.strip_prefix('-') {
+                    Some(magnitude) => format!("(- {magnitude})"),
+                    None => value.clone(),
+                }),
+                _ => unsupported(format!("constants of type {}", constant.ty)),
+            },
+            Expression::UnaryOp(unary_op) => {
+                let argument = self.expression(&unary_op.argument, context)?;
+                Ok(match unary_op.op_kind {
+                    UnaryOpKind::Not => format!("(not {argument})"),
+                    UnaryOpKind::Minus => format!("(- {argument})"),
+                })
+            }
+            Expression::BinaryOp(binary_op) => {
+                let left = self.expression(&binary_op.left, context)?;
+                let right = self.expression(&binary_op.right, context)?;
+                let operator = match binary_op.op_kind {
+                    BinaryOpKind::NeCmp => return Ok(format!("(not (= {left} {right}))")),
+                    BinaryOpKind::EqCmp => "=",
+                    BinaryOpKind::GtCmp => ">",
+                    BinaryOpKind::GeCmp => ">=",
+                    BinaryOpKind::LtCmp => "<",
+                    BinaryOpKind::LeCmp => "<=",
+                    BinaryOpKind::Add => "+",
+                    BinaryOpKind::Sub => "-",
+                    BinaryOpKind::Mul => "*",
+                    BinaryOpKind::Div => "div",
+                    BinaryOpKind::Mod => "mod",
+                    BinaryOpKind::And => "and",
+                    BinaryOpKind::Or => "or",
+                    BinaryOpKind::Implies => "=>",
+                };
+                Ok(format!("({operator} {left} {right})"))
+            }
+            Expression::ContainerOp(container_op) => {
+                let operands = self.expressions(&container_op.operands, context)?;
+                match container_op.kind {
+                    ContainerOpKind::SeqEmpty => Ok(format!(
+                        "(as seq.empty {})",
+                        sort(&container_op.container_type)?
+                    )),
+                    ContainerOpKind::SeqConstructor => {
+                        let units = container_op
+                            .operands
+                            .iter()
+                            .map(|operand| {
+                                Ok(format!("(seq.unit {})", self.expression(operand, context)?))
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        match units.len() {
+                            0 => Ok(format!(
+                                "(as seq.empty {})",
+                                sort(&container_op.container_type)?
+                            )),
+                            1 => Ok(units[0].clone()),
+                            _ => Ok(format!("(seq.++ {})", units.join(" "))),
+                        }
+                    }
+                    ContainerOpKind::SeqIndex => Ok(format!("(seq.nth {operands})")),
+                    ContainerOpKind::SeqConcat => Ok(format!("(seq.++ {operands})")),
+                    ContainerOpKind::SeqLen => Ok(format!("(seq.len {operands})")),
+                    _ => unsupported(format!("the container operation {}", container_op.kind)),
+                }
+            }
+            Expression::Conditional(conditional) => Ok(format!(
+                "(ite {} {} {})",
+                self.expression(&conditional.guard, context)?,
+                self.expression(&conditional.then_expr, context)?,
+                self.expression(&conditional.else_expr, context)?
+            )),
+            Expression::Quantifier(quantifier) => {
+                let mut variables = context.variables.clone();
+                let mut bindings = Vec::new();
+                for variable in &quantifier.variables {
+                    let name = symbol(&variable.name);
+                    bindings.push(format!("({name} {})", sort(&variable.ty)?));
+                    variables.insert(variable.name.clone(), name);
+                }
+                let body_context = Context {
+                    variables: &variables,
+                    old: context.old,
+                };
+                let body = self.expression(&quantifier.body, &body_context)?;
+                let patterns = quantifier
+                    .triggers
+                    .iter()
+                    .map(|trigger| {
+                        Ok(format!(
+                            ":pattern ({})",
+                            self.expressions(&trigger.terms, &body_context)?
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let kind = match quantifier.kind {
+                    QuantifierKind::ForAll => "forall",
+                    QuantifierKind::Exists => "exists",
+                };
+                let body = if patterns.is_empty() {
+                    body
+                } else {
+                    format!("(! {body} {})", patterns.join(" "))
+                };
+                Ok(format!("({kind} ({}) {body})", bindings.join(" ")))
+            }
+            Expression::LetExpr(let_expr) => {
+                let def = self.expression(&let_expr.def, context)?;
+                let name = symbol(&let_expr.variable.name);
+                let mut variables = context.variables.clone();
+                variables.insert(let_expr.variable.name.clone(), name.clone());
+                let body_context = Context {
+                    variables: &variables,
+                    old: context.old,
+                };
+                let body = self.expression(&let_expr.body, &body_context)?;
+                Ok(format!("(let (({name} {def})) {body})"))
+            }
+            Expression::FuncApp(func_app) => {
+                self.application(&func_app.function_name, &func_app.arguments, context)
+            }
+            Expression::DomainFuncApp(func_app) => {
+                self.application(&func_app.function_name, &func_app.arguments, context)
+            }
+            Expression::Field(_) => unsupported("field accesses"),
+            Expression::MagicWand(_) => unsupported("magic wands"),
+            Expression::PredicateAccessPredicate(_) | Expression::Unfolding(_) => {
+                unsupported("predicates")
+            }
+            Expression::FieldAccessPredicate(_) | Expression::PermBinaryOp(_) => {
+                unsupported("permissions")
+            }
+            Expression::InhaleExhale(_) => unsupported("inhale-exhale expressions"),
+        }
+    }
+}
+
+/// The application of `function` to its parameters.
+fn function_application(function: &FunctionDecl) -> String {
+    if function.parameters.is_empty() {
+        symbol(&function.name)
+    } else {
+        let arguments: Vec<_> = function
+            .parameters
+            .iter()
+            .map(|parameter| symbol(&parameter.name))
+            .collect();
+        format!("({} {})", symbol(&function.name), arguments.join(" "))
+    }
+}
+
+fn expression_position(expression: &Expression) -> Position {
+    match expression {
+        Expression::Local(expression) => expression.position,
+        Expression::Field(expression) => expression.position,
+        Expression::LabelledOld(expression) => expression.position,
+        Expression::Constant(expression) => expression.position,
+        Expression::MagicWand(expression) => expression.position,
+        Expression::PredicateAccessPredicate(expression) => expression.position,
+        Expression::FieldAccessPredicate(expression) => expression.position,
+        Expression::Unfolding(expression) => expression.position,
+        Expression::UnaryOp(expression) => expression.position,
+        Expression::BinaryOp(expression) => expression.position,
+        Expression::PermBinaryOp(expression) => expression.position,
+        Expression::ContainerOp(expression) => expression.position,
+        Expression::Conditional(expression) => expression.position,
+        Expression::Quantifier(expression) => expression.position,
+        Expression::LetExpr(expression) => expression.position,
+        Expression::FuncApp(expression) => expression.position,
+        Expression::DomainFuncApp(expression) => expression.position,
+        Expression::InhaleExhale(expression) => expression.position,
+    }
+}
+
+/// The indices of the blocks of `procedure` in a topological order, starting
+/// with the entry block.
+fn topological_order(procedure: &ProcedureDecl) -> Result<Vec<usize>> {
+    let indices: FxHashMap<&str, usize> = procedure
+        .basic_blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| (block.label.name.as_str(), index))
+        .collect();
+    let successors = procedure
+        .basic_blocks
+        .iter()
+        .map(|block| {
+            let targets: Vec<&str> = match &block.successor {
+                Successor::Return => vec![],
+                Successor::Goto(target) => vec![target.name.as_str()],
+                Successor::GotoSwitch(targets) => targets
+                    .iter()
+                    .map(|(_, target)| target.name.as_str())
+                    .collect(),
+            };
+            targets
+                .into_iter()
+                .map(|target| {
+                    indices.get(target).copied().ok_or_else(|| {
+                        NativeError::Unsupported(format!("jumps to the undeclared label {target}"))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Depth-first search from the entry block, in reverse postorder.
+    let mut order = Vec::new();
+    if procedure.basic_blocks.is_empty() {
+        return Ok(order);
+    }
+    // 0: not visited, 1: on the stack, 2: finished.
+    let mut state = vec![0u8; procedure.basic_blocks.len()];
+    let mut stack = vec![(0, 0)];
+    state[0] = 1;
+    while let Some((block, next_successor)) = stack.pop() {
+        if let Some(&successor) = successors[block].get(next_successor) {
+            stack.push((block, next_successor + 1));
+            match state[successor] {
+                0 => {
+                    state[successor] = 1;
+                    stack.push((successor, 0));
+                }
+                1 => return unsupported("loops"),
+                _ => {}
+            }
+        } else {
+            state[block] = 2;
+            order.push(block);
+        }
+    }
+    order.reverse();
+    Ok(order)
+}
@@ -0,0 +1,155 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::smt_lib::{translate_program, Check};
+use log::{debug, info};
+use std::{
+    fmt,
+    io::Write,
+    process::{Command, Stdio},
+    thread,
+};
+use vir::low::Program;
+
+/// Why a program could not be verified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NativeError {
+    /// The program uses a feature that the native verifier does not support.
+    Unsupported(String),
+    /// The SMT solver could not be run or reported an error.
+    Solver(String),
+}
+
+impl fmt::Display for NativeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NativeError::Unsupported(feature) => {
+                write!(f, "the native verifier does not support {feature}")
+            }
+            NativeError::Solver(message) => write!(f, "the SMT solver failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for NativeError {}
+
+/// A proof obligation that the SMT solver could not prove, with the same
+/// error identifiers as the corresponding Viper errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeVerificationError {
+    pub full_id: String,
+    pub offending_pos_id: Option<String>,
+    pub reason_pos_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NativeVerificationResult {
+    Verified,
+    Failed(Vec<NativeVerificationError>),
+    TimedOut,
+}
+
+/// Verifies low-level VIR programs by running Z3 on their SMT-LIB encoding.
+pub struct NativeVerifier {
+    z3_exe: String,
+    z3_args: Vec<String>,
+}
+
+impl NativeVerifier {
+    /// Creates a verifier that runs the Z3 executable `z3_exe` with the
+    /// additional command-line arguments `z3_args`, e.g. `-T:10`.
+    pub fn new(z3_exe: String, z3_args: Vec<String>) -> Self {
+        Self { z3_exe, z3_args }
+    }
+
+    pub fn verify(&self, program: &Program) -> Result<NativeVerificationResult, NativeError> {
+        let script = translate_program(program)?;
+        info!(
+            "Checking {} proof obligations of {} with Z3",
+            script.checks.len(),
+            program.name
+        );
+        let output = self.run_z3(script.text)?;
+        let mut answers = Vec::new();
+        for line in output.lines().map(str::trim) {
+            match line {
+                "sat" | "unsat" | "unknown" => answers.push(line),
+                "timeout" => return Ok(NativeVerificationResult::TimedOut),
+                "" | "success" => {}
+                _ if line.starts_with("(error") => {
+                    return Err(NativeError::Solver(line.to_string()));
+                }
+                _ => debug!("Unexpected output of Z3: {line}"),
+            }
+        }
+        if answers.len() != script.checks.len() {
+            // Z3 stops answering when its time limit is exceeded.
+            if answers.len() < script.checks.len() && answers.last() == Some(&"unknown") {
+                return Ok(NativeVerificationResult::TimedOut);
+            }
+            return Err(NativeError::Solver(format!(
+                "expected {} answers, but got {}",
+                script.checks.len(),
+                answers.len()
+            )));
+        }
+        let errors: Vec<_> = script
+            .checks
+            .into_iter()
+            .zip(answers)
+            .filter(|(_, answer)| *answer != "unsat")
+            .map(|(check, _)| verification_error(check))
+            .collect();
+        if errors.is_empty() {
+            Ok(NativeVerificationResult::Verified)
+        } else {
+            Ok(NativeVerificationResult::Failed(errors))
+        }
+    }
+
+    fn run_z3(&self, script: String) -> Result<String, NativeError> {
+        let mut child = Command::new(&self.z3_exe)
+            .arg("-smt2")
+            .arg("-in")
+            .args(&self.z3_args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|error| {
+                NativeError::Solver(format!("could not start {}: {error}", self.z3_exe))
+            })?;
+        let mut stdin = child.stdin.take().unwrap();
+        // Write from another thread to not block while Z3 fills its output.
+        let writer = thread::spawn(move || stdin.write_all(script.as_bytes()));
+        let output = child
+            .wait_with_output()
+            .map_err(|error| NativeError::Solver(error.to_string()))?;
+        // Z3 may exit before reading all of its input, e.g. on a timeout.
+        let _ = writer.join();
+        if !output.stderr.is_empty() {
+            debug!("Z3 stderr: {}", String::from_utf8_lossy(&output.stderr));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+fn verification_error(check: Check) -> NativeVerificationError {
+    let position_id = |position: vir::low::Position| {
+        if position.is_default() {
+            None
+        } else {
+            Some(position.id().to_string())
+        }
+    };
+    NativeVerificationError {
+        full_id: check.full_id.to_string(),
+        offending_pos_id: position_id(check.offending_pos),
+        reason_pos_id: position_id(check.reason_pos),
+        message: check.message,
+    }
+}
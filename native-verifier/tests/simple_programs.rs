@@ -0,0 +1,219 @@
+use native_verifier::*;
+use std::env;
+use vir::{
+    common::{check_mode::CheckMode, expression::BinaryOperationHelpers},
+    low::{
+        ast::{function::FunctionKind, statement::Statement},
+        BasicBlock, ConstantValue, Expression, FunctionDecl, Label, Position, ProcedureDecl,
+        Program, Successor, Type, VariableDecl,
+    },
+};
+
+fn verifier() -> NativeVerifier {
+    let z3_exe = env::var("Z3_EXE").expect("the Z3_EXE environment variable should not be empty");
+    NativeVerifier::new(z3_exe, vec![])
+}
+
+fn program(basic_blocks: Vec<BasicBlock>, locals: Vec<VariableDecl>) -> Program {
+    let procedure = ProcedureDecl::new("foo", locals, basic_blocks);
+    Program::new(
+        "test",
+        CheckMode::CoreProof,
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![procedure],
+    )
+}
+
+fn int(value: i64) -> Expression {
+    Expression::constant_no_pos(ConstantValue::Int(value), Type::Int)
+}
+
+#[test]
+fn success_with_empty_program() {
+    let program = Program::new(
+        "test",
+        CheckMode::CoreProof,
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    );
+    assert_eq!(
+        verifier().verify(&program),
+        Ok(NativeVerificationResult::Verified)
+    );
+}
+
+#[test]
+fn failure_with_assert_false() {
+    let position = Position::new(0, 0, 123);
+    let block = BasicBlock::new(
+        Label::new("start"),
+        vec![Statement::assert(false.into(), position)],
+        Successor::Return,
+    );
+    let result = verifier().verify(&program(vec![block], vec![]));
+    let NativeVerificationResult::Failed(errors) = result.unwrap() else {
+        panic!("the verification should fail");
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].full_id, "assert.failed:assertion.false");
+    assert_eq!(errors[0].offending_pos_id.as_deref(), Some("123"));
+}
+
+#[test]
+fn success_with_branches() {
+    let x = VariableDecl::new("x", Type::Int);
+    let local_x = || Expression::local_no_pos(x.clone());
+    let position = Position::new(0, 0, 1);
+    let blocks = vec![
+        BasicBlock::new(
+            Label::new("start"),
+            vec![],
+            Successor::GotoSwitch(vec![
+                (
+                    Expression::less_than(local_x(), int(0)),
+                    Label::new("negative"),
+                ),
+                (true.into(), Label::new("non_negative")),
+            ]),
+        ),
+        BasicBlock::new(
+            Label::new("negative"),
+            vec![Statement::assign(
+                x.clone(),
+                Expression::subtract(int(0), local_x()),
+                position,
+            )],
+            Successor::Goto(Label::new("end")),
+        ),
+        BasicBlock::new(
+            Label::new("non_negative"),
+            vec![],
+            Successor::Goto(Label::new("end")),
+        ),
+        BasicBlock::new(
+            Label::new("end"),
+            vec![Statement::assert(
+                Expression::greater_equals(local_x(), int(0)),
+                position,
+            )],
+            Successor::Return,
+        ),
+    ];
+    assert_eq!(
+        verifier().verify(&program(blocks, vec![x.clone()])),
+        Ok(NativeVerificationResult::Verified)
+    );
+}
+
+#[test]
+fn loops_are_unsupported() {
+    let block = BasicBlock::new(
+        Label::new("start"),
+        vec![],
+        Successor::Goto(Label::new("start")),
+    );
+    assert_eq!(
+        smt_script(&program(vec![block], vec![])),
+        Err(NativeError::Unsupported("loops".to_string()))
+    );
+}
+
+#[test]
+fn failure_with_division_by_zero() {
+    let x = VariableDecl::new("x", Type::Int);
+    let y = VariableDecl::new("y", Type::Int);
+    let block = BasicBlock::new(
+        Label::new("start"),
+        vec![Statement::assign(
+            y.clone(),
+            Expression::divide(int(10), Expression::local_no_pos(x.clone())),
+            Position::new(0, 0, 7),
+        )],
+        Successor::Return,
+    );
+    let result = verifier().verify(&program(vec![block], vec![x, y]));
+    let NativeVerificationResult::Failed(errors) = result.unwrap() else {
+        panic!("the verification should fail");
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].full_id, "assignment.failed:division.by.zero");
+    assert_eq!(errors[0].offending_pos_id.as_deref(), Some("7"));
+}
+
+#[test]
+fn success_with_guarded_division() {
+    let x = VariableDecl::new("x", Type::Int);
+    let local_x = || Expression::local_no_pos(x.clone());
+    let block = BasicBlock::new(
+        Label::new("start"),
+        vec![Statement::assert(
+            Expression::implies(
+                Expression::greater_than(local_x(), int(0)),
+                Expression::equals(Expression::divide(int(0), local_x()), int(0)),
+            ),
+            Position::new(0, 0, 1),
+        )],
+        Successor::Return,
+    );
+    assert_eq!(
+        verifier().verify(&program(vec![block], vec![x])),
+        Ok(NativeVerificationResult::Verified)
+    );
+}
+
+#[test]
+fn failure_with_function_precondition() {
+    let a = VariableDecl::new("a", Type::Int);
+    let function = FunctionDecl::new(
+        "positive",
+        FunctionKind::CallerFor,
+        vec![a.clone()],
+        Type::Int,
+        vec![Expression::greater_than(
+            Expression::local_no_pos(a.clone()),
+            int(0),
+        )],
+        vec![],
+        Some(Expression::local_no_pos(a)),
+    );
+    let x = VariableDecl::new("x", Type::Int);
+    let y = VariableDecl::new("y", Type::Int);
+    let block = BasicBlock::new(
+        Label::new("start"),
+        vec![Statement::assign(
+            y.clone(),
+            Expression::function_call(
+                "positive",
+                vec![Expression::local_no_pos(x.clone())],
+                Type::Int,
+            ),
+            Position::new(0, 0, 1),
+        )],
+        Successor::Return,
+    );
+    let procedure = ProcedureDecl::new("foo", vec![x, y], vec![block]);
+    let program = Program::new(
+        "test",
+        CheckMode::CoreProof,
+        vec![],
+        vec![],
+        vec![function],
+        vec![],
+        vec![procedure],
+    );
+    let result = verifier().verify(&program);
+    let NativeVerificationResult::Failed(errors) = result.unwrap() else {
+        panic!("the verification should fail");
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].full_id,
+        "application.precondition:assertion.false"
+    );
+}
@@ -0,0 +1,792 @@
+//! Prints low-level VIR programs in the syntax of Viper, for verifiers such
+//! as ViperServer that read programs from files instead of building them
+//! through JNI. It supports the same constructs as `low_to_viper`.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+use vir::{
+    legacy::RETURN_LABEL,
+    low::{
+        ast::{
+            expression::{self, Expression},
+            function::FunctionDecl,
+            position::Position,
+            predicate::PredicateDecl,
+            statement::{self, Statement},
+            ty::{self, Type},
+            variable::VariableDecl,
+        },
+        cfg::{procedure::Successor, MethodDecl, ProcedureDecl},
+        program::Program,
+        DomainDecl,
+    },
+};
+
+/// A construct of the program that has no syntax in Viper.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedConstruct(pub String);
+
+impl fmt::Display for UnsupportedConstruct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the Viper printer does not support {}", self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedConstruct {}
+
+type Result<T> = std::result::Result<T, UnsupportedConstruct>;
+
+fn unsupported<T>(construct: impl ToString) -> Result<T> {
+    Err(UnsupportedConstruct(construct.to_string()))
+}
+
+/// The source code of a Viper program with the positions of its nodes.
+pub struct ViperSource {
+    pub text: String,
+    /// The identifiers of the positions of the nodes, keyed by the line and
+    /// column at which the nodes start, both starting at 1.
+    positions: BTreeMap<(u32, u32), u64>,
+}
+
+impl ViperSource {
+    pub fn new(program: &Program) -> Result<Self> {
+        let mut printer = Printer {
+            text: String::new(),
+            line: 1,
+            column: 1,
+            positions: BTreeMap::new(),
+        };
+        printer.program(program)?;
+        Ok(Self {
+            text: printer.text,
+            positions: printer.positions,
+        })
+    }
+
+    /// The identifier of the position of the node that starts at the given
+    /// line and column or, if there is none, of the last node that starts
+    /// before it on the same line. For example, Viper may report a binary
+    /// operation at the start of its left operand instead of its parenthesis.
+    pub fn position_id(&self, line: u32, column: u32) -> Option<String> {
+        self.positions
+            .range((line, 1)..=(line, column))
+            .next_back()
+            .map(|(_, id)| id.to_string())
+    }
+}
+
+struct Printer {
+    text: String,
+    line: u32,
+    column: u32,
+    positions: BTreeMap<(u32, u32), u64>,
+}
+
+impl Printer {
+    fn write(&mut self, text: &str) {
+        for character in text.chars() {
+            if character == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.text.push_str(text);
+    }
+
+    fn line(&mut self, indentation: usize, text: &str) {
+        self.indent(indentation);
+        self.write(text);
+        self.write("\n");
+    }
+
+    fn indent(&mut self, indentation: usize) {
+        self.write(&"  ".repeat(indentation));
+    }
+
+    /// Records that the node with `position` starts at the current column.
+    fn mark(&mut self, position: Position) {
+        if !position.is_default() {
+            self.positions
+                .entry((self.line, self.column))
+                .or_insert_with(|| position.id());
+        }
+    }
+
+    fn program(&mut self, program: &Program) -> Result<()> {
+        for domain in &program.domains {
+            self.domain(domain)?;
+        }
+        for predicate in &program.predicates {
+            self.predicate(predicate)?;
+        }
+        for function in &program.functions {
+            self.function(function)?;
+        }
+        for procedure in &program.procedures {
+            self.procedure(procedure)?;
+        }
+        for method in &program.methods {
+            self.method(method)?;
+        }
+        Ok(())
+    }
+
+    fn domain(&mut self, domain: &DomainDecl) -> Result<()> {
+        self.line(0, &format!("domain {} {{", domain.name));
+        for function in &domain.functions {
+            let unique = if function.is_unique { "unique " } else { "" };
+            let parameters = variable_decls(&function.parameters)?;
+            let return_type = type_name(&function.return_type)?;
+            self.line(
+                1,
+                &format!(
+                    "{unique}function {}({parameters}): {return_type}",
+                    function.name
+                ),
+            );
+        }
+        for axiom in &domain.axioms {
+            if let Some(comment) = &axiom.comment {
+                self.comment(1, comment);
+            }
+            self.line(1, &format!("axiom {} {{", axiom.name));
+            self.indent(2);
+            self.expression(&axiom.body, false)?;
+            self.write("\n");
+            self.line(1, "}");
+        }
+        self.line(0, "}");
+        Ok(())
+    }
+
+    fn predicate(&mut self, predicate: &PredicateDecl) -> Result<()> {
+        self.write(&format!(
+            "predicate {}({})",
+            predicate.name,
+            variable_decls(&predicate.parameters)?
+        ));
+        if let Some(body) = &predicate.body {
+            self.write(" {\n");
+            self.indent(1);
+            self.expression(body, false)?;
+            self.write("\n}");
+        }
+        self.write("\n");
+        Ok(())
+    }
+
+    fn function(&mut self, function: &FunctionDecl) -> Result<()> {
+        self.line(
+            0,
+            &format!(
+                "function {}({}): {}",
+                function.name,
+                variable_decls(&function.parameters)?,
+                type_name(&function.return_type)?
+            ),
+        );
+        self.contract(&function.pres, &function.posts)?;
+        if let Some(body) = &function.body {
+            self.line(0, "{");
+            self.indent(1);
+            self.expression(body, false)?;
+            self.write("\n");
+            self.line(0, "}");
+        }
+        Ok(())
+    }
+
+    fn contract(&mut self, pres: &[Expression], posts: &[Expression]) -> Result<()> {
+        for (keyword, expressions) in [("requires", pres), ("ensures", posts)] {
+            for expression in expressions {
+                self.indent(1);
+                self.write(keyword);
+                self.write(" ");
+                self.expression(expression, false)?;
+                self.write("\n");
+            }
+        }
+        Ok(())
+    }
+
+    fn procedure(&mut self, procedure: &ProcedureDecl) -> Result<()> {
+        self.line(0, &format!("method {}()", procedure.name));
+        self.line(0, "{");
+        for local in &procedure.locals {
+            self.line(1, &format!("var {}", variable_decl(local)?));
+        }
+        for block in &procedure.basic_blocks {
+            self.line(0, &format!("label {}", block.label.name));
+            self.statements(1, &block.statements)?;
+            match &block.successor {
+                Successor::Goto(target) => self.line(1, &format!("goto {}", target.name)),
+                Successor::GotoSwitch(targets) => {
+                    for (test, target) in targets {
+                        self.indent(1);
+                        self.write("if (");
+                        self.expression(test, false)?;
+                        self.write(&format!(") {{ goto {} }}\n", target.name));
+                    }
+                    self.line(1, "assert false");
+                }
+                Successor::Return => self.line(1, &format!("goto {RETURN_LABEL}")),
+            }
+        }
+        self.line(0, &format!("label {RETURN_LABEL}"));
+        self.line(0, "}");
+        Ok(())
+    }
+
+    fn method(&mut self, method: &MethodDecl) -> Result<()> {
+        let mut signature = format!(
+            "method {}({})",
+            method.name,
+            variable_decls(&method.parameters)?
+        );
+        if !method.targets.is_empty() {
+            write!(signature, " returns ({})", variable_decls(&method.targets)?).unwrap();
+        }
+        self.line(0, &signature);
+        self.contract(&method.pres, &method.posts)?;
+        if let Some(body) = &method.body {
+            self.line(0, "{");
+            self.statements(1, body)?;
+            self.line(0, "}");
+        }
+        Ok(())
+    }
+
+    fn comment(&mut self, indentation: usize, comment: &str) {
+        for line in comment.lines() {
+            self.line(indentation, &format!("// {line}"));
+        }
+    }
+
+    fn statements(&mut self, indentation: usize, statements: &[Statement]) -> Result<()> {
+        for statement in statements {
+            self.statement(indentation, statement)?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, indentation: usize, statement: &Statement) -> Result<()> {
+        if let Statement::Comment(statement::Comment { comment }) = statement {
+            self.comment(indentation, comment);
+            return Ok(());
+        }
+        self.indent(indentation);
+        match statement {
+            Statement::Comment(_) => unreachable!(),
+            Statement::LogEvent(statement) => {
+                self.write("inhale ");
+                self.expression(&statement.expression, false)?;
+            }
+            Statement::Assume(statement::Assume {
+                expression,
+                position,
+            })
+            | Statement::Inhale(statement::Inhale {
+                expression,
+                position,
+            }) => self.keyword_statement("inhale", expression, *position)?,
+            Statement::Assert(statement::Assert {
+                expression,
+                position,
+            }) => self.keyword_statement("assert", expression, *position)?,
+            Statement::Exhale(statement::Exhale {
+                expression,
+                position,
+            }) => self.keyword_statement("exhale", expression, *position)?,
+            Statement::Fold(statement::Fold {
+                expression,
+                position,
+            }) => self.keyword_statement("fold", expression, *position)?,
+            Statement::Unfold(statement::Unfold {
+                expression,
+                position,
+            }) => self.keyword_statement("unfold", expression, *position)?,
+            Statement::ApplyMagicWand(statement::ApplyMagicWand {
+                expression,
+                position,
+            }) => self.keyword_statement("apply", expression, *position)?,
+            Statement::Conditional(statement) => {
+                self.mark(statement.position);
+                self.write("if (");
+                self.expression(&statement.guard, false)?;
+                self.write(") {\n");
+                self.statements(indentation + 1, &statement.then_branch)?;
+                self.indent(indentation);
+                self.write("} else {\n");
+                self.statements(indentation + 1, &statement.else_branch)?;
+                self.indent(indentation);
+                self.write("}");
+            }
+            Statement::MethodCall(statement) => {
+                self.mark(statement.position);
+                if !statement.targets.is_empty() {
+                    self.expressions(&statement.targets, false)?;
+                    self.write(" := ");
+                }
+                self.write(&statement.method_name);
+                self.write("(");
+                self.expressions(&statement.arguments, false)?;
+                self.write(")");
+            }
+            Statement::Assign(statement) => {
+                self.mark(statement.position);
+                self.write(&statement.target.name);
+                self.write(" := ");
+                self.expression(&statement.value, false)?;
+            }
+        }
+        self.write("\n");
+        Ok(())
+    }
+
+    fn keyword_statement(
+        &mut self,
+        keyword: &str,
+        expression: &Expression,
+        position: Position,
+    ) -> Result<()> {
+        self.mark(position);
+        self.write(keyword);
+        self.write(" ");
+        self.expression(expression, false)
+    }
+
+    fn expressions(&mut self, expressions: &[Expression], inside_trigger: bool) -> Result<()> {
+        for (index, expression) in expressions.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            self.expression(expression, inside_trigger)?;
+        }
+        Ok(())
+    }
+
+    fn binary(&mut self, left: &Expression, operator: &str, right: &Expression) -> Result<()> {
+        self.write("(");
+        self.expression(left, false)?;
+        self.write(&format!(" {operator} "));
+        self.expression(right, false)?;
+        self.write(")");
+        Ok(())
+    }
+
+    fn expression(&mut self, expression: &Expression, inside_trigger: bool) -> Result<()> {
+        match expression {
+            Expression::Local(expression) => {
+                self.mark(expression.position);
+                if expression.variable.name == "__result" {
+                    self.write("result");
+                } else {
+                    self.write(&expression.variable.name);
+                }
+            }
+            Expression::LabelledOld(expression) => {
+                self.mark(expression.position);
+                if let Some(label) = &expression.label {
+                    self.write(&format!("old[{label}]("));
+                } else {
+                    self.write("old(");
+                }
+                self.expression(&expression.base, inside_trigger)?;
+                self.write(")");
+            }
+            Expression::Constant(expression) => {
+                self.mark(expression.position);
+                self.constant(expression)?;
+            }
+            Expression::MagicWand(expression) => {
+                self.mark(expression.position);
+                self.binary(&expression.left, "--*", &expression.right)?;
+            }
+            Expression::PredicateAccessPredicate(expression) => {
+                self.mark(expression.position);
+                if !inside_trigger {
+                    self.write("acc(");
+                }
+                self.write(&expression.name);
+                self.write("(");
+                self.expressions(&expression.arguments, false)?;
+                self.write(")");
+                if !inside_trigger {
+                    self.write(", ");
+                    self.expression(&expression.permission, false)?;
+                    self.write(")");
+                }
+            }
+            Expression::UnaryOp(expression) => {
+                self.mark(expression.position);
+                self.write(match expression.op_kind {
+                    expression::UnaryOpKind::Not => "!",
+                    expression::UnaryOpKind::Minus => "-",
+                });
+                self.write("(");
+                self.expression(&expression.argument, inside_trigger)?;
+                self.write(")");
+            }
+            Expression::BinaryOp(expression) => {
+                self.mark(expression.position);
+                let operator = match expression.op_kind {
+                    expression::BinaryOpKind::EqCmp => "==",
+                    expression::BinaryOpKind::NeCmp => "!=",
+                    expression::BinaryOpKind::GtCmp => ">",
+                    expression::BinaryOpKind::GeCmp => ">=",
+                    expression::BinaryOpKind::LtCmp => "<",
+                    expression::BinaryOpKind::LeCmp => "<=",
+                    expression::BinaryOpKind::Add => "+",
+                    expression::BinaryOpKind::Sub => "-",
+                    expression::BinaryOpKind::Mul => "*",
+                    expression::BinaryOpKind::Div => "\\",
+                    expression::BinaryOpKind::Mod => "%",
+                    expression::BinaryOpKind::And => "&&",
+                    expression::BinaryOpKind::Or => "||",
+                    expression::BinaryOpKind::Implies => "==>",
+                };
+                self.binary(&expression.left, operator, &expression.right)?;
+            }
+            Expression::PermBinaryOp(expression) => {
+                self.mark(expression.position);
+                let operator = match expression.op_kind {
+                    expression::PermBinaryOpKind::Add => "+",
+                    expression::PermBinaryOpKind::Sub => "-",
+                    expression::PermBinaryOpKind::Mul => "*",
+                    expression::PermBinaryOpKind::Div => "/",
+                };
+                self.binary(&expression.left, operator, &expression.right)?;
+            }
+            Expression::ContainerOp(expression) => {
+                self.mark(expression.position);
+                self.container_op(expression)?;
+            }
+            Expression::Conditional(expression) => {
+                self.mark(expression.position);
+                self.write("(");
+                self.expression(&expression.guard, false)?;
+                self.write(" ? ");
+                self.expression(&expression.then_expr, false)?;
+                self.write(" : ");
+                self.expression(&expression.else_expr, false)?;
+                self.write(")");
+            }
+            Expression::Quantifier(expression) => {
+                self.mark(expression.position);
+                let kind = match expression.kind {
+                    expression::QuantifierKind::ForAll => "forall",
+                    expression::QuantifierKind::Exists => "exists",
+                };
+                self.write(&format!(
+                    "({kind} {} ::",
+                    variable_decls(&expression.variables)?
+                ));
+                for trigger in &expression.triggers {
+                    self.write(" { ");
+                    self.expressions(&trigger.terms, true)?;
+                    self.write(" }");
+                }
+                self.write(" ");
+                self.expression(&expression.body, false)?;
+                self.write(")");
+            }
+            Expression::FuncApp(expression::FuncApp {
+                function_name,
+                arguments,
+                position,
+                ..
+            })
+            | Expression::DomainFuncApp(expression::DomainFuncApp {
+                function_name,
+                arguments,
+                position,
+                ..
+            }) => {
+                self.mark(*position);
+                self.write(function_name);
+                self.write("(");
+                self.expressions(arguments, inside_trigger)?;
+                self.write(")");
+            }
+            Expression::Field(_)
+            | Expression::FieldAccessPredicate(_)
+            | Expression::Unfolding(_)
+            | Expression::LetExpr(_)
+            | Expression::InhaleExhale(_) => {
+                return unsupported(format!("the expression `{expression}`"));
+            }
+        }
+        Ok(())
+    }
+
+    fn constant(&mut self, constant: &expression::Constant) -> Result<()> {
+        let text = match (&constant.ty, &constant.value) {
+            (Type::Int, expression::ConstantValue::Int(value)) => {
+                if *value < 0 {
+                    format!("({value})")
+                } else {
+                    value.to_string()
+                }
+            }
+            (Type::Int, expression::ConstantValue::BigInt(value)) => {
+                if value.starts_with('-') {
+                    format!("({value})")
+                } else {
+                    value.clone()
+                }
+            }
+            (Type::Bool, expression::ConstantValue::Bool(value)) => value.to_string(),
+            (Type::Perm, expression::ConstantValue::Int(0)) => "none".to_string(),
+            (Type::Perm, expression::ConstantValue::Int(1)) => "write".to_string(),
+            (Type::Perm, expression::ConstantValue::Int(-1)) => "wildcard".to_string(),
+            _ => return unsupported(format!("the constant `{constant}` of type {}", constant.ty)),
+        };
+        self.write(&text);
+        Ok(())
+    }
+
+    fn container_op(&mut self, container_op: &expression::ContainerOp) -> Result<()> {
+        let operands = &container_op.operands;
+        let element_type = || match &container_op.container_type {
+            Type::Seq(ty::Seq { element_type, .. })
+            | Type::Set(ty::Set { element_type, .. })
+            | Type::MultiSet(ty::MultiSet { element_type, .. }) => type_name(element_type),
+            _ => unreachable!("{}", container_op.container_type),
+        };
+        let empty = |printer: &mut Self, container: &str| {
+            printer.write(&format!("{container}[{}]()", element_type()?));
+            Ok(())
+        };
+        let explicit = |printer: &mut Self, container: &str| {
+            if operands.is_empty() {
+                empty(printer, container)
+            } else {
+                printer.write(container);
+                printer.write("(");
+                printer.expressions(operands, false)?;
+                printer.write(")");
+                Ok(())
+            }
+        };
+        match container_op.kind {
+            expression::ContainerOpKind::SeqEmpty => empty(self, "Seq"),
+            expression::ContainerOpKind::SeqConstructor => explicit(self, "Seq"),
+            expression::ContainerOpKind::SeqIndex | expression::ContainerOpKind::MapLookup => {
+                self.expression(&operands[0], false)?;
+                self.write("[");
+                self.expression(&operands[1], false)?;
+                self.write("]");
+                Ok(())
+            }
+            expression::ContainerOpKind::SeqConcat => self.binary(&operands[0], "++", &operands[1]),
+            expression::ContainerOpKind::SeqLen
+            | expression::ContainerOpKind::MapLen
+            | expression::ContainerOpKind::SetCardinality
+            | expression::ContainerOpKind::MultiSetCardinality => {
+                self.write("|");
+                self.expression(&operands[0], false)?;
+                self.write("|");
+                Ok(())
+            }
+            expression::ContainerOpKind::MapEmpty => {
+                let Type::Map(ty::Map { key_type, val_type }) = &container_op.container_type else {
+                    unreachable!("{}", container_op.container_type)
+                };
+                self.write(&format!(
+                    "Map[{}, {}]()",
+                    type_name(key_type)?,
+                    type_name(val_type)?
+                ));
+                Ok(())
+            }
+            expression::ContainerOpKind::MapUpdate => {
+                self.expression(&operands[0], false)?;
+                self.write("[");
+                self.expression(&operands[1], false)?;
+                self.write(" := ");
+                self.expression(&operands[2], false)?;
+                self.write("]");
+                Ok(())
+            }
+            expression::ContainerOpKind::MapContains => {
+                self.binary(&operands[1], "in", &operands[0])
+            }
+            expression::ContainerOpKind::SetEmpty => empty(self, "Set"),
+            expression::ContainerOpKind::SetConstructor => explicit(self, "Set"),
+            expression::ContainerOpKind::MultiSetEmpty => empty(self, "Multiset"),
+            expression::ContainerOpKind::MultiSetConstructor => explicit(self, "Multiset"),
+            expression::ContainerOpKind::SetUnion | expression::ContainerOpKind::MultiSetUnion => {
+                self.binary(&operands[0], "union", &operands[1])
+            }
+            expression::ContainerOpKind::SetIntersection
+            | expression::ContainerOpKind::MultiSetIntersection => {
+                self.binary(&operands[0], "intersection", &operands[1])
+            }
+            expression::ContainerOpKind::SetSubset
+            | expression::ContainerOpKind::MultiSetSubset => {
+                self.binary(&operands[0], "subset", &operands[1])
+            }
+            expression::ContainerOpKind::SetMinus | expression::ContainerOpKind::MultiSetMinus => {
+                self.binary(&operands[0], "setminus", &operands[1])
+            }
+            expression::ContainerOpKind::SetContains
+            | expression::ContainerOpKind::MultiSetContains => {
+                self.binary(&operands[0], "in", &operands[1])
+            }
+        }
+    }
+}
+
+fn type_name(ty: &Type) -> Result<String> {
+    Ok(match ty {
+        Type::Int => "Int".to_string(),
+        Type::Bool => "Bool".to_string(),
+        Type::Ref => "Ref".to_string(),
+        Type::Perm => "Perm".to_string(),
+        Type::Domain(ty) => ty.name.clone(),
+        Type::Seq(ty) => format!("Seq[{}]", type_name(&ty.element_type)?),
+        Type::Set(ty) => format!("Set[{}]", type_name(&ty.element_type)?),
+        Type::MultiSet(ty) => format!("Multiset[{}]", type_name(&ty.element_type)?),
+        Type::Map(ty) => format!(
+            "Map[{}, {}]",
+            type_name(&ty.key_type)?,
+            type_name(&ty.val_type)?
+        ),
+        // The backend types of Viper have no syntax of their own.
+        Type::Float(_) | Type::BitVector(_) => return unsupported(format!("the type {ty}")),
+    })
+}
+
+fn variable_decl(variable: &VariableDecl) -> Result<String> {
+    Ok(format!("{}: {}", variable.name, type_name(&variable.ty)?))
+}
+
+fn variable_decls(variables: &[VariableDecl]) -> Result<String> {
+    Ok(variables
+        .iter()
+        .map(variable_decl)
+        .collect::<Result<Vec<_>>>()?
+        .join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vir::{
+        common::{check_mode::CheckMode, expression::BinaryOperationHelpers},
+        low::{
+            ast::field::FieldDecl, BasicBlock, ConstantValue, Label, ProcedureDecl, VariableDecl,
+        },
+    };
+
+    fn program(procedure: ProcedureDecl) -> Program {
+        Program::new(
+            "test",
+            CheckMode::CoreProof,
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![procedure],
+        )
+    }
+
+    fn int(value: i64) -> Expression {
+        Expression::constant_no_pos(ConstantValue::Int(value), Type::Int)
+    }
+
+    #[test]
+    fn procedures_are_printed_with_their_positions() {
+        let x = VariableDecl::new("x", Type::Int);
+        let local_x = || Expression::local_no_pos(x.clone());
+        let blocks = vec![
+            BasicBlock::new(
+                Label::new("start"),
+                vec![Statement::assign(
+                    x.clone(),
+                    int(-1),
+                    Position::new(0, 0, 1),
+                )],
+                Successor::GotoSwitch(vec![(
+                    Expression::greater_than(local_x(), int(0)),
+                    Label::new("end"),
+                )]),
+            ),
+            BasicBlock::new(
+                Label::new("end"),
+                vec![Statement::assert(
+                    Expression::greater_than(local_x(), int(0)),
+                    Position::new(0, 0, 2),
+                )],
+                Successor::Return,
+            ),
+        ];
+        let procedure = ProcedureDecl::new("foo", vec![x.clone()], blocks);
+        let source = ViperSource::new(&program(procedure)).unwrap();
+        assert_eq!(
+            source.text,
+            format!(
+                "method foo()\n\
+                {{\n  \
+                  var x: Int\n\
+                label start\n  \
+                  x := (-1)\n  \
+                  if ((x > 0)) {{ goto end }}\n  \
+                  assert false\n\
+                label end\n  \
+                  assert (x > 0)\n  \
+                  goto {RETURN_LABEL}\n\
+                label {RETURN_LABEL}\n\
+                }}\n"
+            )
+        );
+        assert_eq!(source.position_id(5, 3).as_deref(), Some("1"));
+        assert_eq!(source.position_id(9, 3).as_deref(), Some("2"));
+        // Viper may report the comparison at its left operand.
+        assert_eq!(source.position_id(9, 11).as_deref(), Some("2"));
+        assert_eq!(source.position_id(9, 2), None);
+        assert_eq!(source.position_id(4, 1), None);
+    }
+
+    #[test]
+    fn backend_types_are_unsupported() {
+        let x = VariableDecl::new("x", Type::Float(ty::Float::F32));
+        let procedure = ProcedureDecl::new("foo", vec![x], vec![]);
+        assert_eq!(
+            ViperSource::new(&program(procedure)).err(),
+            Some(UnsupportedConstruct("the type F32".to_string()))
+        );
+    }
+
+    #[test]
+    fn unsupported_expressions_and_constants_are_errors() {
+        let x = VariableDecl::new("x", Type::Ref);
+        let field = Expression::field_no_pos(
+            Expression::local_no_pos(x.clone()),
+            FieldDecl::new("f", Type::Int),
+        );
+        let null = Expression::constant_no_pos(ConstantValue::Int(0), Type::Ref);
+        for expression in [field, null] {
+            let block = BasicBlock::new(
+                Label::new("start"),
+                vec![Statement::assert(
+                    Expression::equals(expression, int(0)),
+                    Position::default(),
+                )],
+                Successor::Return,
+            );
+            let procedure = ProcedureDecl::new("foo", vec![x.clone()], vec![block]);
+            let error = ViperSource::new(&program(procedure)).err().unwrap();
+            assert!(
+                error
+                    .to_string()
+                    .starts_with("the Viper printer does not support the "),
+                "{error}"
+            );
+        }
+    }
+}
@@ -9,12 +9,14 @@ pub use self::{
     to_graphviz::ToGraphViz,
 };
 pub use low_to_viper::Context as LoweringContext;
+pub use low_to_viper_source::{UnsupportedConstruct, ViperSource};
 pub use vir::{high as vir_high, legacy::*, polymorphic as polymorphic_vir};
 
 pub mod fixes;
 pub mod optimizations;
 mod to_viper;
 mod low_to_viper;
+mod low_to_viper_source;
 mod to_graphviz;
 pub mod program;
 pub mod macros;
@@ -0,0 +1,244 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::vir::polymorphic_vir::{
+    ast::*,
+    utils::{walk_functions, walk_methods},
+    Program,
+};
+use log::debug;
+use rustc_hash::{FxHashMap, FxHashSet};
+use vir::common::identifier::WithIdentifier;
+
+/// Delete the builtin methods that are never called and the functions that
+/// are not reachable from the methods, the kept builtin methods and the
+/// predicates.
+///
+/// The definition collector already emits only the functions mentioned by
+/// the encoded methods, but it has to emit all builtin methods, because the
+/// purification introduces calls to them. The optimizations that run
+/// afterwards can also remove the last use of a function, for example by
+/// inlining it.
+#[tracing::instrument(level = "debug", skip(program))]
+pub fn delete_unused_definitions(mut program: Program) -> Program {
+    let mut collector = UsedDefinitionCollector::default();
+    walk_methods(&program.methods, &mut collector);
+    let used_methods = std::mem::take(&mut collector.used_methods);
+    program
+        .builtin_methods
+        .retain(|method| used_methods.contains(&method.name));
+    debug!("The used builtin methods are {:?}", used_methods);
+
+    for method in &program.builtin_methods {
+        for expr in method.pres.iter().chain(&method.posts) {
+            ExprWalker::walk(&mut collector, expr);
+        }
+    }
+    for predicate in &program.viper_predicates {
+        walk_predicate(predicate, &mut collector);
+    }
+    let used_functions = std::mem::take(&mut collector.used_functions);
+
+    let functions_in_functions: FxHashMap<_, _> = program
+        .functions
+        .iter()
+        .map(|function| {
+            walk_functions(std::slice::from_ref(function), &mut collector);
+            (
+                function.get_identifier(),
+                std::mem::take(&mut collector.used_functions),
+            )
+        })
+        .collect();
+    let reachable_functions = compute_reachable_functions(&functions_in_functions, used_functions);
+    debug!("The used functions are {:?}", reachable_functions);
+    program
+        .functions
+        .retain(|function| reachable_functions.contains(&function.get_identifier()));
+
+    program
+}
+
+fn walk_predicate(predicate: &Predicate, collector: &mut UsedDefinitionCollector) {
+    match predicate {
+        Predicate::Struct(StructPredicate { body, .. }) => {
+            for expr in body {
+                ExprWalker::walk(collector, expr);
+            }
+        }
+        Predicate::Enum(EnumPredicate {
+            discriminant_bounds,
+            variants,
+            ..
+        }) => {
+            ExprWalker::walk(collector, discriminant_bounds);
+            for (guard, _, variant) in variants {
+                ExprWalker::walk(collector, guard);
+                for expr in &variant.body {
+                    ExprWalker::walk(collector, expr);
+                }
+            }
+        }
+        Predicate::Bodyless(_, _) => {}
+    }
+}
+
+/// Return the functions that are reachable from `used_functions`, including
+/// `used_functions` themselves.
+fn compute_reachable_functions(
+    functions_in_functions: &FxHashMap<String, FxHashSet<String>>,
+    used_functions: FxHashSet<String>,
+) -> FxHashSet<String> {
+    let mut to_visit: Vec<_> = used_functions.iter().cloned().collect();
+    let mut visited = used_functions;
+    while let Some(function) = to_visit.pop() {
+        if let Some(callees) = functions_in_functions.get(&function) {
+            for callee in callees {
+                if visited.insert(callee.clone()) {
+                    to_visit.push(callee.clone());
+                }
+            }
+        }
+    }
+    visited
+}
+
+#[derive(Default)]
+struct UsedDefinitionCollector {
+    /// The identifiers of the called functions.
+    used_functions: FxHashSet<String>,
+    /// The names of the called methods.
+    used_methods: FxHashSet<String>,
+}
+
+impl ExprWalker for UsedDefinitionCollector {
+    fn walk_func_app(
+        &mut self,
+        FuncApp {
+            function_name,
+            type_arguments,
+            arguments,
+            formal_arguments,
+            return_type,
+            ..
+        }: &FuncApp,
+    ) {
+        self.used_functions.insert(compute_identifier(
+            function_name,
+            type_arguments,
+            formal_arguments,
+            return_type,
+        ));
+        for argument in arguments {
+            ExprWalker::walk(self, argument);
+        }
+    }
+}
+
+impl StmtWalker for UsedDefinitionCollector {
+    fn walk_expr(&mut self, expr: &Expr) {
+        ExprWalker::walk(self, expr);
+    }
+
+    fn walk_method_call(
+        &mut self,
+        MethodCall {
+            method_name,
+            arguments,
+            ..
+        }: &MethodCall,
+    ) {
+        self.used_methods.insert(method_name.clone());
+        for argument in arguments {
+            ExprWalker::walk(self, argument);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vir::polymorphic_vir::CfgMethod;
+
+    fn function(name: &str, body: Expr) -> Function {
+        Function {
+            name: name.to_string(),
+            type_arguments: vec![],
+            formal_args: vec![],
+            return_type: Type::Int,
+            pres: vec![],
+            posts: vec![],
+            body: Some(body),
+        }
+    }
+
+    fn call(name: &str) -> Expr {
+        Expr::func_app(
+            name.to_string(),
+            vec![],
+            vec![],
+            vec![],
+            Type::Int,
+            Position::default(),
+        )
+    }
+
+    fn builtin_method(name: &str) -> BodylessMethod {
+        BodylessMethod {
+            name: name.to_string(),
+            formal_args: vec![],
+            formal_returns: vec![],
+            pres: vec![],
+            posts: vec![],
+        }
+    }
+
+    #[test]
+    fn unreachable_definitions_are_deleted() {
+        let x = LocalVar::new("x", Type::Int);
+        let mut method = CfgMethod::new("m".to_string(), vec![], vec![x.clone()], vec![]);
+        let block = method.add_block(
+            "start",
+            vec![
+                Stmt::MethodCall(MethodCall {
+                    method_name: "builtin$havoc_int".to_string(),
+                    arguments: vec![],
+                    targets: vec![x.clone()],
+                }),
+                Stmt::Assign(Assign {
+                    target: x.into(),
+                    source: call("f"),
+                    kind: AssignKind::Copy,
+                }),
+            ],
+        );
+        method.set_successor(block, crate::vir::polymorphic_vir::Successor::Return);
+        let program = Program {
+            name: "p".to_string(),
+            domains: vec![],
+            backend_types: vec![],
+            fields: vec![],
+            builtin_methods: vec![
+                builtin_method("builtin$havoc_int"),
+                builtin_method("builtin$havoc_bool"),
+            ],
+            methods: vec![method],
+            functions: vec![
+                function("f", call("g")),
+                function("g", 1.into()),
+                function("h", call("f")),
+            ],
+            viper_predicates: vec![],
+        };
+
+        let program = delete_unused_definitions(program);
+
+        let builtin_methods: Vec<_> = program.builtin_methods.iter().map(|m| &m.name).collect();
+        assert_eq!(builtin_methods, ["builtin$havoc_int"]);
+        let functions: Vec<_> = program.functions.iter().map(|f| &f.name).collect();
+        assert_eq!(functions, ["f", "g"]);
+    }
+}
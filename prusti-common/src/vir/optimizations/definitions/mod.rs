@@ -0,0 +1,11 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A module that contains optimizations for the definitions of a program.
+
+mod delete_unused_definitions;
+
+pub use self::delete_unused_definitions::delete_unused_definitions;
@@ -91,11 +91,20 @@ impl ast::StmtFolder for StmtOptimizer {
     }
 }
 
-type UnfoldingMap = FxHashMap<ast::Expr, (ast::Type, ast::PermAmount, ast::MaybeEnumVariantIndex)>;
+type UnfoldingMap = FxHashMap<
+    ast::Expr,
+    (
+        ast::Type,
+        ast::PermAmount,
+        ast::MaybeEnumVariantIndex,
+        ast::Position,
+    ),
+>;
 type RequirementSet = FxHashSet<ast::Expr>;
 
 struct ExprOptimizer {
-    /// Predicate argument → (predicate name, amount, enum index).
+    /// Predicate argument → (predicate name, amount, enum index, position of
+    /// the original unfolding).
     unfoldings: UnfoldingMap,
     /// Unfolding requirements: how deeply a specific place should be unfolded.
     requirements: RequirementSet,
@@ -132,8 +141,14 @@ fn restore_unfoldings(unfolding_map: UnfoldingMap, mut expr: ast::Expr) -> ast::
             }
         }
     });
-    for (arg, (name, perm_amount, variant)) in unfoldings {
-        let position = expr.pos();
+    for (arg, (name, perm_amount, variant, position)) in unfoldings {
+        // Keep the position of the original unfolding, so that errors about
+        // the predicate still point to the place where it was unfolded.
+        let position = if position.is_default() {
+            expr.pos()
+        } else {
+            position
+        };
         expr = ast::Expr::unfolding_with_pos(name, vec![arg], expr, perm_amount, variant, position);
     }
     expr
@@ -352,12 +367,12 @@ impl ast::FallibleExprFolder for ExprOptimizer {
                 base: box body,
                 permission: perm,
                 variant,
-                ..
+                position,
             }) => {
                 assert!(args.len() == 1);
                 let new_expr = self.fallible_fold(body)?;
                 self.unfoldings
-                    .insert(args.pop().unwrap(), (name, perm, variant));
+                    .insert(args.pop().unwrap(), (name, perm, variant, position));
                 new_expr
             }
             ast::Expr::Downcast(ast::DowncastExpr {
@@ -109,7 +109,10 @@ impl<'a> ast::ExprFolder for ConstantFunctionInliner<'a> {
         }: ast::FuncApp,
     ) -> ast::Expr {
         if self.pure_function_map.contains_key(&function_name) {
-            self.pure_function_map[&function_name].clone()
+            // Errors about the inlined body should point to the call.
+            self.pure_function_map[&function_name]
+                .clone()
+                .set_pos(position)
         } else {
             ast::Expr::FuncApp(ast::FuncApp {
                 function_name,
@@ -134,7 +137,7 @@ impl<'a> ast::ExprFolder for ConstantFunctionInliner<'a> {
     ) -> ast::Expr {
         base = self.fold_boxed(base);
         if base.is_constant() {
-            *base
+            (*base).set_pos_if_default(position)
         } else {
             ast::Expr::Unfolding(ast::Unfolding {
                 predicate,
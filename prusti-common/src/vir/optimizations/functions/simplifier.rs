@@ -92,7 +92,7 @@ impl ExprSimplifier {
                         ..
                     }),
                 right: box conjunct,
-                ..
+                position: pos,
             })
             | ast::Expr::BinOp(ast::BinOp {
                 op_kind: ast::BinaryOpKind::And,
@@ -102,12 +102,12 @@ impl ExprSimplifier {
                         value: ast::Const::Bool(b),
                         ..
                     }),
-                ..
+                position: pos,
             }) => {
                 if b {
-                    conjunct
+                    conjunct.set_pos_if_default(pos)
                 } else {
-                    false.into()
+                    ast::Expr::from(false).set_pos(pos)
                 }
             }
             ast::Expr::BinOp(ast::BinOp {
@@ -118,7 +118,7 @@ impl ExprSimplifier {
                         ..
                     }),
                 right: box disjunct,
-                ..
+                position: pos,
             })
             | ast::Expr::BinOp(ast::BinOp {
                 op_kind: ast::BinaryOpKind::Or,
@@ -128,12 +128,12 @@ impl ExprSimplifier {
                         value: ast::Const::Bool(b),
                         ..
                     }),
-                ..
+                position: pos,
             }) => {
                 if b {
-                    true.into()
+                    ast::Expr::from(true).set_pos(pos)
                 } else {
-                    disjunct
+                    disjunct.set_pos_if_default(pos)
                 }
             }
             ast::Expr::BinOp(ast::BinOp {
@@ -147,7 +147,7 @@ impl ExprSimplifier {
                 position: pos,
             }) => {
                 if b {
-                    true.into()
+                    ast::Expr::from(true).set_pos(pos)
                 } else {
                     ast::Expr::UnaryOp(ast::UnaryOp {
                         op_kind: ast::UnaryOpKind::Not,
@@ -164,12 +164,12 @@ impl ExprSimplifier {
                         ..
                     }),
                 right: box body,
-                ..
+                position: pos,
             }) => {
                 if b {
-                    body
+                    body.set_pos_if_default(pos)
                 } else {
-                    true.into()
+                    ast::Expr::from(true).set_pos(pos)
                 }
             }
             ast::Expr::BinOp(ast::BinOp {
@@ -237,3 +237,34 @@ impl ExprFolder for ExprSimplifier {
         Self::apply_rules(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(name: &str) -> ast::Expr {
+        ast::Expr::local(ast::LocalVar::new(name, ast::Type::Bool))
+    }
+
+    #[test]
+    fn simplified_expressions_keep_their_position() {
+        let pos = ast::Position::new(3, 7, 42);
+        let conjunction = ast::Expr::and(true.into(), local("x")).set_pos(pos);
+        let simplified = conjunction.simplify();
+        assert_eq!(simplified, local("x"));
+        assert_eq!(simplified.pos(), pos);
+
+        let implication = ast::Expr::implies(false.into(), local("x")).set_pos(pos);
+        let simplified = implication.simplify();
+        assert_eq!(simplified, true.into());
+        assert_eq!(simplified.pos(), pos);
+    }
+
+    #[test]
+    fn more_precise_positions_are_not_overwritten() {
+        let outer = ast::Position::new(3, 7, 42);
+        let inner = ast::Position::new(3, 12, 43);
+        let disjunction = ast::Expr::or(false.into(), local("x").set_pos(inner)).set_pos(outer);
+        assert_eq!(disjunction.simplify().pos(), inner);
+    }
+}
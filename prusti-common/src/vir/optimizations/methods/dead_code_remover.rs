@@ -0,0 +1,297 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Optimization that removes code that is never executed or whose result is
+//! never used.
+
+use crate::{
+    config,
+    vir::polymorphic_vir::{ast, cfg, Const},
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Remove dead code:
+/// * the targets of conditional jumps whose guard is `false`, and the
+///   targets after a guard that is `true`,
+/// * the basic blocks that are not reachable from the start block,
+/// * the labels that are not used by any `old[label](...)` expression,
+/// * the copy assignments to local variables that are never read, if the
+///   assigned expression is always well-defined.
+///
+/// The variables that are no longer used are removed by `remove_unused_vars`.
+/// The labels and the assignments are kept if counterexamples are enabled,
+/// because the counterexample of a program is translated by looking at the
+/// values of its variables at its labels.
+pub fn remove_dead_code(mut method: cfg::CfgMethod) -> cfg::CfgMethod {
+    for block in &mut method.basic_blocks {
+        remove_dead_targets(&mut block.successor);
+    }
+    method = remove_unreachable_blocks(method);
+    if !config::counterexample() {
+        method = remove_unused_labels(method);
+        method = remove_unread_assignments(method);
+    }
+    method
+}
+
+fn as_bool(expr: &ast::Expr) -> Option<bool> {
+    match expr {
+        ast::Expr::Const(ast::ConstExpr {
+            value: Const::Bool(value),
+            ..
+        }) => Some(*value),
+        _ => None,
+    }
+}
+
+fn remove_dead_targets(successor: &mut cfg::Successor) {
+    if let cfg::Successor::GotoSwitch(guarded_targets, default_target) = successor {
+        guarded_targets.retain(|(guard, _)| as_bool(guard) != Some(false));
+        if let Some(index) = guarded_targets
+            .iter()
+            .position(|(guard, _)| as_bool(guard) == Some(true))
+        {
+            *default_target = guarded_targets[index].1;
+            guarded_targets.truncate(index);
+        }
+        if guarded_targets.is_empty() {
+            *successor = cfg::Successor::Goto(*default_target);
+        }
+    }
+}
+
+fn remove_unreachable_blocks(mut method: cfg::CfgMethod) -> cfg::CfgMethod {
+    if method.basic_blocks.is_empty() {
+        return method;
+    }
+    let mut reachable = vec![false; method.basic_blocks.len()];
+    reachable[0] = true;
+    let mut to_visit = vec![0];
+    while let Some(index) = to_visit.pop() {
+        for target in method.basic_blocks[index].successor.get_following() {
+            if !reachable[target.index()] {
+                reachable[target.index()] = true;
+                to_visit.push(target.index());
+            }
+        }
+    }
+    let mut new_indices = FxHashMap::default();
+    let mut new_basic_blocks = Vec::new();
+    for (index, basic_block) in method.basic_blocks.into_iter().enumerate() {
+        if reachable[index] {
+            new_indices.insert(index, new_basic_blocks.len());
+            new_basic_blocks.push(basic_block);
+        }
+    }
+    for basic_block in &mut new_basic_blocks {
+        match &mut basic_block.successor {
+            cfg::Successor::Undefined | cfg::Successor::Return => {}
+            cfg::Successor::Goto(target) => {
+                target.block_index = new_indices[&target.index()];
+            }
+            cfg::Successor::GotoSwitch(conditional_targets, default_target) => {
+                default_target.block_index = new_indices[&default_target.index()];
+                for (_, target) in conditional_targets {
+                    target.block_index = new_indices[&target.index()];
+                }
+            }
+        }
+    }
+    method.basic_blocks = new_basic_blocks;
+    method
+}
+
+fn remove_unused_labels(mut method: cfg::CfgMethod) -> cfg::CfgMethod {
+    let mut collector = UsedLabelCollector {
+        used_labels: FxHashSet::default(),
+    };
+    method.walk_statements(|stmt| ast::StmtWalker::walk(&mut collector, stmt));
+    method.walk_successors(|successor| {
+        if let cfg::Successor::GotoSwitch(conditional_targets, _) = successor {
+            for (expr, _) in conditional_targets {
+                ast::ExprWalker::walk(&mut collector, expr);
+            }
+        }
+    });
+    let used_labels = collector.used_labels;
+    method.retain_stmts(|stmt| match stmt {
+        ast::Stmt::Label(ast::Label { label }) => used_labels.contains(label.as_str()),
+        _ => true,
+    });
+    method
+}
+
+/// Collects the labels of all `old[label](...)` expressions.
+struct UsedLabelCollector {
+    used_labels: FxHashSet<String>,
+}
+
+impl ast::ExprWalker for UsedLabelCollector {
+    fn walk_labelled_old(&mut self, ast::LabelledOld { label, base, .. }: &ast::LabelledOld) {
+        self.used_labels.insert(label.to_string());
+        ast::ExprWalker::walk(self, base);
+    }
+}
+
+impl ast::StmtWalker for UsedLabelCollector {
+    fn walk_expr(&mut self, expr: &ast::Expr) {
+        ast::ExprWalker::walk(self, expr);
+    }
+}
+
+fn remove_unread_assignments(mut method: cfg::CfgMethod) -> cfg::CfgMethod {
+    loop {
+        let mut collector = ReadVarCollector {
+            read_vars: method
+                .get_formal_returns()
+                .iter()
+                .map(|var| var.name.clone())
+                .collect(),
+        };
+        method.walk_statements(|stmt| ast::StmtWalker::walk(&mut collector, stmt));
+        method.walk_successors(|successor| {
+            if let cfg::Successor::GotoSwitch(conditional_targets, _) = successor {
+                for (expr, _) in conditional_targets {
+                    ast::ExprWalker::walk(&mut collector, expr);
+                }
+            }
+        });
+        let read_vars = collector.read_vars;
+        let mut changed = false;
+        for block in &mut method.basic_blocks {
+            let stmts_count = block.stmts.len();
+            block.stmts.retain(|stmt| match stmt {
+                ast::Stmt::Assign(ast::Assign {
+                    target: ast::Expr::Local(ast::Local { variable, .. }),
+                    source,
+                    kind: ast::AssignKind::Copy,
+                }) => read_vars.contains(&variable.name) || !is_always_well_defined(source),
+                _ => true,
+            });
+            changed |= block.stmts.len() != stmts_count;
+        }
+        if !changed {
+            return method;
+        }
+    }
+}
+
+/// Whether evaluating the expression can never fail, so that removing it
+/// does not hide an error.
+fn is_always_well_defined(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Local(_) | ast::Expr::Const(_) => true,
+        ast::Expr::UnaryOp(ast::UnaryOp { argument, .. }) => is_always_well_defined(argument),
+        ast::Expr::BinOp(ast::BinOp {
+            op_kind,
+            left,
+            right,
+            ..
+        }) => {
+            !matches!(op_kind, ast::BinaryOpKind::Div | ast::BinaryOpKind::Mod)
+                && is_always_well_defined(left)
+                && is_always_well_defined(right)
+        }
+        ast::Expr::Cond(ast::Cond {
+            guard,
+            then_expr,
+            else_expr,
+            ..
+        }) => {
+            is_always_well_defined(guard)
+                && is_always_well_defined(then_expr)
+                && is_always_well_defined(else_expr)
+        }
+        _ => false,
+    }
+}
+
+/// Collects all variables that are read. A variable is read if it is
+/// mentioned somewhere except as the target of a copy assignment.
+struct ReadVarCollector {
+    read_vars: FxHashSet<String>,
+}
+
+impl ast::ExprWalker for ReadVarCollector {
+    fn walk_local_var(&mut self, local_var: &ast::LocalVar) {
+        self.read_vars.insert(local_var.name.clone());
+    }
+}
+
+impl ast::StmtWalker for ReadVarCollector {
+    fn walk_expr(&mut self, expr: &ast::Expr) {
+        ast::ExprWalker::walk(self, expr);
+    }
+    fn walk_local_var(&mut self, local_var: &ast::LocalVar) {
+        self.read_vars.insert(local_var.name.clone());
+    }
+    fn walk_assign(&mut self, statement: &ast::Assign) {
+        let ast::Assign {
+            target,
+            source,
+            kind,
+        } = statement;
+        if !(matches!(target, ast::Expr::Local(_)) && *kind == ast::AssignKind::Copy) {
+            self.walk_expr(target);
+        }
+        self.walk_expr(source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dead_code_is_removed() {
+        let x = ast::LocalVar::new("x", ast::Type::Int);
+        let y = ast::LocalVar::new("y", ast::Type::Int);
+        let mut method =
+            cfg::CfgMethod::new("m".to_string(), vec![], vec![x.clone(), y.clone()], vec![]);
+        let assign = |var: &ast::LocalVar, source: ast::Expr| {
+            ast::Stmt::Assign(ast::Assign {
+                target: var.clone().into(),
+                source,
+                kind: ast::AssignKind::Copy,
+            })
+        };
+        let start = method.add_block(
+            "start",
+            vec![
+                assign(&x, 1.into()),
+                ast::Stmt::label("l1"),
+                ast::Stmt::label("l2"),
+                assign(&y, ast::Expr::add(x.clone().into(), 1.into())),
+            ],
+        );
+        let dead = method.add_block("dead", vec![assign(&x, 2.into())]);
+        let end = method.add_block(
+            "end",
+            vec![ast::Stmt::Assert(ast::Assert {
+                expr: ast::Expr::eq_cmp(ast::Expr::labelled_old("l1", x.clone().into()), 1.into()),
+                position: ast::Position::default(),
+            })],
+        );
+        method.set_successor(
+            start,
+            cfg::Successor::GotoSwitch(vec![(false.into(), dead), (true.into(), end)], dead),
+        );
+        method.set_successor(dead, cfg::Successor::Goto(end));
+        method.set_successor(end, cfg::Successor::Return);
+
+        let method = remove_dead_code(method);
+
+        assert_eq!(method.basic_blocks.len(), 2);
+        assert_eq!(
+            method.basic_blocks[0].stmts,
+            vec![assign(&x, 1.into()), ast::Stmt::label("l1")]
+        );
+        assert_eq!(
+            method.basic_blocks[0].successor.get_following()[0].index(),
+            1
+        );
+    }
+}
@@ -0,0 +1,292 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Optimization that folds constants and removes trivial subexpressions.
+
+use crate::vir::polymorphic_vir::{self as vir, ExprFolder, StmtFolder};
+use std::mem;
+
+/// Simplify the expressions of the method:
+/// * evaluate arithmetic, comparisons and boolean operations on constants,
+/// * replace `true && x`, `x && true`, `false || x`, `x || false` and
+///   `true ==> x` by `x`,
+/// * replace `false && x` by `false`, and `true || x`, `false ==> x` and
+///   `x ==> true` by `true`, the latter only if `x` is always well-defined,
+/// * replace `!!x` by `x`,
+/// * replace conditionals whose guard is a constant by one of their branches.
+///
+/// Magic wands are left unchanged, because they are matched syntactically
+/// when they are applied.
+pub fn simplify_expressions(mut cfg: vir::CfgMethod) -> vir::CfgMethod {
+    let mut simplifier = ExprSimplifier;
+    for block in &mut cfg.basic_blocks {
+        block.stmts = mem::take(&mut block.stmts)
+            .into_iter()
+            .map(|stmt| StmtFolder::fold(&mut simplifier, stmt))
+            .collect();
+        if let vir::Successor::GotoSwitch(guarded_targets, _) = &mut block.successor {
+            for (guard, _) in guarded_targets {
+                let expr = mem::replace(guard, true.into());
+                *guard = ExprFolder::fold(&mut simplifier, expr);
+            }
+        }
+    }
+    cfg
+}
+
+struct ExprSimplifier;
+
+impl ExprFolder for ExprSimplifier {
+    fn fold(&mut self, expr: vir::Expr) -> vir::Expr {
+        match vir::default_fold_expr(self, expr) {
+            vir::Expr::UnaryOp(unary_op) => simplify_unary_op(unary_op),
+            vir::Expr::BinOp(bin_op) => simplify_bin_op(bin_op),
+            vir::Expr::Cond(cond) => simplify_cond(cond),
+            expr => expr,
+        }
+    }
+}
+
+impl StmtFolder for ExprSimplifier {
+    fn fold_expr(&mut self, expr: vir::Expr) -> vir::Expr {
+        ExprFolder::fold(self, expr)
+    }
+
+    fn fold_package_magic_wand(&mut self, statement: vir::PackageMagicWand) -> vir::Stmt {
+        vir::Stmt::PackageMagicWand(vir::PackageMagicWand {
+            package_stmts: statement
+                .package_stmts
+                .into_iter()
+                .map(|stmt| StmtFolder::fold(self, stmt))
+                .collect(),
+            ..statement
+        })
+    }
+
+    fn fold_apply_magic_wand(&mut self, statement: vir::ApplyMagicWand) -> vir::Stmt {
+        vir::Stmt::ApplyMagicWand(statement)
+    }
+}
+
+fn constant(value: vir::Const, position: vir::Position) -> vir::Expr {
+    vir::Expr::Const(vir::ConstExpr { value, position })
+}
+
+fn as_bool(expr: &vir::Expr) -> Option<bool> {
+    match expr {
+        vir::Expr::Const(vir::ConstExpr {
+            value: vir::Const::Bool(value),
+            ..
+        }) => Some(*value),
+        _ => None,
+    }
+}
+
+fn as_int(expr: &vir::Expr) -> Option<i64> {
+    match expr {
+        vir::Expr::Const(vir::ConstExpr {
+            value: vir::Const::Int(value),
+            ..
+        }) => Some(*value),
+        _ => None,
+    }
+}
+
+fn simplify_unary_op(unary_op: vir::UnaryOp) -> vir::Expr {
+    let vir::UnaryOp {
+        op_kind,
+        box argument,
+        position,
+    } = unary_op;
+    match (op_kind, argument) {
+        (
+            vir::UnaryOpKind::Not,
+            vir::Expr::Const(vir::ConstExpr {
+                value: vir::Const::Bool(value),
+                ..
+            }),
+        ) => constant(vir::Const::Bool(!value), position),
+        (
+            vir::UnaryOpKind::Not,
+            vir::Expr::UnaryOp(vir::UnaryOp {
+                op_kind: vir::UnaryOpKind::Not,
+                box argument,
+                ..
+            }),
+        ) => argument.set_pos_if_default(position),
+        (vir::UnaryOpKind::Minus, argument) => match as_int(&argument).and_then(i64::checked_neg) {
+            Some(value) => constant(vir::Const::Int(value), position),
+            None => vir::Expr::UnaryOp(vir::UnaryOp {
+                op_kind,
+                argument: Box::new(argument),
+                position,
+            }),
+        },
+        (op_kind, argument) => vir::Expr::UnaryOp(vir::UnaryOp {
+            op_kind,
+            argument: Box::new(argument),
+            position,
+        }),
+    }
+}
+
+fn simplify_bin_op(bin_op: vir::BinOp) -> vir::Expr {
+    use vir::BinaryOpKind::*;
+    let vir::BinOp {
+        op_kind,
+        box left,
+        box right,
+        position,
+    } = bin_op;
+    if let (Some(left), Some(right)) = (as_int(&left), as_int(&right)) {
+        let value = match op_kind {
+            Add => left.checked_add(right).map(vir::Const::Int),
+            Sub => left.checked_sub(right).map(vir::Const::Int),
+            Mul => left.checked_mul(right).map(vir::Const::Int),
+            Min => Some(vir::Const::Int(left.min(right))),
+            Max => Some(vir::Const::Int(left.max(right))),
+            EqCmp => Some(vir::Const::Bool(left == right)),
+            NeCmp => Some(vir::Const::Bool(left != right)),
+            GtCmp => Some(vir::Const::Bool(left > right)),
+            GeCmp => Some(vir::Const::Bool(left >= right)),
+            LtCmp => Some(vir::Const::Bool(left < right)),
+            LeCmp => Some(vir::Const::Bool(left <= right)),
+            _ => None,
+        };
+        if let Some(value) = value {
+            return constant(value, position);
+        }
+    }
+    if let (Some(left), Some(right)) = (as_bool(&left), as_bool(&right)) {
+        let value = match op_kind {
+            EqCmp => Some(left == right),
+            NeCmp => Some(left != right),
+            _ => None,
+        };
+        if let Some(value) = value {
+            return constant(vir::Const::Bool(value), position);
+        }
+    }
+    // The rules that drop an operand only drop operands that are not
+    // evaluated in Viper either. The left operand of `x ==> true` is
+    // evaluated, so it is only dropped if it cannot fail, e.g. because of a
+    // missing permission or a failing precondition of a function.
+    match (op_kind, as_bool(&left), as_bool(&right)) {
+        (And, Some(true), _) | (Or, Some(false), _) | (Implies, Some(true), _) => {
+            right.set_pos_if_default(position)
+        }
+        (And, _, Some(true)) | (Or, _, Some(false)) => left.set_pos_if_default(position),
+        (And, Some(false), _) => constant(vir::Const::Bool(false), position),
+        (Or, Some(true), _) | (Implies, Some(false), _) => {
+            constant(vir::Const::Bool(true), position)
+        }
+        (Implies, _, Some(true)) if is_always_well_defined(&left) => {
+            constant(vir::Const::Bool(true), position)
+        }
+        _ => vir::Expr::BinOp(vir::BinOp {
+            op_kind,
+            left: Box::new(left),
+            right: Box::new(right),
+            position,
+        }),
+    }
+}
+
+/// Whether the evaluation of `expr` cannot fail in Viper: it only reads local
+/// variables and constants, and applies operators that are defined for all
+/// operands.
+fn is_always_well_defined(expr: &vir::Expr) -> bool {
+    use vir::BinaryOpKind::*;
+    match expr {
+        vir::Expr::Local(_) | vir::Expr::Const(_) => true,
+        vir::Expr::UnaryOp(vir::UnaryOp { argument, .. }) => is_always_well_defined(argument),
+        vir::Expr::BinOp(vir::BinOp {
+            op_kind:
+                EqCmp | NeCmp | GtCmp | GeCmp | LtCmp | LeCmp | Add | Sub | Mul | And | Or | Implies,
+            left,
+            right,
+            ..
+        }) => is_always_well_defined(left) && is_always_well_defined(right),
+        _ => false,
+    }
+}
+
+fn simplify_cond(cond: vir::Cond) -> vir::Expr {
+    match as_bool(&cond.guard) {
+        Some(true) => cond.then_expr.set_pos_if_default(cond.position),
+        Some(false) => cond.else_expr.set_pos_if_default(cond.position),
+        None => vir::Expr::Cond(cond),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simplify(expr: vir::Expr) -> vir::Expr {
+        ExprFolder::fold(&mut ExprSimplifier, expr)
+    }
+
+    fn local(name: &str, typ: vir::Type) -> vir::Expr {
+        vir::Expr::local(vir::LocalVar::new(name, typ))
+    }
+
+    #[test]
+    fn constants_are_folded() {
+        let sum = vir::Expr::add(2.into(), vir::Expr::mul(3.into(), 4.into()));
+        assert_eq!(simplify(vir::Expr::gt_cmp(sum, 14.into())), false.into());
+        assert_eq!(
+            simplify(vir::Expr::minus(vir::Expr::sub(1.into(), 3.into()))),
+            2.into()
+        );
+        let overflowing = vir::Expr::add(i64::MAX.into(), 1.into());
+        assert_eq!(simplify(overflowing.clone()), overflowing);
+    }
+
+    #[test]
+    fn trivial_operands_are_removed() {
+        let x = local("x", vir::Type::Bool);
+        let y = local("y", vir::Type::Bool);
+        assert_eq!(
+            simplify(vir::Expr::and(
+                true.into(),
+                vir::Expr::implies(x.clone(), true.into())
+            )),
+            true.into()
+        );
+        assert_eq!(
+            simplify(vir::Expr::or(
+                vir::Expr::and(x.clone(), vir::Expr::not(vir::Expr::not(y.clone()))),
+                false.into()
+            )),
+            vir::Expr::and(x.clone(), y.clone())
+        );
+        let guarded = vir::Expr::ite(vir::Expr::eq_cmp(1.into(), 1.into()), x.clone(), y);
+        assert_eq!(simplify(guarded), x);
+    }
+
+    #[test]
+    fn operands_that_might_fail_are_kept() {
+        let x = local("x", vir::Type::Int);
+        let y = local("y", vir::Type::Int);
+        // The division fails if `y` is zero.
+        let quotient = vir::Expr::implies(
+            vir::Expr::gt_cmp(vir::Expr::div(x.clone(), y.clone()), 0.into()),
+            true.into(),
+        );
+        assert_eq!(simplify(quotient.clone()), quotient);
+        let field = vir::Expr::implies(
+            vir::Expr::gt_cmp(
+                x.clone().field(vir::Field::new("val_int", vir::Type::Int)),
+                0.into(),
+            ),
+            true.into(),
+        );
+        assert_eq!(simplify(field.clone()), field);
+        let comparison = vir::Expr::implies(vir::Expr::gt_cmp(x, y), true.into());
+        assert_eq!(simplify(comparison), true.into());
+    }
+}
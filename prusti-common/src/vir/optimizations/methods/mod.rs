@@ -8,7 +8,9 @@
 
 mod assert_remover;
 mod cfg_cleaner;
+mod dead_code_remover;
 mod empty_if_remover;
+mod expr_simplifier;
 mod purifier;
 mod quantifier_fixer;
 mod unfolding_fixer;
@@ -19,8 +21,10 @@ use crate::{config::Optimizations, vir::polymorphic_vir::cfg::CfgMethod};
 
 use self::{
     assert_remover::remove_trivial_assertions, cfg_cleaner::clean_cfg,
-    empty_if_remover::remove_empty_if, purifier::purify_vars, quantifier_fixer::fix_quantifiers,
-    unfolding_fixer::fix_unfoldings, var_remover::remove_unused_vars,
+    dead_code_remover::remove_dead_code, empty_if_remover::remove_empty_if,
+    expr_simplifier::simplify_expressions, purifier::purify_vars,
+    quantifier_fixer::fix_quantifiers, unfolding_fixer::fix_unfoldings,
+    var_remover::remove_unused_vars,
 };
 
 #[allow(clippy::let_and_return)]
@@ -58,6 +62,8 @@ pub fn optimize_method_encoding(
     };
     let cfg = apply!(fix_unfoldings, cfg);
     let cfg = apply!(fix_quantifiers, cfg);
+    let cfg = apply!(simplify_expressions, cfg);
+    let cfg = apply!(remove_dead_code, cfg);
     let cfg = apply!(remove_empty_if, cfg);
     let cfg = apply!(remove_unused_vars, cfg);
     let cfg = apply!(remove_trivial_assertions, cfg);
@@ -340,6 +340,7 @@ impl ast::ExprFolder for VarPurifier {
     ) -> ast::Expr {
         if is_purifiable_predicate(&predicate_type) && self.is_pure(&argument) {
             self.get_replacement_bounds(&predicate_type, &argument)
+                .set_default_pos(position)
         } else {
             ast::Expr::PredicateAccessPredicate(ast::PredicateAccessPredicate {
                 predicate_type,
@@ -363,7 +364,7 @@ impl ast::ExprFolder for VarPurifier {
         }) = &receiver
         {
             if self.pure_vars.contains(var) {
-                return true.into();
+                return ast::Expr::from(true).set_pos(position);
             }
         }
         ast::Expr::FieldAccessPredicate(ast::FieldAccessPredicate {
@@ -385,7 +386,7 @@ impl ast::ExprFolder for VarPurifier {
     ) -> ast::Expr {
         assert!(arguments.len() == 1);
         if is_purifiable_predicate(&predicate) && self.is_pure(&arguments[0]) {
-            self.fold(*base)
+            self.fold(*base).set_pos_if_default(position)
         } else {
             ast::Expr::Unfolding(ast::Unfolding {
                 predicate,
@@ -406,7 +407,13 @@ impl ast::ExprFolder for VarPurifier {
         }: ast::FieldExpr,
     ) -> ast::Expr {
         if self.is_pure(&base) {
-            self.get_replacement(&base)
+            // The field access is more precise than its base variable.
+            let replacement = self.get_replacement(&base);
+            if position.is_default() {
+                replacement
+            } else {
+                replacement.set_pos(position)
+            }
         } else {
             ast::Expr::Field(ast::FieldExpr {
                 base: self.fold_boxed(base),
@@ -86,7 +86,8 @@ impl vir::ExprFolder for Optimizer {
             // remove the nested `unfolding (...) in` part because it's duplicate
             self.counter += 1;
             debug!("Removing outer `unfolding` from: {}", &unfolding);
-            self.fold(*unfolding.base)
+            let position = unfolding.position;
+            self.fold(*unfolding.base).set_pos_if_default(position)
         } else {
             // this is not a duplicate `unfolding (...) in`, keep folding
             self.stack.push(hash_unfolding(&unfolding));
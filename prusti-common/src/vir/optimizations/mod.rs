@@ -16,18 +16,61 @@ use crate::{
 use log::debug;
 
 pub mod bitvectors;
+pub mod definitions;
 pub mod folding;
 pub mod functions;
 pub mod methods;
 pub mod predicates;
 pub mod purification;
 
+/// The stages of the encoding pipeline of a method, in the order in which they
+/// are applied. The index of a stage is used to number the files written by
+/// `dump_vir_pass`.
+const VIR_PASSES: &[&str] = &[
+    "before_foldunfold",
+    "after_foldunfold",
+    "encode_bitvectors",
+    "inline_constant_functions",
+    "folding",
+    "purify_vars",
+    "fix_unfoldings",
+    "fix_quantifiers",
+    "simplify_expressions",
+    "remove_dead_code",
+    "remove_empty_if",
+    "remove_unused_vars",
+    "remove_trivial_assertions",
+    "clean_cfg",
+    "purify_methods",
+];
+
+/// Writes the VIR of `cfg` as it is after the stage `pass_name` of the
+/// encoding pipeline, if `config::dump_vir_passes` is enabled.
+pub fn dump_vir_pass(source_file_name: &str, cfg: &CfgMethod, pass_name: &str) {
+    if config::dump_vir_passes() {
+        let index = VIR_PASSES
+            .iter()
+            .position(|&pass| pass == pass_name)
+            .unwrap_or_else(|| unreachable!("unknown VIR pass {}", pass_name));
+        let method_dir =
+            crate::report::log::to_legal_file_name(format!("{}.{}", source_file_name, cfg.name()));
+        crate::report::log::report(
+            &format!("vir_passes/{method_dir}"),
+            format!("{index:02}_{pass_name}.vir"),
+            cfg,
+        );
+    }
+}
+
 fn log_method(
     source_file_name: &str,
     cfg: &CfgMethod,
     optimization_name: &str,
     after_optimization: bool,
 ) {
+    if after_optimization {
+        dump_vir_pass(source_file_name, cfg, optimization_name);
+    }
     if config::dump_debug_info() {
         let namespace = format!(
             "graphviz_method_optimization_{}_{}",
@@ -133,5 +176,9 @@ pub fn optimize_program(p: Program, source_file_name: &str) -> Program {
         log_methods(source_file_name, &program.methods, "purify_methods", true);
     }
 
+    if optimizations.delete_unused_definitions {
+        program = definitions::delete_unused_definitions(program);
+    }
+
     program
 }
@@ -2,10 +2,13 @@ use crate::vir::polymorphic_vir::{
     ast, cfg, utils::walk_method, Expr, Field, LocalVar, Stmt, Type,
 };
 use log::debug;
-use rustc_hash::FxHashSet;
-use std::collections::BTreeSet;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::{BTreeMap, BTreeSet};
 
-/// This purifies local variables in a method body
+/// This purifies local variables in a method body. Local variables of
+/// primitive types become Viper variables of the corresponding Viper type,
+/// and local variables of structs whose fields all have primitive types
+/// become one Viper variable per field.
 #[tracing::instrument(level = "debug", skip_all)]
 pub fn purify_methods(
     mut methods: Vec<cfg::CfgMethod>,
@@ -37,18 +40,94 @@ fn translate_type(typ: &Type) -> Type {
 
 static SUPPORTED_TYPES: &[&str] = &["bool", "i32", "isize", "usize", "u32"];
 
+/// The fields of the struct type `typ`, if all of them have a supported type
+/// and the struct predicate contains only their permissions.
+fn purifiable_struct_fields(typ: &Type, predicates: &[ast::Predicate]) -> Option<Vec<Field>> {
+    let body = predicates.iter().find_map(|predicate| match predicate {
+        ast::Predicate::Struct(predicate) if &predicate.typ == typ => predicate.body.as_ref(),
+        _ => None,
+    })?;
+    let mut conjuncts = vec![body];
+    let mut fields = Vec::new();
+    let mut predicate_fields = Vec::new();
+    while let Some(conjunct) = conjuncts.pop() {
+        match conjunct {
+            Expr::BinOp(ast::BinOp {
+                op_kind: ast::BinaryOpKind::And,
+                box left,
+                box right,
+                ..
+            }) => {
+                conjuncts.push(right);
+                conjuncts.push(left);
+            }
+            Expr::Const(ast::ConstExpr {
+                value: ast::Const::Bool(true),
+                ..
+            }) => {}
+            Expr::FieldAccessPredicate(ast::FieldAccessPredicate {
+                base:
+                    box Expr::Field(ast::FieldExpr {
+                        base: box Expr::Local(_),
+                        field,
+                        ..
+                    }),
+                ..
+            }) if field.typ.is_typed_ref()
+                && SUPPORTED_TYPES.contains(&field.typ.name().as_str()) =>
+            {
+                fields.push(field.clone());
+            }
+            Expr::PredicateAccessPredicate(ast::PredicateAccessPredicate {
+                argument:
+                    box Expr::Field(ast::FieldExpr {
+                        base: box Expr::Local(_),
+                        field,
+                        ..
+                    }),
+                ..
+            }) => {
+                predicate_fields.push(field.clone());
+            }
+            _ => return None,
+        }
+    }
+    if fields.is_empty() || fields != predicate_fields {
+        return None;
+    }
+    Some(fields)
+}
+
+/// The Viper variable that replaces the field `field` of the purified struct
+/// variable `variable`.
+fn field_variable(variable: &LocalVar, field: &Field) -> LocalVar {
+    LocalVar::new(
+        format!("{}${}", variable.name, field.name),
+        translate_type(&field.typ),
+    )
+}
+
 #[tracing::instrument(level = "debug", skip(method, predicates))]
 fn purify_method(method: &mut cfg::CfgMethod, predicates: &[ast::Predicate]) {
     let mut candidates = FxHashSet::default();
+    let mut struct_candidates = FxHashMap::default();
     for var in &method.local_vars {
         match &var.typ {
             Type::TypedRef(..) if SUPPORTED_TYPES.contains(&var.typ.name().as_str()) => {
                 candidates.insert(var.name.clone());
             }
+            Type::TypedRef(..) => {
+                if let Some(fields) = purifiable_struct_fields(&var.typ, predicates) {
+                    struct_candidates.insert(var.name.clone(), fields);
+                }
+            }
             _ => {}
         };
     }
     let mut collector = PurifiableVariableCollector::new(candidates);
+    let mut struct_collector = PurifiableStructCollector {
+        vars: struct_candidates,
+    };
 
     debug!(
         "PurifiableVariableCollector for method {} before filtering {:?}",
@@ -56,18 +135,31 @@ fn purify_method(method: &mut cfg::CfgMethod, predicates: &[ast::Predicate]) {
         collector
     );
     walk_method(method, &mut collector);
+    walk_method(method, &mut struct_collector);
     debug!(
         "PurifiableVariableCollector for method {} after filterng {:?}",
         method.name(),
         collector
     );
+    debug!(
+        "Purifiable struct variables of method {}: {:?}",
+        method.name(),
+        struct_collector.vars
+    );
 
-    for var in &mut method.local_vars {
+    let mut local_vars = Vec::new();
+    for mut var in std::mem::take(&mut method.local_vars) {
         if collector.vars.contains(&var.name) {
             var.typ = translate_type(&var.typ);
+            local_vars.push(var);
+        } else if let Some(fields) = struct_collector.vars.get(&var.name) {
+            local_vars.extend(fields.iter().map(|field| field_variable(&var, field)));
+        } else {
+            local_vars.push(var);
         }
     }
-    let mut p = Purifier::new(collector, predicates);
+    method.local_vars = local_vars;
+    let mut p = Purifier::new(collector, struct_collector, predicates);
 
     for block in &mut method.basic_blocks {
         let stmts = std::mem::take(&mut block.stmts);
@@ -77,22 +169,17 @@ fn purify_method(method: &mut cfg::CfgMethod, predicates: &[ast::Predicate]) {
                 p.purified_predicates = Some(BTreeSet::new());
                 inhale.expr = ast::ExprFolder::fold(&mut p, inhale.expr);
                 block.stmts.push(Stmt::Inhale(inhale));
-                for variable in p.purified_predicates.take().unwrap() {
-                    block.stmts.push(generate_havoc(variable.clone()));
-                    let predicate_type = variable.typ.clone();
-                    if let Some(predicate) = p.find_predicate(&predicate_type) {
-                        let purified_predicate = predicate
-                            .clone()
-                            .replace_place(
-                                &LocalVar::new("self", predicate_type).into(),
-                                &variable.clone().into(),
-                            )
-                            .purify();
+                for place in p.purified_predicates.take().unwrap() {
+                    block.stmts.extend(p.generate_havocs(&place));
+                    if let Some(purified_predicate) = p.purified_predicate(&place) {
                         block.stmts.push(Stmt::Inhale(ast::Inhale {
                             expr: ast::ExprFolder::fold(&mut p, purified_predicate),
                         }));
                     }
                 }
+            } else if let Some(variable) = p.havocked_struct(&stmt) {
+                let havocs = p.generate_havocs(&variable.into());
+                block.stmts.extend(havocs);
             } else {
                 block.stmts.push(ast::StmtFolder::fold(&mut p, stmt));
             };
@@ -139,19 +226,152 @@ impl ast::StmtWalker for PurifiableVariableCollector {
     }
 }
 
+/// Collects the struct variables that can be purified. Unlike a variable of
+/// a primitive type, a struct variable can be purified only if all of its
+/// mentions can be replaced: the permissions to the struct and to its
+/// fields, and the values of its fields. The struct and its fields must
+/// never be assigned, moved or borrowed as a whole.
+#[derive(Debug)]
+struct PurifiableStructCollector {
+    vars: FxHashMap<String, Vec<Field>>,
+}
+
+impl PurifiableStructCollector {
+    fn remove(&mut self, variable: &LocalVar) {
+        if self.vars.remove(&variable.name).is_some() {
+            debug!("Will not purify the struct variable {:?}", variable)
+        }
+    }
+
+    /// Whether `place` is a purifiable struct variable or one of its fields.
+    fn is_purifiable_place(&self, place: &Expr) -> bool {
+        match place {
+            Expr::Local(ast::Local { variable, .. })
+            | Expr::Field(ast::FieldExpr {
+                base: box Expr::Local(ast::Local { variable, .. }),
+                ..
+            }) => self.vars.contains_key(&variable.name),
+            _ => false,
+        }
+    }
+}
+
+impl ast::ExprWalker for PurifiableStructCollector {
+    fn walk_local(&mut self, ast::Local { variable, .. }: &ast::Local) {
+        self.remove(variable);
+    }
+
+    fn walk_field(&mut self, ast::FieldExpr { box base, .. }: &ast::FieldExpr) {
+        // The value of a field is replaced by a variable, but a field that
+        // is used as a reference cannot be replaced.
+        match base {
+            Expr::Field(ast::FieldExpr {
+                base: box Expr::Local(ast::Local { variable, .. }),
+                ..
+            }) if self.vars.contains_key(&variable.name) => {}
+            Expr::Local(ast::Local { variable, .. }) => self.remove(variable),
+            _ => ast::ExprWalker::walk(self, base),
+        }
+    }
+
+    fn walk_field_access_predicate(
+        &mut self,
+        ast::FieldAccessPredicate { box base, .. }: &ast::FieldAccessPredicate,
+    ) {
+        if !self.is_purifiable_place(base) {
+            ast::ExprWalker::walk(self, base);
+        }
+    }
+
+    fn walk_predicate_access_predicate(
+        &mut self,
+        ast::PredicateAccessPredicate { box argument, .. }: &ast::PredicateAccessPredicate,
+    ) {
+        if !self.is_purifiable_place(argument) {
+            ast::ExprWalker::walk(self, argument);
+        }
+    }
+
+    fn walk_unfolding(
+        &mut self,
+        ast::Unfolding {
+            arguments, base, ..
+        }: &ast::Unfolding,
+    ) {
+        if !matches!(arguments.as_slice(), [place] if self.is_purifiable_place(place)) {
+            for argument in arguments {
+                ast::ExprWalker::walk(self, argument);
+            }
+        }
+        ast::ExprWalker::walk(self, base);
+    }
+}
+
+impl ast::StmtWalker for PurifiableStructCollector {
+    fn walk_expr(&mut self, expr: &Expr) {
+        ast::ExprWalker::walk(self, expr);
+    }
+
+    fn walk_local_var(&mut self, local_var: &LocalVar) {
+        self.remove(local_var);
+    }
+
+    fn walk_assign(&mut self, ast::Assign { target, source, .. }: &ast::Assign) {
+        if self.is_purifiable_place(target) {
+            self.remove(&target.get_base());
+        }
+        self.walk_expr(target);
+        self.walk_expr(source);
+    }
+
+    fn walk_method_call(&mut self, method_call: &ast::MethodCall) {
+        for argument in &method_call.arguments {
+            self.walk_expr(argument);
+        }
+        if !method_call.method_name.starts_with("builtin$havoc") {
+            for target in &method_call.targets {
+                self.remove(target);
+            }
+        }
+    }
+
+    fn walk_fold(&mut self, ast::Fold { arguments, .. }: &ast::Fold) {
+        if !matches!(arguments.as_slice(), [place] if self.is_purifiable_place(place)) {
+            for argument in arguments {
+                self.walk_expr(argument);
+            }
+        }
+    }
+
+    fn walk_unfold(&mut self, ast::Unfold { arguments, .. }: &ast::Unfold) {
+        if !matches!(arguments.as_slice(), [place] if self.is_purifiable_place(place)) {
+            for argument in arguments {
+                self.walk_expr(argument);
+            }
+        }
+    }
+}
+
 /// StmtFolder and ExprFolder used to purify local variables
 #[derive(Debug)]
 struct Purifier<'a> {
     /// names of local variables that can be purified
     targets: BTreeSet<String>,
+    /// names of struct variables that can be purified, with their fields
+    struct_targets: BTreeMap<String, Vec<Field>>,
     /// Viper predicates.
     predicates: &'a [ast::Predicate],
-    /// Purified PredicateAccessPredicates within an expression.
-    purified_predicates: Option<BTreeSet<LocalVar>>,
+    /// The places of the purified PredicateAccessPredicates within an
+    /// expression.
+    purified_predicates: Option<BTreeSet<Expr>>,
 }
 
 impl<'a> Purifier<'a> {
-    fn new(c: PurifiableVariableCollector, predicates: &'a [ast::Predicate]) -> Self {
+    fn new(
+        c: PurifiableVariableCollector,
+        s: PurifiableStructCollector,
+        predicates: &'a [ast::Predicate],
+    ) -> Self {
         let mut targets = BTreeSet::new();
         for var in c.vars {
             targets.insert(var);
@@ -159,10 +379,86 @@ impl<'a> Purifier<'a> {
 
         Purifier {
             targets,
+            struct_targets: s.vars.into_iter().collect(),
             predicates,
             purified_predicates: None,
         }
     }
+
+    /// Whether the permissions of `place` are purified: `place` is a
+    /// purified variable, or a purified struct variable or one of its
+    /// fields.
+    fn is_purified_place(&self, place: &Expr) -> bool {
+        match place {
+            Expr::Local(ast::Local { variable, .. }) => {
+                self.targets.contains(&variable.name)
+                    || self.struct_targets.contains_key(&variable.name)
+            }
+            Expr::Field(ast::FieldExpr {
+                base: box Expr::Local(ast::Local { variable, .. }),
+                ..
+            }) => self.struct_targets.contains_key(&variable.name),
+            _ => false,
+        }
+    }
+
+    /// The purified body of the predicate of `place`. The body of a struct
+    /// predicate also contains the purified bodies of its fields.
+    fn purified_predicate(&self, place: &Expr) -> Option<Expr> {
+        let predicate_type = place.get_type();
+        let predicate = self.find_predicate(predicate_type)?;
+        let mut purified_predicate = predicate
+            .clone()
+            .replace_place(&LocalVar::new("self", predicate_type.clone()).into(), place)
+            .purify();
+        if let Expr::Local(ast::Local { variable, .. }) = place {
+            if let Some(fields) = self.struct_targets.get(&variable.name) {
+                for field in fields {
+                    let field_place = place.clone().field(field.clone());
+                    if let Some(field_predicate) = self.purified_predicate(&field_place) {
+                        purified_predicate = Expr::and(purified_predicate, field_predicate);
+                    }
+                }
+            }
+        }
+        Some(purified_predicate)
+    }
+
+    /// The statements that havoc the variables that replace `place`.
+    fn generate_havocs(&self, place: &Expr) -> Vec<Stmt> {
+        match place {
+            Expr::Local(ast::Local { variable, .. }) => {
+                match self.struct_targets.get(&variable.name) {
+                    Some(fields) => fields
+                        .iter()
+                        .map(|field| generate_havoc(field_variable(variable, field), &field.typ))
+                        .collect(),
+                    None => vec![generate_havoc(variable.clone(), &variable.typ)],
+                }
+            }
+            Expr::Field(ast::FieldExpr {
+                base: box Expr::Local(ast::Local { variable, .. }),
+                field,
+                ..
+            }) => vec![generate_havoc(field_variable(variable, field), &field.typ)],
+            _ => unreachable!("{}", place),
+        }
+    }
+
+    /// The purified struct variable that is havocked by `stmt`, if any.
+    fn havocked_struct(&self, stmt: &Stmt) -> Option<LocalVar> {
+        match stmt {
+            Stmt::MethodCall(ast::MethodCall {
+                method_name,
+                targets,
+                ..
+            }) if method_name.starts_with("builtin$havoc") => match targets.as_slice() {
+                [target] if self.struct_targets.contains_key(&target.name) => Some(target.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
     /// Get the body of the struct predicate. If the predicate does not exist,
     /// or is a non-struct predicate, returns `None`.
     fn find_predicate(&self, predicate_type: &Type) -> Option<&Expr> {
@@ -235,7 +531,9 @@ impl<'a> ast::StmtFolder for Purifier<'a> {
             && targets.len() == 1
             && self.targets.contains(&targets[0].name)
         {
-            generate_havoc(targets.pop().unwrap())
+            let target = targets.pop().unwrap();
+            let typ = target.typ.clone();
+            generate_havoc(target, &typ)
         } else {
             Stmt::MethodCall(ast::MethodCall {
                 method_name,
@@ -255,16 +553,9 @@ impl<'a> ast::StmtFolder for Purifier<'a> {
             position,
         }: ast::Fold,
     ) -> Stmt {
-        if let [Expr::Local(ast::Local { variable: l, .. })] = arguments.as_slice() {
-            if self.targets.contains(&l.name) {
-                if let Some(predicate) = self.find_predicate(&predicate_type) {
-                    let purified_predicate = predicate
-                        .clone()
-                        .replace_place(
-                            &LocalVar::new("self", predicate_type).into(),
-                            &l.clone().into(),
-                        )
-                        .purify();
+        if let [place] = arguments.as_slice() {
+            if self.is_purified_place(place) {
+                if let Some(purified_predicate) = self.purified_predicate(place) {
                     return Stmt::Assert(ast::Assert {
                         expr: self.fold_expr(purified_predicate),
                         position,
@@ -293,16 +584,9 @@ impl<'a> ast::StmtFolder for Purifier<'a> {
             enum_variant,
         }: ast::Unfold,
     ) -> Stmt {
-        if let [Expr::Local(ast::Local { variable: l, .. })] = arguments.as_slice() {
-            if self.targets.contains(&l.name) {
-                if let Some(predicate) = self.find_predicate(&predicate_type) {
-                    let purified_predicate = predicate
-                        .clone()
-                        .replace_place(
-                            &LocalVar::new("self", predicate_type).into(),
-                            &l.clone().into(),
-                        )
-                        .purify();
+        if let [place] = arguments.as_slice() {
+            if self.is_purified_place(place) {
+                if let Some(purified_predicate) = self.purified_predicate(place) {
                     return Stmt::Inhale(ast::Inhale {
                         expr: self.fold_expr(purified_predicate),
                     });
@@ -349,7 +633,23 @@ impl<'a> ast::ExprFolder for Purifier<'a> {
         }) = *rec.clone()
         {
             if self.targets.contains(&l.name) {
-                return Expr::local_with_pos(LocalVar::new(l.name, translate_type(&l.typ)), lp);
+                // Prefer the position of the whole field access.
+                let position = if position.is_default() { lp } else { position };
+                return Expr::local_with_pos(
+                    LocalVar::new(l.name, translate_type(&l.typ)),
+                    position,
+                );
+            }
+        }
+        if let Expr::Field(ast::FieldExpr {
+            base: box Expr::Local(ast::Local { variable: l, .. }),
+            field: struct_field,
+            position: fp,
+        }) = &*rec
+        {
+            if self.struct_targets.contains_key(&l.name) {
+                let position = if position.is_default() { *fp } else { position };
+                return Expr::local_with_pos(field_variable(l, struct_field), position);
             }
         }
 
@@ -369,16 +669,11 @@ impl<'a> ast::ExprFolder for Purifier<'a> {
             position,
         }: ast::PredicateAccessPredicate,
     ) -> Expr {
-        if let Expr::Local(ast::Local {
-            variable: local, ..
-        }) = *argument.clone()
-        {
-            if self.targets.contains(&local.name) {
-                if let Some(purified_predicates) = &mut self.purified_predicates {
-                    purified_predicates.insert(local);
-                }
-                return true.into();
+        if self.is_purified_place(&argument) {
+            if let Some(purified_predicates) = &mut self.purified_predicates {
+                purified_predicates.insert(*argument);
             }
+            return Expr::from(true).set_pos(position);
         }
 
         Expr::PredicateAccessPredicate(ast::PredicateAccessPredicate {
@@ -397,11 +692,9 @@ impl<'a> ast::ExprFolder for Purifier<'a> {
             position,
         }: ast::FieldAccessPredicate,
     ) -> Expr {
-        if let Expr::Field(ast::FieldExpr { base, .. }) = *receiver.clone() {
-            if let Expr::Local(ast::Local { variable: l, .. }) = *base {
-                if self.targets.contains(&l.name) {
-                    return true.into();
-                }
+        if let Expr::Field(ast::FieldExpr { base, .. }) = &*receiver {
+            if self.is_purified_place(base) {
+                return Expr::from(true).set_pos(position);
             }
         }
 
@@ -423,12 +716,9 @@ impl<'a> ast::ExprFolder for Purifier<'a> {
             position,
         }: ast::Unfolding,
     ) -> Expr {
-        if let [Expr::Local(ast::Local {
-            variable: local, ..
-        })] = arguments.as_slice()
-        {
-            if self.targets.contains(&local.name) {
-                return ast::ExprFolder::fold(self, *base);
+        if let [place] = arguments.as_slice() {
+            if self.is_purified_place(place) {
+                return ast::ExprFolder::fold(self, *base).set_pos_if_default(position);
             }
         }
 
@@ -461,14 +751,15 @@ impl<'a> ast::ExprFolder for Purifier<'a> {
                 position,
             })
         } else {
-            *folded_body
+            (*folded_body).set_pos_if_default(position)
         }
     }
 }
 
-fn generate_havoc(variable: LocalVar) -> Stmt {
-    assert!(matches!(variable.typ, Type::TypedRef(..)));
-    let target_name = variable.typ.name();
+/// Havoc the purified `variable`, whose type was `typ` before purification.
+fn generate_havoc(variable: LocalVar, typ: &Type) -> Stmt {
+    assert!(matches!(typ, Type::TypedRef(..)));
+    let target_name = typ.name();
     match target_name.as_str() {
         "bool" => Stmt::MethodCall(ast::MethodCall {
             method_name: "builtin$havoc_bool".to_string(),
@@ -483,3 +774,89 @@ fn generate_havoc(variable: LocalVar) -> Stmt {
         x => unreachable!("{}", x),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vir::polymorphic_vir::PermAmount;
+
+    #[test]
+    fn struct_variables_are_split_into_field_variables() {
+        let int = Type::typed_ref("i32");
+        let point = Type::typed_ref("Point");
+        let x = Field::new("f$x", int.clone());
+        let y = Field::new("f$y", int.clone());
+        let val_int = Field::new("val_int", Type::Int);
+        let int_self: Expr = LocalVar::new("self", int.clone()).into();
+        let predicates = vec![
+            ast::Predicate::Struct(ast::StructPredicate {
+                typ: int.clone(),
+                this: LocalVar::new("self", int.clone()),
+                body: Some(Expr::acc_permission(
+                    int_self.field(val_int.clone()),
+                    PermAmount::Write,
+                )),
+            }),
+            ast::Predicate::Struct(ast::StructPredicate::new(
+                point.clone(),
+                vec![x.clone(), y.clone()],
+            )),
+        ];
+        let s = LocalVar::new("s", point.clone());
+        let s_x = Expr::from(s.clone()).field(x);
+        let s_x_value = s_x.clone().field(val_int);
+        let unfold = |predicate: &Type, place: Expr| {
+            Stmt::Unfold(ast::Unfold {
+                predicate: predicate.clone(),
+                arguments: vec![place],
+                permission: PermAmount::Write,
+                enum_variant: None,
+            })
+        };
+        let mut method = cfg::CfgMethod::new("m".to_string(), vec![], vec![s.clone()], vec![]);
+        method.add_block(
+            "start",
+            vec![
+                Stmt::MethodCall(ast::MethodCall {
+                    method_name: "builtin$havoc_ref".to_string(),
+                    arguments: vec![],
+                    targets: vec![s.clone()],
+                }),
+                Stmt::Inhale(ast::Inhale {
+                    expr: Expr::predicate_access_predicate(
+                        point.clone(),
+                        s.clone().into(),
+                        PermAmount::Write,
+                    ),
+                }),
+                unfold(&point, s.into()),
+                unfold(&int, s_x.clone()),
+                Stmt::Assign(ast::Assign {
+                    target: s_x_value.clone(),
+                    source: 5.into(),
+                    kind: ast::AssignKind::Copy,
+                }),
+            ],
+        );
+
+        let methods = purify_methods(vec![method], &predicates);
+
+        let x_variable = LocalVar::new("s$f$x", Type::Int);
+        assert_eq!(
+            methods[0].local_vars,
+            vec![x_variable.clone(), LocalVar::new("s$f$y", Type::Int)]
+        );
+        let stmts = &methods[0].basic_blocks[0].stmts;
+        assert!(stmts
+            .iter()
+            .all(|stmt| !matches!(stmt, Stmt::Unfold(_) | Stmt::Fold(_))));
+        assert_eq!(
+            stmts.last().unwrap(),
+            &Stmt::Assign(ast::Assign {
+                target: x_variable.into(),
+                source: 5.into(),
+                kind: ast::AssignKind::Copy,
+            })
+        );
+    }
+}
@@ -29,6 +29,24 @@ impl Program {
     pub fn get_name_with_check_mode(&self) -> String {
         format!("{}-{}", self.get_name(), self.get_check_mode())
     }
+    /// The name of the file, without extension, to which the program is
+    /// exported under `name`, e.g. `module::function`. Programs that check
+    /// only the core proof or only the specifications get their check mode as
+    /// a suffix.
+    pub fn get_export_file_name(&self, name: &str) -> String {
+        let mut file_name: String = name
+            .chars()
+            .map(|c| match c {
+                '/' | '\\' => '-',
+                ':' | '*' | '?' | '"' | '<' | '>' | '|' if cfg!(windows) => '-',
+                _ => c,
+            })
+            .collect();
+        if self.get_check_mode() != vir::common::check_mode::CheckMode::Both {
+            file_name.push_str(&format!("-{}", self.get_check_mode()));
+        }
+        file_name
+    }
 }
 
 impl<'v> ToViper<'v, viper::Program<'v>> for Program {
@@ -39,3 +57,40 @@ impl<'v> ToViper<'v, viper::Program<'v>> for Program {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vir::common::check_mode::CheckMode;
+
+    fn low_program(check_mode: CheckMode) -> Program {
+        Program::Low(vir::low::Program {
+            name: "m_test".to_string(),
+            check_mode,
+            domains: vec![],
+            predicates: vec![],
+            functions: vec![],
+            methods: vec![],
+            procedures: vec![],
+        })
+    }
+
+    #[test]
+    fn exported_programs_are_read_back() {
+        let program = low_program(CheckMode::CoreProof);
+        let json = serde_json::to_string(&program).unwrap();
+        assert_eq!(serde_json::from_str::<Program>(&json).unwrap(), program);
+    }
+
+    #[test]
+    fn export_file_names_contain_the_check_mode() {
+        assert_eq!(
+            low_program(CheckMode::Both).get_export_file_name("test"),
+            "test"
+        );
+        assert_eq!(
+            low_program(CheckMode::Specifications).get_export_file_name("a/b"),
+            "a-b-Specifications"
+        );
+    }
+}
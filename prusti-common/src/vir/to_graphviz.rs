@@ -19,6 +19,9 @@ pub trait ToGraphViz {
 
     fn to_graphviz_with_extra<F: Fn(usize) -> Vec<String>>(&self, graph: &mut dyn Write, extra: F);
 
+    /// Writes all reborrowing DAGs of the method, one cluster per DAG.
+    fn reborrowing_dags_to_graphviz(&self, graph: &mut dyn Write);
+
     fn index_to_label(&self, index: usize) -> String;
 
     fn block_to_graphviz<'b>(
@@ -61,52 +64,7 @@ impl ToGraphViz for CfgMethod {
 
             if config::dump_reborrowing_dag_in_debug_info() {
                 for dag in reborrowing_dags {
-                    writeln!(graph, "subgraph cluster_{label} {{").unwrap();
-                    writeln!(graph, "   label=\"Reborrowing DAG {label}\"").unwrap();
-                    for node in dag.iter() {
-                        writeln!(
-                            graph,
-                            "dag_{}_node_{:?} [shape=none,label=<",
-                            label, node.borrow
-                        )
-                        .unwrap();
-                        writeln!(graph, "<table>").unwrap();
-                        writeln!(
-                            graph,
-                            "<tr><td colspan=\"2\">{:?} (guard: {})</td></tr>",
-                            node,
-                            escape_html(&dag.guard(node.borrow))
-                        )
-                        .unwrap();
-                        for (i, stmt) in node.stmts.iter().enumerate() {
-                            writeln!(
-                                graph,
-                                "<tr><td>{}</td><td>{}</td></tr>",
-                                i,
-                                escape_html(stmt)
-                            )
-                            .unwrap();
-                        }
-                        writeln!(graph, "</table>").unwrap();
-                        writeln!(graph, ">];").unwrap();
-                        for r_node in &node.reborrowing_nodes {
-                            writeln!(
-                                graph,
-                                "\"dag_{}_node_{:?}\" -> \"dag_{}_node_{:?}\";",
-                                label, r_node, label, node.borrow
-                            )
-                            .unwrap();
-                        }
-                        for r_node in &node.reborrowed_nodes {
-                            writeln!(
-                                graph,
-                                "\"dag_{}_node_{:?}\" -> \"dag_{}_node_{:?}\";",
-                                label, node.borrow, label, r_node
-                            )
-                            .unwrap();
-                        }
-                    }
-                    writeln!(graph, "}}").unwrap();
+                    reborrowing_dag_to_graphviz(graph, &label, dag);
 
                     let node = dag.iter().next().unwrap();
                     writeln!(
@@ -136,6 +94,27 @@ impl ToGraphViz for CfgMethod {
         writeln!(graph, "}}").unwrap();
     }
 
+    fn reborrowing_dags_to_graphviz(&self, graph: &mut dyn Write) {
+        writeln!(graph, "digraph ReborrowingDAGs {{").unwrap();
+        writeln!(graph, "graph [fontname=monospace];").unwrap();
+        writeln!(graph, "node [fontname=monospace];").unwrap();
+        writeln!(graph, "edge [fontname=monospace];").unwrap();
+        writeln!(graph, "labelloc=\"t\";").unwrap();
+        writeln!(
+            graph,
+            "label=\"Reborrowing DAGs of method {}\";",
+            escape_html(self.name())
+        )
+        .unwrap();
+        for (index, block) in self.basic_blocks.iter().enumerate() {
+            let block_label = self.index_to_label(index);
+            for (dag_index, dag) in block_reborrowing_dags(block).into_iter().enumerate() {
+                reborrowing_dag_to_graphviz(graph, &format!("{block_label}_{dag_index}"), dag);
+            }
+        }
+        writeln!(graph, "}}").unwrap();
+    }
+
     fn index_to_label(&self, index: usize) -> String {
         self.basic_blocks_labels()[index].clone()
     }
@@ -148,7 +127,7 @@ impl ToGraphViz for CfgMethod {
         header_rows: &[String],
         footer_rows: &[String],
     ) -> (String, Vec<&'b vir::borrows::DAG>) {
-        let mut reborrowing_dags = Vec::new();
+        let reborrowing_dags = block_reborrowing_dags(block);
         let mut lines: Vec<String> = vec![];
         lines.push("<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">".to_string());
 
@@ -171,22 +150,6 @@ impl ToGraphViz for CfgMethod {
                 lines.push("<br/>".to_string());
             }
             first_row = false;
-            match stmt {
-                vir::Stmt::ExpireBorrows(vir::ExpireBorrows { ref dag }) => {
-                    reborrowing_dags.push(dag);
-                }
-                vir::Stmt::PackageMagicWand(vir::PackageMagicWand {
-                    package_stmts: ref stmts,
-                    ..
-                }) => {
-                    for stmt in stmts {
-                        if let vir::Stmt::ExpireBorrows(vir::ExpireBorrows { ref dag }) = stmt {
-                            reborrowing_dags.push(dag);
-                        }
-                    }
-                }
-                _ => {}
-            }
             let stmt_string = stmt.to_string();
             let mut splitted_stmt_lines = vec![];
             for stmt_line in stmt_string.lines() {
@@ -245,6 +208,81 @@ impl ToGraphViz for CfgMethod {
     }
 }
 
+/// The reborrowing DAGs expired in `block`, including those expired while
+/// packaging a magic wand.
+fn block_reborrowing_dags(block: &CfgBlock) -> Vec<&vir::borrows::DAG> {
+    let mut reborrowing_dags = Vec::new();
+    for stmt in &block.stmts {
+        match stmt {
+            vir::Stmt::ExpireBorrows(vir::ExpireBorrows { ref dag }) => {
+                reborrowing_dags.push(dag);
+            }
+            vir::Stmt::PackageMagicWand(vir::PackageMagicWand {
+                package_stmts: ref stmts,
+                ..
+            }) => {
+                for stmt in stmts {
+                    if let vir::Stmt::ExpireBorrows(vir::ExpireBorrows { ref dag }) = stmt {
+                        reborrowing_dags.push(dag);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    reborrowing_dags
+}
+
+/// Writes `dag` as a cluster named `cluster_<label>`.
+fn reborrowing_dag_to_graphviz(graph: &mut dyn Write, label: &str, dag: &vir::borrows::DAG) {
+    writeln!(graph, "subgraph cluster_{label} {{").unwrap();
+    writeln!(graph, "   label=\"Reborrowing DAG {label}\"").unwrap();
+    for node in dag.iter() {
+        writeln!(
+            graph,
+            "dag_{}_node_{:?} [shape=none,label=<",
+            label, node.borrow
+        )
+        .unwrap();
+        writeln!(graph, "<table>").unwrap();
+        writeln!(
+            graph,
+            "<tr><td colspan=\"2\">{:?} (guard: {})</td></tr>",
+            node,
+            escape_html(&dag.guard(node.borrow))
+        )
+        .unwrap();
+        for (i, stmt) in node.stmts.iter().enumerate() {
+            writeln!(
+                graph,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                i,
+                escape_html(stmt)
+            )
+            .unwrap();
+        }
+        writeln!(graph, "</table>").unwrap();
+        writeln!(graph, ">];").unwrap();
+        for r_node in &node.reborrowing_nodes {
+            writeln!(
+                graph,
+                "\"dag_{}_node_{:?}\" -> \"dag_{}_node_{:?}\";",
+                label, r_node, label, node.borrow
+            )
+            .unwrap();
+        }
+        for r_node in &node.reborrowed_nodes {
+            writeln!(
+                graph,
+                "\"dag_{}_node_{:?}\" -> \"dag_{}_node_{:?}\";",
+                label, node.borrow, label, r_node
+            )
+            .unwrap();
+        }
+    }
+    writeln!(graph, "}}").unwrap();
+}
+
 fn sub_strings(string: &str, first_sub_len: usize, sub_len: usize) -> Vec<&str> {
     let mut subs = Vec::with_capacity(string.len() / sub_len);
     let mut iter = string.chars();
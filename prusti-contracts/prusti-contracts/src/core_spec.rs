@@ -16,3 +16,281 @@ impl<T, E: ::core::fmt::Debug> ::core::result::Result<T, E> {
     #[requires(matches!(self, Ok(_)))]
     fn unwrap(self) -> T;
 }
+
+// The contracts of the arithmetic methods of the integer types. The
+// conditions are written such that the arithmetic in the specifications
+// cannot overflow.
+
+macro_rules! unsigned_arithmetic_specs {
+    ($($t:ident),*) => {$(
+        #[extern_spec]
+        impl $t {
+            #[pure]
+            #[ensures(match result {
+                Some(r) => rhs <= $t::MAX - self && r == self + rhs,
+                None => rhs > $t::MAX - self,
+            })]
+            fn checked_add(self, rhs: $t) -> Option<$t>;
+
+            #[pure]
+            #[ensures(match result {
+                Some(r) => rhs <= self && r == self - rhs,
+                None => rhs > self,
+            })]
+            fn checked_sub(self, rhs: $t) -> Option<$t>;
+
+            #[pure]
+            #[ensures(match result {
+                Some(r) => (self == 0 || rhs <= $t::MAX / self) && r == self * rhs,
+                None => self != 0 && rhs > $t::MAX / self,
+            })]
+            fn checked_mul(self, rhs: $t) -> Option<$t>;
+
+            #[pure]
+            #[ensures(rhs <= $t::MAX - self ==> result == self + rhs)]
+            #[ensures(rhs > $t::MAX - self ==> result == self - ($t::MAX - rhs) - 1)]
+            fn wrapping_add(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(rhs <= self ==> result == self - rhs)]
+            #[ensures(rhs > self ==> result == $t::MAX - (rhs - self) + 1)]
+            fn wrapping_sub(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(self == 0 || rhs <= $t::MAX / self ==> result == self * rhs)]
+            fn wrapping_mul(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(rhs <= $t::MAX - self ==> result == self + rhs)]
+            #[ensures(rhs > $t::MAX - self ==> result == $t::MAX)]
+            fn saturating_add(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(rhs <= self ==> result == self - rhs)]
+            #[ensures(rhs > self ==> result == 0)]
+            fn saturating_sub(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(self == 0 || rhs <= $t::MAX / self ==> result == self * rhs)]
+            #[ensures(self != 0 && rhs > $t::MAX / self ==> result == $t::MAX)]
+            fn saturating_mul(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(result.0 == self.wrapping_add(rhs))]
+            #[ensures(result.1 == (rhs > $t::MAX - self))]
+            fn overflowing_add(self, rhs: $t) -> ($t, bool);
+
+            #[pure]
+            #[ensures(result.0 == self.wrapping_sub(rhs))]
+            #[ensures(result.1 == (rhs > self))]
+            fn overflowing_sub(self, rhs: $t) -> ($t, bool);
+        }
+    )*};
+}
+
+unsigned_arithmetic_specs!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! signed_arithmetic_specs {
+    ($($t:ident),*) => {$(
+        #[extern_spec]
+        impl $t {
+            #[pure]
+            #[ensures(match result {
+                Some(r) => !(rhs > 0 && self > $t::MAX - rhs)
+                    && !(rhs < 0 && self < $t::MIN - rhs)
+                    && r == self + rhs,
+                None => (rhs > 0 && self > $t::MAX - rhs) || (rhs < 0 && self < $t::MIN - rhs),
+            })]
+            fn checked_add(self, rhs: $t) -> Option<$t>;
+
+            #[pure]
+            #[ensures(match result {
+                Some(r) => !(rhs < 0 && self > $t::MAX + rhs)
+                    && !(rhs > 0 && self < $t::MIN + rhs)
+                    && r == self - rhs,
+                None => (rhs < 0 && self > $t::MAX + rhs) || (rhs > 0 && self < $t::MIN + rhs),
+            })]
+            fn checked_sub(self, rhs: $t) -> Option<$t>;
+
+            #[pure]
+            #[ensures(match result {
+                Some(r) => r == self * rhs,
+                None => true,
+            })]
+            fn checked_mul(self, rhs: $t) -> Option<$t>;
+
+            #[pure]
+            #[ensures(rhs > 0 && self > $t::MAX - rhs ==> result == self - ($t::MAX - rhs) - 1 + $t::MIN)]
+            #[ensures(rhs < 0 && self < $t::MIN - rhs ==> result == self - ($t::MIN - rhs) + 1 + $t::MAX)]
+            #[ensures(!(rhs > 0 && self > $t::MAX - rhs) && !(rhs < 0 && self < $t::MIN - rhs)
+                ==> result == self + rhs)]
+            fn wrapping_add(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(rhs < 0 && self > $t::MAX + rhs ==> result == self - ($t::MAX + rhs) - 1 + $t::MIN)]
+            #[ensures(rhs > 0 && self < $t::MIN + rhs ==> result == self - ($t::MIN + rhs) + 1 + $t::MAX)]
+            #[ensures(!(rhs < 0 && self > $t::MAX + rhs) && !(rhs > 0 && self < $t::MIN + rhs)
+                ==> result == self - rhs)]
+            fn wrapping_sub(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(rhs > 0 && self > $t::MAX - rhs ==> result == $t::MAX)]
+            #[ensures(rhs < 0 && self < $t::MIN - rhs ==> result == $t::MIN)]
+            #[ensures(!(rhs > 0 && self > $t::MAX - rhs) && !(rhs < 0 && self < $t::MIN - rhs)
+                ==> result == self + rhs)]
+            fn saturating_add(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(rhs < 0 && self > $t::MAX + rhs ==> result == $t::MAX)]
+            #[ensures(rhs > 0 && self < $t::MIN + rhs ==> result == $t::MIN)]
+            #[ensures(!(rhs < 0 && self > $t::MAX + rhs) && !(rhs > 0 && self < $t::MIN + rhs)
+                ==> result == self - rhs)]
+            fn saturating_sub(self, rhs: $t) -> $t;
+
+            #[pure]
+            #[ensures(result.0 == self.wrapping_add(rhs))]
+            #[ensures(result.1 == ((rhs > 0 && self > $t::MAX - rhs) || (rhs < 0 && self < $t::MIN - rhs)))]
+            fn overflowing_add(self, rhs: $t) -> ($t, bool);
+
+            #[pure]
+            #[ensures(result.0 == self.wrapping_sub(rhs))]
+            #[ensures(result.1 == ((rhs < 0 && self > $t::MAX + rhs) || (rhs > 0 && self < $t::MIN + rhs)))]
+            fn overflowing_sub(self, rhs: $t) -> ($t, bool);
+        }
+    )*};
+}
+
+signed_arithmetic_specs!(i8, i16, i32, i64, i128, isize);
+
+// The contracts of the operator traits implemented by the integer types, so
+// that calls like `Add::add(a, b)` or `a.lt(&b)` have the same meaning as the
+// corresponding operators. The preconditions rule out the overflows and
+// divisions by zero that make the operators panic.
+
+macro_rules! integer_comparison_specs {
+    ($($t:ident),*) => {$(
+        #[extern_spec]
+        impl ::core::cmp::PartialOrd for $t {
+            #[pure]
+            #[ensures(result == (*self < *other))]
+            fn lt(&self, other: &$t) -> bool;
+
+            #[pure]
+            #[ensures(result == (*self <= *other))]
+            fn le(&self, other: &$t) -> bool;
+
+            #[pure]
+            #[ensures(result == (*self > *other))]
+            fn gt(&self, other: &$t) -> bool;
+
+            #[pure]
+            #[ensures(result == (*self >= *other))]
+            fn ge(&self, other: &$t) -> bool;
+        }
+    )*};
+}
+
+integer_comparison_specs!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! unsigned_operator_specs {
+    ($($t:ident),*) => {$(
+        #[extern_spec]
+        impl ::core::ops::Add for $t {
+            #[pure]
+            #[requires(rhs <= $t::MAX - self)]
+            #[ensures(result == self + rhs)]
+            fn add(self, rhs: $t) -> $t;
+        }
+
+        #[extern_spec]
+        impl ::core::ops::Sub for $t {
+            #[pure]
+            #[requires(rhs <= self)]
+            #[ensures(result == self - rhs)]
+            fn sub(self, rhs: $t) -> $t;
+        }
+
+        #[extern_spec]
+        impl ::core::ops::Mul for $t {
+            #[pure]
+            #[requires(self == 0 || rhs <= $t::MAX / self)]
+            #[ensures(result == self * rhs)]
+            fn mul(self, rhs: $t) -> $t;
+        }
+
+        #[extern_spec]
+        impl ::core::ops::Div for $t {
+            #[pure]
+            #[requires(rhs != 0)]
+            #[ensures(result == self / rhs)]
+            fn div(self, rhs: $t) -> $t;
+        }
+
+        #[extern_spec]
+        impl ::core::ops::Rem for $t {
+            #[pure]
+            #[requires(rhs != 0)]
+            #[ensures(result == self % rhs)]
+            fn rem(self, rhs: $t) -> $t;
+        }
+    )*};
+}
+
+unsigned_operator_specs!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! signed_operator_specs {
+    ($($t:ident),*) => {$(
+        #[extern_spec]
+        impl ::core::ops::Add for $t {
+            #[pure]
+            #[requires(!(rhs > 0 && self > $t::MAX - rhs) && !(rhs < 0 && self < $t::MIN - rhs))]
+            #[ensures(result == self + rhs)]
+            fn add(self, rhs: $t) -> $t;
+        }
+
+        #[extern_spec]
+        impl ::core::ops::Sub for $t {
+            #[pure]
+            #[requires(!(rhs < 0 && self > $t::MAX + rhs) && !(rhs > 0 && self < $t::MIN + rhs))]
+            #[ensures(result == self - rhs)]
+            fn sub(self, rhs: $t) -> $t;
+        }
+
+        #[extern_spec]
+        impl ::core::ops::Mul for $t {
+            #[pure]
+            #[requires(!(self > 0 && rhs > 0) || rhs <= $t::MAX / self)]
+            #[requires(!(self < 0 && rhs < 0) || rhs >= $t::MAX / self)]
+            #[requires(!(self > 0 && rhs < 0) || rhs >= $t::MIN / self)]
+            #[requires(!(self < 0 && rhs > 0) || self >= $t::MIN / rhs)]
+            #[ensures(result == self * rhs)]
+            fn mul(self, rhs: $t) -> $t;
+        }
+
+        #[extern_spec]
+        impl ::core::ops::Div for $t {
+            #[pure]
+            #[requires(rhs != 0 && !(self == $t::MIN && rhs == -1))]
+            #[ensures(result == self / rhs)]
+            fn div(self, rhs: $t) -> $t;
+        }
+
+        #[extern_spec]
+        impl ::core::ops::Rem for $t {
+            #[pure]
+            #[requires(rhs != 0 && !(self == $t::MIN && rhs == -1))]
+            #[ensures(result == self % rhs)]
+            fn rem(self, rhs: $t) -> $t;
+        }
+
+        #[extern_spec]
+        impl ::core::ops::Neg for $t {
+            #[pure]
+            #[requires(self != $t::MIN)]
+            #[ensures(result == -self)]
+            fn neg(self) -> $t;
+        }
+    )*};
+}
+
+signed_operator_specs!(i8, i16, i32, i64, i128, isize);
@@ -66,6 +66,21 @@ pub use prusti_contracts_proc_macros::terminates;
 /// A macro to annotate body variant of a loop to prove termination
 pub use prusti_contracts_proc_macros::body_variant;
 
+/// A macro for defining named sets of specifications that can be attached
+/// to many functions with `#[specs_use]`.
+pub use prusti_contracts_proc_macros::spec_set;
+
+/// A macro for attaching the specifications of one or more sets defined
+/// with `spec_set!` to a function.
+pub use prusti_contracts_proc_macros::specs_use;
+
+#[doc(hidden)]
+pub use prusti_contracts_proc_macros::spec_set_apply;
+
+/// A macro for marking a type as a resource that must be consumed instead of
+/// being dropped, such as a file or a transaction.
+pub use prusti_contracts_proc_macros::must_consume;
+
 #[cfg(not(feature = "prusti"))]
 mod private {
     use core::marker::PhantomData;
@@ -119,6 +134,22 @@ mod private {
     pub struct Ghost<T> {
         _phantom: PhantomData<T>,
     }
+
+    /// An amount of an abstract resource that must be spent. It has no size
+    /// when Prusti is not running.
+    pub struct Credits(());
+
+    impl Credits {
+        pub fn new(_amount: usize) -> Self {
+            Credits(())
+        }
+        pub fn spend(&mut self, _amount: usize) {}
+        pub fn split(&mut self, _amount: usize) -> Self {
+            Credits(())
+        }
+        pub fn join(&mut self, _other: Self) {}
+        pub fn finish(self) {}
+    }
 }
 
 #[cfg(feature = "prusti")]
@@ -126,6 +157,7 @@ pub mod core_spec;
 
 #[cfg(feature = "prusti")]
 mod private {
+    use crate::old;
     use core::{marker::PhantomData, ops::*};
 
     /// A macro for defining a closure with a specification.
@@ -325,6 +357,62 @@ mod private {
             panic!()
         }
     }
+
+    /// An amount of an abstract resource that must be spent, such as time
+    /// credits or the permission to call a function. Credits cannot be
+    /// dropped, so the amount that a function receives has to be spent,
+    /// passed on or returned.
+    #[prusti::must_consume]
+    pub struct Credits {
+        amount: usize,
+    }
+
+    impl Credits {
+        /// Creates `amount` credits. Verified code cannot create credits out
+        /// of nothing, so only trusted functions can call this.
+        #[trusted]
+        #[requires(false)]
+        #[ensures(result.amount() == amount)]
+        pub fn new(amount: usize) -> Self {
+            Credits { amount }
+        }
+
+        #[pure]
+        #[trusted]
+        pub fn amount(&self) -> usize {
+            self.amount
+        }
+
+        #[trusted]
+        #[requires(amount <= self.amount())]
+        #[ensures(self.amount() == old(self.amount()) - amount)]
+        pub fn spend(&mut self, amount: usize) {
+            self.amount -= amount;
+        }
+
+        #[trusted]
+        #[requires(amount <= self.amount())]
+        #[ensures(self.amount() == old(self.amount()) - amount)]
+        #[ensures(result.amount() == amount)]
+        pub fn split(&mut self, amount: usize) -> Self {
+            self.amount -= amount;
+            Credits { amount }
+        }
+
+        #[trusted]
+        #[requires(other.amount() <= usize::MAX - self.amount())]
+        #[ensures(self.amount() == old(self.amount()) + old(other.amount()))]
+        pub fn join(&mut self, other: Self) {
+            self.amount += other.amount;
+            core::mem::forget(other);
+        }
+
+        #[trusted]
+        #[requires(self.amount() == 0)]
+        pub fn finish(self) {
+            core::mem::forget(self);
+        }
+    }
 }
 
 /// This function is used to evaluate an expression in the context just
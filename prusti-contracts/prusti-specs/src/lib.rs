@@ -18,6 +18,7 @@ mod predicate;
 mod rewriter;
 mod span_overrider;
 mod spec_attribute_kind;
+mod spec_set;
 pub mod specifications;
 mod type_model;
 mod user_provided_type_params;
@@ -822,6 +823,30 @@ pub fn predicate(tokens: TokenStream) -> TokenStream {
     parsed.into_token_stream()
 }
 
+pub fn spec_set(tokens: TokenStream) -> TokenStream {
+    handle_result!(spec_set::rewrite_spec_set(tokens))
+}
+
+pub fn specs_use(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    handle_result!(spec_set::rewrite_specs_use(attr, tokens))
+}
+
+pub fn spec_set_apply(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    handle_result!(spec_set::rewrite_spec_set_apply(attr, tokens))
+}
+
+pub fn must_consume(attr: TokenStream, tokens: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(attr.span(), "`#[must_consume]` does not take arguments")
+            .to_compile_error();
+    }
+    let item: syn::DeriveInput = handle_result!(syn::parse2(tokens));
+    quote_spanned! {item.span()=>
+        #[prusti::must_consume]
+        #item
+    }
+}
+
 pub fn rewrite_prusti_attributes_for_types(
     outer_attr_kind: SpecAttributeKind,
     outer_attr_tokens: TokenStream,
@@ -0,0 +1,184 @@
+//! Named groups of specifications (`spec_set!`) and their use on functions
+//! (`#[specs_use]`).
+//!
+//! Every set is turned into a `macro_rules!` macro with the name of the set,
+//! which puts the specifications of the set in front of the item it is
+//! called on. Because the specifications are then part of the body of a
+//! declarative macro, their identifiers would be resolved at the definition
+//! of the set instead of in the function. The hidden `spec_set_apply`
+//! attribute therefore resolves them again at the function before handing
+//! them to the usual specification attributes.
+
+use crate::{common::HasSignature, untyped, SpecAttributeKind};
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use std::convert::TryInto;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Token,
+};
+
+/// One set of the `spec_set!` macro.
+struct SpecSet {
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    name: syn::Ident,
+    specs: Vec<syn::Attribute>,
+}
+
+impl Parse for SpecSet {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let name = input.parse()?;
+        let content;
+        braced!(content in input);
+        let specs = content.call(syn::Attribute::parse_outer)?;
+        if !content.is_empty() {
+            return Err(content.error("expected a specification attribute"));
+        }
+        Ok(Self {
+            attrs,
+            vis,
+            name,
+            specs,
+        })
+    }
+}
+
+/// Checks that `attr` is one of the specifications that can be part of a set
+/// and returns its kind together with its arguments.
+fn parse_spec_attribute(attr: &syn::Attribute) -> syn::Result<(syn::Ident, TokenStream)> {
+    let unsupported = || {
+        syn::Error::new(
+            attr.span(),
+            "only `requires`, `ensures`, `after_expiry` and `assert_on_expiry` can be part of a specification set",
+        )
+    };
+    let kind = attr.path.get_ident().ok_or_else(unsupported)?;
+    match kind.to_string().try_into() {
+        Ok(
+            SpecAttributeKind::Requires
+            | SpecAttributeKind::Ensures
+            | SpecAttributeKind::AfterExpiry
+            | SpecAttributeKind::AssertOnExpiry,
+        ) => {}
+        _ => return Err(unsupported()),
+    }
+    let mut iter = attr.tokens.clone().into_iter();
+    match (iter.next(), iter.next()) {
+        (Some(TokenTree::Group(group)), None) => Ok((kind.clone(), group.stream())),
+        _ => Err(syn::Error::new(
+            attr.span(),
+            format!("expected `#[{kind}(...)]`"),
+        )),
+    }
+}
+
+pub fn rewrite_spec_set(tokens: TokenStream) -> syn::Result<TokenStream> {
+    let sets = syn::parse::Parser::parse2(
+        |input: ParseStream| {
+            let mut sets = Vec::new();
+            while !input.is_empty() {
+                sets.push(input.parse::<SpecSet>()?);
+            }
+            Ok(sets)
+        },
+        tokens,
+    )?;
+    let mut result = TokenStream::new();
+    for set in sets {
+        if let syn::Visibility::Public(_) = set.vis {
+            return Err(syn::Error::new(
+                set.vis.span(),
+                "specification sets cannot be exported from a crate, use `pub(crate)` instead",
+            ));
+        }
+        let mut specs = TokenStream::new();
+        for attr in &set.specs {
+            parse_spec_attribute(attr)?;
+            specs.extend(quote! { #attr });
+        }
+        let SpecSet {
+            attrs, vis, name, ..
+        } = set;
+        result.extend(quote_spanned! {name.span()=>
+            #(#attrs)*
+            #[allow(unused_macros)]
+            macro_rules! #name {
+                ($($item:tt)*) => {
+                    #[::prusti_contracts::spec_set_apply(#specs)]
+                    $($item)*
+                };
+            }
+        });
+        if !matches!(vis, syn::Visibility::Inherited) {
+            result.extend(quote_spanned! {name.span()=>
+                #[allow(unused_imports)]
+                #vis use #name;
+            });
+        }
+    }
+    Ok(result)
+}
+
+pub fn rewrite_specs_use(attr: TokenStream, tokens: TokenStream) -> syn::Result<TokenStream> {
+    let paths =
+        syn::parse::Parser::parse2(Punctuated::<syn::Path, Token![,]>::parse_terminated, attr)?;
+    let mut paths = paths.into_iter();
+    let Some(first) = paths.next() else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`#[specs_use]` expects the paths of one or more specification sets",
+        ));
+    };
+    let rest: Vec<_> = paths.collect();
+    if rest.is_empty() {
+        Ok(quote_spanned! {first.span()=> #first! { #tokens } })
+    } else {
+        Ok(quote_spanned! {first.span()=>
+            #first! {
+                #[::prusti_contracts::specs_use(#(#rest),*)]
+                #tokens
+            }
+        })
+    }
+}
+
+/// Resolves all identifiers in `tokens` as if they were written at `target`.
+fn resolve_at(tokens: TokenStream, target: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(mut ident) => {
+                ident.set_span(ident.span().resolved_at(target));
+                TokenTree::Ident(ident)
+            }
+            TokenTree::Group(group) => {
+                let mut new_group =
+                    Group::new(group.delimiter(), resolve_at(group.stream(), target));
+                new_group.set_span(group.span());
+                TokenTree::Group(new_group)
+            }
+            other => other,
+        })
+        .collect()
+}
+
+pub fn rewrite_spec_set_apply(attr: TokenStream, tokens: TokenStream) -> syn::Result<TokenStream> {
+    let specs = syn::parse::Parser::parse2(syn::Attribute::parse_outer, attr)?;
+    let item: untyped::AnyFnItem = syn::parse2(tokens.clone())?;
+    let target = item.sig().ident.span();
+    let mut result = TokenStream::new();
+    for spec in &specs {
+        let (kind, args) = parse_spec_attribute(spec)?;
+        let args = resolve_at(args, target);
+        let kind = syn::Ident::new(&kind.to_string(), kind.span().resolved_at(target));
+        result.extend(quote_spanned! {target=> #[::prusti_contracts::#kind(#args)] });
+    }
+    result.extend(tokens);
+    Ok(result)
+}
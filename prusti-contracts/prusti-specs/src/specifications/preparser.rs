@@ -766,13 +766,10 @@ impl Quantifier {
     }
 }
 
-// For Prusti-specific operators, in [operator2], [operator3], and [operator4]
+// For Prusti-specific operators, in [operator2], [operator3], and [composite_operator4]
 // we mainly care about the spacing of the last [Punct], as this lets us
 // know that the last character is not itself part of an actual Rust
 // operator.
-//
-// "==>" should still have the expected spacing of [Joint, Joint, Alone]
-// even though "==" and ">" are separate Rust operators.
 fn operator2(op: &str, p1: &Punct, p2: &Punct) -> bool {
     let chars = op.chars().collect::<Vec<_>>();
     [p1.as_char(), p2.as_char()] == chars[0..2] && p1.spacing() == Joint && p2.spacing() == Alone
@@ -786,13 +783,18 @@ fn operator3(op: &str, p1: &Punct, p2: &Punct, p3: &Punct) -> bool {
         && p3.spacing() == Alone
 }
 
-fn operator4(op: &str, p1: &Punct, p2: &Punct, p3: &Punct, p4: &Punct) -> bool {
+// Operators like "==>" consist of several Rust operators ("==" and ">"),
+// and the spacing between those is lost when the specification is part of
+// the body of a `macro_rules!`. No valid Rust expression contains such a
+// sequence of operators, so for these only the last [Punct] is checked.
+fn composite_operator3(op: &str, p1: &Punct, p2: &Punct, p3: &Punct) -> bool {
     let chars = op.chars().collect::<Vec<_>>();
-    [p1.as_char(), p2.as_char(), p3.as_char(), p4.as_char()] == chars[0..4]
-        && p1.spacing() == Joint
-        && p2.spacing() == Joint
-        && p3.spacing() == Joint
-        && p4.spacing() == Alone
+    [p1.as_char(), p2.as_char(), p3.as_char()] == chars[0..3] && p3.spacing() == Alone
+}
+
+fn composite_operator4(op: &str, p1: &Punct, p2: &Punct, p3: &Punct, p4: &Punct) -> bool {
+    let chars = op.chars().collect::<Vec<_>>();
+    [p1.as_char(), p2.as_char(), p3.as_char(), p4.as_char()] == chars[0..4] && p4.spacing() == Alone
 }
 
 impl PrustiToken {
@@ -857,13 +859,13 @@ impl PrustiToken {
         let span = join_spans(join_spans(p1.span(), p2.span()), p3.span());
         Some(Self::BinOp(
             span,
-            if operator3("==>", p1, p2, p3) {
+            if composite_operator3("==>", p1, p2, p3) {
                 PrustiBinaryOp::Implies
-            } else if operator3("<==", p1, p2, p3) {
+            } else if composite_operator3("<==", p1, p2, p3) {
                 PrustiBinaryOp::ImpliesReverse
-            } else if operator3("===", p1, p2, p3) {
+            } else if composite_operator3("===", p1, p2, p3) {
                 PrustiBinaryOp::SnapEq
-            } else if operator3("!==", p1, p2, p3) {
+            } else if composite_operator3("!==", p1, p2, p3) {
                 PrustiBinaryOp::SnapNe
             } else if operator3("..=", p1, p2, p3) {
                 PrustiBinaryOp::Rust(RustOp::RangeInclusive)
@@ -888,7 +890,7 @@ impl PrustiToken {
         );
         Some(Self::BinOp(
             span,
-            if operator4("<==>", p1, p2, p3, p4) {
+            if composite_operator4("<==>", p1, p2, p3, p4) {
                 PrustiBinaryOp::Iff
             } else {
                 return None;
@@ -1106,6 +1108,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_composite_operators_without_spacing() {
+        // The spacing between the Rust operators that make up an operator
+        // like "==>" is lost in the body of a `macro_rules!`.
+        assert_eq!(
+            parse_prusti("a == > b".parse().unwrap())
+                .unwrap()
+                .to_string(),
+            "! (a) || (b)",
+        );
+        assert_eq!(
+            parse_prusti("a <= = > b".parse().unwrap())
+                .unwrap()
+                .to_string(),
+            "(a) == (b)",
+        );
+    }
+
     mod type_cond_specs {
         use std::assert_matches::assert_matches;
 
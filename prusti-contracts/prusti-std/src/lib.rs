@@ -12,3 +12,220 @@ where
         K: ::core::borrow::Borrow<Q>,
         Q: ::core::hash::Hash + Eq;
 }
+
+/// The element at position `index` of `deque`, counted from the front, for
+/// use in specifications.
+#[trusted]
+#[pure]
+#[requires(index < deque.len())]
+pub fn vec_deque_lookup<T>(deque: &::std::collections::VecDeque<T>, index: usize) -> &T {
+    &deque[index]
+}
+
+#[extern_spec]
+impl<T> ::std::collections::VecDeque<T> {
+    #[ensures(result.len() == 0)]
+    pub const fn new() -> ::std::collections::VecDeque<T>;
+
+    #[ensures(result.len() == 0)]
+    pub fn with_capacity(capacity: usize) -> ::std::collections::VecDeque<T>;
+
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(self.len() == old(self.len()) + 1)]
+    #[ensures(snap(vec_deque_lookup(self, self.len() - 1)) === value)]
+    #[ensures(forall(|i: usize| i < old(self.len()) ==>
+        vec_deque_lookup(self, i) === old(vec_deque_lookup(self, i))))]
+    pub fn push_back(&mut self, value: T);
+
+    #[ensures(self.len() == old(self.len()) + 1)]
+    #[ensures(snap(vec_deque_lookup(self, 0)) === value)]
+    #[ensures(forall(|i: usize| i < old(self.len()) ==>
+        vec_deque_lookup(self, i + 1) === old(vec_deque_lookup(self, i))))]
+    pub fn push_front(&mut self, value: T);
+
+    #[ensures(old(self.len()) == 0 ==> self.len() == 0 && result === None)]
+    #[ensures(old(self.len()) > 0 ==> self.len() == old(self.len()) - 1
+        && result === Some(snap(old(vec_deque_lookup(self, self.len() - 1)))))]
+    #[ensures(forall(|i: usize| i < self.len() ==>
+        vec_deque_lookup(self, i) === old(vec_deque_lookup(self, i))))]
+    pub fn pop_back(&mut self) -> Option<T>;
+
+    #[ensures(old(self.len()) == 0 ==> self.len() == 0 && result === None)]
+    #[ensures(old(self.len()) > 0 ==> self.len() == old(self.len()) - 1
+        && result === Some(snap(old(vec_deque_lookup(self, 0)))))]
+    #[ensures(forall(|i: usize| i < self.len() ==>
+        vec_deque_lookup(self, i) === old(vec_deque_lookup(self, i + 1))))]
+    pub fn pop_front(&mut self) -> Option<T>;
+}
+
+#[extern_spec]
+impl<T> ::core::ops::Index<usize> for ::std::collections::VecDeque<T> {
+    #[requires(index < self.len())]
+    #[ensures(result === vec_deque_lookup(self, index))]
+    fn index(&self, index: usize) -> &T;
+}
+
+/// The key at position `index` of `map`, in the ascending order of the keys,
+/// for use in specifications. The ordering can only be used for keys whose
+/// comparisons are pure, such as integers.
+#[trusted]
+#[pure]
+#[requires(index < map.len())]
+#[ensures(forall(|i: usize| i < index ==> *btree_map_key(map, i) < *result))]
+pub fn btree_map_key<K: Ord, V>(map: &::std::collections::BTreeMap<K, V>, index: usize) -> &K {
+    map.keys().nth(index).unwrap()
+}
+
+/// The value at position `index` of `map`, in the ascending order of the
+/// keys, for use in specifications.
+#[trusted]
+#[pure]
+#[requires(index < map.len())]
+pub fn btree_map_value<K, V>(map: &::std::collections::BTreeMap<K, V>, index: usize) -> &V {
+    map.values().nth(index).unwrap()
+}
+
+#[extern_spec]
+impl<K, V> ::std::collections::BTreeMap<K, V> {
+    #[ensures(result.len() == 0)]
+    pub const fn new() -> ::std::collections::BTreeMap<K, V>;
+
+    #[pure]
+    pub fn len(&self) -> usize;
+
+    #[pure]
+    #[ensures(result == (self.len() == 0))]
+    pub fn is_empty(&self) -> bool;
+
+    #[ensures(self.len() == 0)]
+    pub fn clear(&mut self);
+}
+
+#[extern_spec]
+impl<K: Ord, V> ::std::collections::BTreeMap<K, V> {
+    #[ensures(self.len() == 0 ==> result === None)]
+    #[ensures(self.len() > 0 ==> result === Some((btree_map_key(self, 0), btree_map_value(self, 0))))]
+    pub fn first_key_value(&self) -> Option<(&K, &V)>;
+
+    #[ensures(self.len() == 0 ==> result === None)]
+    #[ensures(self.len() > 0 ==> result === Some((
+        btree_map_key(self, self.len() - 1),
+        btree_map_value(self, self.len() - 1),
+    )))]
+    pub fn last_key_value(&self) -> Option<(&K, &V)>;
+
+    #[ensures(btree_map_iter_len(&result) == self.len())]
+    #[ensures(forall(|i: usize| i < self.len() ==>
+        btree_map_iter_key(&result, i) === btree_map_key(self, i)
+        && btree_map_iter_value(&result, i) === btree_map_value(self, i)))]
+    pub fn iter(&self) -> ::std::collections::btree_map::Iter<'_, K, V>;
+
+    #[ensures(btree_map_range_start(self, &result) + btree_map_iter_len(&result) <= self.len())]
+    #[ensures(forall(|i: usize| i < btree_map_iter_len(&result) ==>
+        btree_map_iter_key(&result, i) === btree_map_key(self, btree_map_range_start(self, &result) + i)
+        && btree_map_iter_value(&result, i)
+            === btree_map_value(self, btree_map_range_start(self, &result) + i)))]
+    pub fn range<T: ?Sized, R>(&self, range: R) -> ::std::collections::btree_map::Range<'_, K, V>
+    where
+        T: Ord,
+        K: ::core::borrow::Borrow<T> + Ord,
+        R: ::core::ops::RangeBounds<T>;
+
+    #[ensures(old(self.len()) == 0 ==> self.len() == 0 && result === None)]
+    #[ensures(old(self.len()) > 0 ==> self.len() == old(self.len()) - 1
+        && result === Some((snap(old(btree_map_key(self, 0))), snap(old(btree_map_value(self, 0))))))]
+    #[ensures(forall(|i: usize| i < self.len() ==>
+        btree_map_key(self, i) === old(btree_map_key(self, i + 1))
+        && btree_map_value(self, i) === old(btree_map_value(self, i + 1))))]
+    pub fn pop_first(&mut self) -> Option<(K, V)>;
+
+    #[ensures(old(self.len()) == 0 ==> self.len() == 0 && result === None)]
+    #[ensures(old(self.len()) > 0 ==> self.len() == old(self.len()) - 1
+        && result === Some((
+            snap(old(btree_map_key(self, self.len() - 1))),
+            snap(old(btree_map_value(self, self.len() - 1))),
+        )))]
+    #[ensures(forall(|i: usize| i < self.len() ==>
+        btree_map_key(self, i) === old(btree_map_key(self, i))
+        && btree_map_value(self, i) === old(btree_map_value(self, i))))]
+    pub fn pop_last(&mut self) -> Option<(K, V)>;
+}
+
+/// The number of entries that the iterator `iter` over the entries of a
+/// `BTreeMap`, e.g. one returned by `iter` or `range`, has yet to yield, for
+/// use in specifications.
+#[trusted]
+#[pure]
+pub fn btree_map_iter_len<'a, K: 'a, V: 'a, I>(iter: &I) -> usize
+where
+    I: Iterator<Item = (&'a K, &'a V)> + Clone,
+{
+    iter.clone().count()
+}
+
+/// The key of the entry that the iterator `iter` yields after `index` others,
+/// for use in specifications.
+#[trusted]
+#[pure]
+#[requires(index < btree_map_iter_len(iter))]
+pub fn btree_map_iter_key<'a, K: 'a, V: 'a, I>(iter: &I, index: usize) -> &'a K
+where
+    I: Iterator<Item = (&'a K, &'a V)> + Clone,
+{
+    iter.clone().nth(index).unwrap().0
+}
+
+/// The value of the entry that the iterator `iter` yields after `index`
+/// others, for use in specifications.
+#[trusted]
+#[pure]
+#[requires(index < btree_map_iter_len(iter))]
+pub fn btree_map_iter_value<'a, K: 'a, V: 'a, I>(iter: &I, index: usize) -> &'a V
+where
+    I: Iterator<Item = (&'a K, &'a V)> + Clone,
+{
+    iter.clone().nth(index).unwrap().1
+}
+
+/// The position in `map` of the first entry of `range`, which is the map
+/// from which `range` was created. The entries of `range` are consecutive
+/// entries of `map`, so their keys are in ascending order.
+#[trusted]
+#[pure]
+pub fn btree_map_range_start<K: Ord, V>(
+    map: &::std::collections::BTreeMap<K, V>,
+    range: &::std::collections::btree_map::Range<'_, K, V>,
+) -> usize {
+    match range.clone().next() {
+        Some((first, _)) => map.keys().take_while(|key| *key < first).count(),
+        None => map.len(),
+    }
+}
+
+#[extern_spec]
+impl<'a, K, V> Iterator for ::std::collections::btree_map::Iter<'a, K, V> {
+    #[ensures(old(btree_map_iter_len(self)) == 0 ==> btree_map_iter_len(self) == 0 && result === None)]
+    #[ensures(old(btree_map_iter_len(self)) > 0 ==> btree_map_iter_len(self) == old(btree_map_iter_len(self)) - 1
+        && result === Some((old(btree_map_iter_key(self, 0)), old(btree_map_iter_value(self, 0)))))]
+    #[ensures(forall(|i: usize| i < btree_map_iter_len(self) ==>
+        btree_map_iter_key(self, i) === old(btree_map_iter_key(self, i + 1))
+        && btree_map_iter_value(self, i) === old(btree_map_iter_value(self, i + 1))))]
+    fn next(&mut self) -> Option<(&'a K, &'a V)>;
+}
+
+#[extern_spec]
+impl<'a, K, V> Iterator for ::std::collections::btree_map::Range<'a, K, V> {
+    #[ensures(old(btree_map_iter_len(self)) == 0 ==> btree_map_iter_len(self) == 0 && result === None)]
+    #[ensures(old(btree_map_iter_len(self)) > 0 ==> btree_map_iter_len(self) == old(btree_map_iter_len(self)) - 1
+        && result === Some((old(btree_map_iter_key(self, 0)), old(btree_map_iter_value(self, 0)))))]
+    #[ensures(forall(|i: usize| i < btree_map_iter_len(self) ==>
+        btree_map_iter_key(self, i) === old(btree_map_iter_key(self, i + 1))
+        && btree_map_iter_value(self, i) === old(btree_map_iter_value(self, i + 1))))]
+    fn next(&mut self) -> Option<(&'a K, &'a V)>;
+}
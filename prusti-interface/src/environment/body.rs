@@ -1,3 +1,4 @@
+use prusti_common::config;
 use prusti_rustc_interface::{
     macros::{TyDecodable, TyEncodable},
     middle::{
@@ -141,8 +142,12 @@ impl<'tcx> EnvBody<'tcx> {
     }
 
     /// Get local MIR body of spec or pure functions. Retrieves the body from
-    /// the compiler (relatively cheap).
+    /// the compiler (relatively cheap), at the stage selected by
+    /// `config::pure_mir_stage`.
     fn load_local_mir(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> MirBody<'tcx> {
+        if config::pure_mir_stage() == "optimized" {
+            return MirBody(Rc::new(tcx.optimized_mir(def_id).clone()));
+        }
         // SAFETY: This is safe because we are feeding in the same `tcx`
         // that was used to store the data.
         let body = unsafe { mir_storage::retrieve_promoted_mir_body(tcx, def_id) };
@@ -37,7 +37,7 @@ impl PlaceRegions {
         self.for_place(local.into()).unwrap()
     }
 
-    /// Determines the region of a MIR place. Right now, the only supported places are locals and tuples. Tuples cannot be nested inside other tuples.
+    /// Determines the region of a MIR place. Right now, the only supported places are locals and fields of tuples and structs. Tuples and structs cannot be nested inside other tuples or structs.
     pub fn for_place(&self, place: mir::Place) -> Result<Option<facts::Region>, PlaceRegionsError> {
         let (local, fields) = Self::translate_place(place)?;
         Ok(self.0.get(&(local, fields)).cloned())
@@ -93,8 +93,13 @@ fn extract_region_id(region: &ty::RegionKind) -> facts::Region {
     }
 }
 
-#[tracing::instrument(level = "trace", skip(place_regions))]
-fn extract_region(place_regions: &mut PlaceRegions, local: mir::Local, ty: ty::Ty<'_>) {
+#[tracing::instrument(level = "trace", skip(place_regions, tcx))]
+fn extract_region<'tcx>(
+    place_regions: &mut PlaceRegions,
+    tcx: ty::TyCtxt<'tcx>,
+    local: mir::Local,
+    ty: ty::Ty<'tcx>,
+) {
     match ty.kind() {
         ty::TyKind::Ref(region, _, _) => {
             place_regions.add_local(local, extract_region_id(region));
@@ -118,6 +123,25 @@ fn extract_region(place_regions: &mut PlaceRegions, local: mir::Local, ty: ty::T
                 }
             }
         }
+        ty::TyKind::Adt(adt_def, substs) if adt_def.is_struct() => {
+            for (i, field) in adt_def.non_enum_variant().fields.iter().enumerate() {
+                let field_ty = field.ty(tcx, substs);
+                match field_ty.kind() {
+                    ty::TyKind::Ref(region, _, _) => {
+                        place_regions.add(local, vec![i], extract_region_id(region))
+                    }
+                    _ => {
+                        debug!(
+                            "does not contain regions: {:?}.{}: {:?} {:?}",
+                            local,
+                            field.name,
+                            field_ty,
+                            field_ty.kind()
+                        );
+                    }
+                }
+            }
+        }
         _ => {
             debug!(
                 "does not contain regions: {:?}: {:?} {:?}",
@@ -129,13 +153,16 @@ fn extract_region(place_regions: &mut PlaceRegions, local: mir::Local, ty: ty::T
     }
 }
 
-#[tracing::instrument(level = "debug", skip(body))]
-pub fn load_place_regions(body: &mir::Body<'_>) -> io::Result<PlaceRegions> {
+#[tracing::instrument(level = "debug", skip(tcx, body))]
+pub fn load_place_regions<'tcx>(
+    tcx: ty::TyCtxt<'tcx>,
+    body: &mir::Body<'tcx>,
+) -> io::Result<PlaceRegions> {
     let mut place_regions = PlaceRegions::new();
     for (local, local_decl) in body.local_decls.iter_enumerated() {
         let ty = local_decl.ty;
         debug!("local: {:?} {:?}", local, ty);
-        extract_region(&mut place_regions, local, ty);
+        extract_region(&mut place_regions, tcx, local, ty);
     }
     Ok(place_regions)
 }
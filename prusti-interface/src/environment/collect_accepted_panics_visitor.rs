@@ -0,0 +1,57 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::utils::has_prusti_attr;
+use prusti_rustc_interface::{
+    hir,
+    hir::intravisit::{walk_expr, walk_local, Visitor},
+    middle::hir::map::Map,
+    span::Span,
+};
+
+/// Collects the spans of the statements and expressions that are annotated
+/// with `prusti::accept_panic`, including those in nested closures.
+pub struct CollectAcceptedPanicsVisitor<'tcx> {
+    map: Map<'tcx>,
+    result: Vec<Span>,
+}
+
+impl<'tcx> CollectAcceptedPanicsVisitor<'tcx> {
+    pub fn new(map: Map<'tcx>) -> Self {
+        CollectAcceptedPanicsVisitor {
+            map,
+            result: Vec::new(),
+        }
+    }
+    pub fn get_accepted_panic_spans(self) -> Vec<Span> {
+        self.result
+    }
+    fn visit_attributes(&mut self, hir_id: hir::HirId, span: Span) {
+        if has_prusti_attr(self.map.attrs(hir_id), "accept_panic") {
+            self.result.push(span);
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for CollectAcceptedPanicsVisitor<'tcx> {
+    type Map = Map<'tcx>;
+    type NestedFilter = prusti_rustc_interface::middle::hir::nested_filter::OnlyBodies;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.map
+    }
+
+    // The attributes of other statements are attached to their expression.
+    fn visit_local(&mut self, local: &'tcx hir::Local<'tcx>) {
+        self.visit_attributes(local.hir_id, local.span);
+        walk_local(self, local)
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        self.visit_attributes(expr.hir_id, expr.span);
+        walk_expr(self, expr)
+    }
+}
@@ -9,7 +9,7 @@ use prusti_rustc_interface::{
     middle::hir::map::Map,
 };
 
-use crate::utils::has_spec_only_attr;
+use crate::utils::{has_spec_only_attr, has_test_harness_attr};
 
 pub struct CollectClosureDefsVisitor<'env, 'tcx: 'env> {
     env: &'env Environment<'tcx>,
@@ -46,7 +46,12 @@ impl<'env, 'tcx> Visitor<'tcx> for CollectClosureDefsVisitor<'env, 'tcx> {
         }) = expr.kind
         {
             let def_id = local_def_id.to_def_id();
-            if !has_spec_only_attr(self.env.query.get_attributes(def_id)) {
+            // The closures wrapping `#[test]` functions live in the
+            // descriptor constants generated by the test harness.
+            let root_def_id = self.env.tcx().typeck_root_def_id(def_id);
+            if !has_spec_only_attr(self.env.query.get_attributes(def_id))
+                && !has_test_harness_attr(self.env.query.get_attributes(root_def_id))
+            {
                 let item_def_path = self.env.name.get_item_def_path(def_id);
                 trace!("Add {} to result", item_def_path);
                 self.result.push(def_id);
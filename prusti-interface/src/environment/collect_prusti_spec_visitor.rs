@@ -7,7 +7,7 @@
 use super::{EnvName, EnvQuery};
 use crate::{
     environment::Environment,
-    utils::{has_extern_spec_attr, has_spec_only_attr},
+    utils::{has_extern_spec_attr, has_spec_only_attr, has_test_harness_attr},
 };
 use log::trace;
 use prusti_rustc_interface::{
@@ -58,7 +58,11 @@ impl<'tcx> Visitor<'tcx> for CollectPrustiSpecVisitor<'tcx> {
     #[tracing::instrument(level = "trace", skip(self, item))]
     fn visit_item(&mut self, item: &hir::Item) {
         let attrs = self.env_query.get_local_attributes(item.owner_id.def_id);
-        if has_spec_only_attr(attrs) || has_extern_spec_attr(attrs) {
+        if has_spec_only_attr(attrs)
+            || has_extern_spec_attr(attrs)
+            || has_test_harness_attr(attrs)
+            || self.env_query.is_verification_skipped(item.owner_id.def_id)
+        {
             return;
         }
         if let hir::ItemKind::Fn(..) = item.kind {
@@ -84,7 +88,12 @@ impl<'tcx> Visitor<'tcx> for CollectPrustiSpecVisitor<'tcx> {
         let attrs = self
             .env_query
             .get_local_attributes(trait_item.owner_id.def_id);
-        if has_spec_only_attr(attrs) || has_extern_spec_attr(attrs) {
+        if has_spec_only_attr(attrs)
+            || has_extern_spec_attr(attrs)
+            || self
+                .env_query
+                .is_verification_skipped(trait_item.owner_id.def_id)
+        {
             return;
         }
 
@@ -113,7 +122,12 @@ impl<'tcx> Visitor<'tcx> for CollectPrustiSpecVisitor<'tcx> {
         let attrs = self
             .env_query
             .get_local_attributes(impl_item.owner_id.def_id);
-        if has_spec_only_attr(attrs) || has_extern_spec_attr(attrs) {
+        if has_spec_only_attr(attrs)
+            || has_extern_spec_attr(attrs)
+            || self
+                .env_query
+                .is_verification_skipped(impl_item.owner_id.def_id)
+        {
             return;
         }
 
@@ -1,5 +1,5 @@
 use prusti_rustc_interface::{
-    errors::{DiagnosticBuilder, EmissionGuarantee, MultiSpan},
+    errors::{DiagnosticBuilder, DiagnosticId, EmissionGuarantee, MultiSpan},
     middle::ty::TyCtxt,
 };
 use std::cell::RefCell;
@@ -20,10 +20,14 @@ impl<'tcx> EnvDiagnostic<'tcx> {
     fn configure_diagnostic<S: Into<MultiSpan> + Clone, T: EmissionGuarantee>(
         diagnostic: &mut DiagnosticBuilder<T>,
         sp: S,
+        code: Option<&str>,
         help: &Option<String>,
         notes: &[(String, Option<S>)],
     ) {
         diagnostic.set_span(sp);
+        if let Some(code) = code {
+            diagnostic.code(DiagnosticId::Error(code.to_string()));
+        }
         if let Some(help_msg) = help {
             diagnostic.help(help_msg.clone());
         }
@@ -41,11 +45,12 @@ impl<'tcx> EnvDiagnostic<'tcx> {
         &self,
         sp: S,
         msg: &str,
+        code: Option<&str>,
         help: &Option<String>,
         notes: &[(String, Option<S>)],
     ) {
         let mut diagnostic = self.tcx.sess.struct_err(msg.to_string());
-        Self::configure_diagnostic(&mut diagnostic, sp, help, notes);
+        Self::configure_diagnostic(&mut diagnostic, sp, code, help, notes);
         for warn in self.warn_buffer.borrow_mut().iter_mut() {
             self.tcx.sess.diagnostic().emit_diagnostic(warn);
         }
@@ -57,11 +62,12 @@ impl<'tcx> EnvDiagnostic<'tcx> {
         &self,
         sp: S,
         msg: &str,
+        code: Option<&str>,
         help: &Option<String>,
         notes: &[(String, Option<S>)],
     ) {
         let mut diagnostic = self.tcx.sess.struct_warn(msg.to_string());
-        Self::configure_diagnostic(&mut diagnostic, sp, help, notes);
+        Self::configure_diagnostic(&mut diagnostic, sp, code, help, notes);
         diagnostic.emit();
     }
 
@@ -70,11 +76,12 @@ impl<'tcx> EnvDiagnostic<'tcx> {
         &self,
         sp: S,
         msg: &str,
+        code: Option<&str>,
         help: &Option<String>,
         notes: &[(String, Option<S>)],
     ) {
         let mut diagnostic = self.tcx.sess.struct_warn(msg.to_string());
-        Self::configure_diagnostic(&mut diagnostic, sp, help, notes);
+        Self::configure_diagnostic(&mut diagnostic, sp, code, help, notes);
         diagnostic.buffer(&mut self.warn_buffer.borrow_mut());
     }
 
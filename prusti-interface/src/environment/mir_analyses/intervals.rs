@@ -0,0 +1,60 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module provides the bounds of integer locals inferred by the interval
+//! analysis of the `analysis` crate.
+
+pub use analysis::domains::Interval;
+use analysis::{
+    abstract_interpretation::{AbstractState, FixpointEngine},
+    domains::IntervalsAnalysis,
+};
+use log::debug;
+use prusti_common::Stopwatch;
+use prusti_rustc_interface::{
+    hir::def_id::DefId,
+    middle::{mir, ty::TyCtxt},
+};
+use rustc_hash::FxHashMap;
+
+/// Computes, for each of the given `blocks`, the bounds that hold for the
+/// integer locals at the end of the block. The bounds that are implied by the
+/// type of a local are omitted.
+///
+/// If the analysis does not support the body, no bounds are returned.
+pub fn compute_bounds_after_blocks<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    body: &mir::Body<'tcx>,
+    blocks: &[mir::BasicBlock],
+) -> FxHashMap<mir::BasicBlock, Vec<(mir::Local, Interval)>> {
+    let stopwatch = Stopwatch::start_debug("prusti-client", "interval analysis");
+    let analysis = IntervalsAnalysis::new(tcx, def_id, body);
+    let bounds = match analysis.run_fwd_analysis() {
+        Ok(pointwise_state) => blocks
+            .iter()
+            .map(|&bb| {
+                let mut state = analysis.new_bottom();
+                if let Some(states_after_block) = pointwise_state.lookup_after_block(bb) {
+                    for state_after_block in states_after_block.values() {
+                        state.join(state_after_block);
+                    }
+                }
+                (bb, state.get_bounded_locals())
+            })
+            .collect(),
+        Err(e) => {
+            debug!(
+                "Interval analysis of {:?} failed: {}",
+                def_id,
+                e.to_pretty_str(body)
+            );
+            FxHashMap::default()
+        }
+    };
+    stopwatch.finish();
+    bounds
+}
@@ -0,0 +1,237 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module finds the places where a value of a type marked with
+//! `prusti::must_consume` might be leaked.
+//!
+//! MIR contains a `Drop` terminator for every variable with drop glue that
+//! goes out of scope, even if its value was moved out before. The variables
+//! without drop glue only have a `StorageDead` statement, or nothing at all
+//! for the arguments. A value is leaked at such a point if a part of it that
+//! contains a value of a `prusti::must_consume` type might still be
+//! initialized.
+
+use crate::environment::EnvQuery;
+use prusti_rustc_interface::{
+    abi::{VariantIdx, FIRST_VARIANT},
+    dataflow::{
+        impls::MaybeInitializedPlaces,
+        move_paths::{LookupResult, MoveData, MovePathIndex},
+        Analysis, MoveDataParamEnv,
+    },
+    hir::def_id::DefId,
+    middle::{
+        mir,
+        ty::{self, Ty},
+    },
+};
+use rustc_hash::FxHashSet;
+
+/// Whether `ty` is a `prusti::must_consume` type, or contains one as a type
+/// argument or in the fields of an ADT, like `Option<T>` or a struct with a
+/// field of type `T`, where `T` is a `prusti::must_consume` type.
+pub fn is_must_consume_type<'tcx>(query: EnvQuery<'tcx>, ty: Ty<'tcx>) -> bool {
+    contains_must_consume_type(query, ty, &mut FxHashSet::default())
+}
+
+fn contains_must_consume_type<'tcx>(
+    query: EnvQuery<'tcx>,
+    ty: Ty<'tcx>,
+    visited: &mut FxHashSet<Ty<'tcx>>,
+) -> bool {
+    ty.walk().any(|arg| match arg.unpack() {
+        ty::GenericArgKind::Type(ty) => match ty.kind() {
+            ty::TyKind::Adt(adt_def, substs) => {
+                is_must_consume_adt(query, adt_def.did())
+                    || (visited.insert(ty)
+                        && adt_def.all_fields().any(|field| {
+                            contains_must_consume_type(
+                                query,
+                                field.ty(query.tcx(), substs),
+                                visited,
+                            )
+                        }))
+            }
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+fn is_must_consume_adt(query: EnvQuery, adt_id: DefId) -> bool {
+    query.has_prusti_attribute(adt_id, "must_consume")
+}
+
+/// Returns the locations at which a value of a `prusti::must_consume` type might
+/// be leaked. These are the `Drop` terminators of values with drop glue, and
+/// the `StorageDead` statements of the other locals, or the `Return`
+/// terminators for the arguments without drop glue, which do not have storage
+/// markers. Locations in cleanup blocks are ignored, because resources might
+/// be leaked when panicking.
+pub fn compute_leaks<'tcx>(
+    query: EnvQuery<'tcx>,
+    def_id: DefId,
+    body: &mir::Body<'tcx>,
+) -> FxHashSet<mir::Location> {
+    let tcx = query.tcx();
+    let param_env = tcx.param_env(def_id);
+    // The locals with drop glue are checked at their `Drop` terminator
+    // instead, which does not count as a move.
+    let is_leak_candidate = |local: mir::Local| {
+        let local_decl = &body.local_decls[local];
+        !local_decl.is_deref_temp()
+            && is_must_consume_type(query, local_decl.ty)
+            && !local_decl.ty.needs_drop(tcx, param_env)
+    };
+    let mut candidates = Vec::new();
+    for (bb, bb_data) in body.basic_blocks.iter_enumerated() {
+        if bb_data.is_cleanup {
+            continue;
+        }
+        for (statement_index, statement) in bb_data.statements.iter().enumerate() {
+            if let mir::StatementKind::StorageDead(local) = statement.kind {
+                if is_leak_candidate(local) {
+                    candidates.push((
+                        mir::Location {
+                            block: bb,
+                            statement_index,
+                        },
+                        mir::Place::from(local),
+                    ));
+                }
+            }
+        }
+        let location = body.terminator_loc(bb);
+        match bb_data.terminator().kind {
+            mir::TerminatorKind::Drop { place, .. }
+                if is_must_consume_type(query, place.ty(body, tcx).ty) =>
+            {
+                candidates.push((location, place));
+            }
+            mir::TerminatorKind::Return => {
+                candidates.extend(
+                    body.args_iter()
+                        .filter(|&local| is_leak_candidate(local))
+                        .map(|local| (location, mir::Place::from(local))),
+                );
+            }
+            _ => {}
+        }
+    }
+    if candidates.is_empty() {
+        return FxHashSet::default();
+    }
+
+    let (Ok(move_data) | Err((move_data, _))) = MoveData::gather_moves(body, tcx, param_env);
+    let env = MoveDataParamEnv {
+        move_data,
+        param_env,
+    };
+    let mut maybe_inits = MaybeInitializedPlaces::new(tcx, body, &env)
+        .into_engine(tcx, body)
+        .pass_name("leaks")
+        .iterate_to_fixpoint()
+        .into_results_cursor(body);
+
+    let mut leaks = FxHashSet::default();
+    for (location, place) in candidates {
+        maybe_inits.seek_before_primary_effect(location);
+        // Like the drop elaboration, ignore the drops of untracked places.
+        let LookupResult::Exact(path) = env.move_data.rev_lookup.find(place.as_ref()) else {
+            continue;
+        };
+        if might_leak(
+            query,
+            body,
+            &env.move_data,
+            &|path| maybe_inits.contains(path),
+            path,
+        ) {
+            leaks.insert(location);
+        }
+    }
+    leaks
+}
+
+/// Whether a part of the value at `path` that might be initialized contains a
+/// `prusti::must_consume` value. The parts of a value that were moved out or
+/// assigned on their own, e.g. `session.tx` in `commit(session.tx)`, have
+/// move paths of their own. The other parts are initialized if and only if
+/// the whole value is.
+fn might_leak<'tcx>(
+    query: EnvQuery<'tcx>,
+    body: &mir::Body<'tcx>,
+    move_data: &MoveData<'tcx>,
+    maybe_init: &dyn Fn(MovePathIndex) -> bool,
+    path: MovePathIndex,
+) -> bool {
+    let tcx = query.tcx();
+    let move_path = &move_data.move_paths[path];
+    let place_ty = move_path.place.ty(body, tcx);
+    if !is_must_consume_type(query, place_ty.ty) {
+        return false;
+    }
+    let children: Vec<_> = move_path.children(&move_data.move_paths).collect();
+    if children
+        .iter()
+        .any(|&(child, _)| might_leak(query, body, move_data, maybe_init, child))
+    {
+        return true;
+    }
+    if !maybe_init(path) {
+        return false;
+    }
+    let child_elems: Vec<_> = children
+        .iter()
+        .filter_map(|(_, child_path)| child_path.place.projection.last())
+        .collect();
+    let has_field_path = |field: usize| {
+        child_elems.iter().any(|elem| {
+            matches!(elem, mir::ProjectionElem::Field(index, _) if index.as_usize() == field)
+        })
+    };
+    let has_variant_path = |variant: VariantIdx| {
+        child_elems.iter().any(
+            |elem| matches!(elem, mir::ProjectionElem::Downcast(_, index) if *index == variant),
+        )
+    };
+    let has_deref_path = child_elems
+        .iter()
+        .any(|elem| matches!(elem, mir::ProjectionElem::Deref));
+    match place_ty.ty.kind() {
+        _ if children.is_empty() => true,
+        ty::TyKind::Adt(adt_def, _) if is_must_consume_adt(query, adt_def.did()) => true,
+        ty::TyKind::Adt(_, substs) if place_ty.ty.is_box() => {
+            !has_deref_path && is_must_consume_type(query, substs.type_at(0))
+        }
+        ty::TyKind::Adt(adt_def, substs) => match place_ty.variant_index {
+            None if adt_def.is_enum() => {
+                adt_def
+                    .variants()
+                    .iter_enumerated()
+                    .any(|(variant_index, variant)| {
+                        !has_variant_path(variant_index)
+                            && variant
+                                .fields
+                                .iter()
+                                .any(|field| is_must_consume_type(query, field.ty(tcx, substs)))
+                    })
+            }
+            variant_index => adt_def
+                .variant(variant_index.unwrap_or(FIRST_VARIANT))
+                .fields
+                .iter()
+                .enumerate()
+                .any(|(field, field_def)| {
+                    !has_field_path(field) && is_must_consume_type(query, field_def.ty(tcx, substs))
+                }),
+        },
+        ty::TyKind::Tuple(field_tys) => field_tys.iter().enumerate().any(|(field, field_ty)| {
+            !has_field_path(field) && is_must_consume_type(query, field_ty)
+        }),
+        _ => true,
+    }
+}
@@ -0,0 +1,238 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! This module recognizes loop guards that compare an integer local with
+//! another integer local or with the length of a slice or array, such as
+//! `while i < n` or `while i < v.len()`.
+//!
+//! In MIR, the guard is evaluated through temporaries:
+//!
+//! ```text
+//! _7 = _2;
+//! _9 = &(*_1);
+//! _8 = core::slice::<impl [u32]>::len(move _9);
+//! _6 = Lt(move _7, move _8);
+//! switchInt(move _6) -> [0: exit, otherwise: body];
+//! ```
+//!
+//! The temporaries are resolved back to the locals they were computed from,
+//! so that the comparison can be stated at a later point of the loop.
+
+use prusti_rustc_interface::middle::{
+    mir,
+    ty::{self, TyCtxt},
+};
+
+/// An operand of a loop guard comparison.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuardTerm<'tcx> {
+    /// The value of an integer local
+    Local(mir::Local),
+    /// The length of the slice or array at the given place
+    Len(mir::Place<'tcx>),
+}
+
+/// A comparison `left < right` or `left <= right` that holds whenever the
+/// loop guard lets the execution enter the loop body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GuardComparison<'tcx> {
+    /// Either `BinOp::Lt` or `BinOp::Le`
+    pub op: mir::BinOp,
+    pub left: GuardTerm<'tcx>,
+    pub right: GuardTerm<'tcx>,
+}
+
+/// Returns the comparison checked by the `SwitchInt` terminator of
+/// `guard_switch` on the edge that stays in the loop.
+///
+/// `loop_body` are the blocks of the loop, and `guard_blocks` are the blocks
+/// from the loop head up to the point at which the comparison is needed. The
+/// comparison is only returned if the locals that it mentions are not changed
+/// in `guard_blocks`, apart from the temporaries that compute the guard.
+pub fn compute_guard_comparison<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &mir::Body<'tcx>,
+    guard_switch: mir::BasicBlock,
+    loop_body: &[mir::BasicBlock],
+    guard_blocks: &[mir::BasicBlock],
+) -> Option<GuardComparison<'tcx>> {
+    let resolver = GuardResolver {
+        tcx,
+        body,
+        guard_blocks,
+    };
+    let mir::TerminatorKind::SwitchInt { discr, targets } = &body[guard_switch].terminator().kind
+    else {
+        return None;
+    };
+    let mut values = targets.iter();
+    let (Some((0, false_target)), None) = (values.next(), values.next()) else {
+        return None;
+    };
+    let holds = match (
+        loop_body.contains(&targets.otherwise()),
+        loop_body.contains(&false_target),
+    ) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => return None,
+    };
+    let Some(Definition::Rvalue(mir::Rvalue::BinaryOp(op, box (left, right)))) =
+        resolver.definition(discr.place()?.as_local()?)
+    else {
+        return None;
+    };
+    if !left.ty(body, tcx).is_integral() {
+        return None;
+    }
+    let left = resolver.resolve(left)?;
+    let right = resolver.resolve(right)?;
+    // Normalize to `left < right` or `left <= right`.
+    let (op, left, right) = match (*op, holds) {
+        (mir::BinOp::Lt, true) | (mir::BinOp::Ge, false) => (mir::BinOp::Lt, left, right),
+        (mir::BinOp::Le, true) | (mir::BinOp::Gt, false) => (mir::BinOp::Le, left, right),
+        (mir::BinOp::Gt, true) | (mir::BinOp::Le, false) => (mir::BinOp::Lt, right, left),
+        (mir::BinOp::Ge, true) | (mir::BinOp::Lt, false) => (mir::BinOp::Le, right, left),
+        _ => return None,
+    };
+    Some(GuardComparison { op, left, right })
+}
+
+struct GuardResolver<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a mir::Body<'tcx>,
+    guard_blocks: &'a [mir::BasicBlock],
+}
+
+/// How a local is computed in the guard blocks.
+enum Definition<'a, 'tcx> {
+    Rvalue(&'a mir::Rvalue<'tcx>),
+    SliceLen(&'a mir::Operand<'tcx>),
+}
+
+impl<'a, 'tcx> GuardResolver<'a, 'tcx> {
+    /// The places in the guard blocks that are assigned, or borrowed mutably,
+    /// together with the definition if the whole local is assigned.
+    fn writes(&self) -> impl Iterator<Item = (mir::Place<'tcx>, Option<Definition<'a, 'tcx>>)> {
+        let body = self.body;
+        let tcx = self.tcx;
+        let guard_blocks = self.guard_blocks;
+        guard_blocks.iter().flat_map(move |&bb| {
+            let bb_data = &body[bb];
+            let statement_writes = bb_data.statements.iter().filter_map(|stmt| {
+                let mir::StatementKind::Assign(box (place, rvalue)) = &stmt.kind else {
+                    return None;
+                };
+                Some((*place, Some(Definition::Rvalue(rvalue))))
+            });
+            let borrow_writes = bb_data
+                .statements
+                .iter()
+                .filter_map(|stmt| match &stmt.kind {
+                    mir::StatementKind::Assign(box (
+                        _,
+                        mir::Rvalue::Ref(_, mir::BorrowKind::Mut { .. }, place)
+                        | mir::Rvalue::AddressOf(_, place),
+                    )) => Some((*place, None)),
+                    _ => None,
+                });
+            let call_write = match &bb_data.terminator().kind {
+                mir::TerminatorKind::Call {
+                    func,
+                    args,
+                    destination,
+                    ..
+                } => {
+                    let is_slice_len = func.const_fn_def().map_or(false, |(def_id, _)| {
+                        tcx.def_path_str(def_id) == "core::slice::<impl [T]>::len"
+                    });
+                    let definition = match &args[..] {
+                        [arg] if is_slice_len => Some(Definition::SliceLen(arg)),
+                        _ => None,
+                    };
+                    Some((*destination, definition))
+                }
+                _ => None,
+            };
+            statement_writes.chain(borrow_writes).chain(call_write)
+        })
+    }
+
+    /// Whether `local` is not changed in the guard blocks.
+    fn is_stable(&self, local: mir::Local) -> bool {
+        self.writes().all(|(place, _)| place.local != local)
+    }
+
+    /// The unique definition of `local` in the guard blocks.
+    fn definition(&self, local: mir::Local) -> Option<Definition<'a, 'tcx>> {
+        let mut writes = self.writes().filter(|(place, _)| place.local == local);
+        match (writes.next(), writes.next()) {
+            (Some((place, definition)), None) if place.as_local().is_some() => definition,
+            _ => None,
+        }
+    }
+
+    fn resolve(&self, operand: &mir::Operand<'tcx>) -> Option<GuardTerm<'tcx>> {
+        let local = operand.place()?.as_local()?;
+        if self.is_stable(local) {
+            return self.body.local_decls[local]
+                .ty
+                .is_integral()
+                .then_some(GuardTerm::Local(local));
+        }
+        match self.definition(local)? {
+            Definition::Rvalue(mir::Rvalue::Use(operand)) => self.resolve(operand),
+            Definition::Rvalue(mir::Rvalue::Len(place)) => self.resolve_sequence(*place),
+            Definition::SliceLen(operand) => {
+                let reference = self.resolve_reference(operand)?;
+                self.resolve_sequence(self.tcx.mk_place_deref(reference))
+            }
+            _ => None,
+        }
+    }
+
+    /// Resolves a shared reference to a slice or array, which is either a
+    /// stable local or a reborrow of a stable place.
+    fn resolve_reference(&self, operand: &mir::Operand<'tcx>) -> Option<mir::Place<'tcx>> {
+        let local = operand.place()?.as_local()?;
+        if self.is_stable(local) {
+            return Some(local.into());
+        }
+        match self.definition(local)? {
+            Definition::Rvalue(mir::Rvalue::Use(operand)) => self.resolve_reference(operand),
+            Definition::Rvalue(mir::Rvalue::Ref(_, mir::BorrowKind::Shared, place)) => {
+                match place.as_ref() {
+                    mir::PlaceRef {
+                        local,
+                        projection: [mir::ProjectionElem::Deref],
+                    } => Some(local.into()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `place` if it is a stable local of array type, or the target of
+    /// a stable reference to a slice or array.
+    fn resolve_sequence(&self, place: mir::Place<'tcx>) -> Option<GuardTerm<'tcx>> {
+        if !self.is_stable(place.local) {
+            return None;
+        }
+        let is_sequence =
+            |ty: ty::Ty<'tcx>| matches!(ty.kind(), ty::TyKind::Array(..) | ty::TyKind::Slice(..));
+        let local_ty = self.body.local_decls[place.local].ty;
+        let supported = match place.projection.as_slice() {
+            [] => is_sequence(local_ty),
+            [mir::ProjectionElem::Deref] => match local_ty.kind() {
+                ty::TyKind::Ref(_, target_ty, _) => is_sequence(*target_ty),
+                _ => false,
+            },
+            _ => false,
+        };
+        supported.then_some(GuardTerm::Len(place))
+    }
+}
@@ -9,3 +9,6 @@
 
 pub mod allocation;
 pub mod initialization;
+pub mod intervals;
+pub mod leaks;
+pub mod loop_guards;
@@ -19,6 +19,7 @@ pub trait SplitAggregateAssignment<'tcx> {
     ///     _3.0 = move _1;
     ///     _3.1 = move _2;
     /// ```
+    /// The same holds for the aggregate assignment of a struct.
     ///
     /// Statements that are no assignments are returned untouched.
     fn split_assignment(
@@ -40,12 +41,7 @@ impl<'tcx> SplitAggregateAssignment<'tcx> for mir::Statement<'tcx> {
         };
 
         let atomic_assignments = match rhs {
-            mir::Rvalue::Aggregate(box kind, operands) => {
-                assert_eq!(
-                    kind,
-                    mir::AggregateKind::Tuple,
-                    "The only supported aggregates are tuples."
-                );
+            mir::Rvalue::Aggregate(box mir::AggregateKind::Tuple, operands) => {
                 let local = lhs.as_local().unwrap();
                 let items_ty = mir.local_decls[local].ty.tuple_items().unwrap();
                 operands
@@ -60,6 +56,28 @@ impl<'tcx> SplitAggregateAssignment<'tcx> for mir::Statement<'tcx> {
                     })
                     .collect()
             }
+            mir::Rvalue::Aggregate(
+                box mir::AggregateKind::Adt(adt_did, variant_index, substs, _, None),
+                operands,
+            ) if tcx.adt_def(adt_did).is_struct() => {
+                let local = lhs.as_local().unwrap();
+                let variant = &tcx.adt_def(adt_did).variants()[variant_index];
+                operands
+                    .into_iter()
+                    .zip(variant.fields.iter())
+                    .enumerate()
+                    .map(|(i, (rhs, field_def))| {
+                        let field = FieldIdx::from_usize(i);
+                        let ty = field_def.ty(tcx, substs);
+                        let lhs = tcx.mk_place_field(local.into(), field, ty);
+                        let rhs = mir::Rvalue::Use(rhs);
+                        (lhs, rhs)
+                    })
+                    .collect()
+            }
+            mir::Rvalue::Aggregate(..) => {
+                unreachable!("The only supported aggregates are tuples and structs.")
+            }
             mir::Rvalue::Use(_) | mir::Rvalue::Ref(_, _, _) => vec![(lhs, rhs)],
             // slice creation is ok
             mir::Rvalue::Cast(
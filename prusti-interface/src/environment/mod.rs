@@ -11,6 +11,7 @@ use rustc_middle::ty::GenericArgsRef;
 
 pub mod body;
 pub mod borrowck;
+mod collect_accepted_panics_visitor;
 mod collect_closure_defs_visitor;
 mod collect_prusti_spec_visitor;
 pub mod debug_utils;
@@ -99,7 +100,12 @@ impl<'tcx> Environment<'tcx> {
             .visit_all_item_likes_in_crate(&mut cl_visitor);
 
         let (mut procedures, types) = visitor.into_result();
-        procedures.extend(cl_visitor.get_closure_defs());
+        procedures.extend(
+            cl_visitor
+                .get_closure_defs()
+                .into_iter()
+                .filter(|def_id| !self.query.is_verification_skipped(def_id.expect_local())),
+        );
         (procedures, types)
     }
 
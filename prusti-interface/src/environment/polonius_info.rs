@@ -685,7 +685,7 @@ impl<'a, 'tcx: 'a> PoloniusInfo<'a, 'tcx> {
         //         def_path.to_filename_friendly_no_crate()
         //     ));
         // debug!("Renumber path: {:?}", renumber_path);
-        let place_regions = regions::load_place_regions(mir).unwrap();
+        let place_regions = regions::load_place_regions(tcx, mir).unwrap();
 
         let mut call_magic_wands = FxHashMap::default();
 
@@ -1,10 +1,11 @@
 use std::fmt::Debug;
 
-use crate::data::ProcedureDefId;
+use super::collect_accepted_panics_visitor::CollectAcceptedPanicsVisitor;
+use crate::{data::ProcedureDefId, utils::ItemConfigValue};
 use log::debug;
 use prusti_rustc_interface::{
     ast::ast::Attribute,
-    hir::hir_id::HirId,
+    hir::{hir_id::HirId, intravisit::Visitor},
     middle::{
         hir::map::Map,
         ty::{self, GenericArgsRef, ImplPolarity, ParamEnv, TraitPredicate, TyCtxt},
@@ -83,6 +84,76 @@ impl<'tcx> EnvQuery<'tcx> {
         crate::utils::has_prusti_attr(self.get_attributes(def_id.into_param()), name)
     }
 
+    /// Whether the verification of a local item is disabled by a `prusti::skip`
+    /// attribute on it or on an enclosing item, such as an `impl` block, a
+    /// module or the crate. The closest item that has a `prusti::skip` or a
+    /// `prusti::verify` attribute decides.
+    pub fn is_verification_skipped(self, def_id: LocalDefId) -> bool {
+        let mut current = Some(def_id);
+        while let Some(local_def_id) = current {
+            let attrs = self.get_local_attributes(local_def_id);
+            if crate::utils::has_prusti_attr(attrs, "skip") {
+                return true;
+            }
+            if crate::utils::has_prusti_attr(attrs, "verify")
+                || crate::utils::has_prusti_attr(attrs, "verified")
+            {
+                return false;
+            }
+            current = self.tcx.opt_local_parent(local_def_id);
+        }
+        false
+    }
+
+    /// The spans of the statements and expressions annotated with
+    /// `prusti::accept_panic` in the body of the function that contains
+    /// `def_id`, which may be a closure.
+    pub fn get_accepted_panic_spans(self, def_id: LocalDefId) -> Vec<Span> {
+        let root_def_id = self
+            .tcx
+            .typeck_root_def_id(def_id.to_def_id())
+            .expect_local();
+        let Some(body_id) = self.hir().maybe_body_owned_by(root_def_id) else {
+            return Vec::new();
+        };
+        let mut visitor = CollectAcceptedPanicsVisitor::new(self.hir());
+        visitor.visit_body(self.hir().body(body_id));
+        visitor.get_accepted_panic_spans()
+    }
+
+    /// Whether only the absence of panics is verified for a local item, either
+    /// because of the `panic_freedom_only` flag or because of a
+    /// `prusti::no_panic` attribute on it or on an enclosing item.
+    pub fn is_panic_freedom_only(self, def_id: LocalDefId) -> bool {
+        if prusti_common::config::panic_freedom_only() {
+            return true;
+        }
+        let mut current = Some(def_id);
+        while let Some(local_def_id) = current {
+            let attrs = self.get_local_attributes(local_def_id);
+            if crate::utils::has_prusti_attr(attrs, "no_panic") {
+                return true;
+            }
+            current = self.tcx.opt_local_parent(local_def_id);
+        }
+        false
+    }
+
+    /// The value given to `flag` with a `prusti::config(<flag> = <value>)`
+    /// attribute on a local item or on the closest enclosing item that sets
+    /// it, such as an `impl` block, a module or the crate.
+    pub fn item_config(self, def_id: DefId, flag: &str) -> Option<ItemConfigValue> {
+        let mut current = def_id.as_local();
+        while let Some(local_def_id) = current {
+            let attrs = self.get_local_attributes(local_def_id);
+            if let Some(value) = crate::utils::read_prusti_config_flag(attrs, flag) {
+                return Some(value);
+            }
+            current = self.tcx.opt_local_parent(local_def_id);
+        }
+        None
+    }
+
     /// Get the span of the given definition.
     pub fn get_def_span(self, def_id: impl IntoParam<DefId>) -> Span {
         self.tcx.def_span(def_id.into_param())
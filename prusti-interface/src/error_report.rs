@@ -0,0 +1,269 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Serializable summaries of Prusti errors, used for machine-readable output.
+
+use prusti_rustc_interface::{
+    errors::MultiSpan,
+    span::{source_map::SourceMap, Span},
+};
+use serde::Serialize;
+
+/// A source range resolved to a file name and 1-based lines and columns.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SpanReport {
+    pub file_name: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub line_end: usize,
+    pub column_end: usize,
+}
+
+impl SpanReport {
+    pub fn from_span(source_map: &SourceMap, span: Span) -> Self {
+        let lo = source_map.lookup_char_pos(span.lo());
+        let hi = source_map.lookup_char_pos(span.hi());
+        SpanReport {
+            file_name: lo.file.name.prefer_local().to_string(),
+            line_start: lo.line,
+            column_start: lo.col.0 + 1,
+            line_end: hi.line,
+            column_end: hi.col.0 + 1,
+        }
+    }
+
+    /// Resolves the primary spans of `span`, skipping dummy spans.
+    pub fn from_multispan(source_map: &SourceMap, span: &MultiSpan) -> Vec<Self> {
+        span.primary_spans()
+            .iter()
+            .filter(|span| !span.is_dummy())
+            .map(|&span| Self::from_span(source_map, span))
+            .collect()
+    }
+}
+
+/// A note or counterexample entry attached to an error.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NoteReport {
+    pub message: String,
+    pub spans: Vec<SpanReport>,
+}
+
+/// A `PrustiError` with all spans resolved, see `PrustiError::to_report`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ErrorReport {
+    /// The stable error code, e.g. `"P0009"`.
+    pub code: Option<&'static str>,
+    /// Either `"error"` or `"warning"`.
+    pub level: &'static str,
+    pub message: String,
+    pub spans: Vec<SpanReport>,
+    /// The specification clause or assertion that failed, if known.
+    pub failing_assertion: Vec<SpanReport>,
+    pub help: Option<String>,
+    pub notes: Vec<NoteReport>,
+    /// The values of variables in a failing execution, if counterexamples are
+    /// enabled.
+    pub counterexample: Vec<NoteReport>,
+}
+
+/// The outcome of verifying a single Viper program, which corresponds to a
+/// single Rust item.
+#[derive(Clone, Debug, Serialize)]
+pub struct VerificationReport {
+    /// Distinguishes Prusti's records from `rustc`'s JSON diagnostics, which
+    /// use the same field.
+    #[serde(rename = "$message_type")]
+    pub message_type: &'static str,
+    pub item: String,
+    pub success: bool,
+    pub time_ms: u128,
+    pub errors: Vec<ErrorReport>,
+}
+
+impl VerificationReport {
+    pub fn new(item: String, time_ms: u128, errors: Vec<ErrorReport>) -> Self {
+        VerificationReport {
+            message_type: "prusti_verification_result",
+            item,
+            success: errors.iter().all(|error| error.level != "error"),
+            time_ms,
+            errors,
+        }
+    }
+}
+
+/// The start or the end of the verification of a single Viper program, which
+/// is printed as soon as it happens, see `config::json_progress`.
+#[derive(Clone, Debug, Serialize)]
+pub struct VerificationEvent {
+    /// Distinguishes Prusti's records from `rustc`'s JSON diagnostics, which
+    /// use the same field.
+    #[serde(rename = "$message_type")]
+    pub message_type: &'static str,
+    /// Either `"begin"` or `"end"`.
+    pub event: &'static str,
+    pub item: String,
+    /// The span of the verified item, if the program corresponds to one.
+    pub span: Option<SpanReport>,
+    /// The result of the backend, only for `"end"` events: `"succeeded"`,
+    /// `"failed"`, `"timed out"` or `"aborted"`.
+    pub verdict: Option<&'static str>,
+    pub time_ms: Option<u128>,
+}
+
+impl VerificationEvent {
+    pub fn begin(item: String, span: Option<SpanReport>) -> Self {
+        VerificationEvent {
+            message_type: "prusti_verification_event",
+            event: "begin",
+            item,
+            span,
+            verdict: None,
+            time_ms: None,
+        }
+    }
+
+    pub fn end(
+        item: String,
+        span: Option<SpanReport>,
+        verdict: &'static str,
+        time_ms: u128,
+    ) -> Self {
+        VerificationEvent {
+            message_type: "prusti_verification_event",
+            event: "end",
+            item,
+            span,
+            verdict: Some(verdict),
+            time_ms: Some(time_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prusti_rustc_interface::span::{
+        create_default_session_globals_then, source_map::FilePathMapping, BytePos, FileName,
+    };
+
+    const SOURCE: &str = "fn main() {\n    assert!(false);\n}\n";
+
+    /// Resolves the span of `assert!(false)` in `SOURCE`.
+    fn assertion_span_report() -> SpanReport {
+        create_default_session_globals_then(|| {
+            let source_map = SourceMap::new(FilePathMapping::empty());
+            let file =
+                source_map.new_source_file(FileName::Custom("main.rs".into()), SOURCE.into());
+            let start = SOURCE.find("assert!").unwrap() as u32;
+            let end = SOURCE.find(';').unwrap() as u32;
+            let span = Span::with_root_ctxt(
+                file.start_pos + BytePos(start),
+                file.start_pos + BytePos(end),
+            );
+            SpanReport::from_span(&source_map, span)
+        })
+    }
+
+    fn assertion_error() -> ErrorReport {
+        ErrorReport {
+            code: Some("P0003"),
+            level: "error",
+            message: "[Prusti: verification error] the asserted expression might not hold"
+                .to_string(),
+            spans: vec![assertion_span_report()],
+            failing_assertion: vec![],
+            help: None,
+            notes: vec![],
+            counterexample: vec![NoteReport {
+                message: "counterexample for \"x\"".to_string(),
+                spans: vec![],
+            }],
+        }
+    }
+
+    #[test]
+    fn span_positions_are_one_based() {
+        assert_eq!(
+            assertion_span_report(),
+            SpanReport {
+                file_name: "<main.rs>".to_string(),
+                line_start: 2,
+                column_start: 5,
+                line_end: 2,
+                column_end: 19,
+            }
+        );
+    }
+
+    #[test]
+    fn verification_report_renders_as_one_json_object() {
+        let report = VerificationReport::new("main".to_string(), 42, vec![assertion_error()]);
+        assert_eq!(
+            serde_json::to_value(report).unwrap(),
+            serde_json::json!({
+                "$message_type": "prusti_verification_result",
+                "item": "main",
+                "success": false,
+                "time_ms": 42,
+                "errors": [{
+                    "code": "P0003",
+                    "level": "error",
+                    "message": "[Prusti: verification error] the asserted expression might not hold",
+                    "spans": [{
+                        "file_name": "<main.rs>",
+                        "line_start": 2,
+                        "column_start": 5,
+                        "line_end": 2,
+                        "column_end": 19,
+                    }],
+                    "failing_assertion": [],
+                    "help": null,
+                    "notes": [],
+                    "counterexample": [{
+                        "message": "counterexample for \"x\"",
+                        "spans": [],
+                    }],
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn warnings_do_not_fail_the_verification() {
+        let warning = ErrorReport {
+            level: "warning",
+            ..assertion_error()
+        };
+        assert!(VerificationReport::new("main".to_string(), 0, vec![warning]).success);
+        assert!(VerificationReport::new("main".to_string(), 0, vec![]).success);
+    }
+
+    #[test]
+    fn verification_events_render_as_json_lines() {
+        let begin = VerificationEvent::begin("main".to_string(), Some(assertion_span_report()));
+        assert_eq!(
+            serde_json::to_string(&begin).unwrap(),
+            "{\"$message_type\":\"prusti_verification_event\",\"event\":\"begin\",\
+            \"item\":\"main\",\"span\":{\"file_name\":\"<main.rs>\",\"line_start\":2,\
+            \"column_start\":5,\"line_end\":2,\"column_end\":19},\"verdict\":null,\
+            \"time_ms\":null}"
+        );
+        let end = VerificationEvent::end("main".to_string(), None, "failed", 42);
+        assert_eq!(
+            serde_json::to_value(end).unwrap(),
+            serde_json::json!({
+                "$message_type": "prusti_verification_event",
+                "event": "end",
+                "item": "main",
+                "span": null,
+                "verdict": "failed",
+                "time_ms": 42,
+            })
+        );
+    }
+}
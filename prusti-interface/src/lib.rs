@@ -23,7 +23,11 @@ extern crate rustc_type_ir;
 
 pub mod data;
 pub mod environment;
+pub mod error_report;
+pub mod semantic_hash;
 pub mod specs;
+pub mod trust_audit;
+pub mod unsupported_features;
 pub mod utils;
 
 pub use prusti_error::*;
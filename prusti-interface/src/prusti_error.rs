@@ -4,10 +4,17 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::environment::EnvDiagnostic;
+use crate::{
+    environment::EnvDiagnostic,
+    error_report::{ErrorReport, NoteReport, SpanReport},
+    unsupported_features::{UnsupportedFeature, UnsupportedPolicy},
+};
 use ::log::warn;
-use prusti_common::config;
-use prusti_rustc_interface::{errors::MultiSpan, span::Span};
+use prusti_common::{config, report::error_codes::ErrorCode};
+use prusti_rustc_interface::{
+    errors::MultiSpan,
+    span::{source_map::SourceMap, Span},
+};
 
 /// The Prusti message that will be reported to the user.
 ///
@@ -29,9 +36,13 @@ pub struct PrustiError {
     /// field should be removed.
     is_disabled: bool,
     message: String,
+    /// The stable code of the error category, shown as `error[Pxxxx]`.
+    code: Option<ErrorCode>,
     span: Box<MultiSpan>,
     help: Option<String>,
     notes: Vec<(String, Option<MultiSpan>)>,
+    /// The span of the specification clause or assertion that failed.
+    failing_assertion: Option<Box<MultiSpan>>,
 }
 /// Determines how a `PrustiError` is reported.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -61,9 +72,11 @@ impl PrustiError {
             kind: PrustiErrorKind::Error,
             is_disabled: false,
             message,
+            code: None,
             span: Box::new(span),
             help: None,
             notes: vec![],
+            failing_assertion: None,
         }
     }
 
@@ -86,15 +99,19 @@ impl PrustiError {
         error
     }
 
-    /// Report an unsupported feature of the verified Rust code (e.g. dereferencing raw pointers)
+    /// Report an unsupported feature of the verified Rust code (e.g. dereferencing raw pointers).
+    /// It is reported according to the policy of its category, see `UnsupportedFeature`.
     pub fn unsupported<S: ToString>(message: S, span: MultiSpan) -> Self {
         check_message(message.to_string());
+        let policy = UnsupportedFeature::of_message(&message.to_string()).policy();
         let mut error = PrustiError::new(
             format!("[Prusti: unsupported feature] {}", message.to_string()),
             span,
         );
-        if config::skip_unsupported_features() {
-            error.set_warning();
+        match policy {
+            UnsupportedPolicy::Error => {}
+            UnsupportedPolicy::Warn => error.set_warning(),
+            UnsupportedPolicy::Trust => error.kind = PrustiErrorKind::WarningOnError,
         }
         error
     }
@@ -149,6 +166,11 @@ impl PrustiError {
         self.kind = PrustiErrorKind::Warning;
     }
 
+    /// Set that this Prusti error should be reported as an error to the user
+    pub fn set_error(&mut self) {
+        self.kind = PrustiErrorKind::Error;
+    }
+
     pub fn is_error(&self) -> bool {
         matches!(self.kind, PrustiErrorKind::Error)
     }
@@ -160,6 +182,17 @@ impl PrustiError {
         self.is_disabled
     }
 
+    /// Set the error code under which the error is reported.
+    #[must_use]
+    pub fn set_code(mut self, code: ErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.code
+    }
+
     #[must_use]
     pub fn set_help<S: ToString>(mut self, message: S) -> Self {
         self.help = Some(message.to_string());
@@ -186,24 +219,56 @@ impl PrustiError {
             PrustiErrorKind::Error => env_diagnostic.span_err_with_help_and_notes(
                 *self.span,
                 &self.message,
+                self.code.map(ErrorCode::code),
                 &self.help,
                 &self.notes,
             ),
             PrustiErrorKind::Warning => env_diagnostic.span_warn_with_help_and_notes(
                 *self.span,
                 &self.message,
+                self.code.map(ErrorCode::code),
                 &self.help,
                 &self.notes,
             ),
             PrustiErrorKind::WarningOnError => env_diagnostic.span_warn_on_err_with_help_and_notes(
                 *self.span,
                 &self.message,
+                self.code.map(ErrorCode::code),
                 &self.help,
                 &self.notes,
             ),
         };
     }
 
+    /// Resolve all spans of the error for machine-readable output.
+    pub fn to_report(&self, source_map: &SourceMap) -> ErrorReport {
+        let (counterexample, notes) = self
+            .notes
+            .iter()
+            .map(|(message, opt_span)| NoteReport {
+                message: message.clone(),
+                spans: opt_span
+                    .as_ref()
+                    .map(|span| SpanReport::from_multispan(source_map, span))
+                    .unwrap_or_default(),
+            })
+            .partition(|note| note.message.starts_with("counterexample for"));
+        ErrorReport {
+            code: self.code.map(ErrorCode::code),
+            level: if self.is_error() { "error" } else { "warning" },
+            message: self.message.clone(),
+            spans: SpanReport::from_multispan(source_map, &self.span),
+            failing_assertion: self
+                .failing_assertion
+                .as_ref()
+                .map(|span| SpanReport::from_multispan(source_map, span))
+                .unwrap_or_default(),
+            help: self.help.clone(),
+            notes,
+            counterexample,
+        }
+    }
+
     /// Cancel the error.
     pub fn cancel(self) {
         assert!(self.is_disabled);
@@ -217,6 +282,7 @@ impl PrustiError {
         if let Some(span) = opt_span {
             let note = "the failing assertion is here".to_string();
             self.notes.push((note, Some(span.clone())));
+            self.failing_assertion = Some(Box::new(span.clone()));
         }
         self
     }
@@ -230,6 +296,7 @@ impl PrustiError {
             self.notes
                 .push(("the error originates here".to_string(), Some(*self.span)));
             *self.span = span.clone();
+            self.failing_assertion = Some(Box::new(span.clone()));
         }
         self
     }
@@ -0,0 +1,338 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Hashes of everything that the verification of a procedure depends on, used
+//! as keys of the verification cache. See `config::semantic_cache`.
+//!
+//! The hash of a procedure covers its MIR, its contract, the contracts of all
+//! functions that it (transitively) calls, the bodies of the pure functions
+//! and predicates that it uses, the type invariants and fields of the types
+//! that it mentions, and the versions of the crates that it depends on. Spans
+//! are not hashed, so that moving a function does not invalidate the verdicts
+//! of the functions around it. They only matter where they are part of the
+//! name of a type, as for closures. The Prusti attributes of each item and of
+//! its enclosing items are hashed too, since they can change how the item is
+//! verified.
+
+use crate::{
+    environment::Environment,
+    specs::typed::{DefSpecificationMap, ProcedureSpecification, ProcedureSpecificationKind},
+    trust_audit::called_functions,
+};
+use prusti_rustc_interface::{
+    ast,
+    ast_pretty::pprust,
+    hir::{def::DefKind, def_id::DefId},
+    middle::{
+        mir::{self, visit::Visitor},
+        ty,
+    },
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    collections::hash_map::{DefaultHasher, Entry},
+    hash::{Hash, Hasher},
+};
+
+/// Computes the semantic hash of each of `procedures`.
+pub fn procedure_hashes<'tcx>(
+    env: &Environment<'tcx>,
+    def_spec: &DefSpecificationMap,
+    procedures: &[DefId],
+) -> FxHashMap<DefId, u64> {
+    let hasher = SemanticHasher { env, def_spec };
+    let mut items = FxHashMap::default();
+    procedures
+        .iter()
+        .map(|&proc_id| (proc_id, hasher.procedure_hash(proc_id, &mut items)))
+        .collect()
+}
+
+/// The hash of each item on its own and the functions that it calls, keyed by
+/// the item and whether its body is relied upon.
+type ItemHashes = FxHashMap<(DefId, bool), (u64, Vec<DefId>)>;
+
+struct SemanticHasher<'a, 'tcx> {
+    env: &'a Environment<'tcx>,
+    def_spec: &'a DefSpecificationMap,
+}
+
+impl<'a, 'tcx> SemanticHasher<'a, 'tcx> {
+    fn procedure_hash(&self, proc_id: DefId, items: &mut ItemHashes) -> u64 {
+        let mut digests = Vec::new();
+        let mut visited = FxHashSet::default();
+        let mut queue = vec![(proc_id, !self.is_trusted(proc_id))];
+        while let Some(key) = queue.pop() {
+            if !visited.insert(key) {
+                continue;
+            }
+            if let Entry::Vacant(entry) = items.entry(key) {
+                entry.insert(self.item_hash(key.0, key.1));
+            }
+            let (digest, callees) = &items[&key];
+            digests.push((self.env.name.get_item_def_path(key.0), key.1, *digest));
+            queue.extend(
+                callees
+                    .iter()
+                    .map(|&callee| (callee, self.is_body_relied_upon(callee))),
+            );
+        }
+        digests.sort();
+        let mut hasher = DefaultHasher::new();
+        digests.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The bodies of called pure functions are part of the encoding of the
+    /// caller, unless they are trusted. The bodies of predicates are hashed
+    /// as part of their specification.
+    fn is_body_relied_upon(&self, def_id: DefId) -> bool {
+        self.is_pure(def_id) && !self.is_trusted(def_id)
+    }
+
+    fn is_pure(&self, def_id: DefId) -> bool {
+        self.spec(def_id).map_or(false, |spec| {
+            matches!(
+                spec.kind.extract_with_selective_replacement(),
+                Some(ProcedureSpecificationKind::Pure)
+            )
+        })
+    }
+
+    fn spec(&self, def_id: DefId) -> Option<&'a ProcedureSpecification> {
+        self.def_spec
+            .get_proc_spec(&def_id)
+            .map(|spec| &spec.base_spec)
+    }
+
+    fn is_trusted(&self, def_id: DefId) -> bool {
+        self.spec(def_id)
+            .and_then(|spec| spec.trusted.extract_inherit())
+            .unwrap_or(false)
+    }
+
+    /// Hashes the Prusti attributes of a local item and of the items that
+    /// enclose it, since they can change how the item is verified.
+    /// Attributes of the form `prusti::<name> = <value>` only link items to
+    /// their specifications. They are skipped, because their values are
+    /// generated anew in each compilation and the specifications are hashed
+    /// on their own.
+    fn hash_prusti_attrs(&self, def_id: DefId, hasher: &mut DefaultHasher) {
+        let mut current = def_id.as_local();
+        while let Some(local_def_id) = current {
+            for attr in self.env.query.get_local_attributes(local_def_id) {
+                let ast::AttrKind::Normal(normal_attr) = &attr.kind else {
+                    continue;
+                };
+                let item = &normal_attr.item;
+                if item.path.segments[0].ident.as_str() != "prusti"
+                    || matches!(item.args, ast::AttrArgs::Eq(..))
+                {
+                    continue;
+                }
+                pprust::path_to_string(&item.path).hash(hasher);
+                if let ast::AttrArgs::Delimited(args) = &item.args {
+                    pprust::tts_to_string(&args.tokens).hash(hasher);
+                }
+            }
+            current = self.env.tcx().opt_local_parent(local_def_id);
+        }
+    }
+
+    /// Hashes the contract of `def_id` and, if `with_body`, its body. Returns
+    /// the hash and the functions called by the hashed MIR bodies.
+    fn item_hash(&self, def_id: DefId, with_body: bool) -> (u64, Vec<DefId>) {
+        let tcx = self.env.tcx();
+        let mut body_hasher = BodyHasher {
+            env: self.env,
+            def_spec: self.def_spec,
+            hasher: DefaultHasher::new(),
+            callees: Vec::new(),
+            visited_adts: FxHashSet::default(),
+        };
+        if !def_id.is_local() {
+            tcx.crate_hash(def_id.krate).hash(&mut body_hasher.hasher);
+        }
+        let def_kind = tcx.def_kind(def_id);
+        def_kind.hash(&mut body_hasher.hasher);
+        self.hash_prusti_attrs(def_id, &mut body_hasher.hasher);
+        if matches!(def_kind, DefKind::Fn | DefKind::AssocFn | DefKind::Ctor(..)) {
+            let fn_sig = tcx.fn_sig(def_id).instantiate_identity();
+            format!("{fn_sig:?}").hash(&mut body_hasher.hasher);
+        }
+
+        let all_specs = self
+            .def_spec
+            .get_proc_spec(&def_id)
+            .into_iter()
+            .flat_map(|spec| {
+                std::iter::once(&spec.base_spec).chain(spec.specs_with_constraints.values())
+            });
+        for spec in all_specs {
+            (spec.source != def_id).hash(&mut body_hasher.hasher);
+            spec.trusted.extract_inherit().hash(&mut body_hasher.hasher);
+            spec.kind.is_pure().ok().hash(&mut body_hasher.hasher);
+            let mut spec_items = Vec::new();
+            spec_items.extend(spec.pres.extract_with_selective_replacement_iter());
+            spec_items.extend(spec.posts.extract_with_selective_replacement_iter());
+            if let Some(Some(terminates)) = spec.terminates.extract_with_selective_replacement() {
+                spec_items.push(terminates.to_def_id());
+            }
+            if let Some(pledges) = spec.pledges.extract_with_selective_replacement() {
+                for pledge in pledges {
+                    spec_items.extend(pledge.lhs);
+                    spec_items.push(pledge.rhs);
+                }
+            }
+            if let Some(ProcedureSpecificationKind::Predicate(Some(predicate_id))) =
+                spec.kind.extract_with_selective_replacement()
+            {
+                spec_items.push(*predicate_id);
+            }
+            spec_items.len().hash(&mut body_hasher.hasher);
+            for spec_id in spec_items {
+                let body = self.env.body.get_expression_body(
+                    spec_id,
+                    self.env.query.identity_substs(spec_id),
+                    def_id,
+                );
+                body_hasher.hash_body(spec_id, &body);
+            }
+        }
+
+        // Like in the trust audit, the bodies of closures and predicates are
+        // not hashed here: the former are hashed as part of the enclosing
+        // body, and the latter as part of the specification.
+        if let Some(local_def_id) = def_id.as_local()
+            && with_body
+            && matches!(def_kind, DefKind::Fn | DefKind::AssocFn)
+            && self.env.query.has_body(def_id)
+        {
+            let is_predicate = self.spec(def_id).map_or(false, |spec| {
+                matches!(
+                    spec.kind.extract_with_selective_replacement(),
+                    Some(ProcedureSpecificationKind::Predicate(_))
+                )
+            });
+            if !is_predicate {
+                let body = if self.is_pure(def_id) {
+                    self.env.body.get_pure_fn_body(
+                        def_id,
+                        self.env.query.identity_substs(def_id),
+                        def_id,
+                    )
+                } else {
+                    self.env.body.get_impure_fn_body_identity(local_def_id)
+                };
+                body_hasher.hash_body(def_id, &body);
+            }
+        }
+        (body_hasher.hasher.finish(), body_hasher.callees)
+    }
+}
+
+/// Hashes MIR bodies without their spans, together with the closures and
+/// promoted constants that they contain and the types that they mention.
+struct BodyHasher<'a, 'tcx> {
+    env: &'a Environment<'tcx>,
+    def_spec: &'a DefSpecificationMap,
+    hasher: DefaultHasher,
+    callees: Vec<DefId>,
+    visited_adts: FxHashSet<DefId>,
+}
+
+impl<'a, 'tcx> BodyHasher<'a, 'tcx> {
+    fn hash_body(&mut self, def_id: DefId, body: &mir::Body<'tcx>) {
+        self.callees.extend(called_functions(self.env, def_id, body));
+        for local_decl in &body.local_decls {
+            self.hash_ty(local_decl.ty);
+        }
+        for block in body.basic_blocks.iter() {
+            for statement in &block.statements {
+                format!("{:?}", statement.kind).hash(&mut self.hasher);
+            }
+            format!("{:?}", block.terminator().kind).hash(&mut self.hasher);
+        }
+        let mut nested = NestedBodies::default();
+        nested.visit_body(body);
+        for (promoted_def_id, promoted) in nested.promoted {
+            if !promoted_def_id.is_local() {
+                continue;
+            }
+            let promoted_body = &self.env.tcx().promoted_mir(promoted_def_id)[promoted];
+            self.hash_body(promoted_def_id, promoted_body);
+        }
+        for local in body.local_decls.iter() {
+            if let ty::TyKind::Closure(closure_def_id, substs) = local.ty.kind()
+                && closure_def_id.is_local()
+                && closure_def_id != &def_id
+            {
+                let closure_body =
+                    self.env
+                        .body
+                        .get_closure_body(*closure_def_id, substs, def_id);
+                self.hash_body(*closure_def_id, &closure_body);
+            }
+        }
+    }
+
+    /// Hashes `ty` and the fields and invariants of the ADTs that it mentions.
+    fn hash_ty(&mut self, ty: ty::Ty<'tcx>) {
+        format!("{ty:?}").hash(&mut self.hasher);
+        for arg in ty.walk() {
+            let Some(ty) = arg.as_type() else {
+                continue;
+            };
+            let ty::TyKind::Adt(adt_def, _) = ty.kind() else {
+                continue;
+            };
+            if !self.visited_adts.insert(adt_def.did()) {
+                continue;
+            }
+            let tcx = self.env.tcx();
+            for field in adt_def.all_fields() {
+                field.name.as_str().hash(&mut self.hasher);
+                self.hash_ty(tcx.type_of(field.did).instantiate_identity());
+            }
+            if let Some(type_spec) = self.def_spec.get_type_spec(&adt_def.did()) {
+                type_spec.trusted.extract_inherit().hash(&mut self.hasher);
+                for &invariant_id in type_spec
+                    .invariant
+                    .extract_with_selective_replacement_iter()
+                {
+                    let body = self.env.body.get_spec_body(
+                        invariant_id,
+                        self.env.query.identity_substs(invariant_id),
+                        adt_def.did(),
+                    );
+                    self.hash_body(invariant_id, &body);
+                }
+            }
+        }
+    }
+}
+
+/// The promoted constants used by a body.
+#[derive(Default)]
+struct NestedBodies {
+    promoted: Vec<(DefId, mir::Promoted)>,
+}
+
+impl<'tcx> Visitor<'tcx> for NestedBodies {
+    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, _location: mir::Location) {
+        if let mir::ConstantKind::Unevaluated(
+            mir::UnevaluatedConst {
+                def,
+                promoted: Some(promoted),
+                ..
+            },
+            _,
+        ) = constant.literal
+        {
+            self.promoted.push((def, promoted));
+        }
+    }
+}
@@ -45,6 +45,10 @@ impl CrossCrateSpecs {
         }
     }
 
+    /// Imports the specs of all dependencies, including indirect ones, since
+    /// the contracts of a direct dependency may refer to pure functions of its
+    /// own dependencies. The specs of direct dependencies are imported first;
+    /// an indirect dependency does not override them.
     #[tracing::instrument(level = "debug", skip_all)]
     fn import_specs(env: &mut Environment, def_spec: &mut DefSpecificationMap) {
         // TODO: atm one needs to write `extern crate extern_spec_lib` to import the specs
@@ -52,28 +56,40 @@ impl CrossCrateSpecs {
         // Otherwise the crate doesn't show up in `tcx.crates()`.  Is there some better way
         // to get dependency crates, which doesn't ignore unused ones? Maybe:
         // https://doc.rust-lang.org/stable/nightly-rustc/rustc_metadata/creader/struct.CrateMetadataRef.html#method.dependencies
-        for crate_num in env.tcx().crates(()) {
-            if let Some(extern_crate) = env.tcx().extern_crate(crate_num.as_def_id()) {
-                if extern_crate.is_direct() {
-                    let crate_name = env.tcx().crate_name(*crate_num);
-                    let crate_source = env.tcx().used_crate_source(*crate_num);
-                    let mut source = crate_source.paths().next().unwrap().clone();
-                    source.set_extension("specs");
-                    if source.is_file() {
-                        if let Err(e) =
-                            Self::import_from_file(env, def_spec, &source, crate_name.as_str())
-                        {
-                            PrustiError::internal(
-                                format!(
-                                    "error importing specs from file \"{}\": {}",
-                                    source.to_string_lossy(),
-                                    e
-                                ),
-                                DUMMY_SP.into(),
-                            )
-                            .emit(&env.diagnostic);
-                        }
-                    }
+        let mut dependencies: Vec<_> = env
+            .tcx()
+            .crates(())
+            .iter()
+            .filter_map(|&crate_num| {
+                let extern_crate = env.tcx().extern_crate(crate_num.as_def_id())?;
+                Some((!extern_crate.is_direct(), crate_num))
+            })
+            .collect();
+        dependencies.sort();
+        for (is_indirect, crate_num) in dependencies {
+            let crate_name = env.tcx().crate_name(crate_num);
+            let crate_source = env.tcx().used_crate_source(crate_num);
+            let Some(source) = crate_source.paths().next() else {
+                continue;
+            };
+            let source = source.with_extension("specs");
+            if source.is_file() {
+                if let Err(e) = Self::import_from_file(
+                    env,
+                    def_spec,
+                    &source,
+                    crate_name.as_str(),
+                    !is_indirect,
+                ) {
+                    PrustiError::internal(
+                        format!(
+                            "error importing specs from file \"{}\": {}",
+                            source.to_string_lossy(),
+                            e
+                        ),
+                        DUMMY_SP.into(),
+                    )
+                    .emit(&env.diagnostic);
                 }
             }
         }
@@ -99,6 +115,7 @@ impl CrossCrateSpecs {
         def_spec: &mut DefSpecificationMap,
         path: &path::PathBuf,
         crate_name: &str,
+        is_direct: bool,
     ) -> io::Result<()> {
         use std::io::Read;
         let mut data = Vec::new();
@@ -109,7 +126,7 @@ impl CrossCrateSpecs {
         let proc_specs = FxHashMap::decode(&mut decoder);
         let type_specs = FxHashMap::decode(&mut decoder);
         let mirs_of_specs = CrossCrateBodies::decode(&mut decoder);
-        def_spec.import_external(proc_specs, type_specs, env);
+        def_spec.import_external(proc_specs, type_specs, is_direct, env);
         env.body.import_external_bodies(mirs_of_specs);
         Ok(())
     }
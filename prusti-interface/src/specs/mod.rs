@@ -2,7 +2,7 @@ use crate::{
     environment::Environment,
     utils::{
         has_abstract_predicate_attr, has_extern_spec_attr, has_prusti_attr, has_to_model_fn_attr,
-        read_prusti_attr, read_prusti_attrs,
+        read_prusti_attr, read_prusti_attrs, read_prusti_config_attr, ItemConfigValue,
     },
     PrustiError,
 };
@@ -27,6 +27,7 @@ pub mod cross_crate;
 pub mod decoder;
 pub mod encoder;
 pub mod external;
+mod purity_inference;
 pub mod typed;
 
 use typed::SpecIdRef;
@@ -193,6 +194,26 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         }
     }
 
+    /// Whether a function without any specification is treated as pure,
+    /// because it is trivial and `infer_pure_functions` is enabled for it.
+    fn infers_purity(
+        &self,
+        fn_kind: intravisit::FnKind<'tcx>,
+        body_id: hir::BodyId,
+        local_id: LocalDefId,
+    ) -> bool {
+        let enabled = match self
+            .env
+            .query
+            .item_config(local_id.to_def_id(), "infer_pure_functions")
+        {
+            Some(ItemConfigValue::Bool(value)) => value,
+            _ => config::infer_pure_functions(),
+        };
+        enabled
+            && purity_inference::is_trivially_pure(self.env.query.tcx(), fn_kind, body_id, local_id)
+    }
+
     fn determine_extern_specs(&self, def_spec: &mut typed::DefSpecificationMap) {
         self.extern_resolver.check_errors(&self.env.diagnostic);
         for (extern_spec_decl, spec_id) in self.extern_resolver.extern_fn_map.iter() {
@@ -426,7 +447,10 @@ fn get_procedure_spec_ids(def_id: DefId, attrs: &[ast::Attribute]) -> Option<Pro
 
     let pure = has_prusti_attr(attrs, "pure");
     let trusted = has_prusti_attr(attrs, "trusted")
-        || (!is_predicate && config::opt_in_verification() && !has_prusti_attr(attrs, "verified"));
+        || (!is_predicate
+            && config::opt_in_verification()
+            && !has_prusti_attr(attrs, "verified")
+            && !has_prusti_attr(attrs, "verify"));
     let abstract_predicate = has_abstract_predicate_attr(attrs);
 
     if abstract_predicate || pure || trusted || !spec_id_refs.is_empty() {
@@ -449,6 +473,17 @@ impl<'a, 'tcx> intravisit::Visitor<'tcx> for SpecCollector<'a, 'tcx> {
         self.env.query.hir()
     }
 
+    /// Report the malformed arguments of `prusti::config(..)` attributes, which
+    /// are read with `EnvQuery::item_config`.
+    fn visit_attribute(&mut self, attr: &'tcx ast::Attribute) {
+        for argument in read_prusti_config_attr(attr).into_iter().flatten() {
+            if let Err((span, message)) = argument {
+                PrustiError::incorrect(message, MultiSpan::from_span(span))
+                    .emit(&self.env.diagnostic);
+            }
+        }
+    }
+
     fn visit_trait_item(&mut self, ti: &'tcx prusti_rustc_interface::hir::TraitItem) {
         intravisit::walk_trait_item(self, ti);
 
@@ -564,6 +599,18 @@ impl<'a, 'tcx> intravisit::Visitor<'tcx> for SpecCollector<'a, 'tcx> {
             // Collect procedure specifications
             if let Some(procedure_spec_ref) = get_procedure_spec_ids(def_id, attrs) {
                 self.procedure_specs.insert(local_id, procedure_spec_ref);
+            } else if !has_extern_spec_attr(attrs) && self.infers_purity(fn_kind, body_id, local_id)
+            {
+                debug!("Function {:?} is inferred to be pure", def_id);
+                self.procedure_specs.insert(
+                    local_id,
+                    ProcedureSpecRefs {
+                        spec_id_refs: vec![],
+                        pure: true,
+                        abstract_predicate: false,
+                        trusted: false,
+                    },
+                );
             }
 
             // Collect model type flag
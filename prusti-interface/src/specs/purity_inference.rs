@@ -0,0 +1,132 @@
+//! Detection of trivial functions that are treated as pure without a
+//! `#[pure]` attribute, see `config::infer_pure_functions`.
+
+use prusti_rustc_interface::{
+    hir::{
+        self,
+        def::{DefKind, Res},
+        def_id::LocalDefId,
+        intravisit::{self, FnKind, Visitor},
+    },
+    middle::ty::{self, TyCtxt},
+};
+
+/// Whether `local_id` is a trivial function that is known to terminate and to
+/// have no side effects: a safe, non-async function that is not a trait method
+/// implementation, whose arguments are shared references or `Copy` values,
+/// whose result is a `Copy` value that is not a reference or `()`, and whose
+/// body is a single expression without loops, mutations or calls other than
+/// constructors.
+pub(super) fn is_trivially_pure<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    fn_kind: FnKind<'tcx>,
+    body_id: hir::BodyId,
+    local_id: LocalDefId,
+) -> bool {
+    let header = match fn_kind {
+        FnKind::ItemFn(_, _, header) => header,
+        FnKind::Method(_, sig) => sig.header,
+        FnKind::Closure => return false,
+    };
+    if header.unsafety != hir::Unsafety::Normal || header.is_async() {
+        return false;
+    }
+    let def_id = local_id.to_def_id();
+    if tcx
+        .impl_of_method(def_id)
+        .and_then(|impl_id| tcx.trait_id_of_impl(impl_id))
+        .is_some()
+    {
+        return false;
+    }
+
+    let param_env = tcx.param_env(def_id);
+    let is_copy = |ty: ty::Ty<'tcx>| ty.is_copy_modulo_regions(tcx, param_env);
+    let sig = tcx.liberate_late_bound_regions(def_id, tcx.fn_sig(def_id).instantiate_identity());
+    let inputs_supported = sig.inputs().iter().all(|ty| match ty.kind() {
+        ty::TyKind::Ref(_, _, mutability) => mutability.is_not(),
+        _ => is_copy(*ty),
+    });
+    let output = sig.output();
+    if !inputs_supported || output.is_unit() || output.is_ref() || !is_copy(output) {
+        return false;
+    }
+
+    let mut checker = TrivialBodyChecker {
+        typeck_results: tcx.typeck_body(body_id),
+        is_trivial: true,
+    };
+    checker.visit_expr(tcx.hir().body(body_id).value);
+    checker.is_trivial
+}
+
+/// Checks that an expression only reads locals and constants, and combines
+/// them with operations on primitive types, conditionals and constructors.
+struct TrivialBodyChecker<'tcx> {
+    typeck_results: &'tcx ty::TypeckResults<'tcx>,
+    is_trivial: bool,
+}
+
+impl<'tcx> TrivialBodyChecker<'tcx> {
+    fn is_primitive(&self, expr: &hir::Expr) -> bool {
+        self.typeck_results.expr_ty(expr).is_primitive()
+    }
+
+    fn is_supported(&self, expr: &hir::Expr) -> bool {
+        match expr.kind {
+            hir::ExprKind::Lit(_)
+            | hir::ExprKind::Field(..)
+            | hir::ExprKind::Type(..)
+            | hir::ExprKind::DropTemps(_)
+            | hir::ExprKind::Let(_)
+            | hir::ExprKind::If(..)
+            | hir::ExprKind::Match(..)
+            | hir::ExprKind::Tup(_)
+            | hir::ExprKind::Struct(..)
+            | hir::ExprKind::AddrOf(hir::BorrowKind::Ref, hir::Mutability::Not, _) => true,
+            hir::ExprKind::Binary(_, left, right) => {
+                // Other operands use the trait implementations of the operators.
+                self.is_primitive(left) && self.is_primitive(right)
+            }
+            hir::ExprKind::Unary(hir::UnOp::Deref, operand) => {
+                self.typeck_results.expr_ty(operand).is_ref()
+            }
+            hir::ExprKind::Unary(_, operand) | hir::ExprKind::Cast(operand, _) => {
+                self.is_primitive(operand)
+            }
+            hir::ExprKind::Call(callee, _) => {
+                // Only the constructors of tuple structs and enum variants.
+                matches!(
+                    callee.kind,
+                    hir::ExprKind::Path(hir::QPath::Resolved(_, path))
+                        if matches!(path.res, Res::Def(DefKind::Ctor(..), _))
+                )
+            }
+            hir::ExprKind::Block(block, _) => {
+                block.stmts.is_empty()
+                    && block.expr.is_some()
+                    && block.rules == hir::BlockCheckMode::DefaultBlock
+            }
+            hir::ExprKind::Path(hir::QPath::Resolved(_, path)) => matches!(
+                path.res,
+                Res::Local(_)
+                    | Res::SelfCtor(_)
+                    | Res::Def(DefKind::Const | DefKind::AssocConst | DefKind::Ctor(..), _)
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for TrivialBodyChecker<'tcx> {
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        if !self.is_trivial {
+            return;
+        }
+        if !self.is_supported(expr) {
+            self.is_trivial = false;
+            return;
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
@@ -110,10 +110,17 @@ impl DefSpecificationMap {
         (specs, pure_fns, predicates)
     }
 
+    /// Adds the specs exported by a dependency. A spec of the local crate,
+    /// such as an `#[extern_spec]` for a std function, takes precedence over
+    /// the specs of dependencies, including the ones bundled with
+    /// `prusti-contracts`. Other duplicate specs are reported as errors if the
+    /// dependency is direct, and are ignored otherwise, i.e. the specs
+    /// imported first win.
     pub(crate) fn import_external(
         &mut self,
         proc_specs: FxHashMap<DefId, SpecGraph<ProcedureSpecification>>,
         type_specs: FxHashMap<DefId, TypeSpecification>,
+        is_direct: bool,
         env: &Environment,
     ) {
         let duplicate_error = |item_id, spec_id_a: DefId, spec_id_b: DefId| {
@@ -132,12 +139,22 @@ impl DefSpecificationMap {
             .emit(&env.diagnostic)
         };
         for (k, v) in proc_specs {
+            if let Some(existing) = self.proc_specs.get(&k) {
+                if !is_direct || existing.base_spec.source.is_local() {
+                    continue;
+                }
+            }
             if let Some(other) = self.proc_specs.insert(k, v) {
                 let v = self.proc_specs.get(&k).unwrap();
                 duplicate_error(k, other.base_spec.source, v.base_spec.source);
             }
         }
         for (k, v) in type_specs {
+            if let Some(existing) = self.type_specs.get(&k) {
+                if !is_direct || existing.source.is_local() {
+                    continue;
+                }
+            }
             if let Some(other) = self.type_specs.insert(k, v) {
                 let v = self.type_specs.get(&k).unwrap();
                 duplicate_error(k, other.source, v.source);
@@ -0,0 +1,205 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Collection of the trust assumptions that the verification of a set of entry
+//! points depends on, i.e. the trusted computing base that a reviewer has to
+//! audit manually. See `config::trust_audit`.
+
+use crate::{
+    environment::Environment,
+    specs::typed::{DefSpecificationMap, ProcedureSpecificationKind},
+};
+use prusti_rustc_interface::{
+    hir::{def::DefKind, def_id::DefId},
+    middle::{mir, ty},
+    span::Span,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TrustAssumptionKind {
+    /// A `#[trusted]` function, whose body is not verified.
+    Trusted,
+    /// A `prusti_assume!(..)`, whose condition is assumed without proof.
+    Assumption,
+    /// An `#[extern_spec]`, whose contract is assumed to hold for the
+    /// external function.
+    ExternSpec,
+    /// A pure function whose body is not verified, so its postconditions are
+    /// used as axioms.
+    AxiomatizedFunction,
+    /// A function excluded from verification with `prusti::skip`, whose
+    /// contract is relied upon without being verified.
+    Skipped,
+    /// A statement annotated with `prusti::accept_panic`, whose panics are
+    /// assumed to be unreachable.
+    AcceptedPanic,
+}
+
+impl fmt::Display for TrustAssumptionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TrustAssumptionKind::Trusted => "trusted function",
+            TrustAssumptionKind::Assumption => "prusti_assume! in",
+            TrustAssumptionKind::ExternSpec => "extern_spec",
+            TrustAssumptionKind::AxiomatizedFunction => "axiomatized pure function",
+            TrustAssumptionKind::Skipped => "skipped function",
+            TrustAssumptionKind::AcceptedPanic => "prusti::accept_panic in",
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TrustAssumption {
+    pub kind: TrustAssumptionKind,
+    /// The function that is trusted, or that contains the assumption.
+    pub item: DefId,
+    /// The location of the assumption, e.g. the `prusti_assume!` or the
+    /// `#[extern_spec]` block.
+    pub span: Span,
+}
+
+/// Collects the trust assumptions of `entry_points` and of all functions that
+/// they transitively call, either in their body or in their specification.
+pub fn collect_trust_assumptions<'tcx>(
+    env: &Environment<'tcx>,
+    def_spec: &DefSpecificationMap,
+    entry_points: &[DefId],
+) -> Vec<TrustAssumption> {
+    let tcx = env.tcx();
+    let mut assumptions_by_item: FxHashMap<DefId, Vec<DefId>> = FxHashMap::default();
+    for &assumption_id in def_spec.prusti_assumptions.keys() {
+        assumptions_by_item
+            .entry(tcx.typeck_root_def_id(assumption_id))
+            .or_default()
+            .push(assumption_id);
+    }
+
+    let mut result = Vec::new();
+    let mut visited = FxHashSet::default();
+    let mut queue = entry_points.to_vec();
+    while let Some(def_id) = queue.pop() {
+        if !visited.insert(def_id) {
+            continue;
+        }
+        let spec = def_spec.get_proc_spec(&def_id).map(|spec| &spec.base_spec);
+        let is_extern_spec = spec.map_or(false, |spec| spec.source != def_id);
+        let is_trusted = spec
+            .and_then(|spec| spec.trusted.extract_inherit())
+            .unwrap_or(false);
+        let is_pure = spec
+            .and_then(|spec| spec.kind.is_pure().ok())
+            .unwrap_or(false);
+        let is_predicate = spec.map_or(false, |spec| {
+            matches!(
+                spec.kind.extract_with_selective_replacement(),
+                Some(ProcedureSpecificationKind::Predicate(_))
+            )
+        });
+        let span = env
+            .query
+            .get_def_span(spec.map_or(def_id, |spec| spec.source));
+        let kind = if is_pure && (is_trusted || is_extern_spec) {
+            Some(TrustAssumptionKind::AxiomatizedFunction)
+        } else if is_extern_spec {
+            Some(TrustAssumptionKind::ExternSpec)
+        } else if is_trusted {
+            Some(TrustAssumptionKind::Trusted)
+        } else if let Some(local_def_id) = def_id.as_local()
+            && env.query.is_verification_skipped(local_def_id)
+        {
+            Some(TrustAssumptionKind::Skipped)
+        } else {
+            None
+        };
+        if let Some(kind) = kind {
+            result.push(TrustAssumption {
+                kind,
+                item: def_id,
+                span,
+            });
+        }
+        for &assumption_id in assumptions_by_item.get(&def_id).into_iter().flatten() {
+            result.push(TrustAssumption {
+                kind: TrustAssumptionKind::Assumption,
+                item: def_id,
+                span: env.query.get_def_span(assumption_id),
+            });
+        }
+        if let Some(local_def_id) = def_id.as_local()
+            && tcx.typeck_root_def_id(def_id) == def_id
+            && !is_trusted
+        {
+            for span in env.query.get_accepted_panic_spans(local_def_id) {
+                result.push(TrustAssumption {
+                    kind: TrustAssumptionKind::AcceptedPanic,
+                    item: def_id,
+                    span,
+                });
+            }
+        }
+
+        // Functions used in the specification are relied upon even if the
+        // function itself is trusted.
+        if let Some(spec) = spec {
+            let spec_items = spec
+                .pres
+                .extract_with_selective_replacement_iter()
+                .chain(spec.posts.extract_with_selective_replacement_iter());
+            for &spec_id in spec_items {
+                if spec_id.is_local() {
+                    let body =
+                        env.body
+                            .get_spec_body(spec_id, env.query.identity_substs(spec_id), def_id);
+                    queue.extend(called_functions(env, spec_id, &body));
+                }
+            }
+        }
+        // Closures are skipped, because their MIR is not available separately,
+        // and so are predicates, whose Rust body is a placeholder.
+        if let Some(local_def_id) = def_id.as_local()
+            && matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+            && !is_trusted
+            && !is_predicate
+            && env.query.has_body(def_id)
+        {
+            let body = if is_pure {
+                env.body
+                    .get_pure_fn_body(def_id, env.query.identity_substs(def_id), def_id)
+            } else {
+                env.body.get_impure_fn_body_identity(local_def_id)
+            };
+            queue.extend(called_functions(env, def_id, &body));
+        }
+    }
+    result.sort_by_key(|assumption| (assumption.kind, assumption.span));
+    result.dedup_by_key(|assumption| (assumption.kind, assumption.item, assumption.span));
+    result
+}
+
+/// The functions called in `body`, with trait methods resolved to their
+/// implementation where possible.
+pub(crate) fn called_functions<'tcx>(
+    env: &Environment<'tcx>,
+    caller_def_id: DefId,
+    body: &mir::Body<'tcx>,
+) -> Vec<DefId> {
+    body.basic_blocks
+        .iter()
+        .filter_map(|block| match &block.terminator().kind {
+            mir::TerminatorKind::Call { func, .. } => match func.ty(body, env.tcx()).kind() {
+                ty::TyKind::FnDef(called_def_id, call_substs) => Some(
+                    env.query
+                        .resolve_method_call(caller_def_id, *called_def_id, call_substs)
+                        .0,
+                ),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
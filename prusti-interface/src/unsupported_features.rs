@@ -0,0 +1,136 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The categories of the Rust features that Prusti does not support, and how
+//! each of them is reported, see `config::unsupported_feature_policy`.
+
+use prusti_common::config;
+
+/// A category of unsupported features.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsupportedFeature {
+    /// Closures and calls of closures.
+    Closures,
+    /// Raw pointers, unions, thread-local storage and other features that
+    /// usually need `unsafe` code.
+    Unsafe,
+    /// Trait objects, i.e. `dyn Trait` types and unsizing casts.
+    TraitObjects,
+    /// Iterators and generators.
+    Iterators,
+    /// Borrows and loans created in loops.
+    Loops,
+    /// Any other unsupported feature.
+    Other,
+}
+
+impl UnsupportedFeature {
+    pub const ALL: [UnsupportedFeature; 6] = [
+        UnsupportedFeature::Closures,
+        UnsupportedFeature::Unsafe,
+        UnsupportedFeature::TraitObjects,
+        UnsupportedFeature::Iterators,
+        UnsupportedFeature::Loops,
+        UnsupportedFeature::Other,
+    ];
+
+    /// The name of the category in `config::unsupported_feature_policy`.
+    pub fn name(self) -> &'static str {
+        match self {
+            UnsupportedFeature::Closures => "closures",
+            UnsupportedFeature::Unsafe => "unsafe",
+            UnsupportedFeature::TraitObjects => "trait_objects",
+            UnsupportedFeature::Iterators => "iterators",
+            UnsupportedFeature::Loops => "loops",
+            UnsupportedFeature::Other => "other",
+        }
+    }
+
+    /// The category of the unsupported feature reported with `message`.
+    pub fn of_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|word| message.contains(word));
+        if mentions(&["closure"]) {
+            UnsupportedFeature::Closures
+        } else if mentions(&[
+            "raw pointer",
+            "raw address",
+            "union",
+            "unsafe",
+            "thread-local",
+            "inline assembly",
+        ]) {
+            UnsupportedFeature::Unsafe
+        } else if mentions(&["dynamic", "dyn ", "trait object", "unsizing"]) {
+            UnsupportedFeature::TraitObjects
+        } else if mentions(&["iterator", "generator"]) {
+            UnsupportedFeature::Iterators
+        } else if mentions(&["loop"]) {
+            UnsupportedFeature::Loops
+        } else {
+            UnsupportedFeature::Other
+        }
+    }
+
+    /// How the unsupported features of this category are reported.
+    pub fn policy(self) -> UnsupportedPolicy {
+        let mut default = None;
+        for entry in config::unsupported_feature_policy() {
+            let (category, policy) = entry.split_once('=').unwrap_or_else(|| {
+                panic!(
+                    "Invalid entry `{entry}` of the `unsupported_feature_policy` flag, \
+                    expected `<category>=<policy>`"
+                )
+            });
+            let category = category.trim();
+            let policy = UnsupportedPolicy::parse(policy.trim()).unwrap_or_else(|| {
+                panic!(
+                    "Invalid policy `{policy}` in the `unsupported_feature_policy` flag, \
+                    expected `error`, `warn` or `trust`"
+                )
+            });
+            if category == self.name() {
+                return policy;
+            } else if category == "default" {
+                default = Some(policy);
+            } else if !Self::ALL.iter().any(|feature| feature.name() == category) {
+                panic!(
+                    "Unknown category `{category}` in the `unsupported_feature_policy` flag, \
+                    expected `default` or one of: {}",
+                    Self::ALL.map(Self::name).join(", ")
+                );
+            }
+        }
+        default.unwrap_or(if config::skip_unsupported_features() {
+            UnsupportedPolicy::Warn
+        } else {
+            UnsupportedPolicy::Error
+        })
+    }
+}
+
+/// How an unsupported feature is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsupportedPolicy {
+    /// Report an error.
+    Error,
+    /// Report a warning and skip the verification of the affected item.
+    Warn,
+    /// Assume that the executions reaching the feature are correct. It is only
+    /// reported when verification fails for another reason.
+    Trust,
+}
+
+impl UnsupportedPolicy {
+    fn parse(policy: &str) -> Option<Self> {
+        match policy {
+            "error" => Some(UnsupportedPolicy::Error),
+            "warn" => Some(UnsupportedPolicy::Warn),
+            "trust" => Some(UnsupportedPolicy::Trust),
+            _ => None,
+        }
+    }
+}
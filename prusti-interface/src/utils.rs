@@ -10,6 +10,7 @@ use prusti_rustc_interface::{
     ast::ast,
     data_structures::fx::FxHashSet,
     middle::{mir, ty::TyCtxt},
+    span::{symbol::sym, Span},
 };
 use std::borrow::Borrow;
 
@@ -208,6 +209,14 @@ pub fn has_prusti_attr(attrs: &[ast::Attribute], name: &str) -> bool {
     })
 }
 
+/// Check if the attributes mark an item generated by rustc's test harness,
+/// i.e. the generated `main` or the descriptor constant of a `#[test]` function.
+pub fn has_test_harness_attr(attrs: &[ast::Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|attr| attr.has_name(sym::rustc_main) || attr.has_name(sym::rustc_test_marker))
+}
+
 /// Check if `prusti::spec_only` is among the attributes.
 pub fn has_spec_only_attr(attrs: &[ast::Attribute]) -> bool {
     has_prusti_attr(attrs, "spec_only")
@@ -279,3 +288,151 @@ pub fn read_prusti_attrs<T: Borrow<ast::Attribute>>(attr_name: &str, attrs: &[T]
 pub fn read_prusti_attr<T: Borrow<ast::Attribute>>(attr_name: &str, attrs: &[T]) -> Option<String> {
     read_prusti_attrs(attr_name, attrs).pop()
 }
+
+/// Read the integer argument of a Prusti attribute (e.g. `prusti::<attr_name>(42)`).
+/// Returns `Some(None)` if the attribute is present, but its argument is not a
+/// single integer.
+pub fn read_prusti_attr_int(attr_name: &str, attrs: &[ast::Attribute]) -> Option<Option<u128>> {
+    let attr = attrs.iter().rev().find(|attr| match &attr.kind {
+        ast::AttrKind::Normal(normal_attr) => {
+            let segments = &normal_attr.item.path.segments;
+            segments.len() == 2
+                && segments[0].ident.as_str() == "prusti"
+                && segments[1].ident.as_str() == attr_name
+        }
+        _ => false,
+    })?;
+    let value = match attr.meta_item_list().as_deref() {
+        Some([argument]) => match argument.lit().map(|lit| &lit.kind) {
+            Some(ast::LitKind::Int(value, _)) => Some(*value),
+            _ => None,
+        },
+        _ => None,
+    };
+    Some(value)
+}
+
+/// Read the boolean argument of a Prusti attribute (e.g. `prusti::<attr_name>(true)`).
+/// Returns `Some(None)` if the attribute is present, but its argument is not a
+/// single boolean.
+pub fn read_prusti_attr_bool(attr_name: &str, attrs: &[ast::Attribute]) -> Option<Option<bool>> {
+    let attr = attrs.iter().rev().find(|attr| match &attr.kind {
+        ast::AttrKind::Normal(normal_attr) => {
+            let segments = &normal_attr.item.path.segments;
+            segments.len() == 2
+                && segments[0].ident.as_str() == "prusti"
+                && segments[1].ident.as_str() == attr_name
+        }
+        _ => false,
+    })?;
+    let value = match attr.meta_item_list().as_deref() {
+        Some([argument]) => match argument.lit().map(|lit| &lit.kind) {
+            Some(ast::LitKind::Bool(value)) => Some(*value),
+            _ => None,
+        },
+        _ => None,
+    };
+    Some(value)
+}
+
+/// The configuration flags that can be set for single items and modules with
+/// `prusti::config(<flag> = <value>)`, and whether their value is a boolean
+/// (otherwise it is an integer).
+pub const ITEM_CONFIG_FLAGS: [(&str, bool); 7] = [
+    ("check_overflows", true),
+    ("skip_unsupported_features", true),
+    ("infer_pure_functions", true),
+    ("unsafe_core_proof", true),
+    ("time_limit", false),
+    ("smt_rlimit", false),
+    ("smt_random_seed", false),
+];
+
+/// A value given to a configuration flag with `prusti::config(<flag> = <value>)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemConfigValue {
+    Bool(bool),
+    Int(u128),
+}
+
+/// Read the `<flag> = <value>` arguments of a `prusti::config(..)` attribute.
+/// Returns `None` if `attr` is another attribute. Each argument that does not
+/// set one of the `ITEM_CONFIG_FLAGS` to a value of the right type is returned
+/// as an error message with its span.
+#[allow(clippy::type_complexity)]
+pub fn read_prusti_config_attr(
+    attr: &ast::Attribute,
+) -> Option<Vec<Result<(&'static str, ItemConfigValue), (Span, String)>>> {
+    let ast::AttrKind::Normal(normal_attr) = &attr.kind else {
+        return None;
+    };
+    let segments = &normal_attr.item.path.segments;
+    if segments.len() != 2
+        || segments[0].ident.as_str() != "prusti"
+        || segments[1].ident.as_str() != "config"
+    {
+        return None;
+    }
+    let Some(arguments) = attr.meta_item_list() else {
+        return Some(vec![Err((
+            attr.span,
+            "the `prusti::config` attribute expects a list of flags, e.g. \
+            `prusti::config(check_overflows = false)`"
+                .to_string(),
+        ))]);
+    };
+    let values = arguments
+        .iter()
+        .map(|argument| {
+            let span = argument.span();
+            let Some((flag, literal)) = argument.name_value_literal() else {
+                return Err((
+                    span,
+                    "expected an argument of the form `<flag> = <value>`".to_string(),
+                ));
+            };
+            let Some(&(flag, is_bool)) = ITEM_CONFIG_FLAGS
+                .iter()
+                .find(|(name, _)| *name == flag.as_str())
+            else {
+                let flags: Vec<_> = ITEM_CONFIG_FLAGS
+                    .iter()
+                    .map(|(name, _)| format!("`{name}`"))
+                    .collect();
+                return Err((
+                    span,
+                    format!(
+                        "the flag `{flag}` cannot be set for single items, only {} can",
+                        flags.join(", ")
+                    ),
+                ));
+            };
+            match (&literal.kind, is_bool) {
+                (ast::LitKind::Bool(value), true) => Ok((flag, ItemConfigValue::Bool(*value))),
+                (ast::LitKind::Int(value, _), false) => Ok((flag, ItemConfigValue::Int(*value))),
+                _ => Err((
+                    span,
+                    format!(
+                        "the flag `{flag}` expects {}",
+                        if is_bool { "a boolean" } else { "an integer" }
+                    ),
+                )),
+            }
+        })
+        .collect();
+    Some(values)
+}
+
+/// The value given to `flag` by the last `prusti::config(<flag> = <value>)`
+/// attribute among `attrs` that sets it. Malformed arguments are ignored, they
+/// are reported when collecting the specifications.
+pub fn read_prusti_config_flag(attrs: &[ast::Attribute], flag: &str) -> Option<ItemConfigValue> {
+    attrs
+        .iter()
+        .filter_map(read_prusti_config_attr)
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|(name, _)| *name == flag)
+        .map(|(_, value)| value)
+        .last()
+}
@@ -5,8 +5,19 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 #![feature(let_chains)]
 
-use prusti_utils::{config, launch};
-use std::{env, fs, io, path::PathBuf, process::Command};
+use prusti_utils::{
+    config, launch,
+    report::{sarif, user},
+};
+use serde_json::Value;
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader},
+    net::{SocketAddr, TcpStream},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, SystemTime},
+};
 
 fn main() {
     if let Err(code) = process(env::args().skip(1)) {
@@ -30,41 +41,114 @@ where
     let args = args.skip_while(|arg| arg == "prusti");
     // Remove the "-- -Pflag" arguments since these won't apply to `cargo check`.
     // They have already been loaded (and the Category B flags are used below).
-    let args = args.take_while(|arg| arg != "--");
+    let args: Vec<String> = args.take_while(|arg| arg != "--").collect();
+    let (args, only) = split_option_arg(args, "--only");
+    let (mut args, only_file) = split_option_arg(args, "--only-file");
+    let check_only = launch::take_no_verify_arg(&mut args);
+
+    if let Some(code) = launch::find_prusti_explain_arg(&args) {
+        return launch::explain_error_code(code);
+    }
 
     // Category B flags (see dev-guide flags table):
     let cargo_path = config::cargo_path();
     let command = config::cargo_command();
 
-    let features = if launch::enable_prusti_feature(&cargo_path) && !config::be_rustc() {
+    let features = if launch::enable_prusti_feature(&cargo_path, &args) && !config::be_rustc() {
         ["--features", "prusti-contracts/prusti"].iter()
     } else {
         [].iter()
     };
+    let tests = if config::verify_tests() {
+        ["--tests"].iter()
+    } else {
+        [].iter()
+    };
     let cargo_target = env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
     let cargo_target: PathBuf = [cargo_target, "verify".to_string()].into_iter().collect();
-    let exit_status = Command::new(cargo_path)
+    let mut dependency_specs: Vec<String> = config::dependency_specs()
+        .into_iter()
+        .map(|(crate_name, path)| format!("{crate_name}={}", path.to_string_lossy()))
+        .collect();
+    dependency_specs.extend(packaged_dependency_specs(&cargo_path, &args));
+    let server_address = if config::daemon()
+        && config::server_address().is_none()
+        && config::server_addresses().is_empty()
+    {
+        daemon_address(&cargo_target)
+            .map_err(|error| {
+                user::message(format!(
+                    "Could not start the verification daemon, verifying without it: {error}"
+                ))
+            })
+            .ok()
+    } else {
+        None
+    };
+    let cargo = || {
+        let mut cargo = Command::new(&cargo_path);
+        cargo
+            .env("RUST_TOOLCHAIN", launch::get_rust_toolchain_channel())
+            .env("RUSTUP_TOOLCHAIN", launch::get_rust_toolchain_channel())
+            .env("RUSTC", &prusti_rustc_path)
+            .env("PRUSTI_CARGO", "")
+            .env("CARGO_TARGET_DIR", &cargo_target)
+            // Category B flags (update the docs if any more are added):
+            .env("PRUSTI_BE_RUSTC", config::be_rustc().to_string())
+            .env(
+                "PRUSTI_NO_VERIFY_DEPS",
+                config::no_verify_deps().to_string(),
+            )
+            .env("PRUSTI_DEPENDENCY_SPECS", dependency_specs.join(" "))
+            .envs(only.iter().map(|path| ("PRUSTI_SELECTIVE_VERIFY", path)))
+            .envs(
+                only_file
+                    .iter()
+                    .map(|path| ("PRUSTI_SELECTIVE_VERIFY_FILE", path)),
+            )
+            .envs(check_only.then_some(("PRUSTI_CHECK_ONLY", "true")))
+            .envs(
+                server_address
+                    .iter()
+                    .map(|address| ("PRUSTI_SERVER_ADDRESS", address)),
+            )
+            // Category A* flags:
+            .env("DEFAULT_PRUSTI_QUIET", "true")
+            .env("DEFAULT_PRUSTI_FULL_COMPILATION", "true")
+            .env("DEFAULT_PRUSTI_LOG_DIR", cargo_target.join("log"))
+            .env("DEFAULT_PRUSTI_CACHE_PATH", cargo_target.join("cache.bin"));
+        cargo
+    };
+    let start_time = SystemTime::now();
+    let mut exit_status = cargo()
         .arg(&command)
-        .args(features)
-        .args(args)
-        .env("RUST_TOOLCHAIN", launch::get_rust_toolchain_channel())
-        .env("RUSTUP_TOOLCHAIN", launch::get_rust_toolchain_channel())
-        .env("RUSTC", prusti_rustc_path)
-        .env("PRUSTI_CARGO", "")
-        .env("CARGO_TARGET_DIR", &cargo_target)
-        // Category B flags (update the docs if any more are added):
-        .env("PRUSTI_BE_RUSTC", config::be_rustc().to_string())
-        .env(
-            "PRUSTI_NO_VERIFY_DEPS",
-            config::no_verify_deps().to_string(),
-        )
-        // Category A* flags:
-        .env("DEFAULT_PRUSTI_QUIET", "true")
-        .env("DEFAULT_PRUSTI_FULL_COMPILATION", "true")
-        .env("DEFAULT_PRUSTI_LOG_DIR", cargo_target.join("log"))
-        .env("DEFAULT_PRUSTI_CACHE_PATH", cargo_target.join("cache.bin"))
+        .args(features.clone())
+        .args(tests)
+        .args(&args)
         .status()
         .expect("could not run cargo");
+    if exit_status.success() && config::verify_doctests() && !check_only {
+        // rustdoc compiles each doctest with `prusti-rustc`, which verifies it
+        // against the specifications exported for the library.
+        exit_status = cargo()
+            .args(["test", "--doc"])
+            .args(features)
+            .args(&args)
+            .env(
+                "CARGO_ENCODED_RUSTDOCFLAGS",
+                doctest_rustdoc_flags(&prusti_rustc_path),
+            )
+            .status()
+            .expect("could not run cargo");
+    }
+    if let Some(semantic_cache_dir) = config::semantic_cache_dir() {
+        report_unchanged_crates(&semantic_cache_dir, start_time).ok();
+    }
+    if let Some(sarif_path) = config::sarif_path() {
+        // The crates verified in parallel might each have missed the runs of
+        // the others when they wrote the log.
+        sarif::write_log(&sarif_path).ok();
+    }
 
     if exit_status.success() {
         if command == "build" {
@@ -76,23 +160,227 @@ where
     }
 }
 
-/// Copy specs from '{cargo_target}/*/deps/*.specs' to '{cargo_target}/*/*.specs'
+/// The rustdoc flags with which `cargo test --doc` verifies the doctests, see
+/// `config::verify_doctests`: rustdoc compiles the doctests with
+/// `prusti-rustc` instead of rustc, and does not run them. `cfg(prusti)` is
+/// set, so that rustdoc also collects the doctests of `#[cfg(prusti)]`
+/// items. The flags of `RUSTDOCFLAGS` are kept, separated by `\x1f` as in
+/// `CARGO_ENCODED_RUSTDOCFLAGS`.
+fn doctest_rustdoc_flags(prusti_rustc_path: &Path) -> String {
+    let mut flags: Vec<String> = env::var("RUSTDOCFLAGS")
+        .map(|flags| flags.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+    flags.extend([
+        "--cfg=prusti".to_string(),
+        "-Zunstable-options".to_string(),
+        "--no-run".to_string(),
+        "--test-builder".to_string(),
+        prusti_rustc_path.to_string_lossy().into_owned(),
+    ]);
+    flags.join("\x1f")
+}
+
+/// The address of the verification daemon of the target directory, which is
+/// started if it is not running, see `config::daemon`. The daemon is a Prusti
+/// server that keeps the JVM running between invocations, until it is idle
+/// for `config::daemon_idle_timeout()` seconds.
+fn daemon_address(cargo_target: &Path) -> io::Result<String> {
+    let address_file = cargo_target.join("daemon");
+    if let Ok(address) = fs::read_to_string(&address_file) {
+        let address = address.trim();
+        let running = address
+            .parse::<SocketAddr>()
+            .map_or(false, |socket_address| {
+                TcpStream::connect_timeout(&socket_address, Duration::from_secs(1)).is_ok()
+            });
+        if running {
+            return Ok(address.to_string());
+        }
+    }
+
+    fs::create_dir_all(cargo_target)?;
+    let mut prusti_server_path = env::current_exe()?.with_file_name("prusti-server");
+    if cfg!(windows) {
+        prusti_server_path.set_extension("exe");
+    }
+    let mut command = Command::new(prusti_server_path);
+    command
+        .args(["--port", "0"])
+        .env("DEFAULT_PRUSTI_CACHE_PATH", cargo_target.join("cache.bin"))
+        .env(
+            "PRUSTI_SERVER_IDLE_TIMEOUT",
+            config::daemon_idle_timeout().to_string(),
+        )
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(fs::File::create(cargo_target.join("daemon.log"))?);
+    // The daemon must not be stopped by a Ctrl-C of this invocation.
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut daemon = command.spawn()?;
+
+    // The server prints its port once it is ready.
+    let stdout = BufReader::new(daemon.stdout.take().unwrap());
+    for line in stdout.lines() {
+        if let Some(port) = line?.strip_prefix("port: ") {
+            let address = format!("127.0.0.1:{port}");
+            fs::write(&address_file, &address)?;
+            user::message(format!("Started the verification daemon at {address}"));
+            return Ok(address);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        format!(
+            "the daemon stopped, see {}",
+            cargo_target.join("daemon.log").display()
+        ),
+    ))
+}
+
+/// Removes `<name> <value>` or `<name>=<value>` from the arguments, which cargo
+/// does not know, and returns the value. Used for `--only <path>`, see
+/// `config::selective_verify`, and `--only-file <path>`, see
+/// `config::selective_verify_file`.
+fn split_option_arg(args: Vec<String>, name: &str) -> (Vec<String>, Option<String>) {
+    let mut value = None;
+    let mut remaining = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == name {
+            value = args.next();
+        } else if let Some(rest) = arg
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            value = Some(rest.to_string());
+        } else {
+            remaining.push(arg);
+        }
+    }
+    (remaining, value)
+}
+
+/// The `.prusti-specs` files declared with `specs = "<path>"` in the
+/// `[package.metadata.prusti]` section of the packages in the dependency graph,
+/// as `<crate name>=<path>` entries of the `DEPENDENCY_SPECS` flag.
+fn packaged_dependency_specs(cargo_path: &str, args: &[String]) -> Vec<String> {
+    let mut command = Command::new(cargo_path);
+    command.args(["metadata", "--format-version", "1"]);
+    if let Some(index) = args.iter().position(|arg| arg == "--manifest-path") {
+        command.args(&args[index..(index + 2).min(args.len())]);
+    } else if let Some(arg) = args.iter().find(|arg| arg.starts_with("--manifest-path=")) {
+        command.arg(arg);
+    }
+    let metadata = match command.output() {
+        Ok(output) if output.status.success() => output.stdout,
+        _ => return vec![],
+    };
+    let Ok(metadata) = serde_json::from_slice::<Value>(&metadata) else {
+        return vec![];
+    };
+    let mut result = vec![];
+    for package in metadata["packages"].as_array().into_iter().flatten() {
+        let Some(specs) = package["metadata"]["prusti"]["specs"].as_str() else {
+            continue;
+        };
+        let Some(package_dir) = package["manifest_path"]
+            .as_str()
+            .and_then(|path| Path::new(path).parent())
+        else {
+            continue;
+        };
+        let lib_target = package["targets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|target| {
+                target["kind"].as_array().map_or(false, |kinds| {
+                    kinds.iter().any(|kind| kind == "lib" || kind == "rlib")
+                })
+            });
+        if let Some(crate_name) = lib_target.and_then(|target| target["name"].as_str()) {
+            result.push(format!(
+                "{}={}",
+                crate_name.replace('-', "_"),
+                package_dir.join(specs).to_string_lossy()
+            ));
+        }
+    }
+    result
+}
+
+/// Reports the crates that were not verified again, because cargo found them
+/// unchanged since their last verification, using the summary of that
+/// verification stored in the semantic cache.
+fn report_unchanged_crates(semantic_cache_dir: &Path, start_time: SystemTime) -> io::Result<()> {
+    let mut unchanged = vec![];
+    for entry in fs::read_dir(semantic_cache_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.extension().map_or(true, |ext| ext != "json")
+            || entry.metadata()?.modified()? >= start_time
+        {
+            continue;
+        }
+        let Some(crate_name) = path.file_stem() else {
+            continue;
+        };
+        let Some(cache) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        else {
+            continue;
+        };
+        let count = |key: &str| cache["summary"][key].as_u64().unwrap_or(0);
+        unchanged.push((
+            crate_name.to_string_lossy().into_owned(),
+            count("verified") + count("cached"),
+            count("failed"),
+        ));
+    }
+    unchanged.sort();
+    for (crate_name, verified, failed) in unchanged {
+        user::message(format!(
+            "Skipped the verification of `{crate_name}`, which did not change since its last \
+            verification ({verified} procedures verified, {failed} failed)"
+        ));
+    }
+    Ok(())
+}
+
+/// Copy specs from '{cargo_target}/*/deps/*.specs' to '{cargo_target}/*/*.specs',
+/// and from '{cargo_target}/<triple>/*/deps/*.specs' when building with `--target`.
 fn copy_exported_specs(cargo_target: PathBuf) -> io::Result<()> {
     for de in fs::read_dir(cargo_target)? {
         let build_dir = de?.path();
-        let deps_dir = build_dir.join("deps");
-        if build_dir.is_dir() && deps_dir.is_dir() {
-            for entry in fs::read_dir(deps_dir)? {
-                let entry = entry?.path();
-                if let Some(ext) = entry.extension() && ext == "specs" {
-                    if let Some(fname) = entry.file_name() {
-                        let pkg_name = fname.to_string_lossy();
-                        if let Some(pkg_name) = pkg_name.split('-').next() {
-                            let mut tgt = build_dir.join(pkg_name);
-                            tgt.set_extension("specs");
-                            fs::copy(entry, tgt)?;
-                        }
-                    }
+        if !build_dir.is_dir() {
+            continue;
+        }
+        if build_dir.join("deps").is_dir() {
+            copy_exported_specs_of_build(&build_dir)?;
+        } else {
+            for de in fs::read_dir(build_dir)? {
+                let build_dir = de?.path();
+                if build_dir.join("deps").is_dir() {
+                    copy_exported_specs_of_build(&build_dir)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn copy_exported_specs_of_build(build_dir: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(build_dir.join("deps"))? {
+        let entry = entry?.path();
+        if let Some(ext) = entry.extension() && ext == "specs" {
+            if let Some(fname) = entry.file_name() {
+                let pkg_name = fname.to_string_lossy();
+                if let Some(pkg_name) = pkg_name.split('-').next() {
+                    let mut tgt = build_dir.join(pkg_name);
+                    tgt.set_extension("specs");
+                    fs::copy(entry, tgt)?;
                 }
             }
         }
@@ -5,7 +5,7 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use prusti_utils::launch;
-use std::{env, io::Write, path::PathBuf, process::Command};
+use std::{env, io::Write, process::Command};
 
 fn main() {
     if let Err(code) = process(std::env::args().skip(1).collect()) {
@@ -14,6 +14,10 @@ fn main() {
 }
 
 fn process(mut args: Vec<String>) -> Result<(), i32> {
+    if let Some(code) = launch::find_prusti_explain_arg(&args) {
+        return launch::explain_error_code(code);
+    }
+
     let _setup = launch::job::setup().unwrap(); // Kill all subprocesses on kill or Ctrl-C
 
     let prusti_home = launch::get_current_executable_dir();
@@ -23,15 +27,17 @@ fn process(mut args: Vec<String>) -> Result<(), i32> {
         prusti_driver_path.set_extension("exe");
     }
 
-    let java_home = match env::var("JAVA_HOME") {
-        Ok(java_home) => PathBuf::from(java_home),
-        Err(_) => launch::find_java_home()
-            .expect("Failed to find Java home directory. Try setting JAVA_HOME"),
+    let launch::JavaRuntime {
+        java_home,
+        libjvm_dir: libjvm_path,
+    } = match launch::find_java_runtime(&prusti_home) {
+        Ok(java_runtime) => java_runtime,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return Err(1);
+        }
     };
 
-    let libjvm_path =
-        launch::find_libjvm(&java_home).expect("Failed to find JVM library. Check JAVA_HOME");
-
     let prusti_sysroot = launch::prusti_sysroot().expect("Failed to find Rust's sysroot");
 
     let compiler_bin = prusti_sysroot.join("bin");
@@ -44,6 +50,10 @@ fn process(mut args: Vec<String>) -> Result<(), i32> {
 
     launch::set_environment_settings(&mut cmd, &prusti_home, &java_home);
 
+    if launch::take_no_verify_arg(&mut args) {
+        cmd.env("PRUSTI_CHECK_ONLY", "true");
+    }
+
     let cargo_invoked = env::var("PRUSTI_CARGO").is_ok();
 
     // No need to check if we happen to be running on e.g. the `prusti-contracts` crate since this
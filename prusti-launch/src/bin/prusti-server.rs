@@ -5,7 +5,7 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use prusti_utils::launch;
-use std::{path::PathBuf, process::Command};
+use std::process::Command;
 
 fn main() {
     if let Err(code) = process(std::env::args().skip(1).collect()) {
@@ -23,10 +23,15 @@ fn process(args: Vec<String>) -> Result<(), i32> {
         prusti_server_driver_path.set_extension("exe");
     }
 
-    let java_home = match std::env::var("JAVA_HOME") {
-        Ok(java_home) => PathBuf::from(java_home),
-        Err(_) => launch::find_java_home()
-            .expect("Failed to find Java home directory. Try setting JAVA_HOME"),
+    let launch::JavaRuntime {
+        java_home,
+        libjvm_dir: libjvm_path,
+    } = match launch::find_java_runtime(&current_executable_dir) {
+        Ok(java_runtime) => java_runtime,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return Err(1);
+        }
     };
 
     let mut cmd = Command::new(&prusti_server_driver_path);
@@ -35,8 +40,6 @@ fn process(args: Vec<String>) -> Result<(), i32> {
     // Prevent shadowing of default log behavior.
     cmd.env("DEFAULT_PRUSTI_LOG", "info");
 
-    let libjvm_path =
-        launch::find_libjvm(&java_home).expect("Failed to find JVM library. Check JAVA_HOME");
     launch::add_to_loader_path(vec![libjvm_path], &mut cmd);
 
     launch::set_environment_settings(&mut cmd, &current_executable_dir, &java_home);
@@ -113,6 +113,25 @@ fn test_prusti_rustc_dump() {
     });
 }
 
+#[test]
+fn test_prusti_rustc_explain() {
+    let prusti_rustc = find_compiled_executable("prusti-rustc");
+
+    let output = Command::new(&prusti_rustc)
+        .arg("--explain")
+        .arg("P0009")
+        .output()
+        .unwrap_or_else(|err| panic!("Failed to execute process: {err:?}"));
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("postcondition"));
+
+    let output = Command::new(&prusti_rustc)
+        .arg("--explain=P9999")
+        .output()
+        .unwrap_or_else(|err| panic!("Failed to execute process: {err:?}"));
+    assert!(!output.status.success());
+}
+
 /*
 // The `PRUSTI_BE_RUSTC` flag doesn't change the behaviour of Prusti macros
 // so this test fails.
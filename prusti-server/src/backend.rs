@@ -1,13 +1,25 @@
-use crate::dump_viper_program;
+use crate::{dump_smt_script, dump_viper_program, dump_viper_source};
+use native_verifier::{NativeVerificationResult, NativeVerifier};
 use prusti_common::{
     config,
-    vir::{LoweringContext, ToViper},
+    report::log::to_legal_file_name,
+    vir::{program::Program, LoweringContext, ToViper, ViperSource},
     Stopwatch,
 };
-use viper::{VerificationContext, VerificationResult};
+use std::fs;
+use viper::{
+    VerificationBackend, VerificationContext, VerificationError, VerificationResult,
+    ViperServerClient,
+};
 
 pub enum Backend<'a> {
     Viper(viper::Verifier<'a>, &'a VerificationContext<'a>),
+    Native(NativeVerifier),
+    ViperServer {
+        client: ViperServerClient,
+        backend: VerificationBackend,
+        verifier_args: Vec<String>,
+    },
 }
 
 impl<'a> Backend<'a> {
@@ -36,6 +48,83 @@ impl<'a> Backend<'a> {
                     viper.verify(viper_program)
                 })
             }
+            Backend::Native(verifier) => {
+                let Program::Low(low_program) = program else {
+                    return VerificationResult::BackendErrors(vec![
+                        "the native backend only supports programs of the core proof encoding, \
+                        see `unsafe_core_proof`"
+                            .to_string(),
+                    ]);
+                };
+                if config::dump_viper_program() {
+                    dump_smt_script(low_program, &program.get_name_with_check_mode());
+                }
+                let _stopwatch = Stopwatch::start("prusti-server backend", "native verification");
+                match verifier.verify(low_program) {
+                    Ok(NativeVerificationResult::Verified) => VerificationResult::Success,
+                    Ok(NativeVerificationResult::Failed(errors)) => VerificationResult::Failure(
+                        errors
+                            .into_iter()
+                            .map(|error| {
+                                VerificationError::new(
+                                    error.full_id,
+                                    error.offending_pos_id.clone(),
+                                    error.offending_pos_id,
+                                    error.reason_pos_id,
+                                    error.message,
+                                    None,
+                                )
+                            })
+                            .collect(),
+                    ),
+                    Ok(NativeVerificationResult::TimedOut) => VerificationResult::Timeout,
+                    Err(error) => VerificationResult::BackendErrors(vec![error.to_string()]),
+                }
+            }
+            Backend::ViperServer {
+                client,
+                backend,
+                verifier_args,
+            } => {
+                let Program::Low(low_program) = program else {
+                    return VerificationResult::BackendErrors(vec![
+                        "the ViperServer client only supports programs of the core proof \
+                        encoding, see `unsafe_core_proof`"
+                            .to_string(),
+                    ]);
+                };
+                let program_name = program.get_name_with_check_mode();
+                let mut stopwatch =
+                    Stopwatch::start("prusti-server backend", "printing of Viper program");
+                let source = match ViperSource::new(low_program) {
+                    Ok(source) => source,
+                    Err(error) => {
+                        return VerificationResult::BackendErrors(vec![error.to_string()])
+                    }
+                };
+                if config::dump_viper_program() {
+                    dump_viper_source(&source.text, &program_name);
+                }
+                // ViperServer reads the program from a file.
+                let program_path = std::env::temp_dir().join(format!(
+                    "prusti-{}-{}.vpr",
+                    to_legal_file_name(&program_name),
+                    uuid::Uuid::new_v4()
+                ));
+                if let Err(error) = fs::write(&program_path, &source.text) {
+                    return VerificationResult::BackendErrors(vec![format!(
+                        "could not write the Viper program to {}: {error}",
+                        program_path.display()
+                    )]);
+                }
+                stopwatch.start_next("ViperServer verification");
+                let result =
+                    client.verify(*backend, verifier_args, &program_path, |line, column| {
+                        source.position_id(line, column)
+                    });
+                let _ = fs::remove_file(&program_path);
+                result
+            }
         }
     }
 }
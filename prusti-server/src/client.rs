@@ -4,9 +4,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::VerificationRequest;
+use crate::{BatchResult, JobId, JobStatus, VerificationBatch, VerificationRequest};
 use prusti_common::config;
-use reqwest::Client;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, AUTHORIZATION},
+    Certificate, Client, Response,
+};
+use serde::de::DeserializeOwned;
+use std::fs;
 use url::{ParseError, Url};
 use viper::VerificationResult;
 
@@ -16,17 +21,41 @@ pub struct PrustiClient {
 }
 
 impl PrustiClient {
+    /// Creates a client of the server at `server_address`, which authenticates
+    /// with the `server_auth_token`, if any. Servers that use TLS have
+    /// addresses of the form `https://host:port`.
     pub fn new<S: ToString>(server_address: S) -> Result<Self, ParseError> {
         let mut address = server_address.to_string();
         if !address.starts_with("http") {
             address = format!("http://{address}");
         }
+        let mut headers = HeaderMap::new();
+        if let Some(token) = config::server_auth_token() {
+            let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+                .expect("the server_auth_token contains invalid characters");
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        let mut builder = Client::builder().default_headers(headers);
+        if let Some(path) = config::server_tls_ca_path() {
+            let certificate = fs::read(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|pem| Certificate::from_pem(&pem).map_err(|error| error.to_string()))
+                .unwrap_or_else(|error| panic!("Could not load the certificate {path}: {error}"));
+            builder = builder.add_root_certificate(certificate);
+        }
         Ok(Self {
-            client: Client::new(),
+            client: builder
+                .build()
+                .expect("could not construct the HTTP client"),
             server_url: Url::parse(address.as_str())?,
         })
     }
 
+    pub fn server_url(&self) -> &Url {
+        &self.server_url
+    }
+
     pub async fn verify(
         &self,
         request: VerificationRequest,
@@ -58,4 +87,135 @@ impl PrustiClient {
         };
         Ok(response)
     }
+    /// Queues the requests as jobs of the given priority and returns their
+    /// identifiers, in the order of the requests.
+    pub async fn submit(&self, batch: VerificationBatch) -> reqwest::Result<Vec<JobId>> {
+        let use_json = config::json_communication();
+        let base = self.client.post(
+            self.server_url
+                .join(if use_json { "json/" } else { "bincode/" })
+                .unwrap()
+                .join("jobs")
+                .unwrap(),
+        );
+        let response = if use_json {
+            base.json(&batch)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?
+        } else {
+            let bytes = base
+                .body(bincode::serialize(&batch).expect("error encoding verification batch"))
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?;
+            bincode::deserialize(&bytes).expect("error decoding job ids")
+        };
+        Ok(response)
+    }
+
+    /// Returns the current status of the job. Once the job is finished, the
+    /// server forgets it.
+    pub async fn job_status(&self, id: JobId) -> reqwest::Result<JobStatus> {
+        self.client
+            .get(self.server_url.join(&format!("json/jobs/{id}")).unwrap())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Follows the status of the job until it is finished and returns its
+    /// last status, which is only unfinished if the server closed the
+    /// connection before.
+    pub async fn wait_for_job(&self, id: JobId) -> reqwest::Result<JobStatus> {
+        let response = self
+            .client
+            .get(
+                self.server_url
+                    .join(&format!("json/jobs/{id}/stream"))
+                    .unwrap(),
+            )
+            .send()
+            .await?
+            .error_for_status()?;
+        let mut status = JobStatus::Queued;
+        read_json_lines(response, |line: JobStatus| {
+            status = line;
+            !status.is_finished()
+        })
+        .await?;
+        Ok(status)
+    }
+
+    /// Queues the requests as jobs of the given priority and calls
+    /// `on_result` with the result of each request as soon as it is known.
+    /// Returns once the results of all requests are known or the server
+    /// closed the connection.
+    pub async fn verify_batch(
+        &self,
+        batch: VerificationBatch,
+        mut on_result: impl FnMut(BatchResult),
+    ) -> reqwest::Result<()> {
+        let use_json = config::json_communication();
+        let base = self.client.post(
+            self.server_url
+                .join(if use_json { "json/" } else { "bincode/" })
+                .unwrap()
+                .join("jobs/stream")
+                .unwrap(),
+        );
+        let request = if use_json {
+            base.json(&batch)
+        } else {
+            base.body(bincode::serialize(&batch).expect("error encoding verification batch"))
+        };
+        let response = request.send().await?.error_for_status()?;
+        let mut remaining = batch.requests.len();
+        read_json_lines(response, |result: BatchResult| {
+            on_result(result);
+            remaining -= 1;
+            remaining > 0
+        })
+        .await
+    }
+
+    /// Adds the results of a cache file, e.g. one saved at the `cache_path`
+    /// of a previous run, to the cache of the server. Returns the number of
+    /// results that were not yet in the cache.
+    pub async fn warm_cache(&self, cache_file: Vec<u8>) -> reqwest::Result<usize> {
+        self.client
+            .post(self.server_url.join("bincode/cache/warm").unwrap())
+            .body(cache_file)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+}
+
+/// Calls `on_line` with every line of a response in the newline-delimited
+/// JSON format, until it returns `false` or the response ends.
+async fn read_json_lines<T: DeserializeOwned>(
+    mut response: Response,
+    mut on_line: impl FnMut(T) -> bool,
+) -> reqwest::Result<()> {
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = serde_json::from_slice(&line).expect("error decoding a streamed line");
+            if !on_line(line) {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
 }
@@ -6,6 +6,7 @@
 
 use clap::Parser;
 use prusti_utils::config;
+use std::{net::IpAddr, path::PathBuf};
 
 /// A verification server to handle Prusti verification requests.
 #[derive(Parser, Debug)]
@@ -15,6 +16,15 @@ struct Args {
     /// Pass 0 to get a free one assigned by the OS.
     #[clap(short, long, value_name = "PORT", default_value_t = 0)]
     port: u16,
+    /// Sets the IP address on which to listen. Addresses other than loopback
+    /// addresses require the `server_auth_token` configuration flag.
+    #[clap(short, long, value_name = "ADDRESS", default_value = "127.0.0.1")]
+    address: IpAddr,
+    /// Verifies the VIR programs exported with the `vir_export_dir`
+    /// configuration flag, or Viper programs in `.vpr` files, instead of
+    /// starting a server.
+    #[clap(long, value_name = "FILE")]
+    verify: Vec<PathBuf>,
 }
 
 fn main() {
@@ -26,5 +36,10 @@ fn main() {
 
     let args = Args::parse();
 
-    prusti_server::start_server_on_port(args.port);
+    if !args.verify.is_empty() {
+        let success = prusti_server::verify_exported_programs(&args.verify);
+        std::process::exit(if success { 0 } else { 1 });
+    }
+
+    prusti_server::start_server(args.address, args.port);
 }
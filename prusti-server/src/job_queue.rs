@@ -0,0 +1,311 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::VerificationRequest;
+use rustc_hash::FxHashMap;
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fmt,
+    str::FromStr,
+    sync::{Condvar, Mutex},
+    time::Duration,
+};
+use tokio::sync::watch;
+use viper::VerificationResult;
+
+/// The priority of a verification job. The server always starts the queued
+/// job with the highest priority, and the jobs of the same priority in the
+/// order in which they were submitted.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPriority {
+    /// E.g. the verification of a whole workspace.
+    Background,
+    #[default]
+    Normal,
+    /// E.g. a verification requested from an IDE.
+    Interactive,
+}
+
+impl FromStr for JobPriority {
+    type Err = String;
+
+    fn from_str(priority: &str) -> Result<Self, Self::Err> {
+        match priority.to_lowercase().as_str() {
+            "background" => Ok(JobPriority::Background),
+            "normal" => Ok(JobPriority::Normal),
+            "interactive" => Ok(JobPriority::Interactive),
+            _ => Err(format!(
+                "'{priority}' is not a valid priority, expected 'background', 'normal' or \
+                'interactive'"
+            )),
+        }
+    }
+}
+
+/// Verification requests that are queued with the same priority.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct VerificationBatch {
+    pub priority: JobPriority,
+    pub requests: Vec<VerificationRequest>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(transparent)]
+pub struct JobId(pub u64);
+
+impl fmt::Display for JobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    /// The job is done, after verifying for `duration` (excluding the time
+    /// in the queue).
+    Finished {
+        result: VerificationResult,
+        duration: Duration,
+    },
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Finished { .. })
+    }
+}
+
+/// The result of a request of a batch, which the server streams as soon as it
+/// is known.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BatchResult {
+    /// The index of the request in the batch.
+    pub index: usize,
+    pub job: JobId,
+    pub result: VerificationResult,
+    pub duration: Duration,
+}
+
+struct QueuedJob {
+    priority: JobPriority,
+    id: JobId,
+    request: VerificationRequest,
+    /// The number of times the job was queued again after a failed
+    /// verification.
+    retries: usize,
+}
+
+struct RunningJob {
+    priority: JobPriority,
+    retries: usize,
+}
+
+impl QueuedJob {
+    fn key(&self) -> (JobPriority, Reverse<JobId>) {
+        (self.priority, Reverse(self.id))
+    }
+}
+
+impl PartialEq for QueuedJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for QueuedJob {}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedJob {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// The numbers of jobs of a queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueStatistics {
+    /// The jobs submitted since the start of the server.
+    pub submitted: u64,
+    pub queued: usize,
+    pub running: usize,
+}
+
+#[derive(Default)]
+struct QueueState {
+    next_id: u64,
+    queued: BinaryHeap<QueuedJob>,
+    running: FxHashMap<JobId, RunningJob>,
+    /// The status of every job whose result was not taken yet.
+    jobs: FxHashMap<JobId, watch::Sender<JobStatus>>,
+}
+
+/// The verification jobs of a server. The jobs are taken by worker threads,
+/// so a job of a higher priority overtakes the queued jobs of lower
+/// priorities, but does not interrupt the running ones.
+#[derive(Default)]
+pub struct JobQueue {
+    state: Mutex<QueueState>,
+    job_queued: Condvar,
+}
+
+impl JobQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the requests of the batch and returns the identifiers of their
+    /// jobs, in the order of the requests.
+    pub fn submit(&self, batch: VerificationBatch) -> Vec<JobId> {
+        let mut state = self.state.lock().unwrap();
+        let ids = batch
+            .requests
+            .into_iter()
+            .map(|request| {
+                let id = JobId(state.next_id);
+                state.next_id += 1;
+                state.queued.push(QueuedJob {
+                    priority: batch.priority,
+                    id,
+                    request,
+                    retries: 0,
+                });
+                state.jobs.insert(id, watch::channel(JobStatus::Queued).0);
+                id
+            })
+            .collect();
+        self.job_queued.notify_all();
+        ids
+    }
+
+    /// Returns the current status of the job, or `None` if the job does not
+    /// exist or its result was already taken.
+    pub fn status(&self, id: JobId) -> Option<JobStatus> {
+        let state = self.state.lock().unwrap();
+        state.jobs.get(&id).map(|status| status.borrow().clone())
+    }
+
+    /// Returns a receiver that is notified about every change of the status
+    /// of the job.
+    pub fn subscribe(&self, id: JobId) -> Option<watch::Receiver<JobStatus>> {
+        let state = self.state.lock().unwrap();
+        state.jobs.get(&id).map(|status| status.subscribe())
+    }
+
+    /// Forgets a finished job, so that its result can no longer be queried.
+    pub fn remove(&self, id: JobId) {
+        let mut state = self.state.lock().unwrap();
+        let finished = state
+            .jobs
+            .get(&id)
+            .map_or(false, |status| status.borrow().is_finished());
+        if finished {
+            state.jobs.remove(&id);
+        }
+    }
+
+    pub fn statistics(&self) -> QueueStatistics {
+        let state = self.state.lock().unwrap();
+        QueueStatistics {
+            submitted: state.next_id,
+            queued: state.queued.len(),
+            running: state.running.len(),
+        }
+    }
+
+    /// Blocks until a job is queued, marks the one with the highest priority
+    /// as running, and returns it.
+    pub fn next_job(&self) -> (JobId, VerificationRequest) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(job) = state.queued.pop() {
+                state.running.insert(
+                    job.id,
+                    RunningJob {
+                        priority: job.priority,
+                        retries: job.retries,
+                    },
+                );
+                if let Some(status) = state.jobs.get(&job.id) {
+                    status.send_replace(JobStatus::Running);
+                }
+                return (job.id, job.request);
+            }
+            state = self.job_queued.wait(state).unwrap();
+        }
+    }
+
+    /// Queues a running job again, e.g. after its verification crashed,
+    /// unless it was already retried `max_retries` times. Returns whether
+    /// the job was queued.
+    pub fn retry(&self, id: JobId, request: VerificationRequest, max_retries: usize) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let Some(job) = state.running.get(&id) else {
+            return false;
+        };
+        if job.retries >= max_retries {
+            return false;
+        }
+        let job = state.running.remove(&id).unwrap();
+        // The job keeps its identifier, so it is started before the jobs of
+        // the same priority that were submitted later.
+        state.queued.push(QueuedJob {
+            priority: job.priority,
+            id,
+            request,
+            retries: job.retries + 1,
+        });
+        if let Some(status) = state.jobs.get(&id) {
+            status.send_replace(JobStatus::Queued);
+        }
+        self.job_queued.notify_one();
+        true
+    }
+
+    pub fn finish(&self, id: JobId, result: VerificationResult, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.running.remove(&id);
+        if let Some(status) = state.jobs.get(&id) {
+            status.send_replace(JobStatus::Finished { result, duration });
+        }
+    }
+}
+
+/// Waits until the job is finished and returns its result and verification
+/// time, or `None` if the job was removed before.
+pub async fn wait_until_finished(
+    mut status: watch::Receiver<JobStatus>,
+) -> Option<(VerificationResult, Duration)> {
+    loop {
+        let current = status.borrow_and_update().clone();
+        if let JobStatus::Finished { result, duration } = current {
+            return Some((result, duration));
+        }
+        status.changed().await.ok()?;
+    }
+}
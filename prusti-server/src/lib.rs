@@ -7,16 +7,24 @@
 #![warn(clippy::disallowed_types)]
 
 mod client;
+mod job_queue;
+mod metrics;
 mod process_verification;
 mod server;
+mod server_pool;
 mod verification_request;
+mod verifier_pool;
 mod backend;
 
 pub use backend::*;
 pub use client::*;
+pub use job_queue::*;
+pub use metrics::*;
 pub use process_verification::*;
 pub use server::*;
+pub use server_pool::*;
 pub use verification_request::*;
+pub use verifier_pool::*;
 
 // Futures returned by `Client` need to be executed in a compatible tokio runtime.
 pub use tokio;
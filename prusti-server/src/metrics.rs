@@ -0,0 +1,299 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::QueueStatistics;
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use viper::{Cache, PersistentCache, VerificationResult};
+
+/// The upper bounds of the buckets of the verification time histogram, in
+/// seconds.
+const DURATION_BUCKETS: [f64; 11] = [
+    0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0,
+];
+
+const RESULT_KINDS: [&str; 6] = [
+    "success",
+    "failure",
+    "consistency_errors",
+    "java_exception",
+    "timeout",
+    "backend_errors",
+];
+
+/// Metrics of a server, which are exported in the text format of Prometheus.
+#[derive(Default)]
+pub struct Metrics {
+    results: [AtomicU64; RESULT_KINDS.len()],
+    retries: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    durations: Mutex<Histogram>,
+}
+
+#[derive(Default)]
+struct Histogram {
+    bucket_counts: [u64; DURATION_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+/// The used and the maximum size of the heap of the JVM, in bytes.
+pub struct HeapUsage {
+    pub used: i64,
+    pub max: i64,
+}
+
+impl Metrics {
+    pub fn record_result(&self, result: &VerificationResult, duration: Duration) {
+        let kind = match result {
+            VerificationResult::Success => 0,
+            VerificationResult::Failure(_) => 1,
+            VerificationResult::ConsistencyErrors(_) => 2,
+            VerificationResult::JavaException(_) => 3,
+            VerificationResult::Timeout => 4,
+            VerificationResult::BackendErrors(_) => 5,
+        };
+        self.results[kind].fetch_add(1, Ordering::Relaxed);
+        let seconds = duration.as_secs_f64();
+        let mut durations = self.durations.lock().unwrap();
+        for (bound, count) in DURATION_BUCKETS.iter().zip(&mut durations.bucket_counts) {
+            if seconds <= *bound {
+                *count += 1;
+            }
+        }
+        durations.count += 1;
+        durations.sum += seconds;
+    }
+
+    /// Counts a job that is queued again after its verification crashed.
+    pub fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Wraps the cache to count its hits and misses.
+    pub fn counting_cache<'a>(
+        &'a self,
+        cache: &'a Arc<Mutex<PersistentCache>>,
+    ) -> CountingCache<'a> {
+        CountingCache {
+            cache,
+            metrics: self,
+        }
+    }
+
+    pub fn render(&self, queue: QueueStatistics, heap: Option<HeapUsage>) -> String {
+        let mut text = String::new();
+        let out = &mut text;
+        let name = "prusti_server_jobs_submitted_total";
+        header(
+            out,
+            name,
+            "counter",
+            "Number of submitted verification jobs.",
+        );
+        writeln!(out, "{name} {}", queue.submitted).unwrap();
+        let name = "prusti_server_jobs_queued";
+        header(
+            out,
+            name,
+            "gauge",
+            "Number of verification jobs waiting in the queue.",
+        );
+        writeln!(out, "{name} {}", queue.queued).unwrap();
+        let name = "prusti_server_jobs_running";
+        header(
+            out,
+            name,
+            "gauge",
+            "Number of verification jobs being verified.",
+        );
+        writeln!(out, "{name} {}", queue.running).unwrap();
+
+        let name = "prusti_server_jobs_finished_total";
+        header(
+            out,
+            name,
+            "counter",
+            "Number of finished verification jobs, by result.",
+        );
+        for (kind, count) in RESULT_KINDS.iter().zip(&self.results) {
+            let count = count.load(Ordering::Relaxed);
+            writeln!(out, "{name}{{result=\"{kind}\"}} {count}").unwrap();
+        }
+
+        let name = "prusti_server_jobs_retried_total";
+        header(
+            out,
+            name,
+            "counter",
+            "Number of verification jobs queued again after a crash of their verification.",
+        );
+        writeln!(out, "{name} {}", self.retries.load(Ordering::Relaxed)).unwrap();
+
+        let name = "prusti_server_verification_duration_seconds";
+        header(
+            out,
+            name,
+            "histogram",
+            "Time spent verifying a job, excluding the time in the queue.",
+        );
+        let durations = self.durations.lock().unwrap();
+        for (bound, count) in DURATION_BUCKETS.iter().zip(durations.bucket_counts) {
+            writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {count}").unwrap();
+        }
+        writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", durations.count).unwrap();
+        writeln!(out, "{name}_sum {}", durations.sum).unwrap();
+        writeln!(out, "{name}_count {}", durations.count).unwrap();
+        drop(durations);
+
+        let name = "prusti_server_cache_hits_total";
+        header(
+            out,
+            name,
+            "counter",
+            "Number of verification requests answered from the cache.",
+        );
+        writeln!(out, "{name} {}", self.cache_hits.load(Ordering::Relaxed)).unwrap();
+        let name = "prusti_server_cache_misses_total";
+        header(
+            out,
+            name,
+            "counter",
+            "Number of verification requests not found in the cache.",
+        );
+        writeln!(out, "{name} {}", self.cache_misses.load(Ordering::Relaxed)).unwrap();
+
+        // The JVM is only started by the first verification.
+        if let Some(heap) = heap {
+            let name = "prusti_server_jvm_heap_used_bytes";
+            header(out, name, "gauge", "Used heap memory of the JVM.");
+            writeln!(out, "{name} {}", heap.used).unwrap();
+            let name = "prusti_server_jvm_heap_max_bytes";
+            header(out, name, "gauge", "Maximum heap memory of the JVM.");
+            writeln!(out, "{name} {}", heap.max).unwrap();
+        }
+        text
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {name} {help}").unwrap();
+    writeln!(out, "# TYPE {name} {kind}").unwrap();
+}
+
+#[derive(Clone, Copy)]
+pub struct CountingCache<'a> {
+    cache: &'a Arc<Mutex<PersistentCache>>,
+    metrics: &'a Metrics,
+}
+
+impl Cache for CountingCache<'_> {
+    fn get(&mut self, request: u64) -> Option<VerificationResult> {
+        let result = self.cache.get(request);
+        let counter = if result.is_some() {
+            &self.metrics.cache_hits
+        } else {
+            &self.metrics.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    fn insert(self, request: u64, result: VerificationResult) -> Option<VerificationResult> {
+        self.cache.insert(request, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn statistics() -> QueueStatistics {
+        QueueStatistics {
+            submitted: 3,
+            queued: 1,
+            running: 0,
+        }
+    }
+
+    #[test]
+    fn results_and_durations_are_counted() {
+        let metrics = Metrics::default();
+        metrics.record_result(&VerificationResult::Success, Duration::from_millis(300));
+        metrics.record_result(&VerificationResult::Timeout, Duration::from_secs(20));
+        metrics.record_retry();
+        let text = metrics.render(statistics(), None);
+        let lines: Vec<_> = text.lines().collect();
+        for line in [
+            "# TYPE prusti_server_jobs_submitted_total counter",
+            "prusti_server_jobs_submitted_total 3",
+            "prusti_server_jobs_queued 1",
+            "prusti_server_jobs_running 0",
+            "prusti_server_jobs_finished_total{result=\"success\"} 1",
+            "prusti_server_jobs_finished_total{result=\"failure\"} 0",
+            "prusti_server_jobs_finished_total{result=\"timeout\"} 1",
+            "prusti_server_jobs_retried_total 1",
+            "prusti_server_verification_duration_seconds_bucket{le=\"0.1\"} 0",
+            "prusti_server_verification_duration_seconds_bucket{le=\"0.5\"} 1",
+            "prusti_server_verification_duration_seconds_bucket{le=\"10\"} 1",
+            "prusti_server_verification_duration_seconds_bucket{le=\"30\"} 2",
+            "prusti_server_verification_duration_seconds_bucket{le=\"+Inf\"} 2",
+            "prusti_server_verification_duration_seconds_sum 20.3",
+            "prusti_server_verification_duration_seconds_count 2",
+        ] {
+            assert!(lines.contains(&line), "`{line}` is missing in:\n{text}");
+        }
+        assert!(!text.contains("prusti_server_jvm_heap"), "{text}");
+    }
+
+    #[test]
+    fn cache_hits_and_misses_are_counted() {
+        let metrics = Metrics::default();
+        let cache = Arc::new(Mutex::new(PersistentCache::load_cache(PathBuf::new())));
+        assert_eq!(metrics.counting_cache(&cache).get(1), None);
+        metrics
+            .counting_cache(&cache)
+            .insert(1, VerificationResult::Success);
+        assert_eq!(
+            metrics.counting_cache(&cache).get(1),
+            Some(VerificationResult::Success)
+        );
+        let text = metrics.render(statistics(), None);
+        assert!(
+            text.contains("\nprusti_server_cache_hits_total 1\n"),
+            "{text}"
+        );
+        assert!(
+            text.contains("\nprusti_server_cache_misses_total 1\n"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn the_heap_usage_is_exported_once_the_jvm_started() {
+        let heap = HeapUsage {
+            used: 1024,
+            max: 4096,
+        };
+        let text = Metrics::default().render(statistics(), Some(heap));
+        assert!(
+            text.contains("\nprusti_server_jvm_heap_used_bytes 1024\n"),
+            "{text}"
+        );
+        assert!(
+            text.contains("\nprusti_server_jvm_heap_max_bytes 4096\n"),
+            "{text}"
+        );
+    }
+}
@@ -4,28 +4,45 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::{Backend, VerificationRequest, ViperBackendConfig};
-use log::info;
+use crate::{Backend, VerificationRequest, VerifierPool, ViperBackendConfig};
+use log::{info, warn};
+use native_verifier::NativeVerifier;
 use once_cell::sync::Lazy;
 use prusti_common::{
     config,
     report::log::{report, to_legal_file_name},
-    vir::{program_normalization::NormalizationInfo, ToViper},
+    vir::{
+        program::Program, program_normalization::NormalizationInfo, ToViper, UnsupportedConstruct,
+        ViperSource,
+    },
     Stopwatch,
 };
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{
+    fs::{self, create_dir_all},
+    path::{Path, PathBuf},
+};
 use viper::{
-    smt_manager::SmtManager, Cache, VerificationBackend, VerificationContext, VerificationResult,
+    smt_manager::SmtManager, Cache, CacheLimits, PersistentCache, SmtSolver, VerificationBackend,
+    VerificationContext, VerificationResult, Viper, ViperServerClient,
 };
+use vir::common::check_mode::CheckMode;
+
+/// Loads the verification cache from `config::cache_path()`, with the limits
+/// of the configuration.
+pub fn load_cache() -> PersistentCache {
+    PersistentCache::load_cache(config::cache_path()).with_limits(CacheLimits {
+        max_entries: config::cache_max_entries(),
+        max_size: config::cache_max_size_mb().map(|size| size * 1024 * 1024),
+    })
+}
 
 #[tracing::instrument(level = "debug", skip_all, fields(program = %request.program.get_name()))]
 pub fn process_verification_request<'v, 't: 'v>(
     verification_context: &'v Lazy<VerificationContext<'t>, impl Fn() -> VerificationContext<'t>>,
     mut request: VerificationRequest,
-    cache: impl Cache,
+    mut cache: impl Cache,
+    verifiers: &mut VerifierPool<'v>,
 ) -> viper::VerificationResult {
-    let ast_utils = verification_context.new_ast_utils();
-
     // Only for testing: Check that the normalization is reversible.
     if config::print_hash() {
         debug_assert!({
@@ -36,6 +53,12 @@ pub fn process_verification_request<'v, 't: 'v>(
         });
     }
 
+    // Exported before the normalization and the cache, so that the exported
+    // program is the original one, also when its result is cached.
+    if let (Some(dir), Some(export_name)) = (config::viper_export_dir(), &request.export_name) {
+        export_viper_program(verification_context, &request.program, &dir, export_name);
+    }
+
     // Normalize the request before reaching the cache.
     let normalization_info = NormalizationInfo::normalize_program(&mut request.program);
 
@@ -46,23 +69,36 @@ pub fn process_verification_request<'v, 't: 'v>(
         request.program.get_name()
     );
 
-    let build_or_dump_viper_program = || {
-        let mut stopwatch = Stopwatch::start("prusti-server", "construction of JVM objects");
-        let ast_factory = verification_context.new_ast_factory();
-        let viper_program = request
-            .program
-            .to_viper(prusti_common::vir::LoweringContext::default(), &ast_factory);
-
-        if config::dump_viper_program() {
-            stopwatch.start_next("dumping viper program");
-            dump_viper_program(
-                &ast_utils,
-                viper_program,
-                &request.program.get_name_with_check_mode(),
-            );
+    // The native backend and the ViperServer client do not need the JVM, so
+    // the Viper program is only built when it is dumped for another backend.
+    // A program that cannot be printed for ViperServer is reported as a
+    // backend error, as its verification would be.
+    let dump_program = || match &request.program {
+        Program::Low(program) if request.backend_config.backend == VerificationBackend::Native => {
+            dump_smt_script(program, &request.program.get_name_with_check_mode());
+            Ok(())
+        }
+        Program::Low(program) if config::viper_server_address().is_some() => {
+            let source = ViperSource::new(program)?;
+            dump_viper_source(&source.text, &request.program.get_name_with_check_mode());
+            Ok(())
+        }
+        _ => {
+            let ast_utils = verification_context.new_ast_utils();
+            ast_utils.with_local_frame(16, || {
+                let _stopwatch = Stopwatch::start("prusti-server", "construction of JVM objects");
+                let ast_factory = verification_context.new_ast_factory();
+                let viper_program = request
+                    .program
+                    .to_viper(prusti_common::vir::LoweringContext::default(), &ast_factory);
+                dump_viper_program(
+                    &ast_utils,
+                    viper_program,
+                    &request.program.get_name_with_check_mode(),
+                );
+            });
+            Ok::<_, UnsupportedConstruct>(())
         }
-
-        viper_program
     };
 
     // Only for testing: Print the hash and skip verification.
@@ -74,9 +110,9 @@ pub fn process_verification_request<'v, 't: 'v>(
         println!("Hash of the request is: {hash}");
         // Some tests need the dump to report a diff of the Viper programs.
         if config::dump_viper_program() {
-            ast_utils.with_local_frame(16, || {
-                let _ = build_or_dump_viper_program();
-            });
+            if let Err(error) = dump_program() {
+                return VerificationResult::BackendErrors(vec![error.to_string()]);
+            }
         }
         return viper::VerificationResult::Success;
     }
@@ -90,9 +126,9 @@ pub fn process_verification_request<'v, 't: 'v>(
                 request.program.get_name()
             );
             if config::dump_viper_program() {
-                ast_utils.with_local_frame(16, || {
-                    let _ = build_or_dump_viper_program();
-                });
+                if let Err(error) = dump_program() {
+                    return VerificationResult::BackendErrors(vec![error.to_string()]);
+                }
             }
             normalization_info.denormalize_result(&mut result);
             return result;
@@ -100,25 +136,72 @@ pub fn process_verification_request<'v, 't: 'v>(
     };
 
     let mut stopwatch = Stopwatch::start("prusti-server", "verifier startup");
+    let mut retries = config::verification_retries();
+    let mut result = loop {
+        // Create a new verifier each time, unless the pool keeps verifiers.
+        // Workaround for https://github.com/viperproject/prusti-dev/issues/744
+        let mut backend = match request.backend_config.backend {
+            VerificationBackend::Carbon | VerificationBackend::Silicon
+                if config::viper_server_address().is_some() =>
+            {
+                new_viper_server_backend(request.backend_config.clone())
+            }
+            VerificationBackend::Carbon | VerificationBackend::Silicon => {
+                let verifier = verifiers.take(&request.backend_config).unwrap_or_else(|| {
+                    new_viper_verifier(
+                        request.program.get_name(),
+                        verification_context,
+                        request.backend_config.clone(),
+                    )
+                });
+                Backend::Viper(verifier, verification_context)
+            }
+            VerificationBackend::Native => Backend::Native(NativeVerifier::new(
+                config::smt_solver_path(),
+                request.backend_config.verifier_args.clone(),
+            )),
+        };
 
-    // Create a new verifier each time.
-    // Workaround for https://github.com/viperproject/prusti-dev/issues/744
-    let mut backend = match request.backend_config.backend {
-        VerificationBackend::Carbon | VerificationBackend::Silicon => Backend::Viper(
-            new_viper_verifier(
-                request.program.get_name(),
-                verification_context,
-                request.backend_config,
-            ),
-            verification_context,
-        ),
+        stopwatch.start_next("backend verification");
+        let result = backend.verify(&request.program);
+        match &result {
+            // Only the JVM of this process can be cleaned up.
+            VerificationResult::JavaException(exception)
+                if exception.is_out_of_memory()
+                    && retries > 0
+                    && matches!(backend, Backend::Viper(..)) =>
+            {
+                retries -= 1;
+                // The memory of the verifiers is released before the retry.
+                drop(backend);
+                verifiers.clear();
+                verification_context.collect_garbage();
+                warn!(
+                    "The JVM ran out of memory while verifying {}, retrying with a new verifier",
+                    request.program.get_name()
+                );
+                stopwatch.start_next("verifier startup");
+            }
+            _ => {
+                if let Backend::Viper(verifier, _) = backend {
+                    verifiers.put(request.backend_config.clone(), verifier, &result);
+                }
+                break result;
+            }
+        }
     };
 
-    stopwatch.start_next("backend verification");
-    let mut result = backend.verify(&request.program);
-
-    // Don't cache Java exceptions, which might be due to misconfigured paths.
-    if config::enable_cache() && !matches!(result, VerificationResult::JavaException(_)) {
+    // Don't cache Java exceptions and backend errors, which might be due to
+    // misconfigured paths, nor timeouts, which depend on the load of the
+    // machine.
+    if config::enable_cache()
+        && !matches!(
+            result,
+            VerificationResult::JavaException(_)
+                | VerificationResult::BackendErrors(_)
+                | VerificationResult::Timeout
+        )
+    {
         info!(
             "Storing new cached result {:?} for program {}",
             &result,
@@ -142,12 +225,154 @@ pub fn dump_viper_program(
     report(namespace, filename, ast_utils.pretty_print(program));
 }
 
+/// Dumps a Viper program that was printed without the JVM.
+pub fn dump_viper_source(text: &str, program_name: &str) {
+    let namespace = "viper_program";
+    let filename = format!("{program_name}.vpr");
+    info!("Dumping Viper program to '{}/{}'", namespace, filename);
+    report(namespace, filename, text);
+}
+
+/// Verifies the VIR programs that were exported as JSON to `paths`, see
+/// `config::vir_export_dir`, and the Viper programs of the `.vpr` files among
+/// them, e.g. exported with `config::viper_export_dir` and patched by hand.
+/// Prints the result of each and returns whether all of them verified.
+pub fn verify_exported_programs(paths: &[PathBuf]) -> bool {
+    let viper = Viper::new_with_args(&config::viper_home(), config::extra_jvm_args());
+    let verification_context = Lazy::new(|| viper.attach_current_thread());
+    let mut cache = load_cache();
+    let mut verifiers = VerifierPool::default();
+    let mut success = true;
+    for path in paths {
+        let result = if path
+            .extension()
+            .map_or(false, |extension| extension == "vpr")
+        {
+            Ok(verify_viper_file(&verification_context, path))
+        } else {
+            fs::read_to_string(path)
+                .map_err(|error| error.to_string())
+                .and_then(|json| {
+                    serde_json::from_str::<Program>(&json).map_err(|error| error.to_string())
+                })
+                .map(|program| {
+                    let backend = if program.get_check_mode() == CheckMode::Specifications {
+                        config::verify_specifications_backend()
+                    } else {
+                        config::viper_backend()
+                    }
+                    .parse()
+                    .unwrap();
+                    let request = VerificationRequest {
+                        program,
+                        backend_config: ViperBackendConfig::new(backend),
+                        export_name: None,
+                    };
+                    process_verification_request(
+                        &verification_context,
+                        request,
+                        &mut cache,
+                        &mut verifiers,
+                    )
+                })
+        };
+        match result {
+            Ok(VerificationResult::Success) => println!("{}: verified", path.display()),
+            Ok(VerificationResult::Failure(errors)) => {
+                success = false;
+                println!("{}: failed", path.display());
+                for error in errors {
+                    println!("  {}: {}", error.full_id, error.message);
+                }
+            }
+            Ok(result) => {
+                success = false;
+                println!("{}: {result:?}", path.display());
+            }
+            Err(error) => {
+                success = false;
+                eprintln!("Could not read the VIR program {}: {error}", path.display());
+            }
+        }
+    }
+    success
+}
+
+/// Parses the Viper file at `path` and verifies it with the configured
+/// backend. The errors of an invalid file are reported as consistency errors.
+fn verify_viper_file<'v, 't: 'v>(
+    verification_context: &'v Lazy<VerificationContext<'t>, impl Fn() -> VerificationContext<'t>>,
+    path: &Path,
+) -> VerificationResult {
+    let ast_utils = verification_context.new_ast_utils();
+    ast_utils.with_local_frame(16, || match ast_utils.parse_file(path) {
+        Ok(program) => {
+            let backend_config = ViperBackendConfig::new(config::viper_backend().parse().unwrap());
+            let program_name = path.file_stem().unwrap_or_default().to_string_lossy();
+            new_viper_verifier(&program_name, verification_context, backend_config).verify(program)
+        }
+        Err(errors) => VerificationResult::ConsistencyErrors(errors),
+    })
+}
+
+/// Writes the Viper program to `<dir>/<export_name>.vpr`, see
+/// `config::viper_export_dir`.
+fn export_viper_program<'v, 't: 'v>(
+    verification_context: &'v Lazy<VerificationContext<'t>, impl Fn() -> VerificationContext<'t>>,
+    program: &Program,
+    dir: &Path,
+    export_name: &str,
+) {
+    let text = match program {
+        Program::Low(low_program) => match ViperSource::new(low_program) {
+            Ok(source) => source.text,
+            Err(error) => {
+                warn!(
+                    "Could not export the Viper program {}: {}",
+                    export_name, error
+                );
+                return;
+            }
+        },
+        Program::Legacy(_) => {
+            let ast_utils = verification_context.new_ast_utils();
+            ast_utils.with_local_frame(16, || {
+                let ast_factory = verification_context.new_ast_factory();
+                let viper_program =
+                    program.to_viper(prusti_common::vir::LoweringContext::default(), &ast_factory);
+                ast_utils.pretty_print(viper_program)
+            })
+        }
+    };
+    let path = dir.join(format!("{}.vpr", program.get_export_file_name(export_name)));
+    if let Err(error) = create_dir_all(dir).and_then(|()| fs::write(&path, text)) {
+        warn!(
+            "Could not export the Viper program to {}: {}",
+            path.display(),
+            error
+        );
+    }
+}
+
+/// Dumps the SMT-LIB script that the native backend checks, if the program
+/// is supported.
+pub fn dump_smt_script(program: &vir::low::Program, program_name: &str) {
+    let namespace = "smt_script";
+    let filename = format!("{program_name}.smt2");
+    match native_verifier::smt_script(program) {
+        Ok(script) => {
+            info!("Dumping SMT-LIB script to '{}/{}'", namespace, filename);
+            report(namespace, filename, script);
+        }
+        Err(error) => info!("Not dumping SMT-LIB script of {}: {}", program_name, error),
+    }
+}
+
 fn new_viper_verifier<'v, 't: 'v>(
     program_name: &str,
     verification_context: &'v viper::VerificationContext<'t>,
-    backend_config: ViperBackendConfig,
+    mut backend_config: ViperBackendConfig,
 ) -> viper::Verifier<'v> {
-    let mut verifier_args: Vec<String> = backend_config.verifier_args;
     let report_path: Option<PathBuf>;
     if config::dump_debug_info() {
         let log_path = config::log_dir()
@@ -158,23 +383,28 @@ fn new_viper_verifier<'v, 't: 'v>(
         let log_dir_str = log_path.to_str().unwrap();
         match backend_config.backend {
             VerificationBackend::Silicon => {
-                verifier_args.extend(vec![
+                backend_config.verifier_args.extend(vec![
                     "--tempDirectory".to_string(),
                     log_dir_str.to_string(),
                     "--printMethodCFGs".to_string(),
                     //"--printTranslatedProgram".to_string(),
                 ])
             }
-            VerificationBackend::Carbon => verifier_args.extend(vec![
-                "--boogieOpt".to_string(),
-                format!("/logPrefix {log_dir_str}"),
+            VerificationBackend::Carbon => {
+                // Carbon only keeps the last `--boogieOpt`, which may already
+                // contain other options.
+                backend_config.add_solver_arg("--boogieOpt", format!("/logPrefix {log_dir_str}"));
                 //"--print".to_string(), "./log/boogie_program/program.bpl".to_string(),
-            ]),
+            }
+            VerificationBackend::Native => unreachable!("the native backend does not use Viper"),
         }
     } else {
         report_path = None;
     }
-    let (smt_solver, smt_manager) = if config::use_smt_wrapper() {
+    // The SMT wrapper only supports Z3.
+    let (smt_solver, smt_manager) = if backend_config.smt_solver == SmtSolver::Cvc5 {
+        (config::cvc5_path(), SmtManager::default())
+    } else if config::use_smt_wrapper() {
         std::env::set_var("PRUSTI_ORIGINAL_SMT_SOLVER_PATH", config::smt_solver_path());
         let log_path = config::log_dir()
             .join("smt")
@@ -190,6 +420,7 @@ fn new_viper_verifier<'v, 't: 'v>(
             config::smt_qi_bound_trace_kind(),
             config::smt_unique_triggers_bound(),
             config::smt_unique_triggers_bound_total(),
+            config::smt_qi_profile().then(config::smt_qi_profile_top),
         );
         std::env::set_var(
             "PRUSTI_SMT_SOLVER_MANAGER_PORT",
@@ -198,6 +429,9 @@ fn new_viper_verifier<'v, 't: 'v>(
         if config::log_smt_wrapper_interaction() {
             std::env::set_var("PRUSTI_LOG_SMT_INTERACTION", "true");
         }
+        if config::dump_smt_queries() {
+            std::env::set_var("PRUSTI_DUMP_SMT_QUERIES", "true");
+        }
         (config::smt_solver_wrapper_path(), smt_manager)
     } else {
         (config::smt_solver_path(), SmtManager::default())
@@ -210,10 +444,33 @@ fn new_viper_verifier<'v, 't: 'v>(
 
     verification_context.new_verifier(
         backend_config.backend,
-        verifier_args,
+        backend_config.verifier_args,
         report_path,
+        backend_config.smt_solver,
         smt_solver,
         boogie_path,
         smt_manager,
     )
 }
+
+fn new_viper_server_backend<'v>(backend_config: ViperBackendConfig) -> Backend<'v> {
+    let address = config::viper_server_address().unwrap();
+    // The SMT wrapper runs in the process of Prusti, so ViperServer runs the
+    // solver directly.
+    let smt_solver_exe = if backend_config.smt_solver == SmtSolver::Cvc5 {
+        config::cvc5_path()
+    } else {
+        config::smt_solver_path()
+    };
+    Backend::ViperServer {
+        client: ViperServerClient::new(&address),
+        backend: backend_config.backend,
+        verifier_args: viper::verifier_args(
+            backend_config.backend,
+            backend_config.verifier_args,
+            backend_config.smt_solver,
+            smt_solver_exe,
+            config::boogie_path(),
+        ),
+    }
+}
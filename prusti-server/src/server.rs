@@ -4,25 +4,84 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::{process_verification_request, VerificationRequest};
-use log::info;
+use crate::{
+    load_cache, process_verification_request, wait_until_finished, BatchResult, HeapUsage, JobId,
+    JobPriority, JobQueue, JobStatus, Metrics, VerificationBatch, VerificationRequest,
+    VerifierPool,
+};
+use log::{info, warn};
 use once_cell::sync::Lazy;
 use prusti_common::{config, Stopwatch};
 use std::{
-    net::{Ipv4Addr, SocketAddr},
+    future::Future,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
     sync::{mpsc, Arc, Mutex},
     thread,
+    time::{Duration, Instant},
 };
-use tokio::runtime::Builder;
-use viper::{PersistentCache, Viper};
-use warp::Filter;
+use tokio::{runtime::Builder, sync::watch, task};
+use viper::{VerificationResult, Viper};
+use warp::{http::StatusCode, Filter, Rejection, Reply};
 
 #[derive(Debug)]
 struct BincodeReject(bincode::Error);
 impl warp::reject::Reject for BincodeReject {}
 
+#[derive(Debug)]
+struct Unauthorized;
+impl warp::reject::Reject for Unauthorized {}
+
+/// How often the server saves its verification cache to disk, if the cache
+/// changed.
+const CACHE_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often a server with `config::server_idle_timeout()` checks whether it
+/// is idle.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
 pub fn start_server_on_port(port: u16) {
-    listen_on_port_with_address_callback(port, move |address| {
+    start_server(Ipv4Addr::LOCALHOST.into(), port);
+}
+
+/// The paths to the certificate chain and the private key of a server that
+/// uses TLS.
+struct TlsFiles {
+    cert_path: String,
+    key_path: String,
+}
+
+/// Starts a server that listens on the given IP address. Servers that can be
+/// reached from other machines require a `server_auth_token`, and should use
+/// TLS.
+pub fn start_server(ip: IpAddr, port: u16) {
+    assert!(
+        ip.is_loopback() || config::server_auth_token().is_some(),
+        "Refusing to listen on {ip} without authentication, please set the server_auth_token \
+        configuration flag"
+    );
+    let tls = match (
+        config::server_tls_cert_path(),
+        config::server_tls_key_path(),
+    ) {
+        (Some(cert_path), Some(key_path)) => Some(TlsFiles {
+            cert_path,
+            key_path,
+        }),
+        (None, None) => None,
+        _ => panic!(
+            "The server_tls_cert_path and server_tls_key_path configuration flags must be set \
+            together"
+        ),
+    };
+    if !ip.is_loopback() && tls.is_none() {
+        warn!(
+            "Listening on {} without TLS, the verification requests are sent in plain text",
+            ip
+        );
+    }
+    listen_on_port_with_address_callback(ip, port, tls, move |address| {
         if port == 0 {
             return;
         }
@@ -34,7 +93,9 @@ pub fn spawn_server_thread() -> SocketAddr {
     let (sender, receiver) = mpsc::channel();
     thread::spawn(move || {
         listen_on_port_with_address_callback(
+            Ipv4Addr::LOCALHOST.into(),
             0, // ask system for port
+            None,
             move |address| sender.send(address).unwrap(),
         );
     });
@@ -42,7 +103,12 @@ pub fn spawn_server_thread() -> SocketAddr {
     receiver.recv().unwrap()
 }
 
-fn listen_on_port_with_address_callback<F>(port: u16, address_callback: F) -> !
+fn listen_on_port_with_address_callback<F>(
+    ip: IpAddr,
+    port: u16,
+    tls: Option<TlsFiles>,
+    address_callback: F,
+) -> !
 where
     F: FnOnce(SocketAddr),
 {
@@ -53,23 +119,132 @@ where
 
     stopwatch.finish();
 
-    let cache_data = PersistentCache::load_cache(config::cache_path());
-    let cache = Arc::new(Mutex::new(cache_data));
-    let build_verification_request_handler = |viper_arc: Arc<Lazy<Viper, _>>, cache| {
+    let cache = Arc::new(Mutex::new(load_cache()));
+    {
+        let cache = cache.clone();
+        thread::Builder::new()
+            .name("prusti-server-cache".to_string())
+            .spawn(move || loop {
+                thread::sleep(CACHE_SAVE_INTERVAL);
+                cache.lock().unwrap().save();
+            })
+            .expect("failed to spawn the cache thread");
+    }
+    let queue = Arc::new(JobQueue::new());
+    let metrics = Arc::new(Metrics::default());
+
+    // By default, requests are verified one at a time because
+    // 1. Viper is not thread safe yet (Silicon issue #578), and
+    // 2. By default Silicon already uses as many cores as possible.
+    for index in 0..config::verification_threads() {
+        let viper = viper.clone();
+        let cache = cache.clone();
+        let queue = queue.clone();
+        let metrics = metrics.clone();
+        thread::Builder::new()
+            .name(format!("prusti-server-verifier-{index}"))
+            .spawn(move || {
+                let max_retries = config::verification_retries();
+                let max_stored_verifiers = config::server_max_stored_verifiers().unwrap_or(0);
+                // The thread is attached to the JVM again after a panic,
+                // since the verification context might be broken, e.g. by a
+                // pending Java exception.
+                loop {
+                    let viper_thread = Lazy::new(|| {
+                        let _stopwatch = Stopwatch::start("prusti-server", "attach thread to JVM");
+                        viper.attach_current_thread()
+                    });
+                    let mut verifiers = VerifierPool::new(max_stored_verifiers);
+                    loop {
+                        let (id, request) = queue.next_job();
+                        // The request is kept to retry a crashed verification.
+                        let retry_request = (max_retries > 0).then(|| request.clone());
+                        let start = Instant::now();
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                            let cache = metrics.counting_cache(&cache);
+                            process_verification_request(
+                                &viper_thread,
+                                request,
+                                cache,
+                                &mut verifiers,
+                            )
+                        }));
+                        let duration = start.elapsed();
+                        match result {
+                            Ok(result) => {
+                                metrics.record_result(&result, duration);
+                                queue.finish(id, result, duration);
+                            }
+                            Err(_) => {
+                                let retried = retry_request
+                                    .map_or(false, |request| queue.retry(id, request, max_retries));
+                                if retried {
+                                    warn!("The verification of job {} panicked, retrying", id);
+                                    metrics.record_retry();
+                                } else {
+                                    let result = VerificationResult::BackendErrors(vec![format!(
+                                        "the verification of job {id} panicked"
+                                    )]);
+                                    metrics.record_result(&result, duration);
+                                    queue.finish(id, result, duration);
+                                }
+                                break;
+                            }
+                        }
+                    }
+                }
+            })
+            .expect("failed to spawn a verification thread");
+    }
+
+    // The time of the last request, after which an idle server stops.
+    let last_request = Arc::new(Mutex::new(Instant::now()));
+    if let Some(timeout) = config::server_idle_timeout() {
+        let timeout = Duration::from_secs(timeout);
+        let last_request = last_request.clone();
+        let queue = queue.clone();
+        let cache = cache.clone();
+        thread::Builder::new()
+            .name("prusti-server-idle".to_string())
+            .spawn(move || loop {
+                thread::sleep(IDLE_CHECK_INTERVAL);
+                let statistics = queue.statistics();
+                let idle = last_request.lock().unwrap().elapsed();
+                if statistics.queued == 0 && statistics.running == 0 && idle >= timeout {
+                    info!(
+                        "Prusti Server stops after being idle for {} seconds",
+                        idle.as_secs()
+                    );
+                    cache.lock().unwrap().save();
+                    std::process::exit(0);
+                }
+            })
+            .expect("failed to spawn the idle thread");
+    }
+
+    // The requests of the verification endpoints are queued with the normal
+    // priority.
+    let build_verification_request_handler = |queue: Arc<JobQueue>| {
         move |request: VerificationRequest| {
-            let stopwatch = Stopwatch::start("prusti-server", "attach thread to JVM");
-            let viper_thread = Lazy::new(|| viper_arc.attach_current_thread());
-            stopwatch.finish();
-            process_verification_request(&viper_thread, request, &cache)
+            let queue = queue.clone();
+            async move {
+                let id = queue.submit(VerificationBatch {
+                    priority: JobPriority::Normal,
+                    requests: vec![request],
+                })[0];
+                let status = queue.subscribe(id).unwrap();
+                let (result, _) = wait_until_finished(status)
+                    .await
+                    .expect("the verification job was dropped");
+                queue.remove(id);
+                Ok::<_, warp::Rejection>(result)
+            }
         }
     };
 
     let json_verify = warp::path!("json" / "verify")
         .and(warp::body::json())
-        .map(build_verification_request_handler(
-            viper.clone(),
-            cache.clone(),
-        ))
+        .and_then(build_verification_request_handler(queue.clone()))
         .map(|response| warp::reply::json(&response));
 
     let bincode_verify = warp::path!("bincode" / "verify")
@@ -80,13 +255,32 @@ where
                 warp::reject::custom(BincodeReject(err))
             })
         })
-        .map(build_verification_request_handler(viper, cache.clone()))
+        .and_then(build_verification_request_handler(queue.clone()))
         .map(|result| {
             warp::http::Response::new(
                 bincode::serialize(&result).expect("could not encode verification result"),
             )
         });
 
+    // Warms the cache with the results of a cache file, e.g. one of a
+    // previous CI run, and replies with the number of added results.
+    let warm_cache = warp::post()
+        .and(warp::path!("bincode" / "cache" / "warm"))
+        .and(warp::body::bytes())
+        .map({
+            let cache = cache.clone();
+            move |buf: warp::hyper::body::Bytes| match cache.lock().unwrap().import(&buf) {
+                Ok(added) => {
+                    info!("Warmed the cache with {} results", added);
+                    warp::reply::with_status(warp::reply::json(&added), StatusCode::OK)
+                }
+                Err(message) => {
+                    info!("request cache body error: {}", message);
+                    warp::reply::with_status(warp::reply::json(&message), StatusCode::BAD_REQUEST)
+                }
+            }
+        });
+
     let save_cache = warp::post()
         .and(warp::path("save"))
         .and(warp::path::end())
@@ -95,11 +289,131 @@ where
             warp::reply::html("Saved")
         });
 
-    let endpoints = json_verify.or(bincode_verify).or(save_cache);
+    // Batches of requests are queued as jobs, whose status can be polled or
+    // streamed as newline-delimited JSON until they are finished. The result
+    // of a job can be retrieved only once.
+    let json_submit_jobs = warp::post()
+        .and(warp::path!("json" / "jobs"))
+        .and(warp::body::json())
+        .map({
+            let queue = queue.clone();
+            move |batch: VerificationBatch| warp::reply::json(&queue.submit(batch))
+        });
+
+    let bincode_submit_jobs = warp::post()
+        .and(warp::path!("bincode" / "jobs"))
+        .and(warp::body::bytes())
+        .and_then(|buf: warp::hyper::body::Bytes| async move {
+            bincode::deserialize(&buf).map_err(|err| {
+                info!("request bincode body error: {}", err);
+                warp::reject::custom(BincodeReject(err))
+            })
+        })
+        .map({
+            let queue = queue.clone();
+            move |batch: VerificationBatch| {
+                warp::http::Response::new(
+                    bincode::serialize(&queue.submit(batch)).expect("could not encode job ids"),
+                )
+            }
+        });
+
+    // A batch can also be verified with a single request, whose response
+    // streams the result of every request of the batch as newline-delimited
+    // JSON, in the order in which the results are known.
+    let json_stream_batch = warp::post()
+        .and(warp::path!("json" / "jobs" / "stream"))
+        .and(warp::body::json())
+        .map({
+            let queue = queue.clone();
+            move |batch: VerificationBatch| stream_batch_results(queue.clone(), batch)
+        });
+
+    let bincode_stream_batch = warp::post()
+        .and(warp::path!("bincode" / "jobs" / "stream"))
+        .and(warp::body::bytes())
+        .and_then(|buf: warp::hyper::body::Bytes| async move {
+            bincode::deserialize(&buf).map_err(|err| {
+                info!("request bincode body error: {}", err);
+                warp::reject::custom(BincodeReject(err))
+            })
+        })
+        .map({
+            let queue = queue.clone();
+            move |batch: VerificationBatch| stream_batch_results(queue.clone(), batch)
+        });
+
+    let poll_job = warp::get()
+        .and(warp::path!("json" / "jobs" / u64))
+        .and_then({
+            let queue = queue.clone();
+            move |id: u64| {
+                let queue = queue.clone();
+                async move {
+                    let id = JobId(id);
+                    let status = queue.status(id).ok_or_else(warp::reject::not_found)?;
+                    if status.is_finished() {
+                        queue.remove(id);
+                    }
+                    Ok::<_, warp::Rejection>(warp::reply::json(&status))
+                }
+            }
+        });
+
+    let export_metrics = warp::get().and(warp::path!("metrics")).map({
+        let queue = queue.clone();
+        move || {
+            let heap = Lazy::get(&viper).map(|viper| {
+                let (used, max) = viper.heap_usage();
+                HeapUsage { used, max }
+            });
+            warp::reply::with_header(
+                metrics.render(queue.statistics(), heap),
+                "Content-Type",
+                "text/plain; version=0.0.4",
+            )
+        }
+    });
+
+    let stream_job = warp::get()
+        .and(warp::path!("json" / "jobs" / u64 / "stream"))
+        .and_then(move |id: u64| {
+            let queue = queue.clone();
+            async move {
+                let id = JobId(id);
+                let status = queue.subscribe(id).ok_or_else(warp::reject::not_found)?;
+                let (sender, body) = warp::hyper::Body::channel();
+                task::spawn(stream_job_status(queue, id, status, sender));
+                Ok::<_, warp::Rejection>(
+                    warp::http::Response::builder()
+                        .header("Content-Type", "application/x-ndjson")
+                        .body(body)
+                        .unwrap(),
+                )
+            }
+        });
+
+    let track_activity = warp::any()
+        .map(move || *last_request.lock().unwrap() = Instant::now())
+        .untuple_one();
+
+    let endpoints = track_activity
+        .and(authorization(config::server_auth_token()))
+        .and(
+            json_verify
+                .or(bincode_verify)
+                .or(save_cache)
+                .or(warm_cache)
+                .or(json_submit_jobs)
+                .or(bincode_submit_jobs)
+                .or(json_stream_batch)
+                .or(bincode_stream_batch)
+                .or(poll_job)
+                .or(stream_job)
+                .or(export_metrics),
+        )
+        .recover(reply_unauthorized);
 
-    // Here we use a single thread because
-    // 1. Viper is not thread safe yet (Silicon issue #578), and
-    // 2. By default Silicon already uses as many cores as possible.
     let runtime = Builder::new_current_thread()
         .thread_name("prusti-server")
         .enable_all()
@@ -108,8 +422,25 @@ where
 
     runtime.block_on(async {
         info!("Prusti Server binding to port {}", port);
-        let (address, server_loop) =
-            warp::serve(endpoints).bind_ephemeral((Ipv4Addr::LOCALHOST, port));
+        let server = warp::serve(endpoints);
+        let (address, server_loop): (_, Pin<Box<dyn Future<Output = ()>>>) = match tls {
+            Some(tls) => {
+                info!(
+                    "Prusti Server uses TLS with the certificate {}",
+                    tls.cert_path
+                );
+                let (address, server_loop) = server
+                    .tls()
+                    .cert_path(tls.cert_path)
+                    .key_path(tls.key_path)
+                    .bind_ephemeral((ip, port));
+                (address, Box::pin(server_loop))
+            }
+            None => {
+                let (address, server_loop) = server.bind_ephemeral((ip, port));
+                (address, Box::pin(server_loop))
+            }
+        };
 
         println!("port: {}", address.port());
         address_callback(address);
@@ -120,3 +451,131 @@ where
 
     unreachable!("The server unexpectedly stopped.");
 }
+
+/// Rejects the requests that do not have the header
+/// `Authorization: Bearer <token>`, if a token is set.
+fn authorization(token: Option<String>) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and_then(move |header: Option<String>| {
+            let authorized = match &token {
+                None => true,
+                Some(token) => header
+                    .as_deref()
+                    .and_then(|header| header.strip_prefix("Bearer "))
+                    .map_or(false, |given| {
+                        constant_time_eq(given.as_bytes(), token.as_bytes())
+                    }),
+            };
+            async move {
+                if authorized {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(Unauthorized))
+                }
+            }
+        })
+        .untuple_one()
+}
+
+async fn reply_unauthorized(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        info!("Rejected a request without a valid authentication token");
+        Ok(warp::reply::with_status(
+            "Unauthorized",
+            StatusCode::UNAUTHORIZED,
+        ))
+    } else {
+        Err(rejection)
+    }
+}
+
+/// Compares the tokens in a time that does not depend on the position of the
+/// first difference.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0, |difference, (left, right)| difference | (left ^ right))
+            == 0
+}
+
+/// Sends every status of the job as a line of JSON, until the job is finished
+/// or the client disconnects.
+/// Queues the requests of the batch and replies with a stream of their
+/// results.
+fn stream_batch_results(
+    queue: Arc<JobQueue>,
+    batch: VerificationBatch,
+) -> warp::http::Response<warp::hyper::Body> {
+    let ids = queue.submit(batch);
+    let (sender, body) = warp::hyper::Body::channel();
+    task::spawn(send_batch_results(queue, ids, sender));
+    warp::http::Response::builder()
+        .header("Content-Type", "application/x-ndjson")
+        .body(body)
+        .unwrap()
+}
+
+async fn send_batch_results(
+    queue: Arc<JobQueue>,
+    ids: Vec<JobId>,
+    mut sender: warp::hyper::body::Sender,
+) {
+    let (results_sender, mut results) = tokio::sync::mpsc::unbounded_channel();
+    for (index, job) in ids.into_iter().enumerate() {
+        let Some(status) = queue.subscribe(job) else {
+            continue;
+        };
+        let results_sender = results_sender.clone();
+        task::spawn(async move {
+            if let Some((result, duration)) = wait_until_finished(status).await {
+                let _ = results_sender.send(BatchResult {
+                    index,
+                    job,
+                    result,
+                    duration,
+                });
+            }
+        });
+    }
+    drop(results_sender);
+    let mut connected = true;
+    while let Some(result) = results.recv().await {
+        // The jobs of a disconnected client are still removed once finished.
+        queue.remove(result.job);
+        if !connected {
+            continue;
+        }
+        let mut line = serde_json::to_vec(&result).expect("could not encode batch result");
+        line.push(b'\n');
+        if sender.send_data(line.into()).await.is_err() {
+            info!("The client stopped following the verification batch");
+            connected = false;
+        }
+    }
+}
+
+async fn stream_job_status(
+    queue: Arc<JobQueue>,
+    id: JobId,
+    mut status: watch::Receiver<JobStatus>,
+    mut sender: warp::hyper::body::Sender,
+) {
+    loop {
+        let current = status.borrow_and_update().clone();
+        let mut line = serde_json::to_vec(&current).expect("could not encode job status");
+        line.push(b'\n');
+        if sender.send_data(line.into()).await.is_err() {
+            info!("The client stopped following the verification job {}", id);
+            return;
+        }
+        if current.is_finished() {
+            queue.remove(id);
+            return;
+        }
+        if status.changed().await.is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{
+    BatchResult, JobPriority, JobStatus, PrustiClient, VerificationBatch, VerificationRequest,
+};
+use log::{error, info};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tokio::task;
+use viper::VerificationResult;
+
+/// Distributes verification requests among several Prusti servers.
+pub struct ServerPool {
+    clients: Arc<Vec<PrustiClient>>,
+}
+
+impl ServerPool {
+    pub fn new(clients: Vec<PrustiClient>) -> Self {
+        assert!(!clients.is_empty(), "the server pool is empty");
+        Self {
+            clients: Arc::new(clients),
+        }
+    }
+
+    /// Verifies the requests and returns their results and verification
+    /// times, in the order of the requests. `on_result` is called with the
+    /// index, the result and the verification time of each request as soon
+    /// as the request is verified.
+    ///
+    /// Each server verifies at most `jobs_per_server` requests at a time and
+    /// takes the next request once one of them is finished, so that faster
+    /// servers verify more requests. Servers that cannot be reached are
+    /// removed from the pool, and their requests are verified by the other
+    /// servers. A single server gets all requests at once instead.
+    pub async fn verify<F>(
+        &self,
+        requests: Vec<VerificationRequest>,
+        priority: JobPriority,
+        jobs_per_server: usize,
+        on_result: F,
+    ) -> Vec<(VerificationResult, Duration)>
+    where
+        F: Fn(usize, &VerificationResult, Duration) + Send + Sync + 'static,
+    {
+        if let [client] = self.clients.as_slice() {
+            return verify_batch(client, requests, priority, on_result).await;
+        }
+        let on_result = Arc::new(on_result);
+        let results = Arc::new(Mutex::new(vec![None; requests.len()]));
+        let pending: Arc<Mutex<VecDeque<_>>> =
+            Arc::new(Mutex::new(requests.into_iter().enumerate().collect()));
+        let failed: Arc<Vec<_>> = Arc::new(
+            self.clients
+                .iter()
+                .map(|_| AtomicBool::new(false))
+                .collect(),
+        );
+        let mut handles = vec![];
+        for server in 0..self.clients.len() {
+            for _ in 0..jobs_per_server.max(1) {
+                let clients = self.clients.clone();
+                let results = results.clone();
+                let pending = pending.clone();
+                let failed = failed.clone();
+                let on_result = on_result.clone();
+                handles.push(task::spawn(async move {
+                    while !failed[server].load(Ordering::Relaxed) {
+                        let Some((index, request)) = pending.lock().unwrap().pop_front() else {
+                            return;
+                        };
+                        let result =
+                            verify_on_pool(&clients, &failed, server, request, priority).await;
+                        if let Some((result, duration)) = &result {
+                            on_result(index, result, *duration);
+                        }
+                        results.lock().unwrap()[index] = result;
+                    }
+                }));
+            }
+        }
+        for handle in handles {
+            handle.await.expect("verification task panicked");
+        }
+        let results = std::mem::take(&mut *results.lock().unwrap());
+        results
+            .into_iter()
+            .map(|result| {
+                result.unwrap_or_else(|| panic!("No Prusti server of the pool could be reached"))
+            })
+            .collect()
+    }
+}
+
+/// Verifies all requests on one server, which streams their results.
+async fn verify_batch(
+    client: &PrustiClient,
+    requests: Vec<VerificationRequest>,
+    priority: JobPriority,
+    on_result: impl Fn(usize, &VerificationResult, Duration),
+) -> Vec<(VerificationResult, Duration)> {
+    let mut results = vec![None; requests.len()];
+    let batch = VerificationBatch { priority, requests };
+    client
+        .verify_batch(
+            batch,
+            |BatchResult {
+                 index,
+                 job,
+                 result,
+                 duration,
+             }| {
+                info!("Verified job {} in {:?}", job, duration);
+                on_result(index, &result, duration);
+                results[index] = Some((result, duration));
+            },
+        )
+        .await
+        .unwrap_or_else(|error| {
+            panic!(
+                "Could not verify the requests on the Prusti server {}: {error}",
+                client.server_url()
+            )
+        });
+    results
+        .into_iter()
+        .map(|result| result.expect("the Prusti server did not verify all requests"))
+        .collect()
+}
+
+/// Verifies the request on the server `first_server`, or on the next server
+/// of the pool that can be reached.
+async fn verify_on_pool(
+    clients: &[PrustiClient],
+    failed: &[AtomicBool],
+    first_server: usize,
+    request: VerificationRequest,
+    priority: JobPriority,
+) -> Option<(VerificationResult, Duration)> {
+    for offset in 0..clients.len() {
+        let server = (first_server + offset) % clients.len();
+        if failed[server].load(Ordering::Relaxed) {
+            continue;
+        }
+        match verify_on_server(&clients[server], request.clone(), priority).await {
+            Ok(result) => return Some(result),
+            Err(message) => {
+                error!(
+                    "Removing server {} from the pool, because the verification of {} failed: {}",
+                    clients[server].server_url(),
+                    request.program.get_name(),
+                    message
+                );
+                failed[server].store(true, Ordering::Relaxed);
+            }
+        }
+    }
+    None
+}
+
+async fn verify_on_server(
+    client: &PrustiClient,
+    request: VerificationRequest,
+    priority: JobPriority,
+) -> Result<(VerificationResult, Duration), String> {
+    let program_name = request.program.get_name().to_string();
+    let job_ids = client
+        .submit(VerificationBatch {
+            priority,
+            requests: vec![request],
+        })
+        .await
+        .map_err(|error| error.to_string())?;
+    info!("Verifying {} as job {}", program_name, job_ids[0]);
+    match client
+        .wait_for_job(job_ids[0])
+        .await
+        .map_err(|error| error.to_string())?
+    {
+        JobStatus::Finished { result, duration } => Ok((result, duration)),
+        _ => Err("the server closed the connection".to_string()),
+    }
+}
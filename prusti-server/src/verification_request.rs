@@ -7,34 +7,51 @@
 use prusti_common::{config, vir::program::Program};
 use rustc_hash::FxHasher;
 use std::hash::{Hash, Hasher};
-use viper::{self, VerificationBackend};
+use viper::{self, SmtSolver, VerificationBackend};
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Hash)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct VerificationRequest {
     pub program: Program,
     pub backend_config: ViperBackendConfig,
+    /// The name under which the program is exported, e.g.
+    /// `module::function`, see `config::viper_export_dir`.
+    #[serde(default)]
+    pub export_name: Option<String>,
 }
 
 impl VerificationRequest {
+    /// The hash of the request, which identifies its result in the cache. The
+    /// export name does not affect the result and is not hashed.
     pub(crate) fn get_hash(&self) -> u64 {
         let mut hasher = FxHasher::default();
-        self.hash(&mut hasher);
+        self.program.hash(&mut hasher);
+        self.backend_config.hash(&mut hasher);
         hasher.finish()
     }
 }
 
 /// The configuration for the viper backend, (i.e. verifier).
-/// Expresses which backend (silicon or carbon) should be used, and provides command-line arguments
-/// to the viper verifier.
+/// Expresses which backend (silicon or carbon) and SMT solver should be used, and provides
+/// command-line arguments to the viper verifier.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Eq, PartialEq, Hash)]
 pub struct ViperBackendConfig {
     pub backend: VerificationBackend,
+    pub smt_solver: SmtSolver,
     pub verifier_args: Vec<String>,
 }
 
 impl ViperBackendConfig {
     pub fn new(backend: VerificationBackend) -> Self {
         let mut verifier_args = config::extra_verifier_args();
+        let plugins = config::viper_plugins();
+        if !plugins.is_empty() {
+            verifier_args.push("--plugin".to_string());
+            verifier_args.push(plugins.join(":"));
+        }
+        let smt_solver = match backend {
+            VerificationBackend::Silicon => config::smt_solver().parse().unwrap(),
+            VerificationBackend::Carbon | VerificationBackend::Native => SmtSolver::Z3,
+        };
         match backend {
             VerificationBackend::Silicon => {
                 verifier_args.push(format!(
@@ -53,21 +70,28 @@ impl ViperBackendConfig {
                     verifier_args.push(number.to_string());
                 }
 
+                if !config::viper_termination_plugin() {
+                    verifier_args.push("--disableTerminationPlugin".to_string());
+                }
                 verifier_args.extend(vec![
-                    "--disableTerminationPlugin".to_string(),
                     "--assertTimeout".to_string(),
                     config::assert_timeout().to_string(),
-                    "--proverConfigArgs".to_string(),
-                    // model.partial changes the default case of functions in counterexamples
-                    // to #unspecified
-                    format!(
-                        "smt.qi.eager_threshold={} model.partial={}",
-                        config::smt_qi_eager_threshold(),
-                        config::counterexample()
-                    ),
-                    "--logLevel".to_string(),
-                    "ERROR".to_string(),
                 ]);
+                // The Z3 options have no counterpart in cvc5, and the options
+                // of cvc5 are only added when needed, see `add_solver_arg`.
+                if smt_solver == SmtSolver::Z3 {
+                    verifier_args.extend(vec![
+                        "--proverConfigArgs".to_string(),
+                        // model.partial changes the default case of functions in counterexamples
+                        // to #unspecified
+                        format!(
+                            "smt.qi.eager_threshold={} model.partial={}",
+                            config::smt_qi_eager_threshold(),
+                            config::counterexample()
+                        ),
+                    ]);
+                }
+                verifier_args.extend(vec!["--logLevel".to_string(), "ERROR".to_string()]);
 
                 if let Some(check_timeout) = config::check_timeout() {
                     verifier_args.push("--checkTimeout".to_string());
@@ -77,10 +101,160 @@ impl ViperBackendConfig {
             VerificationBackend::Carbon => {
                 verifier_args.extend(vec!["--disableAllocEncoding".to_string()]);
             }
+            VerificationBackend::Native => {
+                // The native backend passes the arguments directly to Z3,
+                // which does not understand the Viper options.
+                verifier_args = vec![format!(
+                    "smt.qi.eager_threshold={}",
+                    config::smt_qi_eager_threshold()
+                )];
+                if let Some(check_timeout) = config::check_timeout() {
+                    verifier_args.push(format!("-t:{check_timeout}"));
+                }
+            }
         }
-        Self {
+        let mut backend_config = Self {
             backend,
+            smt_solver,
             verifier_args,
+        };
+        if let Some(rlimit) = config::smt_rlimit() {
+            backend_config.set_rlimit(rlimit);
+        }
+        if let Some(seed) = config::smt_random_seed() {
+            backend_config.set_random_seed(seed);
+        }
+        backend_config
+    }
+
+    /// Sets the resource limit of the SMT solver, which is `rlimit` in Z3
+    /// and `rlimit-per` in cvc5. Carbon passes it on to Z3 through Boogie.
+    pub fn set_rlimit(&mut self, rlimit: u64) {
+        match (self.backend, self.smt_solver) {
+            (VerificationBackend::Native, _) => self.verifier_args.push(format!("rlimit={rlimit}")),
+            (VerificationBackend::Carbon, _) => {
+                self.add_solver_arg("--boogieOpt", format!("/rlimit:{rlimit}"))
+            }
+            (VerificationBackend::Silicon, SmtSolver::Z3) => {
+                self.add_solver_arg("--proverConfigArgs", format!("rlimit={rlimit}"))
+            }
+            (VerificationBackend::Silicon, SmtSolver::Cvc5) => {
+                self.add_solver_arg("--proverArgs", format!("--rlimit-per={rlimit}"))
+            }
+        }
+    }
+
+    /// Sets the random seed of the SMT solver. Carbon passes it on to Z3
+    /// through Boogie.
+    pub fn set_random_seed(&mut self, seed: u64) {
+        match (self.backend, self.smt_solver) {
+            (VerificationBackend::Native, _) => {
+                self.verifier_args.push(format!("smt.random_seed={seed}"));
+                self.verifier_args.push(format!("sat.random_seed={seed}"));
+            }
+            (VerificationBackend::Carbon, _) => {
+                self.add_solver_arg("--boogieOpt", format!("/randomSeed:{seed}"))
+            }
+            (VerificationBackend::Silicon, SmtSolver::Z3) => {
+                self.add_solver_arg("--proverConfigArgs", format!("smt.random_seed={seed}"));
+                self.add_solver_arg("--proverConfigArgs", format!("sat.random_seed={seed}"));
+            }
+            (VerificationBackend::Silicon, SmtSolver::Cvc5) => {
+                self.add_solver_arg("--proverArgs", format!("--seed={seed}"))
+            }
         }
     }
+
+    /// Sets the time limit of the verification in seconds. Carbon has no such
+    /// option, so the limit is ignored.
+    pub fn set_time_limit(&mut self, seconds: u64) {
+        match self.backend {
+            VerificationBackend::Silicon => {
+                self.verifier_args.push("--timeout".to_string());
+                self.verifier_args.push(seconds.to_string());
+            }
+            VerificationBackend::Native => self.verifier_args.push(format!("-T:{seconds}")),
+            VerificationBackend::Carbon => {}
+        }
+    }
+
+    /// Appends `arg` to the value of the verifier option `option`, which
+    /// collects the options passed to the SMT solver or to Boogie, adding the
+    /// option if needed. Later options override earlier ones.
+    pub(crate) fn add_solver_arg(&mut self, option: &str, arg: String) {
+        let position = self
+            .verifier_args
+            .iter()
+            .rposition(|verifier_arg| verifier_arg == option);
+        if let Some(position) = position {
+            let solver_args = &mut self.verifier_args[position + 1];
+            solver_args.push(' ');
+            solver_args.push_str(&arg);
+        } else {
+            self.verifier_args.push(option.to_string());
+            self.verifier_args.push(arg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backend_config(backend: VerificationBackend, smt_solver: SmtSolver) -> ViperBackendConfig {
+        ViperBackendConfig {
+            backend,
+            smt_solver,
+            verifier_args: vec![
+                "--proverConfigArgs".to_string(),
+                "smt.qi.eager_threshold=1000".to_string(),
+            ],
+        }
+    }
+
+    #[test]
+    fn z3_options_are_appended_to_the_prover_config() {
+        let mut config = backend_config(VerificationBackend::Silicon, SmtSolver::Z3);
+        config.set_rlimit(100);
+        config.set_random_seed(7);
+        assert_eq!(
+            config.verifier_args,
+            vec![
+                "--proverConfigArgs",
+                "smt.qi.eager_threshold=1000 rlimit=100 smt.random_seed=7 sat.random_seed=7",
+            ]
+        );
+    }
+
+    #[test]
+    fn cvc5_options_are_passed_as_prover_args() {
+        let mut config = backend_config(VerificationBackend::Silicon, SmtSolver::Cvc5);
+        config.set_rlimit(100);
+        config.set_random_seed(7);
+        assert_eq!(
+            config.verifier_args,
+            vec![
+                "--proverConfigArgs",
+                "smt.qi.eager_threshold=1000",
+                "--proverArgs",
+                "--rlimit-per=100 --seed=7",
+            ]
+        );
+    }
+
+    #[test]
+    fn carbon_passes_solver_options_to_boogie() {
+        let mut config = backend_config(VerificationBackend::Carbon, SmtSolver::Z3);
+        config.set_rlimit(100);
+        config.set_random_seed(7);
+        assert_eq!(
+            config.verifier_args,
+            vec![
+                "--proverConfigArgs",
+                "smt.qi.eager_threshold=1000",
+                "--boogieOpt",
+                "/rlimit:100 /randomSeed:7",
+            ]
+        );
+    }
 }
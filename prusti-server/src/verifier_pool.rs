@@ -0,0 +1,84 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::ViperBackendConfig;
+use log::debug;
+use prusti_common::config;
+use std::collections::VecDeque;
+use viper::{VerificationResult, Verifier};
+
+/// Initialized Viper verifiers of one thread, which are reused for the
+/// requests with the same backend configuration, because initializing a
+/// verifier can take longer than verifying a small program. The verifiers
+/// are bound to the thread, since they are local references of its JNI
+/// environment.
+#[derive(Default)]
+pub struct VerifierPool<'v> {
+    /// The unused verifiers, the most recently used last.
+    verifiers: VecDeque<(ViperBackendConfig, Verifier<'v>)>,
+    capacity: usize,
+}
+
+impl<'v> VerifierPool<'v> {
+    /// Creates a pool that keeps at most `capacity` verifiers. A pool with
+    /// no capacity does not reuse verifiers.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            verifiers: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Whether verifiers can be reused. Verifiers that report to the log
+    /// directory of a program, or talk to the SMT wrapper of a program, are
+    /// created for each program.
+    fn is_enabled(&self) -> bool {
+        self.capacity > 0 && !config::dump_debug_info() && !config::use_smt_wrapper()
+    }
+
+    /// Takes an unused verifier with the given configuration, if any.
+    pub fn take(&mut self, backend_config: &ViperBackendConfig) -> Option<Verifier<'v>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let index = self
+            .verifiers
+            .iter()
+            .rposition(|(config, _)| config == backend_config)?;
+        debug!("Reusing a verifier of the pool");
+        self.verifiers.remove(index).map(|(_, verifier)| verifier)
+    }
+
+    /// Returns a verifier to the pool after it verified a program with the
+    /// given result. Verifiers whose verification failed with an exception or
+    /// a timeout might be in an inconsistent state and are dropped instead.
+    /// When the pool is full, its least recently used verifier is dropped.
+    pub fn put(
+        &mut self,
+        backend_config: ViperBackendConfig,
+        verifier: Verifier<'v>,
+        result: &VerificationResult,
+    ) {
+        let reusable = matches!(
+            result,
+            VerificationResult::Success
+                | VerificationResult::Failure(_)
+                | VerificationResult::ConsistencyErrors(_)
+        );
+        if !reusable || !self.is_enabled() {
+            return;
+        }
+        if self.verifiers.len() == self.capacity {
+            self.verifiers.pop_front();
+        }
+        self.verifiers.push_back((backend_config, verifier));
+    }
+
+    /// Drops all verifiers of the pool.
+    pub fn clear(&mut self) {
+        self.verifiers.clear();
+    }
+}
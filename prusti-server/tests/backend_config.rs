@@ -0,0 +1,26 @@
+use prusti_server::ViperBackendConfig;
+use viper::VerificationBackend;
+
+/// The configuration is read once per process, so all settings are checked in
+/// a single test.
+#[test]
+fn plugins_are_passed_to_the_backends() {
+    std::env::set_var(
+        "PRUSTI_VIPER_PLUGINS",
+        "org.example.First org.example.Second",
+    );
+    std::env::set_var("PRUSTI_VIPER_TERMINATION_PLUGIN", "true");
+
+    for backend in [VerificationBackend::Silicon, VerificationBackend::Carbon] {
+        let verifier_args = ViperBackendConfig::new(backend).verifier_args;
+        let position = verifier_args
+            .iter()
+            .position(|arg| arg == "--plugin")
+            .unwrap_or_else(|| panic!("no plugins in {verifier_args:?}"));
+        assert_eq!(
+            verifier_args[position + 1],
+            "org.example.First:org.example.Second"
+        );
+        assert!(!verifier_args.contains(&"--disableTerminationPlugin".to_string()));
+    }
+}
@@ -1,9 +1,10 @@
 use lazy_static::lazy_static;
 use prusti_common::vir::*;
 use prusti_server::{
-    spawn_server_thread, tokio::runtime::Builder, PrustiClient, VerificationRequest,
-    ViperBackendConfig,
+    spawn_server_thread, tokio::runtime::Builder, JobPriority, PrustiClient, ServerPool,
+    VerificationRequest, ViperBackendConfig,
 };
+use std::sync::{Arc, Mutex};
 use viper::VerificationResult;
 
 lazy_static! {
@@ -36,12 +37,67 @@ fn empty_program() {
     }
 }
 
+#[test]
+fn pool_with_unreachable_server() {
+    // Nothing listens on port 1, so the requests are verified by the other server.
+    let pool = ServerPool::new(vec![
+        PrustiClient::new("127.0.0.1:1").unwrap(),
+        PrustiClient::new(SERVER_ADDRESS.clone()).unwrap(),
+    ]);
+    let requests = (0..3).map(|_| request(|_| ())).collect();
+    let results = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(pool.verify(requests, JobPriority::Normal, 1, |_, _, _| {}));
+    assert_eq!(results.len(), 3);
+    for (result, _) in results {
+        assert_eq!(result, VerificationResult::Success);
+    }
+}
+
+#[test]
+fn streamed_batch_results() {
+    let pool = ServerPool::new(vec![PrustiClient::new(SERVER_ADDRESS.clone()).unwrap()]);
+    let requests = (0..3).map(|_| request(|_| ())).collect();
+    let reported = Arc::new(Mutex::new(vec![]));
+    let results = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(pool.verify(requests, JobPriority::Normal, 1, {
+            let reported = reported.clone();
+            move |index, result, _| reported.lock().unwrap().push((index, result.clone()))
+        }));
+    assert_eq!(results.len(), 3);
+    let mut reported = reported.lock().unwrap().clone();
+    reported.sort_by_key(|(index, _)| *index);
+    assert_eq!(
+        reported,
+        (0..3)
+            .map(|index| (index, VerificationResult::Success))
+            .collect::<Vec<_>>()
+    );
+}
+
 fn process_program<F>(configure: F) -> VerificationResult
 where
     F: FnOnce(&mut Program),
 {
     let client = PrustiClient::new(SERVER_ADDRESS.clone()).expect("Could not connect to server!");
 
+    Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime")
+        .block_on(client.verify(request(configure)))
+        .expect("Verification request failed")
+}
+
+fn request<F>(configure: F) -> VerificationRequest
+where
+    F: FnOnce(&mut Program),
+{
     let mut program = Program {
         name: "dummy".to_string(),
         backend_types: vec![],
@@ -54,17 +110,11 @@ where
     };
     configure(&mut program);
 
-    let request = VerificationRequest {
+    VerificationRequest {
         program: prusti_common::vir::program::Program::Legacy(program),
         backend_config: ViperBackendConfig::new(
             prusti_common::config::viper_backend().parse().unwrap(),
         ),
-    };
-
-    Builder::new_current_thread()
-        .enable_all()
-        .build()
-        .expect("failed to construct Tokio runtime")
-        .block_on(client.verify(request))
-        .expect("Verification request failed")
+        export_name: None,
+    }
 }
@@ -0,0 +1,81 @@
+use prusti_common::vir::{program::Program, Program as LegacyProgram};
+use prusti_server::{
+    JobPriority, JobQueue, JobStatus, VerificationBatch, VerificationRequest, ViperBackendConfig,
+};
+use std::time::Duration;
+use viper::{VerificationBackend, VerificationResult};
+
+fn batch(priority: JobPriority, names: &[&str]) -> VerificationBatch {
+    let requests = names
+        .iter()
+        .map(|name| VerificationRequest {
+            program: Program::Legacy(LegacyProgram {
+                name: name.to_string(),
+                backend_types: vec![],
+                domains: vec![],
+                fields: vec![],
+                builtin_methods: vec![],
+                methods: vec![],
+                functions: vec![],
+                viper_predicates: vec![],
+            }),
+            backend_config: ViperBackendConfig::new(VerificationBackend::Silicon),
+            export_name: None,
+        })
+        .collect();
+    VerificationBatch { priority, requests }
+}
+
+fn next_job_name(queue: &JobQueue) -> String {
+    let (_, request) = queue.next_job();
+    request.program.get_name().to_string()
+}
+
+#[test]
+fn higher_priorities_overtake_queued_jobs() {
+    let queue = JobQueue::new();
+    queue.submit(batch(JobPriority::Background, &["b1", "b2"]));
+    queue.submit(batch(JobPriority::Normal, &["n1"]));
+    queue.submit(batch(JobPriority::Interactive, &["i1", "i2"]));
+    let order: Vec<_> = (0..5).map(|_| next_job_name(&queue)).collect();
+    assert_eq!(order, ["i1", "i2", "n1", "b1", "b2"]);
+}
+
+#[test]
+fn finished_jobs_are_removed() {
+    let queue = JobQueue::new();
+    let ids = queue.submit(batch(JobPriority::Normal, &["p"]));
+    assert_eq!(queue.status(ids[0]), Some(JobStatus::Queued));
+    let (id, _) = queue.next_job();
+    assert_eq!(id, ids[0]);
+    assert_eq!(queue.status(id), Some(JobStatus::Running));
+    // Unfinished jobs are not removed.
+    queue.remove(id);
+    queue.finish(id, VerificationResult::Success, Duration::from_secs(1));
+    assert_eq!(
+        queue.status(id),
+        Some(JobStatus::Finished {
+            result: VerificationResult::Success,
+            duration: Duration::from_secs(1),
+        })
+    );
+    queue.remove(id);
+    assert_eq!(queue.status(id), None);
+}
+
+#[test]
+fn retried_jobs_keep_their_place() {
+    let queue = JobQueue::new();
+    let ids = queue.submit(batch(JobPriority::Normal, &["first", "second"]));
+    let (id, request) = queue.next_job();
+    assert!(queue.retry(id, request, 1));
+    assert_eq!(queue.status(id), Some(JobStatus::Queued));
+    // The retried job is still started before the jobs submitted after it.
+    let (id, request) = queue.next_job();
+    assert_eq!(id, ids[0]);
+    // A job is retried at most `max_retries` times.
+    assert!(!queue.retry(id, request, 1));
+    assert_eq!(queue.statistics().running, 1);
+    queue.finish(id, VerificationResult::Timeout, Duration::from_secs(1));
+    assert_eq!(queue.statistics().running, 0);
+}
@@ -1,8 +1,9 @@
 use std::{
     env,
+    path::PathBuf,
     process::{Command, Stdio},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 /// test that the server starts up on a given port and outputs it
@@ -36,16 +37,60 @@ fn system_assigned_port() {
     );
 }
 
-/// runs the server for a short duration and returns its stdout as a string
-fn run_server_with_port(port: u16) -> String {
-    let server_path = env::current_exe()
+/// test that the server does not listen on a public address without an authentication token
+#[test]
+fn public_address_requires_token() {
+    let status = Command::new(server_path())
+        .arg("--address")
+        .arg("0.0.0.0")
+        .env_remove("PRUSTI_SERVER_AUTH_TOKEN")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .expect("failed to run prusti-server");
+
+    assert!(!status.success(), "server started without a token");
+}
+
+/// test that a server with an idle timeout, such as the daemon of cargo-prusti, stops by itself
+#[test]
+fn idle_server_stops() {
+    let mut server = Command::new(server_path())
+        .arg("--port")
+        .arg("0")
+        .env("PRUSTI_SERVER_IDLE_TIMEOUT", "1")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to run prusti-server");
+
+    // the server checks every 5 seconds whether it is idle
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = server.try_wait().unwrap() {
+            break status;
+        }
+        if start.elapsed() > Duration::from_secs(30) {
+            server.kill().unwrap();
+            panic!("idle server still running after 30 seconds");
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+    assert!(status.success(), "idle server stopped with {status}");
+}
+
+fn server_path() -> PathBuf {
+    env::current_exe()
         .unwrap()
         .parent()
         .unwrap()
         .with_file_name("prusti-server-driver") // can't run prusti-server itself because we need to kill it later, and that wouldn't kill the driver
-        .with_extension(env::consts::EXE_EXTENSION);
+        .with_extension(env::consts::EXE_EXTENSION)
+}
 
-    let mut server = Command::new(server_path)
+/// runs the server for a short duration and returns its stdout as a string
+fn run_server_with_port(port: u16) -> String {
+    let mut server = Command::new(server_path())
         .arg("--port")
         .arg(port.to_string())
         .stdout(Stdio::piped())
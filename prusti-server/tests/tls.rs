@@ -0,0 +1,86 @@
+use prusti_server::{tokio::runtime::Builder, JobPriority, PrustiClient, VerificationBatch};
+use std::{
+    env,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+};
+
+/// The certificates in `tests/tls` are only used by this test: `ca.pem`
+/// signed `cert.pem`, which is valid for `localhost`.
+fn tls_file(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("tls")
+        .join(name)
+}
+
+/// Kills the server when the test ends, also if it fails.
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// The configuration is read once per process, so all settings are checked in
+/// a single test.
+#[test]
+fn clients_trust_servers_with_a_certificate_of_the_configured_authority() {
+    let mut server = Server(
+        Command::new(server_path())
+            .arg("--port")
+            .arg("0")
+            .env("PRUSTI_SERVER_TLS_CERT_PATH", tls_file("cert.pem"))
+            .env("PRUSTI_SERVER_TLS_KEY_PATH", tls_file("key.pem"))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to run prusti-server"),
+    );
+    let mut line = String::new();
+    BufReader::new(server.0.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let port: u16 = line
+        .trim()
+        .strip_prefix("port: ")
+        .unwrap_or_else(|| panic!("port message as first stdout line, instead found {line:?}"))
+        .parse()
+        .expect("valid port number");
+
+    let runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime");
+    let url = format!("https://localhost:{port}/json/jobs");
+    let error = runtime
+        .block_on(reqwest::get(&url))
+        .expect_err("only clients of the configured authority should trust the server");
+    assert!(error.status().is_none(), "{error}");
+
+    env::set_var("PRUSTI_SERVER_TLS_CA_PATH", tls_file("ca.pem"));
+    // An empty batch is answered without starting the JVM.
+    let submit = |address: String| {
+        let client = PrustiClient::new(address).unwrap();
+        runtime.block_on(client.submit(VerificationBatch {
+            priority: JobPriority::Normal,
+            requests: vec![],
+        }))
+    };
+    assert_eq!(submit(format!("https://localhost:{port}")).unwrap(), vec![]);
+    let error = submit(format!("http://localhost:{port}"))
+        .expect_err("the server should not answer requests without TLS");
+    assert!(error.status().is_none(), "{error}");
+}
+
+fn server_path() -> PathBuf {
+    env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .with_file_name("prusti-server-driver")
+        .with_extension(env::consts::EXE_EXTENSION)
+}
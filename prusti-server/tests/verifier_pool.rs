@@ -0,0 +1,53 @@
+use prusti_common::vir::*;
+use prusti_server::{
+    spawn_server_thread, tokio::runtime::Builder, PrustiClient, VerificationRequest,
+    ViperBackendConfig,
+};
+use viper::VerificationResult;
+
+#[test]
+fn reused_verifiers_verify_each_program() {
+    // The configuration is read when the server starts.
+    std::env::set_var("PRUSTI_SERVER_MAX_STORED_VERIFIERS", "1");
+    let client = PrustiClient::new(spawn_server_thread()).unwrap();
+    let runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to construct Tokio runtime");
+    let verify = |fields: Vec<Field>| {
+        runtime
+            .block_on(client.verify(request(fields)))
+            .expect("Verification request failed")
+    };
+
+    assert_eq!(verify(vec![]), VerificationResult::Success);
+    // A reserved identifier.
+    match verify(vec![Field::new("field", Type::Int)]) {
+        VerificationResult::ConsistencyErrors(errors) => assert_eq!(errors.len(), 1),
+        other => panic!("consistency errors not identified, instead found {other:?}"),
+    }
+    assert_eq!(
+        verify(vec![Field::new("f", Type::Int)]),
+        VerificationResult::Success
+    );
+}
+
+fn request(fields: Vec<Field>) -> VerificationRequest {
+    let program = Program {
+        name: "dummy".to_string(),
+        backend_types: vec![],
+        domains: vec![],
+        fields,
+        builtin_methods: vec![],
+        methods: vec![],
+        functions: vec![],
+        viper_predicates: vec![],
+    };
+    VerificationRequest {
+        program: prusti_common::vir::program::Program::Legacy(program),
+        backend_config: ViperBackendConfig::new(
+            prusti_common::config::viper_backend().parse().unwrap(),
+        ),
+        export_name: None,
+    }
+}
@@ -11,11 +11,17 @@ pub(super) struct Context {
     pub(crate) quantifier_instantiations_bound_global: Option<u64>,
     pub(crate) log_file: Option<Mutex<BufWriter<File>>>,
     pub(crate) z3_trace_path: Option<String>,
+    /// Where to write the `[quantifier_instances]` lines printed by Z3 due to
+    /// `smt.qi.profile`.
+    pub(crate) qi_profile_file: Option<Mutex<BufWriter<File>>>,
+    /// Where to write the commands sent to Z3, which form an SMT-LIB script
+    /// that can be replayed without Prusti.
+    pub(crate) query_file: Option<Mutex<BufWriter<File>>>,
 }
 
 impl Context {
     pub(crate) async fn new() -> Self {
-        let (log_file_path, z3_trace_path) =
+        let (log_file_path, z3_trace_path, qi_profile_path, query_path) =
             if let Some(port) = read_integer("PRUSTI_SMT_SOLVER_MANAGER_PORT") {
                 let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
                 let mut reader = BufReader::new(stream);
@@ -25,9 +31,20 @@ impl Context {
                 let mut z3_trace_path = String::new();
                 reader.read_line(&mut z3_trace_path).await.unwrap();
                 z3_trace_path.pop();
-                (Some(log_file_path), Some(z3_trace_path))
+                let mut qi_profile_path = String::new();
+                reader.read_line(&mut qi_profile_path).await.unwrap();
+                qi_profile_path.pop();
+                let mut query_path = String::new();
+                reader.read_line(&mut query_path).await.unwrap();
+                query_path.pop();
+                (
+                    Some(log_file_path),
+                    Some(z3_trace_path),
+                    Some(qi_profile_path),
+                    Some(query_path),
+                )
             } else {
-                (None, None)
+                (None, None, None, None)
             };
         let quantifier_instantiations_bound_global = read_integer("PRUSTI_SMT_QI_BOUND_GLOBAL");
         let log_file = {
@@ -43,10 +60,27 @@ impl Context {
             }
         };
 
+        let qi_profile_file = if let Some(qi_profile_path) = qi_profile_path {
+            let file = File::create(qi_profile_path).await.unwrap();
+            Some(Mutex::new(BufWriter::new(file)))
+        } else {
+            None
+        };
+
+        let query_file = match (std::env::var("PRUSTI_DUMP_SMT_QUERIES"), query_path) {
+            (Ok(value), Some(query_path)) if value == "true" => {
+                let file = File::create(query_path).await.unwrap();
+                Some(Mutex::new(BufWriter::new(file)))
+            }
+            _ => None,
+        };
+
         Self {
             quantifier_instantiations_bound_global,
             log_file,
             z3_trace_path,
+            qi_profile_file,
+            query_file,
         }
     }
 
@@ -62,6 +96,29 @@ impl Context {
         Ok(())
     }
 
+    /// Records a line of Z3's `smt.qi.profile` output. Returns `false` if the
+    /// profile is not collected.
+    pub(crate) async fn write_qi_profile(&self, line: &str) -> Result<bool, std::io::Error> {
+        if let Some(qi_profile_file) = &self.qi_profile_file {
+            let mut file = qi_profile_file.lock().await;
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Records a command sent to Z3, see `config::dump_smt_queries`.
+    pub(crate) async fn write_query(&self, command: &str) -> Result<(), std::io::Error> {
+        if let Some(query_file) = &self.query_file {
+            let mut file = query_file.lock().await;
+            file.write_all(command.as_bytes()).await?;
+            file.flush().await?;
+        }
+        Ok(())
+    }
+
     pub(crate) async fn write_number_to_log(
         &self,
         stream: &str,
@@ -46,6 +46,7 @@ pub(crate) async fn communicate(
     let mut not_finished = true;
     while not_finished && read_command(&mut command).await? {
         context.write_to_log("in ", &command).await?;
+        context.write_query(&command).await?;
         let now = std::time::Instant::now();
         solver_stdin.write_all(command.as_bytes()).await?;
         solver_stdin.flush().await?;
@@ -96,8 +97,14 @@ pub(crate) async fn pass_error(
     context.write_to_log("err", "stderr started\n").await?;
     while solver_stderr.read_line(&mut line).await? > 0 {
         context.write_to_log("err", &line).await?;
-        stderr.write_all(line.as_bytes()).await?;
-        stderr.flush().await?;
+        // The quantifier profile is collected by the SMT manager instead of
+        // being passed to the verifier.
+        let is_qi_profile =
+            line.starts_with("[quantifier_instances]") && context.write_qi_profile(&line).await?;
+        if !is_qi_profile {
+            stderr.write_all(line.as_bytes()).await?;
+            stderr.flush().await?;
+        }
         line.clear();
     }
     context.write_to_log("err", "stderr finished\n").await?;
@@ -0,0 +1,7 @@
+use proc_macro::TokenStream;
+
+#[proc_macro]
+pub fn forty_two(input: TokenStream) -> TokenStream {
+    assert!(input.is_empty(), "`forty_two!` takes no arguments");
+    "42".parse().unwrap()
+}
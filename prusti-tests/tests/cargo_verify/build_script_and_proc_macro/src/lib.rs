@@ -0,0 +1,13 @@
+use prusti_contracts::*;
+
+include!(concat!(env!("OUT_DIR"), "/generated.rs"));
+
+#[ensures(result == 7)]
+pub fn generated() -> u32 {
+    GENERATED
+}
+
+#[ensures(result == 42)]
+pub fn answer() -> u32 {
+    macros::forty_two!()
+}
@@ -0,0 +1,11 @@
+#[cfg(prusti)]
+use prusti_contracts::*;
+
+// The specifications are only compiled by Prusti, so that a normal build
+// does not depend on `prusti-contracts`.
+
+#[cfg_attr(prusti, requires(x < u32::MAX))]
+#[cfg_attr(prusti, ensures(result == x + 1))]
+pub fn increment(x: u32) -> u32 {
+    x + 1
+}
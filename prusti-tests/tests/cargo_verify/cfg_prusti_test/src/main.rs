@@ -0,0 +1,14 @@
+#[cfg(prusti)]
+use prusti_contracts::*;
+use cfg_prusti_counter::increment;
+
+#[cfg_attr(prusti, requires(x < 100))]
+#[cfg_attr(prusti, ensures(result == x + 2))]
+fn increment_twice(x: u32) -> u32 {
+    increment(increment(x))
+}
+
+fn main() {
+    let y = increment_twice(1);
+    assert!(y == 3);
+}
@@ -0,0 +1,22 @@
+use prusti_contracts::*;
+
+/// Returns the successor of `x`.
+///
+/// ```
+/// use doctests_test::successor;
+///
+/// let y = successor(41);
+/// assert!(y == 42);
+/// ```
+///
+/// The precondition rules out an overflow, so the following example would
+/// not verify:
+///
+/// ```ignore
+/// doctests_test::successor(u32::MAX);
+/// ```
+#[requires(x < u32::MAX)]
+#[ensures(result == x + 1)]
+pub fn successor(x: u32) -> u32 {
+    x + 1
+}
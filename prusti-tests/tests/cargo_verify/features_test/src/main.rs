@@ -0,0 +1,17 @@
+use prusti_contracts::*;
+
+// The test runs `cargo prusti --no-default-features --features checked`.
+#[cfg(any(feature = "std", not(feature = "checked")))]
+compile_error!("the features given to cargo-prusti were not passed on to cargo");
+
+#[cfg(feature = "checked")]
+#[requires(x < 100)]
+#[ensures(result == x + 1)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+fn main() {
+    let y = increment(41);
+    assert!(y == 42);
+}
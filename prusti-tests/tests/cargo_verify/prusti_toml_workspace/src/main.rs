@@ -0,0 +1,9 @@
+// Verifies only because `check_overflows = false` in the `Prusti.toml` of the
+// workspace root.
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+fn main() {
+    let _ = increment(unchecked_lib::decrement(1));
+}
@@ -0,0 +1,9 @@
+// Verifies only with both the `Prusti.toml` of the workspace root, which sets
+// `check_overflows = false`, and the one of this crate, which sets
+// `check_panics = false`.
+pub fn decrement(x: u32) -> u32 {
+    if x == 0 {
+        panic!();
+    }
+    x - 1
+}
@@ -0,0 +1,10 @@
+use shipped_specs_lib::clamp;
+
+// Demonstrating specifications shipped with a crate: `shipped_specs_lib` does
+// not use Prusti itself, but declares a `.prusti-specs` file in its manifest,
+// which `cargo prusti` includes when verifying this crate.
+
+fn main() {
+    let x = clamp(42, 0, 10);
+    assert!(x <= 10);
+}
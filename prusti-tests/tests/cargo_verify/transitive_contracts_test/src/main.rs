@@ -0,0 +1,12 @@
+use prusti_contracts::*;
+use transitive_contracts_middle::next_even;
+
+// Demonstrating transitive library contracts: the contract of `next_even`
+// refers to the pure function `is_even` of `transitive_contracts_base`, which
+// is not a direct dependency of this crate. Its specification is imported too.
+
+fn main() {
+    let y = next_even(3);
+    assert!(y % 2 == 0);
+    assert!(y >= 3);
+}
@@ -0,0 +1,6 @@
+use prusti_contracts::*;
+
+#[pure]
+pub fn is_even(x: u32) -> bool {
+    x % 2 == 0
+}
@@ -0,0 +1,15 @@
+use prusti_contracts::*;
+use transitive_contracts_base::is_even;
+
+// The postcondition uses a pure function of a crate that the users of this
+// crate do not necessarily depend on directly.
+#[requires(x < 1000)]
+#[ensures(is_even(result))]
+#[ensures(result >= x)]
+pub fn next_even(x: u32) -> u32 {
+    if x % 2 == 0 {
+        x
+    } else {
+        x + 1
+    }
+}
@@ -0,0 +1,6 @@
+use workspace_lib::double;
+
+fn main() {
+    let y = double(3);
+    assert!(y == 6);
+}
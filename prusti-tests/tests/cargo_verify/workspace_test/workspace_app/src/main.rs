@@ -0,0 +1,16 @@
+use prusti_contracts::*;
+use workspace_lib::{double, is_small};
+
+// The specifications of `workspace_lib` are exported when the member is
+// verified and imported when verifying this member.
+
+#[requires(is_small(x))]
+#[ensures(result % 2 == 0)]
+fn even(x: u32) -> u32 {
+    double(x)
+}
+
+fn main() {
+    let y = even(21);
+    assert!(y == 42);
+}
@@ -0,0 +1,12 @@
+use prusti_contracts::*;
+
+#[pure]
+pub fn is_small(x: u32) -> bool {
+    x < 100
+}
+
+#[requires(is_small(x))]
+#[ensures(result == x * 2)]
+pub fn double(x: u32) -> u32 {
+    x * 2
+}
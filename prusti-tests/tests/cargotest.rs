@@ -4,24 +4,32 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use cargo_test_support::{cargo_test, project, symlink_supported};
+use cargo_test_support::{basic_manifest, cargo_test, project, symlink_supported};
 use std::{
     fs,
     path::{Path, PathBuf},
 };
 
 fn cargo_prusti_path() -> PathBuf {
+    prusti_binary_path("cargo-prusti")
+}
+
+fn prusti_server_path() -> PathBuf {
+    prusti_binary_path("prusti-server")
+}
+
+fn prusti_binary_path(binary_name: &str) -> PathBuf {
     let target_directory = if cfg!(debug_assertions) {
         "debug"
     } else {
         "release"
     };
     let executable_name = if cfg!(windows) {
-        "cargo-prusti.exe"
+        format!("{binary_name}.exe")
     } else {
-        "cargo-prusti"
+        binary_name.to_string()
     };
-    let local_prusti_rustc_path: PathBuf = ["target", target_directory, executable_name]
+    let local_prusti_rustc_path: PathBuf = ["target", target_directory, executable_name.as_str()]
         .iter()
         .collect();
     if local_prusti_rustc_path.exists() {
@@ -29,16 +37,17 @@ fn cargo_prusti_path() -> PathBuf {
             panic!("Failed to canonicalize the path {local_prusti_rustc_path:?}")
         });
     }
-    let workspace_prusti_rustc_path: PathBuf = ["..", "target", target_directory, executable_name]
-        .iter()
-        .collect();
+    let workspace_prusti_rustc_path: PathBuf =
+        ["..", "target", target_directory, executable_name.as_str()]
+            .iter()
+            .collect();
     if workspace_prusti_rustc_path.exists() {
         return fs::canonicalize(&workspace_prusti_rustc_path).unwrap_or_else(|_| {
             panic!("Failed to canonicalize the path {workspace_prusti_rustc_path:?}")
         });
     }
     panic!(
-        "Could not find the {target_directory:?} cargo-prusti binary to be used in tests. \
+        "Could not find the {target_directory:?} {binary_name} binary to be used in tests. \
         It might be that Prusti has not been compiled correctly."
     );
 }
@@ -78,6 +87,315 @@ error: could not compile `foo` (bin \"foo\") due to previous error; 1 warning em
         .run();
 }
 
+#[cargo_test]
+fn sarif_log_of_workspace() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"first\", \"second\"]\n",
+        )
+        .file("first/Cargo.toml", &basic_manifest("first", "0.1.0"))
+        .file("first/src/lib.rs", "pub fn first() { assert!(true); }")
+        .file("second/Cargo.toml", &basic_manifest("second", "0.1.0"))
+        .file("second/src/lib.rs", "pub fn second() { assert!(true); }")
+        .build();
+    let sarif_path = p.root().join("results.sarif");
+    p.process(cargo_prusti_path())
+        .arg("--workspace")
+        .env("PRUSTI_SARIF_PATH", &sarif_path)
+        .run();
+    // The crates are verified in parallel, but the log contains both runs.
+    let log = fs::read_to_string(&sarif_path).unwrap();
+    assert!(log.contains("\"version\": \"2.1.0\""), "{log}");
+    assert!(log.contains("\"id\": \"prusti/first/\""), "{log}");
+    assert!(log.contains("\"id\": \"prusti/second/\""), "{log}");
+    assert!(p.root().join("results.sarif.runs/first.json").is_file());
+    assert!(p.root().join("results.sarif.runs/second.json").is_file());
+}
+
+#[cargo_test]
+fn dump_vir_passes() {
+    let p = project()
+        .file("src/lib.rs", "pub fn identity(x: u32) -> u32 { x }")
+        .build();
+    let log_dir = p.root().join("log");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_DUMP_VIR_PASSES", "true")
+        .env("PRUSTI_LOG_DIR", &log_dir)
+        .run();
+    // Each method gets its own directory, with one numbered file per stage.
+    let method_dirs: Vec<_> = fs::read_dir(log_dir.join("vir_passes"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().contains("identity"))
+        .collect();
+    assert_eq!(method_dirs.len(), 1, "{method_dirs:?}");
+    for stage in ["00_before_foldunfold.vir", "01_after_foldunfold.vir"] {
+        let dump = fs::read_to_string(method_dirs[0].join(stage)).unwrap();
+        assert!(dump.contains("identity"), "{stage}: {dump}");
+    }
+}
+
+#[cargo_test]
+fn dump_foldunfold_graphviz() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "\
+pub struct Pair { pub a: u32, pub b: u32 }
+
+pub fn pick(pair: &mut Pair, first: bool) -> &mut u32 {
+    if first { &mut pair.a } else { &mut pair.b }
+}
+",
+        )
+        .build();
+    let log_dir = p.root().join("log");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_DUMP_FOLDUNFOLD_GRAPHVIZ", "true")
+        .env("PRUSTI_LOG_DIR", &log_dir)
+        .run();
+    for namespace in ["graphviz_foldunfold_joins", "graphviz_reborrowing_dags"] {
+        let dumps: Vec<_> = fs::read_dir(log_dir.join(namespace))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.to_string_lossy().contains("pick"))
+            .collect();
+        assert_eq!(dumps.len(), 1, "{namespace}: {dumps:?}");
+        let dot = fs::read_to_string(&dumps[0]).unwrap();
+        assert!(dot.starts_with("digraph"), "{namespace}: {dot}");
+        if namespace == "graphviz_foldunfold_joins" {
+            // The branches of `pick` are joined with a merged state.
+            assert!(dot.contains("Merged acc:"), "{dot}");
+        }
+    }
+}
+
+#[cargo_test]
+fn dump_smt_queries() {
+    let p = project()
+        .file("src/lib.rs", "pub fn test(x: u32) { assert!(x == x); }")
+        .build();
+    let log_dir = p.root().join("log");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_USE_SMT_WRAPPER", "true")
+        .env("PRUSTI_DUMP_SMT_QUERIES", "true")
+        .env("PRUSTI_LOG_DIR", &log_dir)
+        .run();
+    // Each method has a directory under `log/smt` with the scripts of its Z3
+    // instances.
+    let queries: Vec<_> = fs::read_dir(log_dir.join("smt"))
+        .unwrap()
+        .flat_map(|method_dir| fs::read_dir(method_dir.unwrap().path()).unwrap())
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "smt2"))
+        .collect();
+    assert!(!queries.is_empty());
+    assert!(
+        queries
+            .iter()
+            .any(|path| fs::read_to_string(path).unwrap().contains("(check-sat)")),
+        "{queries:?}"
+    );
+}
+
+#[cargo_test]
+fn verify_exported_vir_programs() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "pub fn pass(x: u32) { assert!(x == x); }\npub fn fail(x: u32) { assert!(x == 0); }",
+        )
+        .build();
+    let export_dir = p.root().join("vir");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_VIR_EXPORT_DIR", &export_dir)
+        .with_status(101)
+        .run();
+    let exported = |name: &str| {
+        fs::read_dir(&export_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.file_stem().unwrap().to_string_lossy().ends_with(name))
+            .unwrap_or_else(|| panic!("{name} was not exported"))
+    };
+    // The exported programs verify again without the crate.
+    p.process(prusti_server_path())
+        .arg("--verify")
+        .arg(exported("pass"))
+        .with_stdout_contains("[..]pass.json: verified")
+        .run();
+    p.process(prusti_server_path())
+        .arg("--verify")
+        .arg(exported("fail"))
+        .with_status(1)
+        .with_stdout_contains("[..]fail.json: failed")
+        .run();
+}
+
+#[cargo_test]
+fn unchanged_crates_are_skipped() {
+    let p = project()
+        .file("src/lib.rs", "pub fn add(a: u32, b: u32) -> u32 { a + b }")
+        .build();
+    let cache_dir = p.root().join("semantic_cache");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_SEMANTIC_CACHE_DIR", &cache_dir)
+        .env("PRUSTI_CHECK_OVERFLOWS", "false")
+        .run();
+    // Nothing changed, so cargo does not run Prusti on the crate again.
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_SEMANTIC_CACHE_DIR", &cache_dir)
+        .env("PRUSTI_CHECK_OVERFLOWS", "false")
+        .with_stderr_contains(
+            "Skipped the verification of `foo`, which did not change since its last \
+            verification (1 procedures verified, 0 failed)",
+        )
+        .run();
+    // A changed Prusti flag makes cargo verify the crate again.
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_SEMANTIC_CACHE_DIR", &cache_dir)
+        .with_status(101)
+        .with_stderr_contains("[..]attempt to add with overflow[..]")
+        .with_stderr_does_not_contain("Skipped the verification[..]")
+        .run();
+}
+
+#[cargo_test]
+fn daemon_is_reused_by_later_runs() {
+    let p = project()
+        .file("src/lib.rs", "pub fn add(a: u32, b: u32) -> u32 { a + b }")
+        .build();
+    let address_file = p.root().join("target").join("verify").join("daemon");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_DAEMON", "true")
+        .env("PRUSTI_DAEMON_IDLE_TIMEOUT", "60")
+        .env("PRUSTI_CHECK_OVERFLOWS", "false")
+        .with_stderr_contains("Started the verification daemon at 127.0.0.1:[..]")
+        .run();
+    let address = fs::read_to_string(&address_file).unwrap();
+    // The daemon of the first run is still running and verifies the changed
+    // crate.
+    p.change_file("src/lib.rs", "pub fn add(a: u32, b: u32) -> u32 { b + a }");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_DAEMON", "true")
+        .env("PRUSTI_DAEMON_IDLE_TIMEOUT", "60")
+        .env("PRUSTI_CHECK_OVERFLOWS", "false")
+        .with_stderr_does_not_contain("Started the verification daemon[..]")
+        .run();
+    assert_eq!(fs::read_to_string(&address_file).unwrap(), address);
+}
+
+/// Verifies a crate with the semantic cache enabled, changes its `src/lib.rs`
+/// from `before` to `after` and checks that the second run reports `error`,
+/// i.e. that the change invalidated the cached result of the affected function.
+fn test_semantic_cache_invalidation(before: &str, after: &str, error: &str) {
+    let prusti_contracts_path = fs::canonicalize("../prusti-contracts").unwrap();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+[package]
+name = "foo"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+prusti-contracts = { path = "prusti-contracts/prusti-contracts" }
+
+[workspace]
+"#,
+        )
+        .file("src/lib.rs", before)
+        .symlink_dir(
+            prusti_contracts_path.as_path(),
+            Path::new("prusti-contracts"),
+        )
+        .build();
+    p.process("cargo")
+        .arg("build")
+        .env("CARGO_TARGET_DIR", "target/verify")
+        .run();
+    let cache_dir = p.root().join("semantic_cache");
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_SEMANTIC_CACHE_DIR", &cache_dir)
+        .run();
+    assert!(cache_dir.join("foo.json").is_file());
+
+    p.change_file("src/lib.rs", after);
+    p.process(cargo_prusti_path())
+        .env("PRUSTI_SEMANTIC_CACHE_DIR", &cache_dir)
+        .with_status(101)
+        .with_stderr_contains(format!("[..]{error}[..]"))
+        .run();
+}
+
+#[cargo_test]
+fn semantic_cache_callee_contract_changed() {
+    test_semantic_cache_invalidation(
+        "\
+use prusti_contracts::*;
+
+#[ensures(result > 0)]
+pub fn positive() -> u32 { 1 }
+
+pub fn caller() { assert!(positive() > 0); }
+",
+        "\
+use prusti_contracts::*;
+
+#[ensures(result >= 0)]
+pub fn positive() -> u32 { 1 }
+
+pub fn caller() { assert!(positive() > 0); }
+",
+        "the asserted expression might not hold",
+    );
+}
+
+#[cargo_test]
+fn semantic_cache_pure_function_body_changed() {
+    test_semantic_cache_invalidation(
+        "\
+use prusti_contracts::*;
+
+#[pure]
+pub fn limit() -> u32 { 10 }
+
+pub fn caller() { assert!(limit() == 10); }
+",
+        "\
+use prusti_contracts::*;
+
+#[pure]
+pub fn limit() -> u32 { 11 }
+
+pub fn caller() { assert!(limit() == 10); }
+",
+        "the asserted expression might not hold",
+    );
+}
+
+#[cargo_test]
+fn semantic_cache_prusti_attribute_changed() {
+    test_semantic_cache_invalidation(
+        "\
+use prusti_contracts::*;
+
+#[cfg_attr(prusti, prusti::no_panic)]
+#[ensures(result == 1)]
+pub fn one() -> u32 { 2 }
+",
+        "\
+use prusti_contracts::*;
+
+#[ensures(result == 1)]
+pub fn one() -> u32 { 2 }
+",
+        "postcondition might not hold",
+    );
+}
+
 /// Test `cargo-prusti` on one of the crates in `test/cargo_verify`.
 ///
 /// Special files and folders in the root of the test crate:
@@ -91,6 +409,10 @@ error: could not compile `foo` (bin \"foo\") due to previous error; 1 warning em
 /// For more details on the special syntax allowed in the `output.*` files, check the documentation
 /// of `cargo_test_support`: <https://doc.crates.io/contrib/tests/writing.html>.
 fn test_local_project<T: Into<PathBuf>>(project_name: T) {
+    test_local_project_with_args(project_name, &[]);
+}
+
+fn test_local_project_with_args<T: Into<PathBuf>>(project_name: T, args: &[&str]) {
     let mut project_builder = project().no_manifest();
     let relative_project_path = Path::new("tests/cargo_verify").join(project_name.into());
     let project_path = fs::canonicalize(&relative_project_path).unwrap_or_else(|_| {
@@ -145,12 +467,13 @@ fn test_local_project<T: Into<PathBuf>>(project_name: T) {
         .arg("--config")
         .arg("net.retry=5")
         .arg("build")
+        .args(args)
         .env("CARGO_TARGET_DIR", "target/verify")
         .run();
 
     // Set the expected exit status, stdout and stderr
     let mut test_builder = project.process(cargo_prusti_path());
-    test_builder.arg("--quiet");
+    test_builder.arg("--quiet").args(args);
     let opt_expected_stdout = fs::read_to_string(project_path.join("output.stdout")).ok();
     let opt_expected_stderr = fs::read_to_string(project_path.join("output.stderr")).ok();
     if let Some(ref expected_stdout) = opt_expected_stdout {
@@ -175,6 +498,21 @@ fn test_symlinks() {
 // TODO: automatically create a test for each folder in `test/cargo_verify`.
 // Each of the following functions, listed in alphabetic order, test a crate in `cargo_verify/`.
 
+#[cargo_test]
+fn test_build_script_and_proc_macro() {
+    test_local_project_with_args("build_script_and_proc_macro", &["--workspace"]);
+}
+
+#[cargo_test]
+fn test_cfg_prusti_test() {
+    test_local_project("cfg_prusti_test");
+}
+
+#[cargo_test]
+fn test_doctests_test() {
+    test_local_project("doctests_test");
+}
+
 #[cargo_test]
 fn test_failing_crate() {
     test_local_project("failing_crate");
@@ -185,6 +523,14 @@ fn test_failing_stable_toolchain() {
     test_local_project("failing_stable_toolchain");
 }
 
+#[cargo_test]
+fn test_features_test() {
+    test_local_project_with_args(
+        "features_test",
+        &["--no-default-features", "--features", "checked"],
+    );
+}
+
 #[cargo_test]
 fn test_foreign_mods() {
     test_local_project("foreign_mods");
@@ -226,6 +572,11 @@ fn test_prusti_toml_fail() {
     }
 }
 
+#[cargo_test]
+fn test_prusti_toml_workspace() {
+    test_local_project("prusti_toml_workspace");
+}
+
 // `#![no_std]` binaries on Windows are not a thing yet,
 // see <https://github.com/viperproject/prusti-dev/pull/762>.
 #[cfg_attr(windows, ignore)]
@@ -234,6 +585,21 @@ fn test_no_std() {
     test_local_project("test_no_std");
 }
 
+#[cargo_test]
+fn test_shipped_specs_test() {
+    test_local_project("shipped_specs_test");
+}
+
+#[cargo_test]
+fn test_transitive_contracts_test() {
+    test_local_project("transitive_contracts_test");
+}
+
+#[cargo_test]
+fn test_workspace_test() {
+    test_local_project_with_args("workspace_test", &["--workspace"]);
+}
+
 #[ignore] // Currently broken
 #[cargo_test]
 fn test_veribetrfs() {
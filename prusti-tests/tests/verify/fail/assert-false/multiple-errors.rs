@@ -0,0 +1,16 @@
+// compile-flags: -Pnum_errors_per_function=0
+use prusti_contracts::*;
+
+fn test(x: u32, y: u32) {
+    assert!(x == 0); //~ ERROR the asserted expression might not hold
+    assert!(y == 0); //~ ERROR the asserted expression might not hold
+    assert!(x == 0);
+}
+
+#[ensures(result > 0)] //~ ERROR postcondition might not hold
+fn test_post(x: u32) -> u32 {
+    prusti_assert!(x < 10); //~ ERROR the asserted expression might not hold
+    x
+}
+
+fn main() {}
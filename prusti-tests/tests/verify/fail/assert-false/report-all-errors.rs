@@ -0,0 +1,24 @@
+// compile-flags: -Preport_all_errors=true
+use prusti_contracts::*;
+
+#[requires(x > 0)]
+fn positive(x: u32) -> u32 {
+    x
+}
+
+fn test(x: u32, y: u32, z: u32, w: u32) {
+    assert!(x == 0); //~ ERROR the asserted expression might not hold
+    assert!(y == 0); //~ ERROR the asserted expression might not hold
+    // A failing assertion is assumed afterwards, so it is reported only once.
+    assert!(x == 0);
+    prusti_assert!(z > 5); //~ ERROR the asserted expression might not hold
+    positive(w); //~ ERROR precondition might not hold
+}
+
+#[ensures(result > 10)] //~ ERROR postcondition might not hold
+fn test_post(x: u32) -> u32 {
+    assert!(x < 10); //~ ERROR the asserted expression might not hold
+    x
+}
+
+fn main() {}
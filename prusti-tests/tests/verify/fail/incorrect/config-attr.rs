@@ -0,0 +1,12 @@
+use prusti_contracts::*;
+
+#[prusti::config(counterexample = true)] //~ ERROR the flag `counterexample` cannot be set for single items
+fn global_flag() {}
+
+#[prusti::config(time_limit = true)] //~ ERROR the flag `time_limit` expects an integer
+fn wrong_type() {}
+
+#[prusti::config(check_overflows)] //~ ERROR expected an argument of the form `<flag> = <value>`
+fn missing_value() {}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// compile-flags: -Pinfer_loop_invariants=true
+
+use prusti_contracts::*;
+
+fn count_up() {
+    let mut i = 0;
+    while i < 10 {
+        i += 1;
+    }
+    assert!(i == 9); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// compile-flags: -Pinfer_loop_invariants=true
+
+use prusti_contracts::*;
+
+fn past_the_end(v: &[u32]) -> u32 {
+    let mut i = 0;
+    let mut last = 0;
+    while i <= v.len() {
+        last = v[i]; //~ ERROR the array or slice index may be out of bounds
+        i += 1;
+    }
+    last
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+fn lookup(values: &[u32], i: usize) -> u32 {
+    if i < values.len() {
+        values[i]
+    } else {
+        // The index is checked by the callers.
+        #[prusti::accept_panic]
+        {
+            unreachable!()
+        }
+    }
+}
+
+fn parse(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        _ => {
+            #[prusti::accept_panic]
+            {
+                panic!("not a digit")
+            }
+        }
+    }
+}
+
+fn unchecked(x: u32) -> u32 {
+    if x == 0 {
+        #[prusti::accept_panic]
+        let y = x + 1;
+        if y > 0 {
+            unreachable!(); //~ ERROR unreachable!(..) statement might be reachable
+        }
+    }
+    x
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+fn checked(x: u32) -> u32 {
+    x + 1 //~ ERROR attempt to add with overflow
+}
+
+#[prusti::check_overflows(false)]
+fn unchecked(x: u32) -> u32 {
+    x + 1
+}
+
+#[prusti::check_overflows(false)]
+mod wrapping {
+    pub fn unchecked(x: u32) -> u32 {
+        x * 2
+    }
+
+    #[prusti::check_overflows(true)]
+    pub fn checked(x: u32) -> u32 {
+        x * 2 //~ ERROR attempt to multiply with overflow
+    }
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+#![prusti::config(check_overflows = false)]
+
+fn unchecked(x: u32) -> u32 {
+    x + 1
+}
+
+#[prusti::config(check_overflows = true)]
+mod checked {
+    pub fn checked(x: u32) -> u32 {
+        x * 2 //~ ERROR attempt to multiply with overflow
+    }
+
+    #[prusti::config(check_overflows = false, smt_random_seed = 3)]
+    pub fn unchecked(x: u32) -> u32 {
+        x * 2
+    }
+
+    #[prusti::config(time_limit = 0)]
+    pub fn zero_limit() {} //~ ERROR the `prusti::time_limit` attribute expects a positive number of seconds
+}
+
+fn main() {}
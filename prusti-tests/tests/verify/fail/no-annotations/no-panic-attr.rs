@@ -0,0 +1,36 @@
+use prusti_contracts::*;
+
+#[prusti::no_panic]
+#[ensures(result == 1)]
+fn wrong_postcondition() -> i32 {
+    prusti_assert!(false);
+    2
+}
+
+#[prusti::no_panic]
+mod baseline {
+    use prusti_contracts::*;
+
+    #[requires(i < v.len())]
+    #[ensures(result == 0)]
+    pub fn get(v: &[u32], i: usize) -> u32 {
+        v[i]
+    }
+
+    pub fn out_of_bounds(v: &[u32]) -> u32 {
+        v[0] //~ ERROR the array or slice index may be out of bounds
+    }
+
+    pub fn caller(v: &[u32]) -> u32 {
+        let x = get(v, 0); //~ ERROR precondition
+        assert!(x == 0); //~ ERROR the asserted expression might not hold
+        x
+    }
+}
+
+#[ensures(result == 1)] //~ ERROR postcondition
+fn checked() -> i32 {
+    2
+}
+
+fn main() {}
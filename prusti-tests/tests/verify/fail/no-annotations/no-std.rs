@@ -0,0 +1,22 @@
+// compile-flags: --crate-type=lib
+#![no_std]
+
+use prusti_contracts::*;
+
+pub fn test_assert(x: u32) {
+    assert!(x == 0); //~ ERROR the asserted expression might not hold
+}
+
+pub fn test_unreachable() {
+    unreachable!(); //~ ERROR unreachable!(..) statement might be reachable
+}
+
+pub fn test_panic() {
+    panic!(); //~ ERROR panic!(..) statement might be reachable
+}
+
+#[requires(x > 0)]
+pub fn test_ok(x: u32) -> u32 {
+    assert!(x != 0);
+    x - 1
+}
@@ -0,0 +1,30 @@
+// compile-flags: -Pverify_tests=true
+
+use prusti_contracts::*;
+
+#[ensures(result == a + b)]
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn main() {}
+
+#[cfg(test)]
+mod prusti_tests {
+    use super::*;
+
+    fn helper() -> u32 {
+        add(1, 2)
+    }
+
+    #[test]
+    fn add_works() {
+        assert!(add(1, 1) == 2);
+        assert!(helper() == 3);
+    }
+
+    #[test]
+    fn add_is_wrong() {
+        assert!(add(1, 1) == 3); //~ ERROR the asserted expression might not hold
+    }
+}
@@ -0,0 +1,23 @@
+// The left-hand side of an implication is evaluated even if the right-hand
+// side is `true`, so the preconditions of the pure functions that it calls are
+// checked.
+use prusti_contracts::*;
+
+#[pure]
+#[requires(y != 0)]
+fn quotient(x: u32, y: u32) -> u32 {
+    x / y
+}
+
+fn assertion(x: u32, y: u32) {
+    prusti_assert!(quotient(x, y) > 0 ==> true); //~ ERROR precondition of pure function call might not hold
+}
+
+#[ensures(quotient(x, y) > 0 ==> true)] //~ ERROR precondition of pure function call might not hold
+fn postcondition(x: u32, y: u32) {}
+
+fn well_defined(x: u32, y: u32) {
+    prusti_assert!(y != 0 ==> (quotient(x, y) > 0 ==> true));
+}
+
+fn main() {}
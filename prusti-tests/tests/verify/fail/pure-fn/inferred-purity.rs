@@ -0,0 +1,30 @@
+// compile-flags: -Pinfer_pure_functions=true
+
+use prusti_contracts::*;
+
+fn get_u32() -> u32 {
+    123
+}
+
+#[prusti::config(infer_pure_functions = false)]
+fn get_u64() -> u64 {
+    123
+}
+
+fn sum(a: u32, b: u32) -> u32 {
+    let c = a + b;
+    c
+}
+
+#[requires(get_u32() == 123)]
+fn client_1() {}
+
+#[requires(get_u64() == 123)]
+//~^ ERROR use of impure function "get_u64" in pure code
+fn client_2() {}
+
+#[requires(sum(1, 2) == 3)]
+//~^ ERROR use of impure function "sum" in pure code
+fn client_3() {}
+
+fn main() {}
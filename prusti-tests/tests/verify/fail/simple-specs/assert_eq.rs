@@ -0,0 +1,18 @@
+use prusti_contracts::*;
+
+#[ensures(result == x + 1)]
+fn inc(x: u32) -> u32 {
+    x + 1
+}
+
+fn test_assert_eq() {
+    assert_eq!(inc(1), 2);
+    assert_ne!(inc(1), 1);
+    assert_eq!(inc(1), 1); //~ ERROR the asserted expression might not hold
+}
+
+fn test_assert_ne(x: u32) {
+    assert_ne!(x, 3); //~ ERROR the asserted expression might not hold
+}
+
+fn main() {}
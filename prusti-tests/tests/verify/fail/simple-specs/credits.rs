@@ -0,0 +1,34 @@
+use prusti_contracts::*;
+
+#[requires(credits.amount() == n)]
+fn visit_all(n: usize, mut credits: Credits) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n && credits.amount() == n - i);
+        credits.spend(1);
+        i += 1;
+    }
+} //~ ERROR resource might be leaked
+
+#[requires(credits.amount() == n)]
+fn overspend(n: usize, mut credits: Credits) {
+    let mut i = 0;
+    while i <= n {
+        body_invariant!(i <= n && credits.amount() == n - i);
+        credits.spend(1); //~ ERROR precondition might not hold
+        i += 1;
+    }
+    credits.finish();
+}
+
+#[requires(credits.amount() == 2)]
+fn finish_early(mut credits: Credits) {
+    credits.spend(1);
+    credits.finish(); //~ ERROR precondition might not hold
+}
+
+fn mint() -> Credits {
+    Credits::new(5) //~ ERROR precondition might not hold
+}
+
+fn main() {}
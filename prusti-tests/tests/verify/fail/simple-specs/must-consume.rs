@@ -0,0 +1,50 @@
+use prusti_contracts::*;
+
+#[must_consume]
+struct Transaction {
+    id: u32,
+}
+
+impl Transaction {
+    fn open(id: u32) -> Self {
+        Transaction { id }
+    }
+
+    fn commit(self) {
+        std::mem::forget(self);
+    }
+}
+
+#[must_consume]
+struct Buffer {
+    data: Vec<u8>,
+}
+
+struct Session {
+    tx: Transaction,
+}
+
+fn commit_if(tx: Transaction, ok: bool) {
+    if ok {
+        tx.commit();
+    }
+} //~ ERROR resource might be leaked
+
+fn forget_to_commit(id: u32) {
+    let tx = Transaction::open(id);
+    let _ = tx.id;
+} //~ ERROR resource might be leaked
+
+fn close_session(session: Session) {
+    let _ = session.tx.id;
+} //~ ERROR resource might be leaked
+
+fn close_one(sessions: (Session, Session)) {
+    sessions.0.tx.commit();
+} //~ ERROR resource might be leaked
+
+fn discard(buffer: Buffer) {
+    let _ = buffer.data.len();
+} //~ ERROR resource might be leaked
+
+fn main() {}
@@ -0,0 +1,25 @@
+use prusti_contracts::*;
+use std::ops::{Add, Div, Neg};
+
+fn overflow(x: u32) -> u32 {
+    Add::add(x, 1) //~ ERROR precondition might not hold
+}
+
+fn division(x: i64, y: i64) -> i64 {
+    if y != 0 {
+        Div::div(x, y) //~ ERROR precondition might not hold
+    } else {
+        0
+    }
+}
+
+fn negation(x: i8) -> i8 {
+    Neg::neg(x) //~ ERROR precondition might not hold
+}
+
+#[requires(x < 100)]
+fn in_range(x: u8) -> u8 {
+    Add::add(x, 100)
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use prusti_contracts::*;
+
+spec_set! {
+    small {
+        #[requires(x < 100)]
+        #[ensures(result > x)] //~ ERROR postcondition might not hold
+    }
+}
+
+#[specs_use(small)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+#[specs_use(small)]
+fn decrement(x: u32) -> u32 {
+    if x > 0 { x - 1 } else { 0 }
+}
+
+fn client() {
+    increment(99);
+    increment(100); //~ ERROR precondition might not hold
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use prusti_contracts::*;
+
+#[prusti::skip]
+mod legacy {
+    #[prusti::verify]
+    pub fn verified() {
+        assert!(false); //~ ERROR the asserted expression might not hold
+    }
+}
+
+fn main() {}
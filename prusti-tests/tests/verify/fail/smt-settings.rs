@@ -0,0 +1,16 @@
+use prusti_contracts::*;
+
+#[prusti::smt_rlimit(0)]
+fn zero_rlimit() {} //~ ERROR the `prusti::smt_rlimit` attribute expects a positive resource limit
+
+#[prusti::smt_random_seed("seven")]
+fn string_seed() {} //~ ERROR the `prusti::smt_random_seed` attribute expects a seed
+
+#[prusti::smt_rlimit(100000000)]
+#[prusti::smt_random_seed(0)]
+#[ensures(result == 42)]
+fn with_settings() -> i32 {
+    42
+}
+
+fn main() {}
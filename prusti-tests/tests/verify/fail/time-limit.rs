@@ -0,0 +1,15 @@
+use prusti_contracts::*;
+
+#[prusti::time_limit(0)]
+fn zero_limit() {} //~ ERROR the `prusti::time_limit` attribute expects a positive number of seconds
+
+#[prusti::time_limit("ten")]
+fn string_limit() {} //~ ERROR the `prusti::time_limit` attribute expects a positive number of seconds
+
+#[prusti::time_limit(60)]
+#[ensures(result == 42)]
+fn generous_limit() -> i32 {
+    42
+}
+
+fn main() {}
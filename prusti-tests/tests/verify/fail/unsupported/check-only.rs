@@ -0,0 +1,20 @@
+// compile-flags: -Pcheck_only=true
+
+use prusti_contracts::*;
+
+union Foo {
+    a: [i32; 1],
+    b: [i32; 1],
+}
+
+fn union() {
+    let _ = Foo { a: [0] }; //~ ERROR unions are not supported
+}
+
+// The encoded programs are not verified.
+#[ensures(result == x)]
+fn wrong(x: u32) -> u32 {
+    x + 1
+}
+
+fn main() {}
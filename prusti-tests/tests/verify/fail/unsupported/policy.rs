@@ -0,0 +1,22 @@
+// compile-flags: -Punsupported_feature_policy=unsafe=error,default=warn
+
+union Foo {
+    a: [i32; 1],
+    b: [i32; 1],
+}
+
+fn union() {
+    let _ = Foo { a: [0] }; //~ ERROR unions are not supported
+}
+
+fn raw_pointer(p: *const i32) -> bool {
+    p.is_null() //~ ERROR raw pointers are not supported
+}
+
+fn iterator(slots: &mut [u64; 16]) {
+    for slot in slots.iter_mut() { //~ WARNING iterators are not fully supported yet
+        *slot = 0;
+    }
+}
+
+fn main() {}
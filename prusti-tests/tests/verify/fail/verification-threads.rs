@@ -0,0 +1,32 @@
+// compile-flags: -Pverification_threads=4
+// Programs that are verified concurrently still report the errors of each
+// function at the right place.
+use prusti_contracts::*;
+
+#[requires(x < 10)]
+#[ensures(result < 20)]
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+#[ensures(result > 0)] //~ ERROR postcondition might not hold
+fn decrement(x: u32) -> u32 {
+    if x > 0 { x - 1 } else { 1 }
+}
+
+fn first(x: u32) {
+    assert!(x == 0); //~ ERROR the asserted expression might not hold
+}
+
+fn second(x: u32) {
+    let y = double(x); //~ ERROR precondition might not hold
+    assert!(y < 20);
+}
+
+#[requires(x < 5)]
+fn third(x: u32) {
+    let y = double(x);
+    assert!(y < 20);
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// compile-flags: -Pviper_backend=Carbon -Psmt_random_seed=1
+
+use prusti_contracts::*;
+
+#[pure]
+#[requires(x < 1000)]
+fn double(x: u32) -> u32 {
+    2 * x
+}
+
+#[requires(x < 1000)]
+#[ensures(result == double(x) + 1)]
+fn double_plus_one(x: u32) -> u32 {
+    x + x + 1
+}
+
+#[prusti::smt_rlimit(100000000)]
+#[requires(forall(|i: usize| i < a.len() ==> a[i] > 0))]
+#[ensures(forall(|i: usize| i < a.len() ==> a[i] >= 1))]
+fn all_positive(a: &[u32]) {}
+
+fn main() {}
@@ -0,0 +1,63 @@
+//! A struct with more fields than `snapshot_large_struct_fields`, whose
+//! snapshot field access axioms are merged into one.
+
+use prusti_contracts::*;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Large {
+    f0: u32,
+    f1: u32,
+    f2: u32,
+    f3: u32,
+    f4: u32,
+    f5: u32,
+    f6: u32,
+    f7: u32,
+    f8: u32,
+    f9: u32,
+}
+
+#[pure]
+fn make(value: u32) -> Large {
+    Large {
+        f0: value,
+        f1: 1,
+        f2: 2,
+        f3: 3,
+        f4: 4,
+        f5: 5,
+        f6: 6,
+        f7: 7,
+        f8: 8,
+        f9: value,
+    }
+}
+
+#[requires(x == y)]
+#[ensures(x.f9 == y.f9)]
+fn equal_structs_have_equal_fields(x: Large, y: Large) {}
+
+#[requires(
+    x.f0 == y.f0
+        && x.f1 == y.f1
+        && x.f2 == y.f2
+        && x.f3 == y.f3
+        && x.f4 == y.f4
+        && x.f5 == y.f5
+        && x.f6 == y.f6
+        && x.f7 == y.f7
+        && x.f8 == y.f8
+        && x.f9 == y.f9
+)]
+#[ensures(x == y)]
+fn structs_with_equal_fields_are_equal(x: Large, y: Large) {}
+
+#[ensures(make(value).f0 == value && make(value).f9 == value)]
+fn fields_of_constructed_structs(value: u32) {}
+
+fn main() {
+    let a = make(3);
+    let b = make(3);
+    assert!(a == b);
+    assert!(a.f5 == 5);
+}
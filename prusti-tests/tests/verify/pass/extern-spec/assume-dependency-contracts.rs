@@ -0,0 +1,14 @@
+// compile-flags: -Passume_dependency_contracts=true
+
+use prusti_contracts::*;
+
+#[ensures(result == (x % 2 == 0))]
+fn is_even(x: i32) -> bool {
+    let result: Result<i32, ()> = if x % 2 == 0 { Ok(x) } else { Err(()) };
+    result.is_ok()
+}
+
+fn main() {
+    assert!(is_even(4));
+    assert!(!is_even(3));
+}
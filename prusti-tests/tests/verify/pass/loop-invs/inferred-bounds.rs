@@ -0,0 +1,45 @@
+// compile-flags: -Pinfer_loop_invariants=true
+
+use prusti_contracts::*;
+
+fn count_up() {
+    let mut i = 0;
+    while i < 10 {
+        i += 1;
+    }
+    assert!(i == 10);
+}
+
+fn count_down() {
+    let mut i: i32 = 10;
+    while i > 0 {
+        i -= 1;
+    }
+    assert!(i == 0);
+}
+
+fn bounded_sum(n: u32) -> u32 {
+    let mut i = 0;
+    let mut total = 0;
+    while i < n {
+        if total < 1000 {
+            total += 2;
+        }
+        i += 1;
+    }
+    assert!(total <= 1001);
+    total
+}
+
+fn with_invariant() {
+    let mut i = 0;
+    let mut j = 0;
+    while i < 10 {
+        body_invariant!(j == 2 * i);
+        i += 1;
+        j += 2;
+    }
+    assert!(j == 20);
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+// compile-flags: -Pinfer_loop_invariants=true
+
+use prusti_contracts::*;
+
+fn last_element(v: &[u32]) -> u32 {
+    let mut i = 0;
+    let mut last = 0;
+    while i < v.len() {
+        last = v[i];
+        i += 1;
+    }
+    last
+}
+
+#[requires(n <= v.len())]
+fn last_in_prefix(v: &[u32], n: usize) -> u32 {
+    let mut i = 0;
+    let mut last = 0;
+    while n > i {
+        last = v[i];
+        i += 1;
+    }
+    last
+}
+
+fn clear(v: &mut [u32]) {
+    let mut i = 0;
+    while i < v.len() {
+        v[i] = 0;
+        i += 1;
+    }
+}
+
+fn main() {}
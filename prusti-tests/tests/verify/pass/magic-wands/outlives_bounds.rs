@@ -0,0 +1,42 @@
+use prusti_contracts::*;
+
+struct T {
+    val: i32,
+}
+
+#[after_expiry(x.val == before_expiry(result.val))]
+fn first<'a, 'b: 'a>(x: &'a mut T, y: &'b mut T) -> &'a mut T {
+    x
+}
+
+#[requires(b ==> x.val == 1)]
+#[requires(!b ==> y.val == 1)]
+#[ensures(result.val == 1)]
+fn choose<'a, 'b>(b: bool, x: &'a mut T, y: &'b mut T) -> &'a mut T
+where
+    'b: 'a,
+{
+    if b {
+        x
+    } else {
+        y
+    }
+}
+
+fn test_first() {
+    let mut a = T { val: 1 };
+    let mut b = T { val: 2 };
+    let r = first(&mut a, &mut b);
+    r.val = 3;
+    assert!(a.val == 3);
+}
+
+fn test_choose() {
+    let mut a = T { val: 1 };
+    let mut b = T { val: 2 };
+    let r = choose(true, &mut a, &mut b);
+    assert!(r.val == 1);
+    r.val = 4;
+}
+
+fn main() {}
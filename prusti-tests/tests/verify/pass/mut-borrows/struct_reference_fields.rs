@@ -0,0 +1,31 @@
+use prusti_contracts::*;
+
+struct View<'a> {
+    value: &'a u32,
+}
+
+struct Cursor<'a> {
+    value: &'a mut u32,
+}
+
+#[requires(*view.value < 100)]
+#[ensures(result == old(*view.value) + 1)]
+fn next(view: View) -> u32 {
+    *view.value + 1
+}
+
+fn test_view() {
+    let x = 5;
+    let view = View { value: &x };
+    let y = next(view);
+    assert!(y == 6);
+}
+
+fn test_cursor() {
+    let mut x = 5;
+    let cursor = Cursor { value: &mut x };
+    *cursor.value = 6;
+    assert!(x == 6);
+}
+
+fn main() {}
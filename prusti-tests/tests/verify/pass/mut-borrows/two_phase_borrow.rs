@@ -0,0 +1,32 @@
+use prusti_contracts::*;
+
+fn foo(a: &mut [i32], l: usize) {}
+
+fn bar(a: &mut [i32]) {
+    foo(a, a.len());
+}
+
+struct Stack {
+    len: usize,
+}
+
+impl Stack {
+    #[pure]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[requires(self.len() < 100)]
+    #[ensures(self.len() == old(self.len()) + 1)]
+    fn push(&mut self, _value: usize) {
+        self.len += 1;
+    }
+}
+
+#[requires(s.len() < 100)]
+#[ensures(s.len() == old(s.len()) + 1)]
+fn push_len(s: &mut Stack) {
+    s.push(s.len());
+}
+
+fn main() {}
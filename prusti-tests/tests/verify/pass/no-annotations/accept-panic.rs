@@ -0,0 +1,21 @@
+fn first(values: &[u32]) -> u32 {
+    #[prusti::accept_panic]
+    {
+        assert!(!values.is_empty());
+    }
+    0
+}
+
+fn digit(c: char) -> u32 {
+    match c.to_digit(10) {
+        Some(d) => d,
+        None => {
+            #[prusti::accept_panic]
+            {
+                unimplemented!()
+            }
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+// compile-flags: -Passume_unimplemented=true
+
+use prusti_contracts::*;
+
+#[ensures(x > 0 ==> result == x)]
+fn unfinished(x: i32) -> i32 {
+    if x > 0 {
+        x
+    } else {
+        todo!() //~ WARNING this statement was assumed to be unreachable
+    }
+}
+
+fn also_unfinished() {
+    unimplemented!() //~ WARNING this statement was assumed to be unreachable
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+// compile-flags: -Punsupported_feature_policy=unsafe=trust
+
+use prusti_contracts::*;
+
+// The executions that reach the raw pointer are assumed to be correct, while
+// the other ones are still verified.
+#[requires(x < 10)]
+#[ensures(result <= 10)]
+fn raw_pointer(x: u32, p: *const i32) -> u32 {
+    if x == 0 {
+        let _ = p.is_null();
+    }
+    x + 1
+}
+
+fn main() {}
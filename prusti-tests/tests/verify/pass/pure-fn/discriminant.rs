@@ -0,0 +1,32 @@
+use prusti_contracts::*;
+use std::mem;
+
+enum Shape {
+    Circle(u32),
+    Square(u32),
+}
+
+#[pure]
+fn same_kind(a: &Shape, b: &Shape) -> bool {
+    mem::discriminant(a) == mem::discriminant(b)
+}
+
+#[pure]
+fn different_kind(a: &Shape, b: &Shape) -> bool {
+    mem::discriminant(a) != mem::discriminant(b)
+}
+
+#[requires(same_kind(a, b))]
+#[ensures(matches!(a, Shape::Circle(_)) ==> matches!(b, Shape::Circle(_)))]
+fn check(a: &Shape, b: &Shape) {}
+
+fn test() {
+    let a = Shape::Circle(1);
+    let b = Shape::Circle(2);
+    let c = Shape::Square(1);
+    assert!(same_kind(&a, &b));
+    assert!(different_kind(&a, &c));
+    check(&a, &b);
+}
+
+fn main() {}
@@ -0,0 +1,61 @@
+// compile-flags: -Pinfer_pure_functions=true
+
+use prusti_contracts::*;
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+enum Shape {
+    Circle(u32),
+    Square(u32),
+}
+
+impl Point {
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn is_origin(&self) -> bool {
+        self.x == 0 && self.y == 0
+    }
+
+    fn mirrored(self) -> Point {
+        Point {
+            x: self.y,
+            y: self.x,
+        }
+    }
+}
+
+fn size(shape: &Shape) -> u32 {
+    match shape {
+        Shape::Circle(radius) => *radius,
+        Shape::Square(side) => *side,
+    }
+}
+
+#[requires(!p.is_origin())]
+#[ensures(result == p.x())]
+fn use_point(p: &Point) -> i32 {
+    p.x
+}
+
+#[ensures(result.x() == p.y)]
+fn mirror(p: Point) -> Point {
+    p.mirrored()
+}
+
+#[requires(size(shape) > 0)]
+fn use_shape(shape: &Shape) -> u32 {
+    size(shape) - 1
+}
+
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    assert!(use_point(&p) == 1);
+    assert!(mirror(p).x == 2);
+    use_shape(&Shape::Square(3));
+}
@@ -0,0 +1,43 @@
+use prusti_contracts::*;
+
+enum State {
+    Empty,
+    One(u32),
+    Two(u32, u32),
+}
+
+#[pure]
+fn is_empty(s: &State) -> bool {
+    matches!(s, State::Empty)
+}
+
+#[pure]
+fn is_non_empty(s: &State) -> bool {
+    matches!(s, State::One(_) | State::Two(_, _))
+}
+
+#[pure]
+fn is_small(s: &State) -> bool {
+    matches!(s, State::One(v) if *v < 10)
+}
+
+#[ensures(is_empty(s) != is_non_empty(s))]
+fn exclusive(s: &State) {}
+
+fn test() {
+    let s = State::Empty;
+    assert!(is_empty(&s));
+    let s = State::Two(1, 2);
+    assert!(!is_empty(&s));
+    assert!(is_non_empty(&s));
+    assert!(!is_small(&s));
+    let s = State::One(3);
+    assert!(is_small(&s));
+    if matches!(s, State::One(_)) {
+        assert!(is_non_empty(&s));
+    } else {
+        unreachable!();
+    }
+}
+
+fn main() {}
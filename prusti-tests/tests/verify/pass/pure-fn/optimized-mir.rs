@@ -0,0 +1,31 @@
+// compile-flags: -Ppure_mir_stage=optimized
+use prusti_contracts::*;
+
+const LIMIT: u32 = 10;
+
+#[pure]
+fn clamp(x: u32) -> u32 {
+    if x > LIMIT {
+        LIMIT
+    } else {
+        x
+    }
+}
+
+#[pure]
+fn is_small(x: u32) -> bool {
+    let limit = LIMIT / 2;
+    x < limit
+}
+
+#[ensures(result <= LIMIT)]
+#[ensures(is_small(x) ==> result == x)]
+fn bounded(x: u32) -> u32 {
+    clamp(x)
+}
+
+fn main() {
+    assert!(clamp(20) == 10);
+    assert!(is_small(3));
+    assert!(bounded(4) == 4);
+}
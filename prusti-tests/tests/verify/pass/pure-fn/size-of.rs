@@ -0,0 +1,31 @@
+use prusti_contracts::*;
+use std::mem;
+
+struct Pair {
+    a: u32,
+    b: u16,
+}
+
+#[pure]
+fn header_size() -> usize {
+    mem::size_of::<u64>() + mem::size_of::<u32>()
+}
+
+#[requires(len >= mem::size_of::<Pair>())]
+#[ensures(result == len - mem::size_of::<Pair>())]
+fn remaining(len: usize) -> usize {
+    len - 8
+}
+
+#[ensures(result == mem::align_of::<u64>())]
+fn alignment() -> usize {
+    8
+}
+
+fn test() {
+    assert!(header_size() == 12);
+    let r = remaining(10);
+    assert!(r == 2);
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// compile-flags: -Pselective_verify_file=src/other.rs
+
+use prusti_contracts::*;
+
+// Not verified, because it is not defined in the selected file. Otherwise the
+// postcondition would fail.
+#[ensures(result == x)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+fn main() {}
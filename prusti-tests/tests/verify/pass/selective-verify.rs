@@ -0,0 +1,29 @@
+// compile-flags: -Pselective_verify=Counter::increment
+
+use prusti_contracts::*;
+
+struct Counter {
+    value: u32,
+}
+
+impl Counter {
+    #[requires(self.value < 100)]
+    #[ensures(self.value == old(self.value) + 1)]
+    fn increment(&mut self) {
+        self.value += 1;
+    }
+
+    // Not verified, because it is not selected. Otherwise the postcondition
+    // would fail.
+    #[ensures(self.value == old(self.value) + 1)]
+    fn reset(&mut self) {
+        self.value = 0;
+    }
+}
+
+fn main() {
+    let mut counter = Counter { value: 0 };
+    counter.increment();
+    counter.reset();
+    assert!(counter.value == 0);
+}
@@ -0,0 +1,40 @@
+use prusti_contracts::*;
+
+#[trusted]
+#[ensures(result.amount() == amount)]
+fn budget(amount: usize) -> Credits {
+    Credits::new(amount)
+}
+
+#[requires(credits.amount() == n)]
+fn visit_all(n: usize, mut credits: Credits) {
+    let mut i = 0;
+    while i < n {
+        body_invariant!(i < n && credits.amount() == n - i);
+        credits.spend(1);
+        i += 1;
+    }
+    credits.finish();
+}
+
+#[requires(credits.amount() == 2 * n)]
+fn visit_twice(n: usize, mut credits: Credits) {
+    let first = credits.split(n);
+    visit_all(n, first);
+    visit_all(n, credits);
+}
+
+#[requires(credits.amount() >= 1)]
+#[ensures(result.amount() == old(credits.amount()) - 1)]
+fn charge(mut credits: Credits) -> Credits {
+    credits.spend(1);
+    credits
+}
+
+fn main() {
+    let mut credits = budget(3);
+    credits.join(budget(1));
+    visit_twice(2, credits);
+    let rest = charge(budget(1));
+    rest.finish();
+}
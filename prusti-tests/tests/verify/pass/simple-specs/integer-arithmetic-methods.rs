@@ -0,0 +1,40 @@
+use prusti_contracts::*;
+
+fn checked(x: u32, y: u32) -> u32 {
+    match x.checked_add(y) {
+        Some(sum) => {
+            assert!(sum >= x);
+            sum
+        }
+        None => {
+            assert!(x > 0 && y > 0);
+            u32::MAX
+        }
+    }
+}
+
+#[ensures(result <= x)]
+fn saturating(x: u8, y: u8) -> u8 {
+    x.saturating_sub(y)
+}
+
+fn wrapping() {
+    assert!(255u8.wrapping_add(1) == 0);
+    assert!(0u8.wrapping_sub(1) == 255);
+    assert!(i8::MAX.wrapping_add(1) == i8::MIN);
+    assert!(i8::MIN.wrapping_sub(1) == i8::MAX);
+    assert!(i32::MIN.saturating_sub(1) == i32::MIN);
+    let (value, overflowed) = u16::MAX.overflowing_add(2);
+    assert!(value == 1 && overflowed);
+}
+
+fn signed(x: i64, y: i64) {
+    if let Some(difference) = x.checked_sub(y) {
+        assert!(difference + y == x);
+    }
+    if let Some(product) = 10i64.checked_mul(3) {
+        assert!(product == 30);
+    }
+}
+
+fn main() {}
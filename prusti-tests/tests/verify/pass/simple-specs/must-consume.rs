@@ -0,0 +1,73 @@
+use prusti_contracts::*;
+
+#[must_consume]
+struct Transaction {
+    id: u32,
+}
+
+impl Transaction {
+    fn open(id: u32) -> Self {
+        Transaction { id }
+    }
+
+    fn commit(self) {
+        std::mem::forget(self);
+    }
+
+    #[trusted]
+    fn rollback(self) {}
+}
+
+#[must_consume]
+struct Buffer {
+    data: Vec<u8>,
+}
+
+impl Buffer {
+    #[trusted]
+    fn flush(self) {}
+}
+
+struct Session {
+    tx: Transaction,
+}
+
+fn commit_or_rollback(tx: Transaction, ok: bool) {
+    if ok {
+        tx.commit();
+    } else {
+        tx.rollback();
+    }
+}
+
+fn pass_on(tx: Transaction) -> Transaction {
+    tx
+}
+
+fn store(id: u32) -> Session {
+    let tx = Transaction::open(id);
+    Session { tx }
+}
+
+fn maybe_open(open: bool) -> Option<Transaction> {
+    if open {
+        Some(Transaction::open(1))
+    } else {
+        None
+    }
+}
+
+fn flush_if(buffer: Buffer, ok: bool) {
+    if ok {
+        buffer.flush();
+    } else {
+        buffer.flush();
+    }
+}
+
+fn main() {
+    let tx = pass_on(Transaction::open(1));
+    commit_or_rollback(tx, true);
+    let session = store(2);
+    session.tx.commit();
+}
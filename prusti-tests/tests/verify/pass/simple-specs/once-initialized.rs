@@ -0,0 +1,21 @@
+use prusti_contracts::*;
+use std::sync::OnceLock;
+
+static TABLE_SIZE: OnceLock<usize> = OnceLock::new();
+
+#[ensures(result >= 16)]
+fn compute_table_size() -> usize {
+    64
+}
+
+#[trusted]
+#[ensures(result >= 16)]
+fn table_size() -> usize {
+    *TABLE_SIZE.get_or_init(compute_table_size)
+}
+
+fn bucket(hash: usize) -> usize {
+    hash % table_size()
+}
+
+fn main() {}
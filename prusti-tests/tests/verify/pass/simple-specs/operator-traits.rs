@@ -0,0 +1,37 @@
+use prusti_contracts::*;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+#[requires(x < u32::MAX)]
+#[ensures(result > x)]
+fn increment(x: u32) -> u32 {
+    Add::add(x, 1)
+}
+
+#[requires(y <= x)]
+#[ensures(result + y == x)]
+fn difference(x: u64, y: u64) -> u64 {
+    x.sub(y)
+}
+
+fn signed(x: i32) {
+    if x > i32::MIN {
+        let negated = Neg::neg(x);
+        assert!(negated + x == 0);
+    }
+    assert!(Mul::mul(-3i64, 4) == -12);
+    assert!(Div::div(7i16, -2) == -3);
+    assert!(Rem::rem(7u8, 3) == 1);
+}
+
+#[ensures(result == (a < b))]
+fn less(a: u64, b: u64) -> bool {
+    a.lt(&b)
+}
+
+fn comparisons(a: i8, b: i8) {
+    if a.ge(&b) {
+        assert!(!a.lt(&b));
+    }
+}
+
+fn main() {}
@@ -0,0 +1,68 @@
+use prusti_contracts::*;
+
+mod contracts {
+    use prusti_contracts::*;
+
+    spec_set! {
+        /// The index is within the bounds of the slice.
+        pub(crate) in_bounds {
+            #[requires(index < slice.len())]
+        }
+
+        pub(crate) keeps_length {
+            #[ensures(slice.len() == old(slice.len()))]
+        }
+    }
+}
+
+spec_set! {
+    small {
+        #[requires(x < 100)]
+        #[ensures(x > 0 ==> result > 1)]
+        #[ensures(result == x + 1)]
+    }
+}
+
+#[specs_use(contracts::in_bounds, contracts::keeps_length)]
+fn reset(slice: &mut [i32], index: usize) {
+    slice[index] = 0;
+}
+
+#[specs_use(contracts::in_bounds)]
+#[ensures(result == slice[index])]
+fn get(slice: &[i32], index: usize) -> i32 {
+    slice[index]
+}
+
+#[specs_use(small)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+#[pure]
+#[specs_use(small)]
+fn increment_pure(x: u32) -> u32 {
+    x + 1
+}
+
+struct Counter;
+
+impl Counter {
+    #[specs_use(small)]
+    fn next(&self, x: u32) -> u32 {
+        x + 1
+    }
+}
+
+fn client(slice: &mut [i32]) {
+    if slice.len() > 2 {
+        reset(slice, 2);
+        assert!(get(slice, 2) == 0);
+        assert!(slice.len() > 2);
+    }
+    assert!(increment(41) == 42);
+    assert!(increment_pure(1) > 1);
+    assert!(Counter.next(0) == 1);
+}
+
+fn main() {}
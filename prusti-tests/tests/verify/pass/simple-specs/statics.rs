@@ -0,0 +1,44 @@
+use prusti_contracts::*;
+
+static LIMIT: u32 = 100;
+static ENABLED: bool = true;
+const STEP: u32 = 10;
+
+struct Counter;
+
+impl Counter {
+    const START: u32 = 5;
+}
+
+#[pure]
+fn limit() -> u32 {
+    LIMIT
+}
+
+#[requires(x < LIMIT)]
+#[ensures(result <= LIMIT)]
+fn bump(x: u32) -> u32 {
+    if x + STEP > LIMIT {
+        LIMIT
+    } else {
+        x + STEP
+    }
+}
+
+#[ensures(result == Counter::START + STEP)]
+fn start() -> u32 {
+    Counter::START + STEP
+}
+
+fn test() {
+    assert!(ENABLED);
+    assert!(limit() == 100);
+    let x = bump(95);
+    assert!(x == 100);
+    let y = start();
+    assert!(y == 15);
+    let r = &LIMIT;
+    assert!(*r == 100);
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+use prusti_contracts::*;
+
+struct Limits {
+    max: u32,
+}
+
+struct Config {
+    retries: u32,
+    timeout: u32,
+    limits: Limits,
+}
+
+#[ensures(result.retries == 3)]
+#[ensures(result.timeout == old(config.timeout))]
+#[ensures(result.limits.max == old(config.limits.max))]
+fn with_retries(config: Config) -> Config {
+    Config {
+        retries: 3,
+        ..config
+    }
+}
+
+fn test() {
+    let config = Config {
+        retries: 0,
+        timeout: 10,
+        limits: Limits { max: 5 },
+    };
+    let config = with_retries(config);
+    assert!(config.retries == 3);
+    assert!(config.timeout == 10);
+    assert!(config.limits.max == 5);
+}
+
+fn main() {}
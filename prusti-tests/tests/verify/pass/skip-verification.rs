@@ -0,0 +1,40 @@
+use prusti_contracts::*;
+
+// Functions that are skipped are not verified, but their contracts are still
+// used by their callers.
+
+#[prusti::skip]
+#[ensures(result == 1)]
+fn wrong_postcondition() -> i32 {
+    2
+}
+
+#[prusti::skip]
+mod legacy {
+    pub fn failing_assertion() {
+        assert!(false);
+    }
+
+    #[prusti::verify]
+    pub fn verified() {
+        assert!(true);
+    }
+}
+
+#[prusti::skip]
+impl Counter {
+    #[ensures(self.value == 0)]
+    fn reset(&mut self) {}
+}
+
+struct Counter {
+    value: u32,
+}
+
+fn main() {
+    assert!(wrong_postcondition() == 1);
+    let mut counter = Counter { value: 3 };
+    counter.reset();
+    assert!(counter.value == 0);
+    legacy::verified();
+}
@@ -0,0 +1,12 @@
+// compile-flags: -Psmt_random_seed=3
+
+use prusti_contracts::*;
+
+#[prusti::smt_random_seed(7)]
+#[requires(x < 100)]
+#[ensures(result > x)]
+fn increment(x: u32) -> u32 {
+    x + 1
+}
+
+fn main() {}
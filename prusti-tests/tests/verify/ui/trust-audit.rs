@@ -0,0 +1,35 @@
+// compile-flags: -Ptrust_audit=true -Ptrust_audit_entry_point=client
+
+use prusti_contracts::*;
+
+#[trusted]
+#[ensures(result > 0)]
+fn positive() -> i32 {
+    1
+}
+
+#[pure]
+#[trusted]
+#[ensures(result == x)]
+fn identity(x: i32) -> i32 {
+    x
+}
+
+#[trusted]
+fn unrelated() {}
+
+#[requires(identity(x) > 0)]
+fn client(x: i32) -> i32 {
+    let y = positive();
+    #[prusti::accept_panic]
+    {
+        assert!(y > 0);
+    }
+    if x > y {
+        x
+    } else {
+        y
+    }
+}
+
+fn main() {}
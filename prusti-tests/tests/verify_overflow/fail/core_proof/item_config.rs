@@ -0,0 +1,38 @@
+// compile-flags: -Puse_smt_wrapper=true -Psmt_qi_bound_global=10000 -Psmt_qi_bound_trace=200 -Psmt_qi_bound_trace_kind=10 -Psmt_qi_bound_global_kind=20
+
+use prusti_contracts::*;
+
+struct T {
+    f: u32,
+}
+
+#[prusti::config(unsafe_core_proof = true)]
+fn core_proof(a: u32, b: T) -> u32 {
+    let x = T { f: a };
+    assert!(x.f == a);
+    assert!(b.f == 0);     //~ ERROR: the asserted expression might not hold
+    x.f
+}
+
+fn heap_encoding(a: u32, b: T) -> u32 {
+    let x = T { f: a };
+    assert!(x.f == a);
+    assert!(b.f == 0);     //~ ERROR: the asserted expression might not hold
+    x.f
+}
+
+#[prusti::config(unsafe_core_proof = true)]
+mod core_proof_module {
+    pub fn callee(a: u32) -> u32 {
+        a
+    }
+
+    #[prusti::config(unsafe_core_proof = false)]
+    pub fn caller(a: u32) -> u32 {
+        let b = callee(a);
+        assert!(b == a);     //~ ERROR: the asserted expression might not hold
+        b
+    }
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+extern crate prusti_std;
+
+use prusti_contracts::*;
+use prusti_std::btree_map_key;
+use std::collections::BTreeMap;
+
+#[requires(map.len() > 0)]
+#[ensures(result == *old(btree_map_key(map, map.len() - 1)))] //~ ERROR postcondition might not hold.
+fn take_largest(map: &mut BTreeMap<u32, u32>) -> u32 {
+    match map.pop_first() {
+        Some((key, _)) => key,
+        None => unreachable!(),
+    }
+}
+
+#[requires(map.len() > 0)]
+#[ensures(result == *btree_map_key(map, map.len() - 1))] //~ ERROR postcondition might not hold.
+fn largest_key(map: &BTreeMap<u32, u32>) -> u32 {
+    match map.first_key_value() {
+        Some((key, _)) => *key,
+        None => unreachable!(),
+    }
+}
+
+fn range_yields_descending_keys(map: &BTreeMap<u32, u32>) {
+    let mut range = map.range(10..20);
+    if let Some((first, _)) = range.next() {
+        if let Some((second, _)) = range.next() {
+            assert!(*first > *second); //~ ERROR the asserted expression might not hold
+        }
+    }
+}
+
+fn main() {
+    let map: BTreeMap<u32, u32> = BTreeMap::new();
+    assert!(matches!(map.first_key_value(), Some(_))); //~ ERROR the asserted expression might not hold
+}
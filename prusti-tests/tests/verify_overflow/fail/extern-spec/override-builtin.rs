@@ -0,0 +1,17 @@
+// A local external specification replaces the built-in one of prusti-contracts.
+
+use prusti_contracts::*;
+use std::ops::Add;
+
+#[extern_spec]
+impl Add for u32 {
+    #[pure]
+    #[requires(self <= 10 && rhs <= 10)]
+    #[ensures(result == self + rhs)]
+    fn add(self, rhs: u32) -> u32;
+}
+
+fn main() {
+    let x = Add::add(11u32, 1); //~ ERROR precondition might not hold
+    assert!(x == 12);
+}
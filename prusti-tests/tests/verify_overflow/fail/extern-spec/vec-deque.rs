@@ -0,0 +1,13 @@
+extern crate prusti_std;
+
+use std::collections::VecDeque;
+
+fn main() {
+    let mut queue = VecDeque::with_capacity(4);
+    queue.push_back(1);
+    queue.push_back(2);
+    assert!(queue[1] == 2);
+    assert!(queue[0] == 2); //~ ERROR the asserted expression might not hold
+    let _x = queue[2]; //~ ERROR precondition might not hold
+    assert!(matches!(queue.pop_front(), Some(2))); //~ ERROR the asserted expression might not hold
+}
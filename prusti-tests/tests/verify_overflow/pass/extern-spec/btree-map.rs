@@ -0,0 +1,79 @@
+use prusti_contracts::*;
+use prusti_std::{btree_map_key, btree_map_value};
+use std::collections::BTreeMap;
+
+#[requires(map.len() > 0)]
+#[ensures(map.len() == old(map.len()) - 1)]
+#[ensures(result == *old(btree_map_key(map, 0)))]
+#[ensures(forall(|i: usize| i < map.len() ==>
+    *btree_map_key(map, i) == *old(btree_map_key(map, i + 1))))]
+fn take_smallest(map: &mut BTreeMap<u32, u32>) -> u32 {
+    match map.pop_first() {
+        Some((key, _)) => key,
+        None => unreachable!(),
+    }
+}
+
+#[requires(map.len() > 0)]
+#[ensures(map.len() == old(map.len()) - 1)]
+#[ensures(result == *old(btree_map_value(map, map.len() - 1)))]
+fn take_value_of_largest(map: &mut BTreeMap<u32, u64>) -> u64 {
+    match map.pop_last() {
+        Some((_, value)) => value,
+        None => unreachable!(),
+    }
+}
+
+#[requires(map.len() >= 2)]
+#[ensures(*btree_map_key(map, 0) < *btree_map_key(map, map.len() - 1))]
+fn keys_are_ascending(map: &BTreeMap<u32, u32>) {}
+
+#[requires(map.len() > 0)]
+#[ensures(result == *btree_map_key(map, 0))]
+fn smallest_key(map: &BTreeMap<u32, u32>) -> u32 {
+    match map.first_key_value() {
+        Some((key, _)) => *key,
+        None => unreachable!(),
+    }
+}
+
+#[requires(map.len() > 0)]
+#[ensures(result == *btree_map_value(map, map.len() - 1))]
+fn value_of_largest(map: &BTreeMap<u32, u64>) -> u64 {
+    match map.last_key_value() {
+        Some((_, value)) => *value,
+        None => unreachable!(),
+    }
+}
+
+#[requires(map.len() >= 2)]
+fn iteration_starts_with_the_smallest_keys(map: &BTreeMap<u32, u32>) {
+    let mut iter = map.iter();
+    let first = iter.next();
+    let second = iter.next();
+    match (first, second) {
+        (Some((first, _)), Some((second, _))) => {
+            assert!(*first == *btree_map_key(map, 0));
+            assert!(*first < *second);
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn range_yields_ascending_keys(map: &BTreeMap<u32, u32>) {
+    let mut range = map.range(10..20);
+    if let Some((first, _)) = range.next() {
+        if let Some((second, _)) = range.next() {
+            assert!(*first < *second);
+        }
+    }
+}
+
+fn main() {
+    let mut map: BTreeMap<u32, u32> = BTreeMap::new();
+    assert!(map.is_empty());
+    assert!(matches!(map.first_key_value(), None));
+    assert!(matches!(map.pop_first(), None));
+    map.clear();
+    assert!(map.len() == 0);
+}
@@ -0,0 +1,22 @@
+// A local external specification replaces the built-in one of prusti-contracts.
+
+use prusti_contracts::*;
+use std::ops::Add;
+
+#[extern_spec]
+impl Add for u32 {
+    #[pure]
+    #[requires(self <= 10 && rhs <= 10)]
+    #[ensures(result == self + rhs)]
+    fn add(self, rhs: u32) -> u32;
+}
+
+#[requires(a <= 10 && b <= 10)]
+#[ensures(result <= 20)]
+fn sum(a: u32, b: u32) -> u32 {
+    Add::add(a, b)
+}
+
+fn main() {
+    assert!(sum(3, 4) == 7);
+}
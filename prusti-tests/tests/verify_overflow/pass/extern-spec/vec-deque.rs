@@ -0,0 +1,38 @@
+use prusti_contracts::*;
+use prusti_std::vec_deque_lookup;
+use std::collections::VecDeque;
+
+#[requires(queue.len() < 100)]
+#[ensures(queue.len() == old(queue.len()) + 2)]
+#[ensures(*vec_deque_lookup(queue, 0) == front)]
+#[ensures(*vec_deque_lookup(queue, queue.len() - 1) == back)]
+fn push_both(queue: &mut VecDeque<u32>, front: u32, back: u32) {
+    queue.push_front(front);
+    queue.push_back(back);
+}
+
+fn main() {
+    let mut queue = VecDeque::new();
+    assert!(queue.is_empty());
+    queue.push_back(2);
+    queue.push_back(3);
+    queue.push_front(1);
+    assert!(queue.len() == 3);
+    assert!(queue[0] == 1);
+    assert!(queue[1] == 2);
+    assert!(queue[2] == 3);
+
+    assert!(matches!(queue.pop_front(), Some(1)));
+    assert!(matches!(queue.pop_back(), Some(3)));
+    assert!(queue.len() == 1);
+    assert!(queue[0] == 2);
+
+    push_both(&mut queue, 4, 5);
+    assert!(queue[0] == 4);
+    assert!(queue[2] == 5);
+
+    queue.pop_back();
+    queue.pop_back();
+    queue.pop_back();
+    assert!(matches!(queue.pop_front(), None));
+}
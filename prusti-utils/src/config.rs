@@ -13,17 +13,24 @@ use ::config::{Config, Environment, File};
 use log::warn;
 use rustc_hash::FxHashSet;
 use serde::Deserialize;
-use std::{env, path::PathBuf, sync::RwLock};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Optimizations {
     pub inline_constant_functions: bool,
     pub delete_unused_predicates: bool,
+    pub delete_unused_definitions: bool,
     pub optimize_folding: bool,
     pub remove_empty_if: bool,
     pub purify_vars: bool,
     pub fix_quantifiers: bool,
     pub fix_unfoldings: bool,
+    pub simplify_expressions: bool,
+    pub remove_dead_code: bool,
     pub remove_unused_vars: bool,
     pub remove_trivial_assertions: bool,
     pub clean_cfg: bool,
@@ -34,11 +41,14 @@ impl Optimizations {
         Optimizations {
             inline_constant_functions: false,
             delete_unused_predicates: false,
+            delete_unused_definitions: false,
             optimize_folding: false,
             remove_empty_if: false,
             purify_vars: false,
             fix_quantifiers: false,
             fix_unfoldings: false,
+            simplify_expressions: false,
+            remove_dead_code: false,
             remove_unused_vars: false,
             remove_trivial_assertions: false,
             clean_cfg: false,
@@ -49,12 +59,15 @@ impl Optimizations {
         Optimizations {
             inline_constant_functions: true,
             delete_unused_predicates: true,
+            delete_unused_definitions: true,
             optimize_folding: true,
             remove_empty_if: true,
             purify_vars: true,
             fix_quantifiers: true,
             // Disabled because https://github.com/viperproject/prusti-dev/issues/892 has been fixed
             fix_unfoldings: false,
+            simplify_expressions: true,
+            remove_dead_code: true,
             remove_unused_vars: true,
             remove_trivial_assertions: true,
             clean_cfg: true,
@@ -70,10 +83,17 @@ lazy_static::lazy_static! {
         // 0. Default values
         settings.set_default("be_rustc", false).unwrap();
         settings.set_default("viper_backend", "Silicon").unwrap();
+        settings.set_default::<Vec<String>>("viper_plugins", vec![]).unwrap();
+        settings.set_default("viper_termination_plugin", false).unwrap();
         settings.set_default::<Option<String>>("smt_solver_path", env::var("Z3_EXE").ok()).unwrap();
         settings.set_default::<Option<String>>("smt_solver_wrapper_path", None).unwrap();
+        settings.set_default("smt_solver", "Z3").unwrap();
+        settings.set_default::<Option<String>>("cvc5_path", env::var("CVC5_EXE").ok()).unwrap();
         settings.set_default::<Option<String>>("boogie_path", env::var("BOOGIE_EXE").ok()).unwrap();
+        settings.set_default("download_viper_tools", false).unwrap();
+        settings.set_default::<Option<String>>("viper_tools_cache_dir", None).unwrap();
         settings.set_default::<Option<String>>("viper_home", None).unwrap();
+        settings.set_default::<Option<String>>("viper_server_address", None).unwrap();
         settings.set_default::<Option<String>>("java_home", None).unwrap();
 
         settings.set_default::<Option<u32>>("check_timeout", None).unwrap();
@@ -82,12 +102,16 @@ lazy_static::lazy_static! {
         settings.set_default("check_panics", true).unwrap();
         settings.set_default("encode_unsigned_num_constraint", true).unwrap();
         settings.set_default("encode_bitvectors", false).unwrap();
+        settings.set_default("infer_loop_invariants", false).unwrap();
+        settings.set_default("infer_pure_functions", false).unwrap();
         settings.set_default("simplify_encoding", true).unwrap();
         settings.set_default("log", "").unwrap();
         settings.set_default("log_style", "auto").unwrap();
         settings.set_default("log_dir", "log").unwrap();
         settings.set_default("log_tracing", false).unwrap();
         settings.set_default("cache_path", "").unwrap();
+        settings.set_default::<Option<u64>>("cache_max_entries", None).unwrap();
+        settings.set_default::<Option<u64>>("cache_max_size_mb", None).unwrap();
         settings.set_default("dump_debug_info", false).unwrap();
         settings.set_default("dump_debug_info_during_fold", false).unwrap();
         settings.set_default("dump_nll_facts", false).unwrap();
@@ -95,23 +119,45 @@ lazy_static::lazy_static! {
         settings.set_default("max_log_file_name_length", 60).unwrap();
         settings.set_default("dump_path_ctxt_in_debug_info", false).unwrap();
         settings.set_default("dump_reborrowing_dag_in_debug_info", false).unwrap();
+        settings.set_default("dump_foldunfold_graphviz", false).unwrap();
         settings.set_default("dump_borrowck_info", false).unwrap();
         settings.set_default("dump_viper_program", false).unwrap();
+        settings.set_default::<Option<String>>("viper_export_dir", None).unwrap();
+        settings.set_default::<Option<String>>("vir_export_dir", None).unwrap();
+        settings.set_default("dump_vir_passes", false).unwrap();
         settings.set_default("foldunfold_state_filter", "").unwrap();
         settings.set_default::<Vec<String>>("extra_jvm_args", vec![]).unwrap();
         settings.set_default::<Vec<String>>("extra_verifier_args", vec![]).unwrap();
         settings.set_default("quiet", false).unwrap();
+        settings.set_default("report_progress", false).unwrap();
         settings.set_default("assert_timeout", 10_000).unwrap();
+        settings.set_default("assume_dependency_contracts", false).unwrap();
+        settings.set_default("assume_unimplemented", false).unwrap();
+        settings.set_default("panic_freedom_only", false).unwrap();
+        settings.set_default("pure_mir_stage", "analysis").unwrap();
+        settings.set_default::<Vec<String>>("dependency_specs", vec![]).unwrap();
         settings.set_default("smt_qi_eager_threshold", 1000).unwrap();
+        settings.set_default::<Option<u64>>("smt_rlimit", None).unwrap();
+        settings.set_default::<Option<u64>>("smt_random_seed", None).unwrap();
         settings.set_default("use_more_complete_exhale", true).unwrap();
         settings.set_default("skip_unsupported_features", false).unwrap();
+        settings.set_default::<Vec<String>>("unsupported_feature_policy", vec![]).unwrap();
         settings.set_default("internal_errors_as_warnings", false).unwrap();
         settings.set_default("allow_unreachable_unsupported_code", false).unwrap();
         settings.set_default("no_verify", false).unwrap();
         settings.set_default("no_verify_deps", false).unwrap();
+        settings.set_default("check_only", false).unwrap();
         settings.set_default("opt_in_verification", false).unwrap();
+        settings.set_default::<Option<String>>("selective_verify", None).unwrap();
+        settings.set_default::<Option<String>>("selective_verify_file", None).unwrap();
         settings.set_default("full_compilation", false).unwrap();
         settings.set_default("json_communication", false).unwrap();
+        settings.set_default("json_diagnostics", false).unwrap();
+        settings.set_default("json_progress", false).unwrap();
+        settings.set_default::<Option<String>>("sarif_path", None).unwrap();
+        settings.set_default::<Option<String>>("coverage_report_dir", None).unwrap();
+        settings.set_default::<Option<String>>("semantic_cache_dir", None).unwrap();
+        settings.set_default::<Option<String>>("flamegraph_dir", None).unwrap();
         settings.set_default("optimizations", "all").unwrap();
         settings.set_default("intern_names", true).unwrap();
         settings.set_default("enable_purification_optimization", false).unwrap();
@@ -127,13 +173,22 @@ lazy_static::lazy_static! {
         settings.set_default("check_no_drops", false).unwrap();
         settings.set_default("enable_type_invariants", false).unwrap();
         settings.set_default("use_new_encoder", true).unwrap();
+        settings.set_default("snapshot_large_struct_fields", 8).unwrap();
+        settings.set_default("snapshot_field_group_size", 1).unwrap();
         settings.set_default::<Option<u8>>("number_of_parallel_verifiers", None).unwrap();
+        settings.set_default("verification_threads", 1).unwrap();
+        settings.set_default("verification_priority", "normal").unwrap();
+        settings.set_default("verification_retries", 1).unwrap();
+        settings.set_default("optimization_threads", 1).unwrap();
         settings.set_default::<Option<String>>("min_prusti_version", None).unwrap();
         settings.set_default("num_errors_per_function", 1).unwrap();
+        settings.set_default("report_all_errors", false).unwrap();
 
         settings.set_default("print_desugared_specs", false).unwrap();
         settings.set_default("print_typeckd_specs", false).unwrap();
         settings.set_default("print_collected_verification_items", false).unwrap();
+        settings.set_default("trust_audit", false).unwrap();
+        settings.set_default::<Option<String>>("trust_audit_entry_point", None).unwrap();
         settings.set_default("hide_uuids", false).unwrap();
         settings.set_default("counterexample", false).unwrap();
         settings.set_default("print_counterexample_if_model_is_present", false).unwrap();
@@ -145,6 +200,8 @@ lazy_static::lazy_static! {
 
         settings.set_default("cargo_path", "cargo").unwrap();
         settings.set_default("cargo_command", "check").unwrap();
+        settings.set_default("daemon", false).unwrap();
+        settings.set_default("daemon_idle_timeout", 1800).unwrap();
 
         // Flags for testing.
         settings.set_default::<Option<i64>>("verification_deadline", None).unwrap();
@@ -160,20 +217,31 @@ lazy_static::lazy_static! {
         // Flags for debugging performance.
         settings.set_default("preserve_smt_trace_files", false).unwrap();
         settings.set_default("write_smt_statistics", false).unwrap();
+        settings.set_default("smt_qi_profile", false).unwrap();
+        settings.set_default("smt_qi_profile_top", 10).unwrap();
         settings.set_default("log_smt_wrapper_interaction", false).unwrap();
+        settings.set_default("dump_smt_queries", false).unwrap();
 
         // Flags for debugging Prusti that can change verification results.
         settings.set_default("disable_name_mangling", false).unwrap();
         settings.set_default("verify_only_preamble", false).unwrap();
+        settings.set_default("verify_tests", false).unwrap();
+        settings.set_default("verify_doctests", false).unwrap();
         settings.set_default("enable_verify_only_basic_block_path", false).unwrap();
         settings.set_default::<Vec<String>>("verify_only_basic_block_path", vec![]).unwrap();
         settings.set_default::<Vec<String>>("delete_basic_blocks", vec![]).unwrap();
+        settings.set_default::<Vec<String>>("server_addresses", vec![]).unwrap();
 
         // Get the list of all allowed flags.
         let mut allowed_keys = get_keys(&settings);
         allowed_keys.insert("server_max_stored_verifiers".to_string());
         allowed_keys.insert("server_max_concurrency".to_string());
         allowed_keys.insert("server_address".to_string());
+        allowed_keys.insert("server_idle_timeout".to_string());
+        allowed_keys.insert("server_auth_token".to_string());
+        allowed_keys.insert("server_tls_cert_path".to_string());
+        allowed_keys.insert("server_tls_key_path".to_string());
+        allowed_keys.insert("server_tls_ca_path".to_string());
         allowed_keys.insert("config".to_string());
         allowed_keys.insert("log".to_string());
         allowed_keys.insert("log_style".to_string());
@@ -196,11 +264,12 @@ lazy_static::lazy_static! {
         ).unwrap();
         check_keys(&settings, &allowed_keys, "the `DEFAULT_PRUSTI_*` environment variables");
 
-        // 2. Override with an optional "Prusti.toml" file in manifest dir
-        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
-        let file = PathBuf::from(manifest_dir).join("Prusti.toml");
-        settings.merge(File::from(file.as_path()).required(false)).unwrap();
-        check_keys(&settings, &allowed_keys, &format!("the file `{}`", file.to_string_lossy()));
+        // 2. Override with optional "Prusti.toml" files, from the workspace root
+        //    down to the manifest dir
+        for file in config_files() {
+            settings.merge(File::from(file.as_path()).required(false)).unwrap();
+            check_keys(&settings, &allowed_keys, &format!("the file `{}`", file.to_string_lossy()));
+        }
 
         // 3. Override with env variables (`PRUSTI_VIPER_BACKEND`, ...)
         settings.merge(
@@ -208,9 +277,13 @@ lazy_static::lazy_static! {
                 .ignore_empty(true)
                 .try_parsing(true)
                 .with_list_parse_key("delete_basic_blocks")
+                .with_list_parse_key("dependency_specs")
                 .with_list_parse_key("extra_jvm_args")
                 .with_list_parse_key("extra_verifier_args")
+                .with_list_parse_key("server_addresses")
+                .with_list_parse_key("unsupported_feature_policy")
                 .with_list_parse_key("verify_only_basic_block_path")
+                .with_list_parse_key("viper_plugins")
                 .list_separator(" ")
         ).unwrap();
         check_keys(&settings, &allowed_keys, "the `PRUSTI_*` environment variables");
@@ -225,6 +298,39 @@ lazy_static::lazy_static! {
     });
 }
 
+/// The `Prusti.toml` files that configure the current crate, from the lowest
+/// to the highest precedence. When run by cargo, these are the files in the
+/// directories from the root of the crate's workspace down to the directory of
+/// the crate's `Cargo.toml`. Otherwise, only the current directory is used.
+fn config_files() -> Vec<PathBuf> {
+    let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") else {
+        return vec![PathBuf::from("Prusti.toml")];
+    };
+    let manifest_dir = PathBuf::from(manifest_dir);
+    let mut dirs = vec![];
+    if manifest_dir.ancestors().any(is_workspace_root) {
+        for dir in manifest_dir.ancestors() {
+            dirs.push(dir);
+            if is_workspace_root(dir) {
+                break;
+            }
+        }
+    } else {
+        dirs.push(manifest_dir.as_path());
+    }
+    dirs.into_iter()
+        .rev()
+        .map(|dir| dir.join("Prusti.toml"))
+        .collect()
+}
+
+/// Whether `dir` contains a `Cargo.toml` with a `[workspace]` section.
+fn is_workspace_root(dir: &Path) -> bool {
+    fs::read_to_string(dir.join("Cargo.toml")).map_or(false, |manifest| {
+        manifest.lines().any(|line| line.trim() == "[workspace]")
+    })
+}
+
 fn get_keys(settings: &Config) -> FxHashSet<String> {
     settings
         .cache
@@ -263,6 +369,20 @@ pub fn dump() -> String {
     pairs.join("\n\n")
 }
 
+/// The environment variables that can set a flag, i.e. `PRUSTI_<FLAG>` and
+/// `DEFAULT_PRUSTI_<FLAG>` for each flag.
+pub fn env_var_names() -> Vec<String> {
+    let settings = SETTINGS.read().unwrap();
+    let mut keys: Vec<_> = get_keys(&settings).into_iter().collect();
+    keys.sort();
+    keys.into_iter()
+        .flat_map(|key| {
+            let key = key.to_uppercase();
+            [format!("PRUSTI_{key}"), format!("DEFAULT_PRUSTI_{key}")]
+        })
+        .collect()
+}
+
 fn read_optional_setting<T>(name: &'static str) -> Option<T>
 where
     T: Deserialize<'static>,
@@ -311,6 +431,8 @@ pub fn check_foldunfold_state() -> bool {
 ///   [Carbon](https://github.com/viperproject/carbon).
 /// - `Silicon` - symbolic-execution-based backend
 ///   [Silicon](https://github.com/viperproject/silicon/).
+/// - `Native` - experimental backend that runs Z3 on an SMT-LIB encoding of
+///   the core proof, without Viper.
 pub fn viper_backend() -> String {
     read_setting::<String>("viper_backend")
         .to_lowercase()
@@ -325,6 +447,24 @@ pub fn smt_solver_path() -> String {
         .expect("Please set the smt_solver_path configuration flag")
 }
 
+/// The SMT solver used by Silicon. Possible values:
+///
+/// - `Z3` - [Z3](https://github.com/Z3Prover/z3).
+/// - `cvc5` - [cvc5](https://cvc5.github.io/), whose path is given by
+///   `cvc5_path`.
+pub fn smt_solver() -> String {
+    read_setting::<String>("smt_solver")
+        .to_lowercase()
+        .trim()
+        .to_string()
+}
+
+/// The path to cvc5, used when `smt_solver` is `cvc5`.
+pub fn cvc5_path() -> String {
+    read_setting::<Option<String>>("cvc5_path")
+        .expect("Please set the cvc5_path configuration flag or the CVC5_EXE environment variable")
+}
+
 /// The path to the SMT solver wrapper. `prusti-rustc` is expected to set this
 /// configuration flag to the correct path.
 pub fn smt_solver_wrapper_path() -> String {
@@ -355,6 +495,14 @@ pub fn viper_home() -> String {
     }
 }
 
+/// When set to the address of a stand-alone ViperServer (e.g.
+/// `"localhost:4000"`), Silicon and Carbon verify the programs of the core
+/// proof encoding by sending them to this server over HTTP, instead of
+/// running Viper through JNI in the current process.
+pub fn viper_server_address() -> Option<String> {
+    read_setting("viper_server_address")
+}
+
 /// The path to the Viper JARs. `prusti-rustc` is expected to set this
 /// configuration flag to the correct path.
 pub fn java_home() -> String {
@@ -411,6 +559,12 @@ pub fn dump_reborrowing_dag_in_debug_info() -> bool {
     read_setting("dump_reborrowing_dag_in_debug_info")
 }
 
+/// When enabled, the reborrowing DAGs and the fold-unfold decisions taken at
+/// the joins of each method will be output as DOT files.
+pub fn dump_foldunfold_graphviz() -> bool {
+    read_setting("dump_foldunfold_graphviz")
+}
+
 /// When enabled, borrow checking info will be output.
 pub fn dump_borrowck_info() -> bool {
     read_setting("dump_borrowck_info")
@@ -421,6 +575,26 @@ pub fn dump_viper_program() -> bool {
     read_setting("dump_viper_program")
 }
 
+/// When set to a directory, the Viper program of each verified procedure is
+/// written to `<path of the procedure>.vpr` in that directory, e.g.
+/// `module::function.vpr`.
+pub fn viper_export_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("viper_export_dir").map(PathBuf::from)
+}
+
+/// When set to a directory, each encoded VIR program is written as JSON to
+/// `<path of the procedure>.json` in that directory, before it is verified.
+/// The files can be verified again with `prusti-server --verify <file>`.
+pub fn vir_export_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("vir_export_dir").map(PathBuf::from)
+}
+
+/// When enabled, the VIR of each method will be output after each stage of
+/// the encoder's optimization pipeline.
+pub fn dump_vir_passes() -> bool {
+    read_setting("dump_vir_passes")
+}
+
 /// Filter for `fold`/`unfold` nodes when debug info is dumped.
 pub fn foldunfold_state_filter() -> String {
     read_setting("foldunfold_state_filter")
@@ -454,6 +628,18 @@ pub fn cache_path() -> PathBuf {
     PathBuf::from(read_setting::<String>("cache_path"))
 }
 
+/// The maximum number of results in the verification cache. When the cache
+/// is full, the least recently used results are evicted.
+pub fn cache_max_entries() -> Option<usize> {
+    read_setting("cache_max_entries")
+}
+
+/// The maximum size of the results in the verification cache, in megabytes.
+/// When the cache is full, the least recently used results are evicted.
+pub fn cache_max_size_mb() -> Option<u64> {
+    read_setting("cache_max_size_mb")
+}
+
 /// When enabled, binary operations and numeric casts will be checked for
 /// overflows.
 pub fn check_overflows() -> bool {
@@ -473,6 +659,36 @@ pub fn encode_bitvectors() -> bool {
     read_setting("encode_bitvectors")
 }
 
+/// When enabled, bounds of integer variables inferred by an interval
+/// analysis, and the comparisons checked by loop guards, are added to the
+/// invariants of loops.
+pub fn infer_loop_invariants() -> bool {
+    read_setting("infer_loop_invariants")
+}
+
+/// When enabled, trivial functions without specifications, such as getters
+/// and simple predicates, are treated as if they were marked `#[pure]`.
+pub fn infer_pure_functions() -> bool {
+    read_setting("infer_pure_functions")
+}
+
+/// When enabled, `prusti-rustc`, `cargo-prusti` and `prusti-server` download
+/// the Viper tools, including Z3, of the version that Prusti was built
+/// against if neither `VIPER_HOME` is set nor the tools are found next to the
+/// executables. The archive is checked against the SHA-256 checksum pinned in
+/// `viper-toolchain.sha256` before it is extracted. The tools are kept in
+/// `viper_tools_cache_dir`.
+pub fn download_viper_tools() -> bool {
+    read_setting("download_viper_tools")
+}
+
+/// The directory in which the downloaded Viper tools are kept, see
+/// `download_viper_tools`. Defaults to a `prusti` directory in the cache
+/// directory of the user.
+pub fn viper_tools_cache_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("viper_tools_cache_dir").map(PathBuf::from)
+}
+
 /// Additional arguments to pass to the JVM when launching a verifier backend.
 pub fn extra_jvm_args() -> Vec<String> {
     read_setting("extra_jvm_args")
@@ -483,12 +699,30 @@ pub fn extra_verifier_args() -> Vec<String> {
     read_setting("extra_verifier_args")
 }
 
+/// The fully qualified class names of additional Viper plugins, e.g.
+/// `com.example.MyPlugin`, to load in the Silicon and Carbon backends.
+pub fn viper_plugins() -> Vec<String> {
+    read_setting("viper_plugins")
+}
+
+/// When enabled, the Silicon backend keeps Viper's termination plugin, which
+/// checks the `decreases` clauses of the verified program.
+pub fn viper_termination_plugin() -> bool {
+    read_setting("viper_termination_plugin")
+}
+
 /// When enabled, user messages are not printed. Otherwise, message output
 /// into `stderr`.
 pub fn quiet() -> bool {
     read_setting("quiet")
 }
 
+/// When enabled, the result of each verified Viper program is printed to
+/// `stderr` as soon as it is known, also when `quiet` is enabled.
+pub fn report_progress() -> bool {
+    read_setting("report_progress")
+}
+
 /// Maximum time (in milliseconds) for the verifier to spend on a single
 /// assertion. Set to `0` to disable timeout. Maps to the verifier command-line
 /// argument `--assertTimeout`.
@@ -496,11 +730,76 @@ pub fn assert_timeout() -> u64 {
     read_setting("assert_timeout")
 }
 
+/// When enabled, the pure functions of dependencies are encoded by their
+/// contract instead of their body, i.e. the contracts of dependencies are
+/// assumed. The dependencies whose contracts were assumed are listed at the
+/// end of the verification.
+pub fn assume_dependency_contracts() -> bool {
+    read_setting("assume_dependency_contracts")
+}
+
+/// When enabled, the `todo!()` and `unimplemented!()` statements are assumed
+/// to be unreachable instead of being checked, so that the rest of the
+/// function can be verified. They are listed at the end of the verification.
+pub fn assume_unimplemented() -> bool {
+    read_setting("assume_unimplemented")
+}
+
+/// When enabled, only the absence of panics, overflows and out-of-bounds
+/// accesses is verified. The postconditions and the `prusti_assert!`
+/// statements of local functions are neither checked nor assumed. The
+/// `prusti::no_panic` attribute enables this for a single item.
+pub fn panic_freedom_only() -> bool {
+    read_setting("panic_freedom_only")
+}
+
+/// The MIR from which the bodies of pure functions, predicates and
+/// specifications are encoded. Possible values:
+///
+/// - `analysis` - the MIR that the borrow checker analyses.
+/// - `optimized` - the MIR after the optimization passes of the compiler.
+///
+/// Non-pure functions are always encoded from the `analysis` MIR, because
+/// their encoding relies on the borrow checker facts of that MIR.
+pub fn pure_mir_stage() -> String {
+    read_setting::<String>("pure_mir_stage")
+        .to_lowercase()
+        .trim()
+        .to_string()
+}
+
+/// The `.prusti-specs` files that specify dependencies, given as a list of
+/// `<crate name>=<path>` entries. The specifications of a crate are only
+/// included when the verified crate depends on it.
+pub fn dependency_specs() -> Vec<(String, PathBuf)> {
+    read_setting::<Vec<String>>("dependency_specs")
+        .into_iter()
+        .filter_map(|entry| match entry.split_once('=') {
+            Some((crate_name, path)) => Some((crate_name.to_string(), PathBuf::from(path))),
+            None => {
+                warn!("Ignoring dependency specs '{}' without a crate name", entry);
+                None
+            }
+        })
+        .collect()
+}
+
 /// Set `qi.eager_threshold` value to the given one.
 pub fn smt_qi_eager_threshold() -> u64 {
     read_setting("smt_qi_eager_threshold")
 }
 
+/// The resource limit (Z3's `rlimit`) of each query to the SMT solver, if any.
+/// Unlike a timeout, it bounds queries independently of the machine load.
+pub fn smt_rlimit() -> Option<u64> {
+    read_setting("smt_rlimit")
+}
+
+/// The random seed of the SMT solver, if any.
+pub fn smt_random_seed() -> Option<u64> {
+    read_setting("smt_random_seed")
+}
+
 /// Maximum time (in milliseconds) for the verifier to spend on checks.
 /// Set to None uses the verifier's default value. Maps to the verifier command-line
 /// argument `--checkTimeout`.
@@ -523,6 +822,19 @@ pub fn print_collected_verification_items() -> bool {
     read_setting("print_collected_verification_items")
 }
 
+/// When enabled, prints every `#[trusted]` function, `prusti_assume!`,
+/// `#[extern_spec]` and axiomatized pure function that the verification of
+/// the crate, or of `trust_audit_entry_point` if set, depends on.
+pub fn trust_audit() -> bool {
+    read_setting("trust_audit")
+}
+
+/// The function whose trust assumptions are printed by `trust_audit`, given
+/// as a path such as `module::function` or `Type::method`.
+pub fn trust_audit_entry_point() -> Option<String> {
+    read_setting("trust_audit_entry_point")
+}
+
 /// When enabled, prints the AST with desugared specifications.
 pub fn print_desugared_specs() -> bool {
     read_setting("print_desugared_specs")
@@ -623,19 +935,15 @@ pub fn enable_cache() -> bool {
     read_setting("enable_cache")
 }
 
-/// Maximum amount of instantiated Viper verifiers the server will keep around
-/// for reuse. If not set, defaults to
-/// `SERVER_MAX_CONCURRENT_VERIFICATION_OPERATIONS`. It also doesn't make much
-/// sense to set this option to less than that, since then the server will
-/// likely have to keep creating new verifiers, reducing the performance gained
-/// from reuse.
+/// Maximum amount of instantiated Viper verifiers that each verification
+/// thread of the server keeps around for reuse across requests with the same
+/// backend configuration. If not set, the server creates a new verifier for
+/// each request.
 ///
 /// **Note:** This does _not_ limit how many verification requests the server
 /// handles concurrently, only the size of what is essentially its verifier
 /// cache.
 pub fn server_max_stored_verifiers() -> Option<usize> {
-    // TODO: default to below in prusti-server
-    // TODO: warn if lower than below
     read_optional_setting("server_max_stored_verifiers")
 }
 
@@ -655,12 +963,109 @@ pub fn server_address() -> Option<String> {
     read_optional_setting("server_address")
 }
 
+/// When set, the server stops after receiving no requests for this number
+/// of seconds, once it verified all jobs.
+pub fn server_idle_timeout() -> Option<u64> {
+    read_optional_setting("server_idle_timeout")
+}
+
+/// When set, the Prusti server only accepts requests with the header
+/// `Authorization: Bearer <token>`, and the client sends this header.
+pub fn server_auth_token() -> Option<String> {
+    read_optional_setting("server_auth_token")
+}
+
+/// The path to the PEM-encoded certificate chain with which the Prusti server
+/// terminates TLS. Requires `server_tls_key_path`.
+pub fn server_tls_cert_path() -> Option<String> {
+    read_optional_setting("server_tls_cert_path")
+}
+
+/// The path to the PEM-encoded private key of the certificate of
+/// `server_tls_cert_path`.
+pub fn server_tls_key_path() -> Option<String> {
+    read_optional_setting("server_tls_key_path")
+}
+
+/// The path to a PEM-encoded certificate that the client trusts in addition to
+/// the system's root certificates, e.g. of a self-signed server certificate.
+pub fn server_tls_ca_path() -> Option<String> {
+    read_optional_setting("server_tls_ca_path")
+}
+
+/// The addresses of several Prusti servers that share the verification of the
+/// programs, e.g. the nodes of a cluster. When not empty, this flag takes
+/// precedence over `server_address`.
+pub fn server_addresses() -> Vec<String> {
+    read_setting("server_addresses")
+}
+
+/// The priority with which the server verifies the programs of this crate,
+/// compared to those of other clients: `"background"`, `"normal"` or
+/// `"interactive"`.
+pub fn verification_priority() -> String {
+    read_setting("verification_priority")
+}
+
+/// How often a verification is retried with a new verifier when the JVM ran
+/// out of memory, or, on the server, when the verification crashed.
+pub fn verification_retries() -> usize {
+    read_setting("verification_retries")
+}
+
 /// When enabled, communication with the server will be encoded as JSON
 /// instead of the default bincode.
 pub fn json_communication() -> bool {
     read_setting("json_communication")
 }
 
+/// When enabled, the result of verifying each item is additionally printed to
+/// `stderr` as a single line of JSON, containing the error kinds, codes, spans,
+/// failing specification clauses, counterexamples and the verification time.
+/// The lines have the field `"$message_type": "prusti_verification_result"`,
+/// which distinguishes them from `rustc`'s own JSON diagnostics.
+pub fn json_diagnostics() -> bool {
+    read_setting("json_diagnostics")
+}
+
+/// When enabled, the start and the end of the verification of each item are
+/// printed to `stderr` as single lines of JSON, as soon as they happen. The
+/// lines have the field `"$message_type": "prusti_verification_event"`.
+pub fn json_progress() -> bool {
+    read_setting("json_progress")
+}
+
+/// When set to a directory, a report listing which functions of the crate
+/// were verified, failed, skipped as unsupported, trusted or pure-only is
+/// written to `<crate name>.json` in that directory.
+pub fn coverage_report_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("coverage_report_dir").map(PathBuf::from)
+}
+
+/// When set to a directory, the time spent encoding and verifying each
+/// procedure is written to `<crate name>.folded` in that directory, in the
+/// folded stacks format used by flamegraph tools.
+pub fn flamegraph_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("flamegraph_dir").map(PathBuf::from)
+}
+
+/// When set to a directory, the procedures that verified successfully are
+/// recorded in `<crate name>.json` in that directory, together with a hash of
+/// everything that their verification depends on. A later run skips the
+/// procedures whose hash did not change.
+pub fn semantic_cache_dir() -> Option<PathBuf> {
+    read_setting::<Option<String>>("semantic_cache_dir").map(PathBuf::from)
+}
+
+/// When set to a path, the verification results are additionally written to
+/// that file in the SARIF format, e.g. for GitHub code scanning. Each crate is
+/// stored as a separate run, replacing an earlier run of the same crate. The
+/// runs are kept in the `<path>.runs` directory, from which the log is
+/// assembled, see `report::sarif`.
+pub fn sarif_path() -> Option<PathBuf> {
+    read_setting::<Option<String>>("sarif_path").map(PathBuf::from)
+}
+
 /// When enabled, Viper name mangling will be disabled.
 ///
 /// **Note:** This is very likely to result in invalid programs being generated
@@ -677,6 +1082,22 @@ pub fn verify_only_preamble() -> bool {
     read_setting("verify_only_preamble")
 }
 
+/// When enabled, crates are compiled with `--test`, so that `#[cfg(test)]`
+/// modules and `#[test]` functions are verified. The test harness generated
+/// by the compiler is not verified.
+pub fn verify_tests() -> bool {
+    read_setting("verify_tests")
+}
+
+/// When enabled, `cargo-prusti` also verifies the examples in the
+/// documentation of the library, by running `cargo test --doc` with rustdoc
+/// compiling each doctest with Prusti. A doctest is verified like a `main`
+/// function that uses the library, against the library's specifications. The
+/// doctests are not run.
+pub fn verify_doctests() -> bool {
+    read_setting("verify_doctests")
+}
+
 /// When enabled, only the path given in `VERIFY_ONLY_BASIC_BLOCK_PATH` will
 /// be verified.
 ///
@@ -699,11 +1120,14 @@ pub fn verify_only_basic_block_path() -> Vec<String> {
 ///
 /// - `"inline_constant_functions"`
 /// - `"delete_unused_predicates"`
+/// - `"delete_unused_definitions"`
 /// - `"optimize_folding"`
 /// - `"remove_empty_if"`
 /// - `"purify_vars"`
 /// - `"fix_quantifiers"`
 /// - `"fix_unfoldings"`
+/// - `"simplify_expressions"`
+/// - `"remove_dead_code"`
 /// - `"remove_unused_vars"`
 /// - `"remove_trivial_assertions"`
 /// - `"clean_cfg"`
@@ -718,11 +1142,14 @@ pub fn optimizations() -> Optimizations {
             "all" => opt = Optimizations::all_enabled(),
             "inline_constant_functions" => opt.inline_constant_functions = true,
             "delete_unused_predicates" => opt.delete_unused_predicates = true,
+            "delete_unused_definitions" => opt.delete_unused_definitions = true,
             "optimize_folding" => opt.optimize_folding = true,
             "remove_empty_if" => opt.remove_empty_if = true,
             "purify_vars" => opt.purify_vars = true,
             "fix_quantifiers" => opt.fix_quantifiers = true,
             "fix_unfoldings" => opt.fix_unfoldings = true,
+            "simplify_expressions" => opt.simplify_expressions = true,
+            "remove_dead_code" => opt.remove_dead_code = true,
             "remove_unused_vars" => opt.remove_unused_vars = true,
             "remove_trivial_assertions" => opt.remove_trivial_assertions = true,
             "clean_cfg" => opt.clean_cfg = true,
@@ -737,6 +1164,9 @@ pub fn optimizations() -> Optimizations {
 /// optimization, which tries to convert heap operations to pure (snapshot-
 /// based) operations.
 ///
+/// Never borrowed local variables of primitive types, and of structs whose
+/// fields all have primitive types, are replaced by Viper variables.
+///
 /// **Note:** this option is highly experimental.
 pub fn enable_purification_optimization() -> bool {
     read_setting("enable_purification_optimization")
@@ -859,12 +1289,33 @@ pub fn write_smt_statistics() -> bool {
     read_smt_wrapper_dependent_bool("write_smt_statistics")
 }
 
+/// Collect the number of instantiations of each quantifier from Z3's
+/// `smt.qi.profile` output and report the quantifiers with the most
+/// instantiations for each verified method.
+pub fn smt_qi_profile() -> bool {
+    read_smt_wrapper_dependent_bool("smt_qi_profile")
+}
+
+/// How many quantifiers are reported per method by `smt_qi_profile`.
+pub fn smt_qi_profile_top() -> usize {
+    read_setting("smt_qi_profile_top")
+}
+
 /// Log communication of Silicon with Z3.
 pub fn log_smt_wrapper_interaction() -> bool {
     read_smt_wrapper_dependent_bool("log_smt_wrapper_interaction")
 }
 
-/// When enabled, the new core proof is used, suitable for unsafe code
+/// Write the commands that Silicon sends to Z3 for each verified method to an
+/// SMT-LIB file, which can be replayed without Prusti and Viper.
+pub fn dump_smt_queries() -> bool {
+    read_smt_wrapper_dependent_bool("dump_smt_queries")
+}
+
+/// When enabled, the new core proof is used, suitable for unsafe code. It
+/// encodes the memory as snapshots instead of the fold/unfold heap encoding,
+/// which is often faster for safe code with little aliasing. The flag can also
+/// be set for single functions with `prusti::config(unsafe_core_proof = ..)`.
 ///
 /// **Note:** This option is currently very incomplete.
 pub fn unsafe_core_proof() -> bool {
@@ -944,11 +1395,43 @@ pub fn use_new_encoder() -> bool {
     read_setting("use_new_encoder")
 }
 
+/// The number of fields above which a struct or enum variant gets a single
+/// snapshot field access axiom, instead of one axiom per field.
+pub fn snapshot_large_struct_fields() -> usize {
+    read_setting("snapshot_large_struct_fields")
+}
+
+/// The number of field accesses that together trigger the snapshot
+/// injectivity axiom of a struct or enum variant with more than
+/// `snapshot_large_struct_fields` fields. With `1`, every field access
+/// triggers it.
+pub fn snapshot_field_group_size() -> usize {
+    read_setting("snapshot_field_group_size")
+}
+
 /// How many parallel verifiers Silicon should use.
 pub fn number_of_parallel_verifiers() -> Option<u8> {
     read_setting("number_of_parallel_verifiers")
 }
 
+/// How many Viper programs are verified concurrently, each by its own verifier
+/// instance. Falls back to a single thread when the SMT wrapper is used.
+pub fn verification_threads() -> usize {
+    let threads: usize = read_setting("verification_threads");
+    if use_smt_wrapper() {
+        1
+    } else {
+        threads.max(1)
+    }
+}
+
+/// How many threads optimize the encoded Viper programs. The encoding of the
+/// MIR itself is always sequential, because the compiler context is not
+/// thread-safe.
+pub fn optimization_threads() -> usize {
+    read_setting::<usize>("optimization_threads").max(1)
+}
+
 /// Throw a compilation error if using a lower prusti version.
 pub fn min_prusti_version() -> Option<String> {
     read_setting("min_prusti_version")
@@ -965,6 +1448,22 @@ pub fn skip_unsupported_features() -> bool {
     read_setting("skip_unsupported_features")
 }
 
+/// How the unsupported features of each category are reported, as
+/// `<category>=<policy>` entries (e.g. `closures=warn`), where the policy is
+/// `error`, `warn` or `trust`. The `default` category applies to the
+/// categories without an entry. Without a `default` entry, the policy of the
+/// other categories is given by `skip_unsupported_features`. Given as a single
+/// string, e.g. with `-P`, the entries are separated by commas or spaces.
+pub fn unsupported_feature_policy() -> Vec<String> {
+    read_optional_setting("unsupported_feature_policy").unwrap_or_else(|| {
+        read_setting::<String>("unsupported_feature_policy")
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|entry| !entry.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+}
+
 /// When enabled, internal errors are reported as warnings instead of errors.
 /// Used for testing.
 pub fn internal_errors_as_warnings() -> bool {
@@ -990,12 +1489,30 @@ pub fn no_verify_deps() -> bool {
     read_setting("no_verify_deps")
 }
 
+/// When enabled, the specifications are type-checked and the items are
+/// encoded, but the encoded programs are not verified.
+pub fn check_only() -> bool {
+    read_setting("check_only")
+}
+
 /// When enabled, verification is skipped for functions
 /// that do not have the `#[verified]` attribute.
 pub fn opt_in_verification() -> bool {
     read_setting("opt_in_verification")
 }
 
+/// When set to a path such as `module::function` or `Type::method`, only the
+/// functions matching it are verified.
+pub fn selective_verify() -> Option<String> {
+    read_setting("selective_verify")
+}
+
+/// When set to a path such as `src/parser.rs`, only the functions defined in
+/// that source file are verified.
+pub fn selective_verify_file() -> Option<PathBuf> {
+    read_setting::<Option<String>>("selective_verify_file").map(PathBuf::from)
+}
+
 /// When enabled, compilation will continue and a binary will be generated
 /// after Prusti terminates.
 pub fn full_compilation() -> bool {
@@ -1019,6 +1536,19 @@ pub fn cargo_command() -> String {
     read_setting("cargo_command")
 }
 
+/// When enabled, `cargo-prusti` verifies the crates with a local Prusti
+/// server that keeps running between invocations, and starts it if needed.
+/// Not relevant when only running as `prusti-rustc`.
+pub fn daemon() -> bool {
+    read_setting("daemon")
+}
+
+/// The time in seconds after which the server started by `cargo-prusti`
+/// stops, if it receives no requests, see `daemon`.
+pub fn daemon_idle_timeout() -> u64 {
+    read_setting("daemon_idle_timeout")
+}
+
 /// When enabled, type invariants can be declared on types using the
 /// `#[invariant(...)]` attribute.
 pub fn enable_type_invariants() -> bool {
@@ -1026,7 +1556,17 @@ pub fn enable_type_invariants() -> bool {
 }
 
 /// The maximum number of verification errors to report per function. This is only used by the
-/// Silicon backend. A value of 0 means no limit.
+/// Silicon backend. A value of 0 means no limit, which is implied by `report_all_errors`.
 pub fn num_errors_per_function() -> u32 {
-    read_setting("num_errors_per_function")
+    if report_all_errors() {
+        0
+    } else {
+        read_setting("num_errors_per_function")
+    }
+}
+
+/// When enabled, all failing assertions of a function are reported in a single run instead of
+/// only the first one. This is the same as setting `num_errors_per_function` to 0.
+pub fn report_all_errors() -> bool {
+    read_setting("report_all_errors")
 }
@@ -5,7 +5,9 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 #![deny(unused_must_use)]
+use crate::{config, report::error_codes::ErrorCode};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use std::{
     env,
     path::{Path, PathBuf},
@@ -96,19 +98,24 @@ fn env_prepend_path(name: &str, value: Vec<PathBuf>, cmd: &mut Command) {
     }
 }
 
-/// Find the sub-folder containing the JVM dynamic library
-pub fn find_libjvm<S: AsRef<Path>>(path: S) -> Option<PathBuf> {
-    #[cfg(target_os = "windows")]
-    const EXPECTED_JVM_FILENAME: &str = "jvm.dll";
-    #[cfg(target_os = "linux")]
-    const EXPECTED_JVM_FILENAME: &str = "libjvm.so";
-    // On MacOS, we need to link to libjli instead of libjvm as a workaround to a Java8 bug.
-    // See: https://github.com/jni-rs/jni-rs/pull/230
-    #[cfg(target_os = "macos")]
-    const EXPECTED_JVM_FILENAME: &str = "libjli.dylib";
+#[cfg(target_os = "windows")]
+const EXPECTED_JVM_FILENAME: &str = "jvm.dll";
+#[cfg(target_os = "linux")]
+const EXPECTED_JVM_FILENAME: &str = "libjvm.so";
+// On MacOS, we need to link to libjli instead of libjvm as a workaround to a Java8 bug.
+// See: https://github.com/jni-rs/jni-rs/pull/230
+#[cfg(target_os = "macos")]
+const EXPECTED_JVM_FILENAME: &str = "libjli.dylib";
+
+/// The oldest Java version that runs the Viper tools.
+const MIN_JAVA_VERSION: u32 = 11;
 
+/// Find the sub-folder containing the JVM dynamic library. If there are
+/// several, e.g. a `client` and a `server` JVM, the `server` one is preferred.
+pub fn find_libjvm<S: AsRef<Path>>(path: S) -> Option<PathBuf> {
     let walker = walkdir::WalkDir::new(path).follow_links(true);
 
+    let mut found = None;
     for entry in walker {
         let entry = match entry {
             Ok(entry) => entry,
@@ -118,11 +125,207 @@ pub fn find_libjvm<S: AsRef<Path>>(path: S) -> Option<PathBuf> {
         let file_name = entry.file_name().to_str().unwrap_or("");
 
         if file_name == EXPECTED_JVM_FILENAME {
-            return entry.path().parent().map(Into::into);
+            let dir = entry.path().parent().map(Path::to_path_buf);
+            if dir.as_ref().and_then(|dir| dir.file_name()) == Some("server".as_ref()) {
+                return dir;
+            }
+            found = found.or(dir);
         }
     }
 
-    None
+    found
+}
+
+/// A Java runtime found by `find_java_runtime`.
+pub struct JavaRuntime {
+    pub java_home: PathBuf,
+    /// The folder containing the JVM dynamic library, see `find_libjvm`.
+    pub libjvm_dir: PathBuf,
+}
+
+/// Find the Java runtime with which to run Viper. The candidates are, in
+/// order: `JAVA_HOME`, a runtime bundled in a `jre` folder next to the
+/// executables (e.g. created with `jlink`), the runtime of the `java` on the
+/// `PATH`, and the usual installation folders of the platform. A candidate is
+/// used if it contains the JVM library and its `release` file, if any, does
+/// not report an older Java version than `MIN_JAVA_VERSION` or another
+/// architecture than the one of Prusti. When `JAVA_HOME` is set, no other
+/// candidate is tried. The error explains why each candidate was rejected.
+pub fn find_java_runtime(current_executable_dir: &Path) -> Result<JavaRuntime, String> {
+    if let Some(java_home) = env::var_os("JAVA_HOME") {
+        let java_home = PathBuf::from(java_home);
+        return check_java_home(&java_home).map_err(|reason| {
+            format!(
+                "JAVA_HOME ({java_home:?}) is not a usable Java runtime: {reason}. \
+                Please point JAVA_HOME to a {}-bit Java {MIN_JAVA_VERSION} or later, \
+                or unset it to let Prusti search for one.",
+                usize::BITS
+            )
+        });
+    }
+    let mut rejected = vec![];
+    let mut try_candidate = |source: &str, java_home: PathBuf| match check_java_home(&java_home) {
+        Ok(runtime) => Some(runtime),
+        Err(reason) => {
+            rejected.push(format!("\n  - {java_home:?} ({source}): {reason}"));
+            None
+        }
+    };
+    let bundled = current_executable_dir.join("jre");
+    if bundled.is_dir() {
+        if let Some(runtime) = try_candidate("bundled", bundled) {
+            return Ok(runtime);
+        }
+    }
+    if let Some(java_home) = find_java_home() {
+        if let Some(runtime) = try_candidate("`java` on the PATH", java_home) {
+            return Ok(runtime);
+        }
+    }
+    for java_home in installed_java_homes() {
+        if let Some(runtime) = try_candidate("installed", java_home) {
+            return Ok(runtime);
+        }
+    }
+    Err(format!(
+        "Failed to find a {}-bit Java {MIN_JAVA_VERSION} or later, which is needed to run \
+        Viper. Please install one or set JAVA_HOME to its installation folder.{}",
+        usize::BITS,
+        if rejected.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " The following candidates were rejected:{}",
+                rejected.concat()
+            )
+        }
+    ))
+}
+
+fn check_java_home(java_home: &Path) -> Result<JavaRuntime, String> {
+    if !java_home.is_dir() {
+        return Err("the folder does not exist".to_string());
+    }
+    // Some installations have no `release` file; they are only checked for
+    // the JVM library.
+    if let Some(version) = release_property(java_home, "JAVA_VERSION") {
+        match java_major_version(&version) {
+            Some(major) if major < MIN_JAVA_VERSION => {
+                return Err(format!(
+                    "it is Java {version}, but Java {MIN_JAVA_VERSION} or later is needed"
+                ));
+            }
+            _ => {}
+        }
+    }
+    if let Some(arch) = release_property(java_home, "OS_ARCH") {
+        if normalize_arch(&arch) != normalize_arch(env::consts::ARCH) {
+            return Err(format!(
+                "it is built for {arch}, but Prusti is built for {}",
+                env::consts::ARCH
+            ));
+        }
+    }
+    match find_libjvm(java_home) {
+        Some(libjvm_dir) => Ok(JavaRuntime {
+            java_home: java_home.to_path_buf(),
+            libjvm_dir,
+        }),
+        None => Err(format!("it contains no {EXPECTED_JVM_FILENAME}")),
+    }
+}
+
+/// The major version of a Java version string, e.g. `8` for `1.8.0_292` and
+/// `17` for `17.0.2`.
+fn java_major_version(version: &str) -> Option<u32> {
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    match parts.next()?.parse().ok()? {
+        1 => parts.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+fn normalize_arch(arch: &str) -> &str {
+    match arch {
+        "amd64" | "x86_64" => "x86_64",
+        "arm64" | "aarch64" => "aarch64",
+        "i386" | "i586" | "i686" | "x86" => "x86",
+        other => other,
+    }
+}
+
+/// The Java installations in the usual folders of the platform, the newest
+/// versions first.
+fn installed_java_homes() -> Vec<PathBuf> {
+    let mut parents = vec![];
+    if cfg!(windows) {
+        for program_files in ["ProgramFiles", "ProgramW6432"] {
+            if let Some(dir) = env::var_os(program_files) {
+                let dir = PathBuf::from(dir);
+                for vendor in [
+                    "Java",
+                    "Eclipse Adoptium",
+                    "Microsoft",
+                    "Zulu",
+                    "Amazon Corretto",
+                ] {
+                    parents.push(dir.join(vendor));
+                }
+            }
+        }
+    } else if cfg!(target_os = "macos") {
+        parents.push(PathBuf::from("/Library/Java/JavaVirtualMachines"));
+        if let Some(home) = env::var_os("HOME") {
+            parents.push(PathBuf::from(home).join("Library/Java/JavaVirtualMachines"));
+        }
+    } else {
+        parents.push(PathBuf::from("/usr/lib/jvm"));
+        parents.push(PathBuf::from("/usr/java"));
+        parents.push(PathBuf::from("/opt/java"));
+    }
+    let mut java_homes = vec![];
+    for parent in parents {
+        let Ok(entries) = std::fs::read_dir(&parent) else {
+            continue;
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.is_dir())
+            .map(|path| {
+                // macOS bundles have the Java home in `Contents/Home`.
+                let bundle_home = path.join("Contents").join("Home");
+                if bundle_home.is_dir() {
+                    bundle_home
+                } else {
+                    path
+                }
+            })
+            .collect();
+        dirs.sort_by_key(|path| std::cmp::Reverse(java_home_version(path)));
+        java_homes.extend(dirs);
+    }
+    java_homes
+}
+
+/// The major Java version of an installation folder, taken from its `release`
+/// file or guessed from its name, for sorting.
+fn java_home_version(java_home: &Path) -> Option<u32> {
+    if let Some(version) = release_property(java_home, "JAVA_VERSION") {
+        return java_major_version(&version);
+    }
+    let name = java_home.file_name()?.to_str()?;
+    let start = name.find(|c: char| c.is_ascii_digit())?;
+    java_major_version(&name[start..])
+}
+
+/// The value of a property in the `release` file of a Java installation, e.g.
+/// `17.0.2` for `JAVA_VERSION="17.0.2"`.
+fn release_property(java_home: &Path, name: &str) -> Option<String> {
+    let release = std::fs::read_to_string(java_home.join("release")).ok()?;
+    release.lines().find_map(|line| {
+        let value = line.strip_prefix(name)?.strip_prefix('=')?;
+        Some(value.trim().trim_matches('"').to_string())
+    })
 }
 
 /// Find the Java home directory
@@ -243,6 +446,174 @@ pub fn find_z3_exe(base_dir: &Path) -> Option<PathBuf> {
     candidates.into_iter().find(|candidate| candidate.is_file())
 }
 
+/// The version of the Viper tools that Prusti is built against, i.e. the tag
+/// of the `viper-ide` release that provides them.
+pub fn viper_tools_version() -> &'static str {
+    include_str!("../../../viper-toolchain").trim()
+}
+
+/// The SHA-256 checksum of the Viper tools archive `archive_name` of
+/// `viper_tools_version()`, as pinned in `viper-toolchain.sha256` by
+/// `x.py update-viper-checksums`.
+fn viper_tools_checksum(archive_name: &str) -> Option<&'static str> {
+    include_str!("../../../viper-toolchain.sha256")
+        .lines()
+        .filter_map(|line| line.split_once("  "))
+        .find(|(_, name)| name.trim() == archive_name)
+        .map(|(checksum, _)| checksum.trim())
+}
+
+fn default_cache_dir() -> Option<PathBuf> {
+    let cache_dir = if cfg!(windows) {
+        PathBuf::from(env::var_os("LOCALAPPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(env::var_os("HOME")?)
+            .join("Library")
+            .join("Caches")
+    } else if let Some(path) = env::var_os("XDG_CACHE_HOME") {
+        PathBuf::from(path)
+    } else {
+        PathBuf::from(env::var_os("HOME")?).join(".cache")
+    };
+    Some(cache_dir.join("prusti"))
+}
+
+/// Returns the directory containing the `viper_tools` of
+/// `viper_tools_version()` in the cache, downloading and extracting them
+/// first if needed, see `config::download_viper_tools`. Returns `None` if the
+/// download is disabled or fails.
+pub fn provision_viper_tools() -> Option<PathBuf> {
+    if !config::download_viper_tools() {
+        return None;
+    }
+    let cache_dir = config::viper_tools_cache_dir().or_else(default_cache_dir)?;
+    let version_dir = cache_dir.join(viper_tools_version());
+    if version_dir.join("viper_tools").is_dir() {
+        return Some(version_dir);
+    }
+    match download_viper_tools(&cache_dir, &version_dir) {
+        Ok(()) => Some(version_dir),
+        Err(error) => {
+            eprintln!("Failed to download the Viper tools: {error}");
+            None
+        }
+    }
+}
+
+fn download_viper_tools(cache_dir: &Path, version_dir: &Path) -> Result<(), String> {
+    let archive_name = if cfg!(windows) {
+        "ViperToolsWin.zip"
+    } else if cfg!(target_os = "macos") {
+        "ViperToolsMac.zip"
+    } else {
+        "ViperToolsLinux.zip"
+    };
+    let Some(expected_checksum) = viper_tools_checksum(archive_name) else {
+        return Err(format!(
+            "no SHA-256 checksum of {archive_name} of version {} is pinned in \
+            viper-toolchain.sha256",
+            viper_tools_version()
+        ));
+    };
+    let url = format!(
+        "https://github.com/viperproject/viper-ide/releases/download/{}/{archive_name}",
+        viper_tools_version()
+    );
+    eprintln!("Downloading the Viper tools from {url} to {version_dir:?}...");
+    install_archive(
+        &url,
+        archive_name,
+        expected_checksum,
+        cache_dir,
+        version_dir,
+    )
+}
+
+/// Downloads the archive `archive_name` from `url`, checks its SHA-256
+/// checksum and extracts it into `<version_dir>/viper_tools`.
+fn install_archive(
+    url: &str,
+    archive_name: &str,
+    expected_checksum: &str,
+    cache_dir: &Path,
+    version_dir: &Path,
+) -> Result<(), String> {
+    // Extract into a fresh directory first, so that an interrupted download
+    // is not mistaken for a complete one and concurrent runs do not see
+    // partially extracted tools.
+    let partial_dir = cache_dir.join(format!(
+        "{}.partial-{}",
+        viper_tools_version(),
+        std::process::id()
+    ));
+    let extract_dir = partial_dir.join("viper_tools");
+    std::fs::create_dir_all(&extract_dir)
+        .map_err(|error| format!("failed to create {extract_dir:?}: {error}"))?;
+    let archive = partial_dir.join(archive_name);
+    let result = run_tool(
+        Command::new("curl")
+            .arg("--fail")
+            .arg("--location")
+            .arg("--silent")
+            .arg("--show-error")
+            .arg("--output")
+            .arg(&archive)
+            .arg(url),
+    )
+    .and_then(|()| {
+        let data = std::fs::read(&archive)
+            .map_err(|error| format!("failed to read {archive:?}: {error}"))?;
+        let checksum = format!("{:x}", Sha256::digest(data));
+        if checksum != expected_checksum {
+            return Err(format!(
+                "the SHA-256 checksum of {url} is {checksum}, but {expected_checksum} was expected"
+            ));
+        }
+        if cfg!(windows) {
+            run_tool(
+                Command::new("tar")
+                    .arg("-xf")
+                    .arg(&archive)
+                    .arg("-C")
+                    .arg(&extract_dir),
+            )
+        } else {
+            run_tool(
+                Command::new("unzip")
+                    .arg("-q")
+                    .arg(&archive)
+                    .arg("-d")
+                    .arg(&extract_dir),
+            )
+        }
+    })
+    .and_then(|()| {
+        std::fs::remove_file(&archive)
+            .map_err(|error| format!("failed to remove {archive:?}: {error}"))?;
+        match std::fs::rename(&partial_dir, version_dir) {
+            Ok(()) => Ok(()),
+            // Another run was faster.
+            Err(_) if version_dir.join("viper_tools").is_dir() => Ok(()),
+            Err(error) => Err(format!(
+                "failed to move {partial_dir:?} to {version_dir:?}: {error}"
+            )),
+        }
+    });
+    if partial_dir.exists() {
+        let _ = std::fs::remove_dir_all(&partial_dir);
+    }
+    result
+}
+
+fn run_tool(cmd: &mut Command) -> Result<(), String> {
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    match cmd.status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("`{program}` failed with {status}")),
+        Err(error) => Err(format!("failed to run `{program}`: {error}")),
+    }
+}
+
 pub fn set_environment_settings(
     cmd: &mut Command,
     current_executable_dir: &Path,
@@ -260,11 +631,14 @@ pub fn set_smt_solver_path_setting(cmd: &mut Command, current_executable_dir: &P
         path.into()
     } else if let Some(path) = find_z3_exe(current_executable_dir) {
         path
+    } else if let Some(path) = provision_viper_tools().and_then(|dir| find_z3_exe(&dir)) {
+        path
     } else {
         panic!(
             "Could not find the Z3 executable. \
             Please set the Z3_EXE environment variable, which should contain the path of a \
-            Z3 executable."
+            Z3 executable, or set PRUSTI_DOWNLOAD_VIPER_TOOLS=true to download the Viper \
+            tools, which include Z3."
         );
     };
     cmd.env("PRUSTI_SMT_SOLVER_PATH", z3_exe);
@@ -289,11 +663,14 @@ pub fn set_viper_home_setting(cmd: &mut Command, current_executable_dir: &Path)
         path.into()
     } else if let Some(viper_home) = find_viper_home(current_executable_dir) {
         viper_home
+    } else if let Some(viper_home) = provision_viper_tools().and_then(|dir| find_viper_home(&dir)) {
+        viper_home
     } else {
         panic!(
             "Could not find the Viper home. \
             Please set the VIPER_HOME environment variable, which should contain the path of \
-            the folder that contains all Viper JAR files."
+            the folder that contains all Viper JAR files, or set \
+            PRUSTI_DOWNLOAD_VIPER_TOOLS=true to download the Viper tools."
         );
     };
     cmd.env("PRUSTI_VIPER_HOME", viper_home);
@@ -303,13 +680,62 @@ pub fn set_java_home_setting(cmd: &mut Command, java_home: &Path) {
     cmd.env("PRUSTI_JAVA_HOME", java_home);
 }
 
+/// Returns the error code passed with `--explain CODE` or `--explain=CODE`, if
+/// it looks like a Prusti error code (i.e. starts with a `P`). Other codes are
+/// left to `rustc --explain`.
+pub fn find_prusti_explain_arg(args: &[String]) -> Option<&str> {
+    let code = args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == "--explain" {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            arg.strip_prefix("--explain=")
+        }
+    })?;
+    if code.starts_with('P') || code.starts_with('p') {
+        Some(code)
+    } else {
+        None
+    }
+}
+
+/// Removes the `--no-verify` arguments, which cargo and rustc do not know, and
+/// returns whether there was any, see `config::check_only`.
+pub fn take_no_verify_arg(args: &mut Vec<String>) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != "--no-verify");
+    args.len() != len
+}
+
+/// Prints the extended description of a Prusti error code, like
+/// `rustc --explain` does for compiler errors.
+pub fn explain_error_code(code: &str) -> Result<(), i32> {
+    match ErrorCode::from_code(code) {
+        Some(error_code) => {
+            println!("{}", error_code.explanation());
+            Ok(())
+        }
+        None => {
+            eprintln!("error: {code} is not a valid Prusti error code");
+            Err(1)
+        }
+    }
+}
+
 /// Checks if the current crate has a (transitive) dependency on `prusti-contracts`
 /// and if that should lead to enabling the `prusti` feature when running cargo.
 /// Will panic if there is a transitive dependency but not a direct one; in such a
 /// case it wouldn't be possible to enable the feature.
-pub fn enable_prusti_feature(cargo_path: &str) -> bool {
+///
+/// The packages selected by `cargo_args` (e.g. with `--workspace` or `-p`) are
+/// all checked, with the selected features and target; since cargo unifies the
+/// features of the packages that are built together, a direct dependency of one
+/// of them is enough. Dependencies that are only included with `cfg(prusti)`,
+/// e.g. in `[target.'cfg(prusti)'.dependencies]`, are taken into account.
+pub fn enable_prusti_feature(cargo_path: &str, cargo_args: &[String]) -> bool {
     let out = Command::new(cargo_path)
         .args(["tree", "--prefix", "depth", "-f", " [{f}] {p}"])
+        .args(dependency_graph_args(cargo_args))
+        .env("CARGO_ENCODED_RUSTFLAGS", rustflags_with_prusti_cfg())
         .output()
         .unwrap_or_else(|_| panic!("Failed to run '{cargo_path} tree'"));
     // Expected stdout:
@@ -336,6 +762,60 @@ pub fn enable_prusti_feature(cargo_path: &str) -> bool {
     }
 }
 
+/// The flags of `CARGO_ENCODED_RUSTFLAGS` or `RUSTFLAGS` with `--cfg=prusti`
+/// added, encoded as in `CARGO_ENCODED_RUSTFLAGS`. This is for the cargo
+/// commands that do not run `prusti-rustc`, but should evaluate conditions
+/// like `[target.'cfg(prusti)'.dependencies]` as the verification does.
+pub fn rustflags_with_prusti_cfg() -> String {
+    let mut flags: Vec<String> = match env::var("CARGO_ENCODED_RUSTFLAGS") {
+        Ok(flags) if !flags.is_empty() => flags.split('\x1f').map(String::from).collect(),
+        _ => env::var("RUSTFLAGS")
+            .map(|flags| flags.split_whitespace().map(String::from).collect())
+            .unwrap_or_default(),
+    };
+    flags.push("--cfg=prusti".to_string());
+    flags.join("\x1f")
+}
+
+/// The arguments among `cargo_args` that shape the dependency graph of a cargo
+/// command: the manifest, the packages (e.g. `--workspace`, `-p <spec>`), the
+/// features (e.g. `--features <features>`, `--no-default-features`) and the
+/// target (`--target <triple>`).
+pub fn dependency_graph_args(cargo_args: &[String]) -> Vec<String> {
+    const FLAGS: [&str; 2] = ["--all-features", "--no-default-features"];
+    const OPTIONS: [&str; 7] = [
+        "-p",
+        "--package",
+        "--exclude",
+        "--manifest-path",
+        "-F",
+        "--features",
+        "--target",
+    ];
+    let mut result = vec![];
+    let mut args = cargo_args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--workspace" || arg == "--all" {
+            // `--all` is a deprecated alias of `--workspace`, but means
+            // something else for `cargo tree`.
+            result.push("--workspace".to_string());
+        } else if FLAGS.contains(&arg.as_str()) {
+            result.push(arg.clone());
+        } else if OPTIONS.contains(&arg.as_str()) {
+            result.push(arg.clone());
+            result.extend(args.next().cloned());
+        } else if OPTIONS.iter().any(|option| {
+            arg.strip_prefix(option).map_or(false, |value| {
+                value.starts_with('=') || (option.len() == 2 && !value.is_empty())
+            })
+        }) {
+            // `--package=<spec>` or `-p<spec>`
+            result.push(arg.clone());
+        }
+    }
+    result
+}
+
 fn classify_line(line: &str) -> Option<u32> {
     let mut line = line.split(' ');
     let depth = line.next()?;
@@ -348,3 +828,142 @@ fn classify_line(line: &str) -> Option<u32> {
     }
     depth.parse().ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A fake Java installation with the given `release` file and JVM
+    /// libraries, which is removed when dropped.
+    struct FakeJavaHome(PathBuf);
+
+    impl FakeJavaHome {
+        fn new(release: Option<&str>, libjvm_dirs: &[&str]) -> Self {
+            let java_home =
+                env::temp_dir().join(format!("prusti-java-home-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&java_home).unwrap();
+            if let Some(release) = release {
+                fs::write(java_home.join("release"), release).unwrap();
+            }
+            for dir in libjvm_dirs {
+                let dir = java_home.join(dir);
+                fs::create_dir_all(&dir).unwrap();
+                fs::write(dir.join(EXPECTED_JVM_FILENAME), "").unwrap();
+            }
+            Self(java_home)
+        }
+    }
+
+    impl Drop for FakeJavaHome {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn archives_with_another_checksum_are_rejected_and_removed() {
+        let cache_dir =
+            env::temp_dir().join(format!("prusti-viper-tools-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&cache_dir).unwrap();
+        let source = cache_dir.join("source.zip");
+        fs::write(&source, "not the Viper tools").unwrap();
+        let url = format!("file://{}", source.display());
+        let checksum = format!("{:x}", Sha256::digest("not the Viper tools"));
+        let expected_checksum = "0".repeat(64);
+        let version_dir = cache_dir.join("v1");
+        let error = install_archive(
+            &url,
+            "ViperToolsLinux.zip",
+            &expected_checksum,
+            &cache_dir,
+            &version_dir,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            format!(
+                "the SHA-256 checksum of {url} is {checksum}, but {expected_checksum} was expected"
+            )
+        );
+        // Neither the tools nor the partial download are kept.
+        assert!(!version_dir.exists());
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 1);
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn java_versions_are_parsed() {
+        assert_eq!(java_major_version("1.8.0_292"), Some(8));
+        assert_eq!(java_major_version("11.0.2"), Some(11));
+        assert_eq!(java_major_version("17"), Some(17));
+        assert_eq!(java_major_version("21-ea"), Some(21));
+        assert_eq!(java_major_version("unknown"), None);
+    }
+
+    #[test]
+    fn architectures_are_normalized() {
+        assert_eq!(normalize_arch("amd64"), normalize_arch("x86_64"));
+        assert_eq!(normalize_arch("arm64"), normalize_arch("aarch64"));
+        assert_ne!(normalize_arch("amd64"), normalize_arch("aarch64"));
+    }
+
+    #[test]
+    fn the_server_jvm_is_preferred() {
+        let java_home = FakeJavaHome::new(None, &["lib/client", "lib/server"]);
+        assert_eq!(
+            find_libjvm(&java_home.0),
+            Some(java_home.0.join("lib").join("server"))
+        );
+    }
+
+    #[test]
+    fn usable_java_homes_are_accepted() {
+        let release = format!(
+            "JAVA_VERSION=\"17.0.2\"\nOS_ARCH=\"{}\"\n",
+            env::consts::ARCH
+        );
+        let java_home = FakeJavaHome::new(Some(&release), &["lib/server"]);
+        let runtime = check_java_home(&java_home.0).unwrap();
+        assert_eq!(runtime.java_home, java_home.0);
+        assert_eq!(runtime.libjvm_dir, java_home.0.join("lib").join("server"));
+        // Installations without a `release` file are only checked for the JVM
+        // library.
+        let java_home = FakeJavaHome::new(None, &["lib/server"]);
+        assert!(check_java_home(&java_home.0).is_ok());
+    }
+
+    #[test]
+    fn unusable_java_homes_are_rejected_with_a_reason() {
+        let reason = |java_home: &FakeJavaHome| check_java_home(&java_home.0).err().unwrap();
+        let old = FakeJavaHome::new(Some("JAVA_VERSION=\"1.8.0_292\"\n"), &["lib/server"]);
+        assert_eq!(
+            reason(&old),
+            "it is Java 1.8.0_292, but Java 11 or later is needed"
+        );
+        let foreign_arch = if env::consts::ARCH == "s390x" {
+            "ppc64le"
+        } else {
+            "s390x"
+        };
+        let foreign = FakeJavaHome::new(
+            Some(&format!("OS_ARCH=\"{foreign_arch}\"\n")),
+            &["lib/server"],
+        );
+        assert!(
+            reason(&foreign).starts_with(&format!("it is built for {foreign_arch}")),
+            "{}",
+            reason(&foreign)
+        );
+        let without_jvm = FakeJavaHome::new(Some("JAVA_VERSION=\"17.0.2\"\n"), &[]);
+        assert_eq!(
+            reason(&without_jvm),
+            format!("it contains no {EXPECTED_JVM_FILENAME}")
+        );
+        let missing = without_jvm.0.join("missing");
+        assert_eq!(
+            check_java_home(&missing).err().unwrap(),
+            "the folder does not exist"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Stable codes for the categories of verification errors reported by Prusti.
+//!
+//! Each code has an extended explanation in a `Pxxxx.md` file next to this
+//! module, which is printed by `prusti-rustc --explain Pxxxx`. Codes must never
+//! be reused or renumbered; add new categories at the end.
+
+macro_rules! error_codes {
+    ($($(#[doc = $doc:literal])* $code:ident => $name:ident,)*) => {
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ErrorCode {
+            $($(#[doc = $doc])* $name,)*
+        }
+
+        impl ErrorCode {
+            /// All known error codes, in increasing order.
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$name,)*];
+
+            /// The code as shown to the user, e.g. `"P0009"`.
+            pub fn code(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$name => stringify!($code),)*
+                }
+            }
+
+            /// The name of the error category, e.g. `"PostconditionViolated"`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$name => stringify!($name),)*
+                }
+            }
+
+            /// The extended description of the error, in Markdown.
+            pub fn explanation(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$name => include_str!(concat!(stringify!($code), ".md")),)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// A statement might panic for an unspecified reason.
+    P0001 => Panic,
+    /// A `panic!(..)` might be reachable.
+    P0002 => PanicReachable,
+    /// An `assert!`, `debug_assert!` or `prusti_assert!` might fail.
+    P0003 => AssertionFailed,
+    /// An `unreachable!(..)` might be reachable.
+    P0004 => UnreachableReachable,
+    /// An `unimplemented!(..)` might be reachable.
+    P0005 => UnimplementedReachable,
+    /// A compiler-inserted runtime check (overflow, division by zero) might fail.
+    P0006 => RuntimeCheckFailed,
+    /// A statement might abort.
+    P0007 => Abort,
+    /// The precondition of a call might not hold.
+    P0008 => PreconditionViolated,
+    /// A postcondition might not hold.
+    P0009 => PostconditionViolated,
+    /// A loop invariant might not hold on loop entry.
+    P0010 => LoopInvariantOnEntry,
+    /// A loop invariant might not be preserved by an iteration.
+    P0011 => LoopInvariantAfterIteration,
+    /// The precondition of a pure function call might not hold.
+    P0012 => PureFunctionPrecondition,
+    /// A pledge might not hold.
+    P0013 => PledgeViolated,
+    /// A pure function might panic or diverge.
+    P0014 => PureFunctionPanic,
+    /// A numeric cast might overflow.
+    P0015 => CastOverflow,
+    /// A type invariant might not hold.
+    P0016 => TypeInvariantViolated,
+    /// A trait method implementation does not refine the trait's contract.
+    P0017 => InvalidSpecRefinement,
+    /// An index might be out of bounds.
+    P0018 => IndexOutOfBounds,
+    /// Unsupported code might be reachable.
+    P0019 => UnsupportedFeatureReachable,
+    /// A loop or call might not terminate.
+    P0020 => NonTermination,
+    /// A refutation might not be refuted.
+    P0021 => RefutationFailed,
+    /// A drop handler might be called.
+    P0022 => DropCalled,
+    /// A value might not be fully initialized.
+    P0023 => Uninitialized,
+    /// The verification of a function exceeded its time limit.
+    P0024 => TimedOut,
+    /// A value of a `#[must_consume]` type might be dropped.
+    P0025 => ResourceLeak,
+}
+
+impl ErrorCode {
+    /// Parses a code given by the user. Like `rustc --explain`, the prefix is
+    /// optional and the comparison is case-insensitive, so `P0009`, `p0009` and
+    /// `9` all denote the same code.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_ascii_uppercase();
+        let normalized = if code.starts_with('P') {
+            code
+        } else {
+            format!("P{code:0>4}")
+        };
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|error_code| error_code.code() == normalized)
+    }
+
+    /// The first paragraph of the explanation, as a single line.
+    pub fn summary(self) -> String {
+        self.explanation()
+            .split("\n\n")
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
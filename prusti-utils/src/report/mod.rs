@@ -4,5 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+pub mod error_codes;
 pub mod log;
+pub mod sarif;
 pub mod user;
@@ -0,0 +1,107 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Storage of the SARIF log written with `config::sarif_path`.
+//!
+//! `cargo prusti` verifies several crates in parallel, so the crates cannot
+//! update a single log in place. Instead, the run of each crate is stored in a
+//! separate file in the `<log path>.runs` directory, and the log is assembled
+//! from all stored runs.
+
+use crate::utils::files::write_atomically;
+use serde_json::{json, Value};
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The directory that stores the run of each crate of the log at `log_path`.
+pub fn runs_dir(log_path: &Path) -> PathBuf {
+    let mut dir_name = log_path.file_name().map(OsString::from).unwrap_or_default();
+    dir_name.push(".runs");
+    log_path.with_file_name(dir_name)
+}
+
+/// Stores `run` as the run of `crate_name`, replacing an earlier run of the
+/// same crate, and assembles the log at `log_path` again.
+pub fn write_run(log_path: &Path, crate_name: &str, run: &Value) -> io::Result<()> {
+    let runs_dir = runs_dir(log_path);
+    fs::create_dir_all(&runs_dir)?;
+    write_atomically(
+        &runs_dir.join(format!("{crate_name}.json")),
+        &serde_json::to_string_pretty(run)?,
+    )?;
+    write_log(log_path)
+}
+
+/// Assembles the log at `log_path` from the stored runs, ordered by crate
+/// name. When several crates are verified in parallel, each of them might
+/// miss the runs that are stored while it assembles the log, so `cargo prusti`
+/// assembles it once more after all crates are verified.
+pub fn write_log(log_path: &Path) -> io::Result<()> {
+    let mut run_paths = vec![];
+    for entry in fs::read_dir(runs_dir(log_path))? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |ext| ext == "json") {
+            run_paths.push(path);
+        }
+    }
+    run_paths.sort();
+    let runs: Vec<Value> = run_paths
+        .iter()
+        .filter_map(|path| {
+            fs::read_to_string(path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+        })
+        .collect();
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": runs,
+    });
+    write_atomically(log_path, &serde_json::to_string_pretty(&log)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_log(log_path: &Path) -> Value {
+        serde_json::from_str(&fs::read_to_string(log_path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn log_contains_the_latest_run_of_each_crate() {
+        let dir = std::env::temp_dir().join(format!("prusti-sarif-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("results.sarif");
+        write_run(&log_path, "second", &json!({ "results": [1] })).unwrap();
+        write_run(&log_path, "first", &json!({ "results": [] })).unwrap();
+        write_run(&log_path, "second", &json!({ "results": [2] })).unwrap();
+
+        let log = read_log(&log_path);
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(log["runs"], json!([{ "results": [] }, { "results": [2] }]));
+        assert_eq!(runs_dir(&log_path), dir.join("results.sarif.runs"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn log_skips_unreadable_runs() {
+        let dir = std::env::temp_dir().join(format!("prusti-sarif-{}", uuid::Uuid::new_v4()));
+        let log_path = dir.join("results.sarif");
+        write_run(&log_path, "valid", &json!({ "results": [] })).unwrap();
+        fs::write(runs_dir(&log_path).join("broken.json"), "{").unwrap();
+        write_log(&log_path).unwrap();
+
+        assert_eq!(read_log(&log_path)["runs"], json!([{ "results": [] }]));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,56 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Replaces the content of the file at `path` with `content`. The content is
+/// first written to a uniquely named file in the same directory, which is then
+/// renamed to `path`. Processes that read the file concurrently thus see
+/// either its old or its new content, but never a partial write.
+pub fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let temp_path = temp_path_for(path);
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path).map_err(|error| {
+        fs::remove_file(&temp_path).ok();
+        error
+    })
+}
+
+/// A unique path next to `path`, of the form `.<file name>.<uuid>.tmp`.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let mut file_name = OsString::from(".");
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_atomically_replaces_the_content_without_leaving_temp_files() {
+        let dir = std::env::temp_dir().join(format!("prusti-files-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cache.json");
+        write_atomically(&path, "old").unwrap();
+        write_atomically(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_atomically_fails_without_a_parent_directory() {
+        let dir = std::env::temp_dir().join(format!("prusti-files-{}", uuid::Uuid::new_v4()));
+        assert!(write_atomically(&dir.join("cache.json"), "content").is_err());
+        assert!(!dir.exists());
+    }
+}
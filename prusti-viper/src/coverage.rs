@@ -0,0 +1,196 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Per-crate summary of which functions were verified, see
+//! `config::coverage_report_dir`.
+
+use prusti_interface::error_report::SpanReport;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+/// How far a function was verified. The order of the variants matters: when
+/// a function gets several statuses, the greatest one is kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageStatus {
+    /// The function was encoded and verified without errors.
+    Verified,
+    /// The function was encoded, but its verification reported an error.
+    Failed,
+    /// The verification of the function exceeded its time limit, see
+    /// `prusti::time_limit`.
+    TimedOut,
+    /// The function could not be encoded, e.g. because it uses an unsupported
+    /// feature, so it was skipped.
+    Unsupported,
+    /// The function is `#[trusted]`, so its body was not verified.
+    Trusted,
+    /// The function is a predicate, which is only used in specifications and
+    /// whose body is not verified.
+    PureOnly,
+}
+
+impl CoverageStatus {
+    pub const ALL: [CoverageStatus; 6] = [
+        CoverageStatus::Verified,
+        CoverageStatus::Failed,
+        CoverageStatus::TimedOut,
+        CoverageStatus::Unsupported,
+        CoverageStatus::Trusted,
+        CoverageStatus::PureOnly,
+    ];
+}
+
+impl fmt::Display for CoverageStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CoverageStatus::Verified => "verified",
+            CoverageStatus::Failed => "failed",
+            CoverageStatus::TimedOut => "timed out",
+            CoverageStatus::Unsupported => "unsupported",
+            CoverageStatus::Trusted => "trusted",
+            CoverageStatus::PureOnly => "pure-only",
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CoverageItem {
+    pub item: String,
+    pub span: Option<SpanReport>,
+    pub status: CoverageStatus,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CoverageCount {
+    pub count: usize,
+    pub percentage: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CoverageReport {
+    pub crate_name: String,
+    pub prusti_version: String,
+    pub total: usize,
+    pub summary: BTreeMap<CoverageStatus, CoverageCount>,
+    pub items: Vec<CoverageItem>,
+}
+
+impl CoverageReport {
+    pub fn new(crate_name: String, prusti_version: String, mut items: Vec<CoverageItem>) -> Self {
+        items.sort_by(|a, b| a.item.cmp(&b.item));
+        let total = items.len();
+        let summary = CoverageStatus::ALL
+            .into_iter()
+            .map(|status| {
+                let count = items.iter().filter(|item| item.status == status).count();
+                let percentage = if total == 0 {
+                    0.0
+                } else {
+                    (count * 1000 / total) as f64 / 10.0
+                };
+                (status, CoverageCount { count, percentage })
+            })
+            .collect();
+        CoverageReport {
+            crate_name,
+            prusti_version,
+            total,
+            summary,
+            items,
+        }
+    }
+
+    /// A one-line summary such as
+    /// `"coverage of foo: 4 functions, 3 verified (75%), 1 trusted (25%)"`.
+    pub fn summary_line(&self) -> String {
+        let counts: Vec<_> = self
+            .summary
+            .iter()
+            .filter(|(status, count)| count.count > 0 || **status == CoverageStatus::Verified)
+            .map(|(status, count)| format!("{} {status} ({}%)", count.count, count.percentage))
+            .collect();
+        format!(
+            "coverage of {}: {} functions, {}",
+            self.crate_name,
+            self.total,
+            counts.join(", ")
+        )
+    }
+
+    /// Writes the report as `<crate name>.json` into `dir`.
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.json", self.crate_name));
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(item: &str, status: CoverageStatus) -> CoverageItem {
+        CoverageItem {
+            item: item.to_string(),
+            span: None,
+            status,
+        }
+    }
+
+    fn report() -> CoverageReport {
+        CoverageReport::new(
+            "foo".to_string(),
+            "0.1.0".to_string(),
+            vec![
+                item("foo::c", CoverageStatus::Verified),
+                item("foo::a", CoverageStatus::Trusted),
+                item("foo::b", CoverageStatus::Verified),
+            ],
+        )
+    }
+
+    #[test]
+    fn summary_counts_each_status_with_percentages() {
+        let report = report();
+        assert_eq!(report.total, 3);
+        let verified = &report.summary[&CoverageStatus::Verified];
+        assert_eq!((verified.count, verified.percentage), (2, 66.6));
+        let trusted = &report.summary[&CoverageStatus::Trusted];
+        assert_eq!((trusted.count, trusted.percentage), (1, 33.3));
+        let unsupported = &report.summary[&CoverageStatus::Unsupported];
+        assert_eq!((unsupported.count, unsupported.percentage), (0, 0.0));
+        assert_eq!(report.summary.len(), CoverageStatus::ALL.len());
+    }
+
+    #[test]
+    fn items_are_sorted_by_path() {
+        let items: Vec<_> = report().items.into_iter().map(|item| item.item).collect();
+        assert_eq!(items, ["foo::a", "foo::b", "foo::c"]);
+    }
+
+    #[test]
+    fn summary_line_lists_the_statuses_that_occur() {
+        assert_eq!(
+            report().summary_line(),
+            "coverage of foo: 3 functions, 2 verified (66.6%), 1 trusted (33.3%)"
+        );
+        let empty = CoverageReport::new("bar".to_string(), "0.1.0".to_string(), vec![]);
+        assert_eq!(
+            empty.summary_line(),
+            "coverage of bar: 0 functions, 0 verified (0%)"
+        );
+    }
+
+    #[test]
+    fn report_serializes_statuses_in_snake_case() {
+        let json = serde_json::to_value(report()).unwrap();
+        assert_eq!(json["crate_name"], "foo");
+        assert_eq!(json["items"][0]["status"], "trusted");
+        assert_eq!(json["summary"]["verified"]["count"], 2);
+        assert_eq!(json["summary"]["pure_only"]["count"], 0);
+    }
+}
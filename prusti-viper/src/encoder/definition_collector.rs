@@ -150,7 +150,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> Collector<'p, 'v, 'tcx> {
     }
     /// The purification optimization that is executed after this assumes that
     /// all bodyless methods are present. That is why we are returning all
-    /// methods here.
+    /// methods here. The ones that are not called are deleted by the
+    /// `delete_unused_definitions` optimization.
     fn get_all_methods(&self) -> Vec<vir::BodylessMethod> {
         let mut methods: Vec<_> = self
             .encoder
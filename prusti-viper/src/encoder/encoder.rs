@@ -24,10 +24,11 @@ use super::{
     },
 };
 use crate::{
+    coverage::CoverageStatus,
     encoder::{
         builtin_encoder::{BuiltinEncoder, BuiltinMethodKind},
         errors::{
-            EncodingError, EncodingResult, ErrorManager, SpannedEncodingError,
+            EncodingError, EncodingErrorKind, EncodingResult, ErrorManager, SpannedEncodingError,
             SpannedEncodingResult,
         },
         foldunfold,
@@ -47,12 +48,15 @@ use prusti_common::{
 use prusti_interface::{
     data::ProcedureDefId,
     environment::Environment,
+    semantic_hash,
     specs::{typed, typed::ProcedureSpecificationKind},
+    utils::ItemConfigValue,
     PrustiError,
 };
 use prusti_rustc_interface::{
-    hir::def_id::DefId,
+    hir::def_id::{CrateNum, DefId},
     middle::{mir, ty},
+    span::{fatal_error::FatalErrorMarker, Span},
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::{
@@ -60,7 +64,10 @@ use std::{
     fmt::Debug,
     io::Write,
     ops::AddAssign,
+    panic,
     rc::Rc,
+    sync::Once,
+    time::{Duration, Instant},
 };
 use vir_crate::{
     common::{check_mode::CheckMode, identifier::WithIdentifier},
@@ -97,6 +104,17 @@ pub struct Encoder<'v, 'tcx: 'v> {
     vir_program_before_foldunfold_writer: Option<RefCell<Box<dyn Write>>>,
     vir_program_before_viper_writer: Option<RefCell<Box<dyn Write>>>,
     encoding_errors_counter: RefCell<usize>,
+    /// The outcome of encoding each procedure of the verification task. At
+    /// this stage, `Verified` only means that the encoding succeeded.
+    procedure_coverage: RefCell<FxHashMap<ProcedureDefId, CoverageStatus>>,
+    /// The wall-clock time spent encoding each procedure of the verification
+    /// task, including the items encoded on demand while doing so.
+    procedure_encoding_times: FxHashMap<ProcedureDefId, Duration>,
+    /// The dependencies whose contracts were assumed at call sites.
+    assumed_dependencies: RefCell<FxHashSet<CrateNum>>,
+    /// The `todo!()` and `unimplemented!()` statements that were assumed to
+    /// be unreachable, see `config::assume_unimplemented`.
+    assumed_unimplemented: RefCell<Vec<Span>>,
     name_interner: RefCell<NameInterner>,
     /// Maps locals to the local of their discriminant.
     pub(super) discriminants_state: DiscriminantsState,
@@ -174,6 +192,10 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             snapshot_encoder_state: Default::default(),
             mirror_encoder: RefCell::new(MirrorEncoder::new()),
             encoding_errors_counter: RefCell::new(0),
+            procedure_coverage: RefCell::new(FxHashMap::default()),
+            assumed_dependencies: RefCell::new(FxHashSet::default()),
+            assumed_unimplemented: RefCell::new(Vec::new()),
+            procedure_encoding_times: FxHashMap::default(),
             name_interner: RefCell::new(NameInterner::new()),
             is_encoding_trigger: Cell::new(false),
             specifications_state: SpecificationsState::new(def_spec),
@@ -248,7 +270,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
     }
 
     pub fn get_core_proof_programs(&mut self) -> Vec<prusti_common::vir::program::Program> {
-        if config::counterexample() && config::unsafe_core_proof() {
+        if config::counterexample() {
             self.take_core_proof_programs()
                 .into_iter()
                 .map(|program| {
@@ -273,10 +295,125 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         prusti_error.emit(&self.env.diagnostic);
     }
 
+    /// Whether the procedure is encoded with the core proof instead of the
+    /// fold/unfold heap encoding, according to the `unsafe_core_proof` flag
+    /// given with `prusti::config` for the procedure or, if there is none,
+    /// the global one.
+    pub fn uses_core_proof(&self, def_id: ProcedureDefId) -> bool {
+        match self.env.query.item_config(def_id, "unsafe_core_proof") {
+            Some(ItemConfigValue::Bool(value)) => value,
+            _ => config::unsafe_core_proof(),
+        }
+    }
+
+    /// Like `register_encoding_error`, but an unsupported feature is reported
+    /// as a warning or an error according to the `skip_unsupported_features`
+    /// flag given with `prusti::config` for the procedure, if any.
+    fn register_procedure_encoding_error(
+        &self,
+        def_id: ProcedureDefId,
+        encoding_error: SpannedEncodingError,
+    ) {
+        let is_unsupported = matches!(encoding_error.kind(), EncodingErrorKind::Unsupported(_));
+        let mut prusti_error: PrustiError = encoding_error.into();
+        if is_unsupported {
+            match self
+                .env
+                .query
+                .item_config(def_id, "skip_unsupported_features")
+            {
+                Some(ItemConfigValue::Bool(true)) => prusti_error.set_warning(),
+                Some(ItemConfigValue::Bool(false)) => prusti_error.set_error(),
+                _ => {}
+            }
+        }
+        if prusti_error.is_error() {
+            self.encoding_errors_counter.borrow_mut().add_assign(1);
+        }
+        prusti_error.emit(&self.env.diagnostic);
+    }
+
     pub fn count_encoding_errors(&self) -> usize {
         *self.encoding_errors_counter.borrow()
     }
 
+    /// Record the outcome of encoding or verifying a procedure. If a procedure
+    /// is recorded several times, e.g. once per check mode, the most severe
+    /// status wins.
+    pub fn record_coverage(&self, proc_def_id: ProcedureDefId, status: CoverageStatus) {
+        let mut coverage = self.procedure_coverage.borrow_mut();
+        let entry = coverage.entry(proc_def_id).or_insert(status);
+        *entry = (*entry).max(status);
+    }
+
+    fn record_encoding_time(&mut self, proc_def_id: ProcedureDefId, duration: Duration) {
+        *self
+            .procedure_encoding_times
+            .entry(proc_def_id)
+            .or_default() += duration;
+    }
+
+    /// The time spent encoding each procedure of the verification task.
+    pub fn get_procedure_encoding_times(&self) -> &FxHashMap<ProcedureDefId, Duration> {
+        &self.procedure_encoding_times
+    }
+
+    /// The outcome of encoding a procedure, if it was part of the verification
+    /// task.
+    pub fn get_procedure_coverage(&self, proc_def_id: ProcedureDefId) -> Option<CoverageStatus> {
+        self.procedure_coverage.borrow().get(&proc_def_id).copied()
+    }
+
+    /// Record that the contract of a function, written in the crate
+    /// `krate`, was assumed at a call site.
+    pub(crate) fn record_assumed_dependency(&self, krate: CrateNum) {
+        self.assumed_dependencies.borrow_mut().insert(krate);
+    }
+
+    /// The dependencies whose contracts were assumed at call sites.
+    pub fn get_assumed_dependencies(&self) -> Ref<FxHashSet<CrateNum>> {
+        self.assumed_dependencies.borrow()
+    }
+
+    /// Record that the `todo!()` or `unimplemented!()` statement at `span` was
+    /// assumed to be unreachable.
+    pub(crate) fn record_assumed_unimplemented(&self, span: Span) {
+        self.assumed_unimplemented.borrow_mut().push(span);
+    }
+
+    /// The `todo!()` and `unimplemented!()` statements that were assumed to
+    /// be unreachable.
+    pub fn get_assumed_unimplemented(&self) -> Ref<Vec<Span>> {
+        self.assumed_unimplemented.borrow()
+    }
+
+    /// Whether the body of the pure function `def_id` is replaced by its
+    /// contract, because it belongs to a dependency, see
+    /// `config::assume_dependency_contracts`. Predicates keep their body,
+    /// which is their definition rather than an implementation.
+    pub(crate) fn is_assumed_dependency_function(
+        &self,
+        def_id: DefId,
+        substs: ty::GenericArgsRef<'tcx>,
+    ) -> bool {
+        config::assume_dependency_contracts()
+            && !def_id.is_local()
+            && matches!(
+                self.get_proc_kind(def_id, Some(substs)),
+                ProcedureSpecificationKind::Pure
+            )
+    }
+
+    /// The semantic hash of each of `procedures`, see
+    /// `config::semantic_cache_dir`.
+    pub fn get_semantic_hashes(
+        &self,
+        procedures: &[ProcedureDefId],
+    ) -> FxHashMap<ProcedureDefId, u64> {
+        let def_spec = self.specifications_state.user_typed_specs();
+        semantic_hash::procedure_hashes(self.env, &def_spec, procedures)
+    }
+
     pub(super) fn get_mirror_domain(&self) -> Option<vir::Domain> {
         self.mirror_encoder.borrow().get_domain().cloned()
     }
@@ -626,7 +763,7 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
             let mut method = match proc_encoder.encode() {
                 Ok(result) => result,
                 Err(error) => {
-                    self.register_encoding_error(error);
+                    self.register_procedure_encoding_error(def_id, error);
                     StubProcedureEncoder::new(self, &procedure).encode()
                 }
             };
@@ -719,6 +856,61 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         Ok(expr)
     }
 
+    /// Encodes the target of a constant shared reference to a primitive
+    /// value, such as the reference that MIR uses to read an immutable
+    /// `static` item. Returns `None` if the constant is not such a reference.
+    pub fn encode_const_deref_expr(
+        &self,
+        ty: ty::Ty<'tcx>,
+        value: mir::ConstantKind<'tcx>,
+    ) -> EncodingResult<Option<vir::Expr>> {
+        let ty::TyKind::Ref(_, target_ty, mir::Mutability::Not) = ty.kind() else {
+            return Ok(None);
+        };
+        if !matches!(
+            target_ty.kind(),
+            ty::TyKind::Bool
+                | ty::TyKind::Char
+                | ty::TyKind::Int(_)
+                | ty::TyKind::Uint(_)
+                | ty::TyKind::Float(_)
+        ) {
+            return Ok(None);
+        }
+        let Ok(mir::interpret::Scalar::Ptr(pointer, _)) = self.const_eval_intlike(value) else {
+            return Ok(None);
+        };
+        let tcx = self.env.tcx();
+        let (alloc_id, offset) = pointer.into_parts();
+        let allocation = match tcx.try_get_global_alloc(alloc_id) {
+            Some(mir::interpret::GlobalAlloc::Static(def_id)) => {
+                tcx.eval_static_initializer(def_id).map_err(|_| {
+                    EncodingError::unsupported(format!(
+                        "the value of static item {} could not be evaluated",
+                        tcx.def_path_str(def_id)
+                    ))
+                })?
+            }
+            Some(mir::interpret::GlobalAlloc::Memory(allocation)) => allocation,
+            _ => return Ok(None),
+        };
+        let size = tcx
+            .layout_of(ty::ParamEnv::reveal_all().and(*target_ty))
+            .map_err(|_| {
+                EncodingError::unsupported(format!("unsupported constant type {target_ty:?}"))
+            })?
+            .size;
+        let scalar = allocation
+            .inner()
+            .read_scalar(&tcx, mir::interpret::alloc_range(offset, size), false)
+            .map_err(|_| {
+                EncodingError::unsupported(format!("unsupported constant value: {value:?}"))
+            })?;
+        let target_value =
+            mir::ConstantKind::Val(mir::interpret::ConstValue::Scalar(scalar), *target_ty);
+        Ok(Some(self.encode_const_expr(*target_ty, target_value)?))
+    }
+
     #[tracing::instrument(level = "debug", skip(self), ret)]
     pub fn encode_int_cast(&self, value: u128, ty: ty::Ty<'tcx>) -> vir::Expr {
         let expr = match ty.kind() {
@@ -800,105 +992,20 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
                     def_id: proc_def_id,
                     substs,
                 } => {
-                    let proc_name = self.env.name.get_unique_item_name(proc_def_id);
-                    let proc_def_path = self.env.name.get_item_def_path(proc_def_id);
-                    info!("Encoding: {} ({})", proc_name, proc_def_path);
                     assert!(substs.is_empty());
-
-                    if config::unsafe_core_proof() {
-                        if self.env.query.is_unsafe_function(proc_def_id) {
-                            if let Err(error) =
-                                self.encode_lifetimes_core_proof(proc_def_id, CheckMode::Both)
-                            {
-                                self.register_encoding_error(error);
-                                debug!(
-                                    "Error encoding function: {:?} {}",
-                                    proc_def_id,
-                                    CheckMode::Both
-                                );
-                            }
-                        } else {
-                            if config::verify_core_proof() {
-                                if let Err(error) = self
-                                    .encode_lifetimes_core_proof(proc_def_id, CheckMode::CoreProof)
-                                {
-                                    self.register_encoding_error(error);
-                                    debug!(
-                                        "Error encoding function: {:?} {}",
-                                        proc_def_id,
-                                        CheckMode::CoreProof
-                                    );
-                                }
-                            }
-                            if config::verify_specifications() {
-                                let check_mode = if config::verify_specifications_with_core_proof()
-                                {
-                                    CheckMode::Both
-                                } else {
-                                    CheckMode::Specifications
-                                };
-                                if let Err(error) =
-                                    self.encode_lifetimes_core_proof(proc_def_id, check_mode)
-                                {
-                                    self.register_encoding_error(error);
-                                    debug!(
-                                        "Error encoding function: {:?} {}",
-                                        proc_def_id, check_mode
-                                    );
-                                }
-                            }
-                        }
-                        continue;
-                    }
-
-                    let proc_kind = self.get_proc_kind(proc_def_id, None);
-
-                    if matches!(proc_kind, ProcedureSpecificationKind::Pure) {
-                        // Check that the pure Rust function satisfies the basic
-                        // requirements by trying to encode it as a Viper function,
-                        // which will automatically run the validity checks.
-
-                        // TODO: Make sure that this encoded function does not end up in
-                        // the Viper file because that would be unsound.
-                        let identity_substs = self.env.query.identity_substs(proc_def_id);
-                        if let Err(error) =
-                            self.encode_pure_function_def(proc_def_id, proc_def_id, identity_substs)
-                        {
-                            self.register_encoding_error(error);
-                            debug!("Error encoding function: {:?}", proc_def_id);
-                            // Skip encoding the function as a method.
-                            continue;
-                        }
-                    }
-
-                    match proc_kind {
-                        _ if self.is_trusted(proc_def_id, None) => {
-                            debug!(
-                                "Trusted procedure will not be encoded or verified: {:?}",
-                                proc_def_id
-                            );
-                        }
-                        ProcedureSpecificationKind::Predicate(_) => {
-                            debug!(
-                                "Predicates will not be encoded or verified: {:?}",
-                                proc_def_id
-                            );
-                        }
-                        ProcedureSpecificationKind::Pure | ProcedureSpecificationKind::Impure => {
-                            if let Err(error) = self.encode_procedure(proc_def_id) {
-                                self.register_encoding_error(error);
-                                debug!("Error encoding function: {:?}", proc_def_id);
-                            } else {
-                                match self.finalize_viper_program(proc_name, proc_def_id) {
-                                    Ok(program) => self.programs.push(program),
-                                    Err(error) => {
-                                        self.register_encoding_error(error);
-                                        debug!("Error finalizing program: {:?}", proc_def_id);
-                                    }
-                                }
-                            }
-                        }
+                    let start = Instant::now();
+                    // A panic while encoding a procedure is reported as an
+                    // error of that procedure, so that the rest of the crate
+                    // still gets verified.
+                    if let Err(message) = catch_panic(|| self.encode_procedure_task(proc_def_id)) {
+                        let proc_def_path = self.env.name.get_item_def_path(proc_def_id);
+                        self.register_encoding_error(SpannedEncodingError::internal(
+                            format!("the encoding of `{proc_def_path}` panicked: {message}"),
+                            self.env.query.get_def_span(proc_def_id),
+                        ));
+                        self.record_coverage(proc_def_id, CoverageStatus::Unsupported);
                     }
+                    self.record_encoding_time(proc_def_id, start.elapsed());
                 }
                 EncodingTask::Type { ty } => {
                     if config::unsafe_core_proof()
@@ -917,6 +1024,113 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         }
     }
 
+    /// Encode a procedure of the verification task, recording whether it
+    /// could be encoded.
+    fn encode_procedure_task(&mut self, proc_def_id: ProcedureDefId) {
+        let proc_name = self.env.name.get_unique_item_name(proc_def_id);
+        let proc_def_path = self.env.name.get_item_def_path(proc_def_id);
+        info!("Encoding: {} ({})", proc_name, proc_def_path);
+
+        if self.uses_core_proof(proc_def_id) {
+            if self.env.query.is_unsafe_function(proc_def_id) {
+                if let Err(error) = self.encode_lifetimes_core_proof(proc_def_id, CheckMode::Both) {
+                    self.register_encoding_error(error);
+                    self.record_coverage(proc_def_id, CoverageStatus::Unsupported);
+                    debug!(
+                        "Error encoding function: {:?} {}",
+                        proc_def_id,
+                        CheckMode::Both
+                    );
+                }
+            } else {
+                if config::verify_core_proof() {
+                    if let Err(error) =
+                        self.encode_lifetimes_core_proof(proc_def_id, CheckMode::CoreProof)
+                    {
+                        self.register_encoding_error(error);
+                        self.record_coverage(proc_def_id, CoverageStatus::Unsupported);
+                        debug!(
+                            "Error encoding function: {:?} {}",
+                            proc_def_id,
+                            CheckMode::CoreProof
+                        );
+                    }
+                }
+                if config::verify_specifications() {
+                    let check_mode = if config::verify_specifications_with_core_proof() {
+                        CheckMode::Both
+                    } else {
+                        CheckMode::Specifications
+                    };
+                    if let Err(error) = self.encode_lifetimes_core_proof(proc_def_id, check_mode) {
+                        self.register_encoding_error(error);
+                        self.record_coverage(proc_def_id, CoverageStatus::Unsupported);
+                        debug!("Error encoding function: {:?} {}", proc_def_id, check_mode);
+                    }
+                }
+            }
+            self.record_coverage(proc_def_id, CoverageStatus::Verified);
+            return;
+        }
+
+        let proc_kind = self.get_proc_kind(proc_def_id, None);
+
+        if matches!(proc_kind, ProcedureSpecificationKind::Pure) {
+            // Check that the pure Rust function satisfies the basic
+            // requirements by trying to encode it as a Viper function,
+            // which will automatically run the validity checks.
+
+            // TODO: Make sure that this encoded function does not end up in
+            // the Viper file because that would be unsound.
+            let identity_substs = self.env.query.identity_substs(proc_def_id);
+            if let Err(error) =
+                self.encode_pure_function_def(proc_def_id, proc_def_id, identity_substs)
+            {
+                self.register_encoding_error(error);
+                self.record_coverage(proc_def_id, CoverageStatus::Unsupported);
+                debug!("Error encoding function: {:?}", proc_def_id);
+                // Skip encoding the function as a method.
+                return;
+            }
+        }
+
+        match proc_kind {
+            _ if self.is_trusted(proc_def_id, None) => {
+                debug!(
+                    "Trusted procedure will not be encoded or verified: {:?}",
+                    proc_def_id
+                );
+                self.record_coverage(proc_def_id, CoverageStatus::Trusted);
+            }
+            ProcedureSpecificationKind::Predicate(_) => {
+                debug!(
+                    "Predicates will not be encoded or verified: {:?}",
+                    proc_def_id
+                );
+                self.record_coverage(proc_def_id, CoverageStatus::PureOnly);
+            }
+            ProcedureSpecificationKind::Pure | ProcedureSpecificationKind::Impure => {
+                if let Err(error) = self.encode_procedure(proc_def_id) {
+                    self.register_encoding_error(error);
+                    self.record_coverage(proc_def_id, CoverageStatus::Unsupported);
+                    debug!("Error encoding function: {:?}", proc_def_id);
+                } else {
+                    match self.finalize_viper_program(proc_name, proc_def_id) {
+                        Ok(program) => {
+                            self.programs.push(program);
+                            self.record_coverage(proc_def_id, CoverageStatus::Verified);
+                        }
+                        Err(error) => {
+                            self.register_encoding_error(error);
+                            self.record_coverage(proc_def_id, CoverageStatus::Unsupported);
+                            debug!("Error finalizing program: {:?}", proc_def_id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     pub fn intern_viper_identifier<S: AsRef<str> + Debug>(
         &self,
         full_name: S,
@@ -944,3 +1158,85 @@ impl<'v, 'tcx> Encoder<'v, 'tcx> {
         self.encode_value_expr(field, ty)
     }
 }
+
+thread_local! {
+    /// Whether `catch_panic` is running on this thread.
+    static CATCHING_PANICS: Cell<bool> = Cell::new(false);
+    /// The location of the last panic caught by `catch_panic` on this thread.
+    static CAUGHT_PANIC_LOCATION: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Installs, once, a panic hook that records the location of the panics
+/// caught by `catch_panic` and delegates all other panics to the previous
+/// hook.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CATCHING_PANICS.try_with(Cell::get).unwrap_or(false) {
+                CAUGHT_PANIC_LOCATION.with(|location| {
+                    *location.borrow_mut() = info.location().map(|location| location.to_string());
+                });
+            } else {
+                previous_hook(info);
+            }
+        }));
+    });
+}
+
+/// Runs `f`, turning a panic into an error message that contains the location
+/// of the panic. A caught panic is not reported as an internal compiler error,
+/// while panics of other threads still are. Fatal errors of the compiler are
+/// not caught.
+fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    install_panic_hook();
+    let was_catching = CATCHING_PANICS.with(|catching| catching.replace(true));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+    CATCHING_PANICS.with(|catching| catching.set(was_catching));
+    let location = CAUGHT_PANIC_LOCATION.with(|location| location.borrow_mut().take());
+    result.map_err(|payload| {
+        if payload.is::<FatalErrorMarker>() {
+            panic::resume_unwind(payload);
+        }
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        match location {
+            Some(location) => format!("{message} (at {location})"),
+            None => message,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_are_passed_through() {
+        assert_eq!(catch_panic(|| 42), Ok(42));
+    }
+
+    #[test]
+    fn panics_become_errors_with_their_location() {
+        let message = catch_panic::<()>(|| panic!("unsupported feature")).unwrap_err();
+        assert!(message.starts_with("unsupported feature (at "), "{message}");
+        assert!(message.contains(file!()), "{message}");
+        let message = catch_panic::<()>(|| panic!("{} is unsupported", "foo")).unwrap_err();
+        assert!(message.starts_with("foo is unsupported (at "), "{message}");
+    }
+
+    #[test]
+    fn nested_panics_are_caught_by_the_innermost_call() {
+        let result = catch_panic::<()>(|| {
+            let inner = catch_panic::<()>(|| panic!("inner"));
+            assert!(inner.unwrap_err().starts_with("inner (at "));
+            panic!("outer")
+        });
+        let message = result.unwrap_err();
+        assert!(message.starts_with("outer (at "), "{message}");
+    }
+}
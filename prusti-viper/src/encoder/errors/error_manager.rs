@@ -8,6 +8,7 @@ use std::fmt::Debug;
 
 use super::PositionManager;
 use log::debug;
+use prusti_common::report::error_codes::ErrorCode;
 use prusti_interface::{data::ProcedureDefId, PrustiError};
 use prusti_rustc_interface::{errors::MultiSpan, span::source_map::SourceMap};
 use rustc_hash::FxHashMap;
@@ -106,6 +107,8 @@ pub enum ErrorCtxt {
     SliceRangeBoundsCheckAssert(String),
     /// A Viper `assert false` that encodes an `abort` Rust terminator
     AbortTerminator,
+    /// A Viper `assert false` that encodes a drop of a value of a `prusti::must_consume` type
+    ResourceLeak,
     /// A Viper `assert false` that encodes an `unreachable` Rust terminator
     UnreachableTerminator,
     /// An error that should never happen
@@ -320,6 +323,29 @@ impl<'tcx> ErrorManager<'tcx> {
             res
         });
 
+        // Carbon reports the features that it does not support and failures
+        // of Boogie as internal errors, which have no error context.
+        if ver_error.full_id.starts_with("internal:") {
+            let error_span = opt_error_span
+                .or(opt_cause_span)
+                .cloned()
+                .unwrap_or_else(MultiSpan::new);
+            return if ver_error.full_id == "internal:feature.unsupported" {
+                PrustiError::unsupported(
+                    format!(
+                        "the verification backend does not support this program: {}",
+                        ver_error.message
+                    ),
+                    error_span,
+                )
+            } else {
+                PrustiError::internal(
+                    format!("the verification backend failed: {}", ver_error.message),
+                    error_span,
+                )
+            };
+        }
+
         if let Some(error_ctxt) = opt_error_ctxts {
             debug_assert!(opt_error_span.is_some());
             let error_span = opt_error_span.cloned().unwrap_or_else(MultiSpan::new);
@@ -369,37 +395,50 @@ impl<'tcx> ErrorManager<'tcx> {
         match (ver_error.full_id.as_str(), error_ctxt) {
             ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::Generic)) => {
                 PrustiError::verification("statement might panic", error_span)
+                    .set_code(ErrorCode::Panic)
                     .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::Panic)) => {
                 PrustiError::verification("panic!(..) statement might be reachable", error_span)
+                    .set_code(ErrorCode::PanicReachable)
                     .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::Assert)) |
             ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::DebugAssert)) => {
                     PrustiError::verification("the asserted expression might not hold", error_span)
+                    .set_code(ErrorCode::AssertionFailed)
                     .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::Unreachable)) => {
                 PrustiError::verification("unreachable!(..) statement might be reachable", error_span)
+                    .set_code(ErrorCode::UnreachableReachable)
                     .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::Panic(PanicCause::Unimplemented)) => {
                 PrustiError::verification("unimplemented!(..) statement might be reachable", error_span)
+                    .set_code(ErrorCode::UnimplementedReachable)
                     .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::AssertTerminator(ref message)) => {
                 PrustiError::verification(format!("assertion might fail with \"{message}\""), error_span)
+                    .set_code(ErrorCode::RuntimeCheckFailed)
                     .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::AbortTerminator) => {
                 PrustiError::verification("statement might abort", error_span)
+                    .set_code(ErrorCode::Abort)
+                    .set_failing_assertion(opt_cause_span)
+            }
+
+            ("assert.failed:assertion.false", ErrorCtxt::ResourceLeak) => {
+                PrustiError::verification("resource might be leaked", error_span)
+                    .set_code(ErrorCode::ResourceLeak)
                     .set_failing_assertion(opt_cause_span)
             }
 
@@ -413,6 +452,7 @@ impl<'tcx> ErrorManager<'tcx> {
 
             ("assert.failed:assertion.false", ErrorCtxt::ExhaleMethodPrecondition) => {
                 PrustiError::verification("precondition might not hold.", error_span)
+                    .set_code(ErrorCode::PreconditionViolated)
                     .set_failing_assertion(opt_cause_span)
             }
 
@@ -420,16 +460,19 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::verification(
                     "implicit type invariant expected by the function call might not hold.",
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::PreconditionViolated)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::ExhaleMethodPostcondition) => {
                 PrustiError::verification("postcondition might not hold.", error_span)
+                    .set_code(ErrorCode::PostconditionViolated)
                     .push_primary_span(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::ExhaleLoopInvariantOnEntry) => {
                 PrustiError::verification("loop invariant might not hold in the first loop iteration.", error_span)
+                    .set_code(ErrorCode::LoopInvariantOnEntry)
                     .push_primary_span(opt_cause_span)
             }
 
@@ -437,11 +480,13 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::verification(
                     "implicit type invariant of a variable might not hold on loop entry.",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::LoopInvariantOnEntry)
+                .push_primary_span(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::AssertLoopInvariantOnEntry) => {
                 PrustiError::verification("loop invariant might not hold in the first loop iteration.", error_span)
+                    .set_code(ErrorCode::LoopInvariantOnEntry)
                     .push_primary_span(opt_cause_span)
             }
 
@@ -449,35 +494,40 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::verification(
                     "loop invariant might not hold after a loop iteration that preserves the loop condition.",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::LoopInvariantAfterIteration)
+                .push_primary_span(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::AssertLoopInvariantAfterIteration) => {
                 PrustiError::verification(
                     "loop invariant might not hold after a loop iteration that preserves the loop condition.",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::LoopInvariantAfterIteration)
+                .push_primary_span(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::DropCall) => {
                 PrustiError::verification(
                     "the drop handler was called.",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::DropCalled)
+                .push_primary_span(opt_cause_span)
             }
 
             ("application.precondition:assertion.false", ErrorCtxt::PureFunctionCall) => {
                 PrustiError::verification(
                     "precondition of pure function call might not hold.",
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::PureFunctionPrecondition)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("package.failed:assertion.false", ErrorCtxt::PackageMagicWandForPostcondition) => {
                 PrustiError::verification(
                     "pledge in the postcondition might not hold.",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::PledgeViolated)
+                .push_primary_span(opt_cause_span)
             }
 
             (
@@ -487,7 +537,8 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::verification(
                     "diverging function call in pure function might be reachable.",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::PureFunctionPanic)
+                .push_primary_span(opt_cause_span)
             }
 
             (
@@ -495,6 +546,7 @@ impl<'tcx> ErrorManager<'tcx> {
                 ErrorCtxt::PanicInPureFunction(PanicCause::Generic),
             ) => {
                 PrustiError::disabled_verification("statement in pure function might panic", error_span)
+                    .set_code(ErrorCode::PureFunctionPanic)
                     .push_primary_span(opt_cause_span)
             }
 
@@ -505,7 +557,8 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::disabled_verification(
                     "panic!(..) statement in pure function might panic",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::PureFunctionPanic)
+                .push_primary_span(opt_cause_span)
             }
 
             (
@@ -513,6 +566,7 @@ impl<'tcx> ErrorManager<'tcx> {
                 ErrorCtxt::PanicInPureFunction(PanicCause::Assert),
             ) => {
                 PrustiError::disabled_verification("asserted expression might not hold", error_span)
+                    .set_code(ErrorCode::PureFunctionPanic)
                     .set_failing_assertion(opt_cause_span)
             }
 
@@ -523,7 +577,8 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::disabled_verification(
                     "unreachable!(..) statement in pure function might be reachable",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::PureFunctionPanic)
+                .push_primary_span(opt_cause_span)
             }
 
             (
@@ -533,7 +588,8 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::disabled_verification(
                     "unimplemented!(..) statement in pure function might be reachable",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::PureFunctionPanic)
+                .push_primary_span(opt_cause_span)
             }
 
             ("postcondition.violated:assertion.false", ErrorCtxt::PureFunctionDefinition) |
@@ -541,7 +597,8 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::disabled_verification(
                     "postcondition of pure function definition might not hold",
                     error_span
-                ).push_primary_span(opt_cause_span)
+                ).set_code(ErrorCode::PostconditionViolated)
+                .push_primary_span(opt_cause_span)
             }
 
             (
@@ -551,23 +608,27 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::disabled_verification(
                     format!("assertion might fail with \"{message}\""),
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::PureFunctionPanic)
+                .set_failing_assertion(opt_cause_span)
             },
 
             ("application.precondition:assertion.false", ErrorCtxt::TypeCast) => {
                 PrustiError::verification(
                     "value might not fit into the target type.",
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::CastOverflow)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("apply.failed:assertion.false", ErrorCtxt::ApplyMagicWandOnExpiry) => {
                 PrustiError::verification("obligation might not hold on borrow expiry", error_span)
+                    .set_code(ErrorCode::PledgeViolated)
                     .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::AssertMethodPostcondition) => {
                 PrustiError::verification("postcondition might not hold.".to_string(), error_span)
+                    .set_code(ErrorCode::PostconditionViolated)
                     .push_primary_span(opt_cause_span)
             }
 
@@ -578,7 +639,8 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::verification(
                     "type invariants might not hold at the end of the method.".to_string(),
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::TypeInvariantViolated)
+                .set_failing_assertion(opt_cause_span)
             },
 
             ("fold.failed:assertion.false", ErrorCtxt::PackageMagicWandForPostcondition) |
@@ -586,32 +648,37 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::verification(
                     "implicit type invariants might not hold at the end of the method.".to_string(),
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::TypeInvariantViolated)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("fold.failed:assertion.false", ErrorCtxt::CopyPlace) => {
                 PrustiError::verification(
                     "the copied value may not be fully initialized.".to_string(),
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::Uninitialized)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("unfold.failed:insufficient.permission", ErrorCtxt::UnfoldUnionVariant) => {
                 PrustiError::verification(
                     "failed to unpack the capability of union's field.".to_string(),
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::Uninitialized)
+                .set_failing_assertion(opt_cause_span)
                 .set_help("check that the field was initialized.")
                 .add_note("Prusti does not support yet reinterpreting memory of Rust unions' fields and allow reading only the field that was previously initialized.", None)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::AssertMethodPreconditionWeakening) => {
                 PrustiError::verification("the method's precondition may not be a valid weakening of the trait's precondition.".to_string(), error_span)
+                    .set_code(ErrorCode::InvalidSpecRefinement)
                     .set_help("The trait's precondition should imply the implemented method's precondition.")
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::AssertMethodPostconditionStrengthening) => {
                 PrustiError::verification("the method's postcondition may not be a valid strengthening of the trait's postcondition.".to_string(), error_span)
+                    .set_code(ErrorCode::InvalidSpecRefinement)
                     .set_help("The implemented method's postcondition should imply the trait's postcondition.")
             }
 
@@ -620,7 +687,8 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::verification(
                     "the array or slice index may be out of bounds".to_string(),
                     error_span,
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::IndexOutOfBounds)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::SliceRangeBoundsCheckAssert(s)) |
@@ -628,89 +696,96 @@ impl<'tcx> ErrorManager<'tcx> {
                 PrustiError::verification(
                     s,
                     error_span,
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::IndexOutOfBounds)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::Unsupported(ref reason)) => {
                 PrustiError::unsupported(
                     format!("an unsupported Rust feature might be reachable: {reason}."),
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::UnsupportedFeatureReachable)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:seq.index.length", ErrorCtxt::Panic(PanicCause::Assert)) => {
                 PrustiError::verification(
                     "the sequence index may be out of bounds".to_string(),
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::IndexOutOfBounds)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:seq.index.negative", ErrorCtxt::Panic(PanicCause::Assert)) => {
                 PrustiError::verification(
                     "the sequence index may be negative".to_string(),
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::IndexOutOfBounds)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("inhale.failed:map.key.contains", _) => {
                 PrustiError::verification(
                     "the key might not be in the map".to_string(),
                     error_span
-                ).set_failing_assertion(opt_cause_span)
+                ).set_code(ErrorCode::IndexOutOfBounds)
+                .set_failing_assertion(opt_cause_span)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::UnexpectedReachableLoop) => {
                 PrustiError::verification(
                     "this loop might not terminate".to_string(),
                     error_span
-                ).set_help("Consider attaching a loop variant at the begin of the loop with the `body_variant!` macro.\nAlternatively, remove the `#[terminates] attribute of this function, in case this is not within a ghost block.")
+                ).set_code(ErrorCode::NonTermination)
+                .set_help("Consider attaching a loop variant at the begin of the loop with the `body_variant!` macro.\nAlternatively, remove the `#[terminates] attribute of this function, in case this is not within a ghost block.")
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::UnexpectedReachableCall) => {
                 PrustiError::verification(
                     "this function call might not terminate".to_string(),
                     error_span
-                ).set_help("Consider marking the called function with `#[terminates]` or making it `#[pure]`\nAlternatively, remove the `#[terminates] attribute of this function.")
+                ).set_code(ErrorCode::NonTermination)
+                .set_help("Consider marking the called function with `#[terminates]` or making it `#[pure]`\nAlternatively, remove the `#[terminates] attribute of this function.")
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::CallTerminationMeasureLower) => {
                 PrustiError::verification(
                     "the termination measure of this call is not necessarily lower".to_string(),
                     error_span
-                )
+                ).set_code(ErrorCode::NonTermination)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::CallTerminationMeasureNonNegative) => {
                 PrustiError::verification(
                     "the termination measure of this call might become negative".to_string(),
                     error_span
-                )
+                ).set_code(ErrorCode::NonTermination)
             }
 
             ("assert.failed:assertion.false", ErrorCtxt::LoopVariantOnEntry) => {
                 PrustiError::verification(
                     "The loop variant might not hold on entry (is lower or equal to zero)".to_string(),
                     error_span
-                )
+                ).set_code(ErrorCode::NonTermination)
             }
             ("assert.failed:assertion.false", ErrorCtxt::LoopVariantNonDecreased) => {
                 PrustiError::verification(
                     "The loop variant might not have decreased".to_string(),
                     error_span
-                )
+                ).set_code(ErrorCode::NonTermination)
             }
             ("assert.failed:assertion.false", ErrorCtxt::LoopVariantAfterIteration) => {
                 PrustiError::verification(
                     "The loop variant might go below zero while the loop continues".to_string(),
                     error_span
-                )
+                ).set_code(ErrorCode::NonTermination)
             }
 
             ("refute.failed:refutation.true", ErrorCtxt::Panic(PanicCause::Refute)) => {
                 PrustiError::verification(
                     "the refuted expression holds in all cases or could not be reached",
                     error_span,
-                )
+                ).set_code(ErrorCode::RefutationFailed)
             }
 
             (full_err_id, ErrorCtxt::Unexpected) => {
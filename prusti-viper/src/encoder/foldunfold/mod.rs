@@ -18,6 +18,7 @@ use prusti_rustc_interface::middle::mir;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::{self, fmt, fmt::Write, ops::Deref};
 use vir_crate::{
+    common::symbol::Symbol,
     polymorphic as vir,
     polymorphic::{
         borrows::Borrow, CfgBlockIndex, CfgReplacer, CheckNoOpAction, FallibleExprFolder,
@@ -205,7 +206,7 @@ pub fn add_fold_unfold<'p, 'v: 'p, 'tcx: 'v>(
     // Collect all old expressions used in the CFG
     let old_exprs = {
         struct OldExprCollector {
-            old_exprs: FxHashMap<String, Vec<vir::Expr>>,
+            old_exprs: FxHashMap<Symbol, Vec<vir::Expr>>,
         }
         impl vir::ExprWalker for OldExprCollector {
             #[tracing::instrument(level = "trace", skip(self))]
@@ -215,10 +216,7 @@ pub fn add_fold_unfold<'p, 'v: 'p, 'tcx: 'v>(
                     label, box base, ..
                 }: &vir::LabelledOld,
             ) {
-                self.old_exprs
-                    .entry(label.to_string())
-                    .or_default()
-                    .push(base.clone());
+                self.old_exprs.entry(*label).or_default().push(base.clone());
                 // Recurse, in case old expressions are nested
                 self.walk(base);
             }
@@ -243,23 +241,34 @@ pub fn add_fold_unfold<'p, 'v: 'p, 'tcx: 'v>(
         old_expr_collector.old_exprs
     };
     let initial_pctxt = PathCtxt::new(cfg_vars, &predicates, &old_exprs);
-    FoldUnfold::new(
+    let mut foldunfold = FoldUnfold::new(
         encoder,
         initial_pctxt,
         &cfg,
         borrow_locations,
         cfg_map,
         method_pos,
-    )
-    .replace_cfg(&cfg)
+    );
+    let new_cfg = foldunfold.replace_cfg(&cfg)?;
+    if config::dump_foldunfold_graphviz() {
+        foldunfold.dump_graphviz(&new_cfg);
+    }
+    Ok(new_cfg)
 }
 
 #[derive(Clone)]
 struct FoldUnfold<'p, 'v: 'p, 'tcx: 'v> {
     encoder: &'p Encoder<'v, 'tcx>,
     initial_pctxt: PathCtxt<'p>,
-    pctxt_at_label: FxHashMap<String, PathCtxt<'p>>,
+    pctxt_at_label: FxHashMap<Symbol, PathCtxt<'p>>,
     dump_debug_info: bool,
+    /// Whether to record the merged states of the joins, see
+    /// `config::dump_foldunfold_graphviz`.
+    dump_joins: bool,
+    /// The merged state computed by the last call of `prepend_join`.
+    last_join_state: Vec<String>,
+    /// The merged state of the join that each pre-join block leads to.
+    join_states: FxHashMap<usize, Vec<String>>,
     /// Used for debugging the dump
     foldunfold_state_filter: String,
     /// Generate additional assertions to check that the state of the fold-unfold algorithm
@@ -286,6 +295,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> FoldUnfold<'p, 'v, 'tcx> {
             initial_pctxt,
             pctxt_at_label: FxHashMap::default(),
             dump_debug_info: config::dump_debug_info_during_fold(),
+            dump_joins: config::dump_foldunfold_graphviz(),
+            last_join_state: Vec::new(),
+            join_states: FxHashMap::default(),
             check_foldunfold_state: config::check_foldunfold_state(),
             foldunfold_state_filter: config::foldunfold_state_filter(),
             cfg,
@@ -295,6 +307,28 @@ impl<'p, 'v: 'p, 'tcx: 'v> FoldUnfold<'p, 'v, 'tcx> {
         }
     }
 
+    /// Writes the fold-unfold decisions taken at the joins of `new_cfg` and
+    /// its reborrowing DAGs as DOT files.
+    fn dump_graphviz(&self, new_cfg: &vir::CfgMethod) {
+        let source_path = self.encoder.env().name.source_path();
+        let source_filename = source_path.file_name().unwrap().to_str().unwrap();
+        let method_name = new_cfg.name();
+        report::log::report_with_writer(
+            "graphviz_foldunfold_joins",
+            format!("{source_filename}.{method_name}.dot"),
+            |writer| {
+                new_cfg.to_graphviz_with_extra(writer, |bb_index| {
+                    self.join_states.get(&bb_index).cloned().unwrap_or_default()
+                })
+            },
+        );
+        report::log::report_with_writer(
+            "graphviz_reborrowing_dags",
+            format!("{source_filename}.{method_name}.dot"),
+            |writer| new_cfg.reborrowing_dags_to_graphviz(writer),
+        );
+    }
+
     fn replace_expr(
         &self,
         expr: &vir::Expr,
@@ -541,7 +575,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
             }) => {
                 if let vir::Expr::MagicWand(vir::MagicWand { box ref left, .. }) = magic_wand {
                     // TODO: This should be done also for magic wand expressions inside inhale/exhale.
-                    let label = "lhs".to_string();
+                    let label = Symbol::from("lhs");
                     let mut labelled_pctxt = pctxt.clone();
                     let labelled_state = labelled_pctxt.mut_state();
                     labelled_state.remove_all();
@@ -555,9 +589,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
                     {
                         labelled_state.insert_acc(argument.clone(), *permission)?;
                     }
-                    labelled_state.replace_places(|place| place.old(&label));
-                    self.pctxt_at_label
-                        .insert(label.to_string(), labelled_pctxt);
+                    labelled_state.replace_places(|place| place.old(label));
+                    self.pctxt_at_label.insert(label, labelled_pctxt);
                 }
             }
 
@@ -688,7 +721,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
                         &mut package_pctxt,
                         curr_block_index,
                         new_cfg,
-                        Some(label),
+                        Some(label.as_str()),
                     )?);
                     if config::dump_debug_info() {
                         //package_stmts.push(
@@ -704,7 +737,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
                                 left.clone(),
                                 right.clone(),
                                 package_stmts.clone(),
-                                label.clone(),
+                                *label,
                                 variables.clone(),
                                 *position,
                             ),
@@ -716,7 +749,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
                     left.clone(),
                     right.clone(),
                     package_stmts,
-                    label.clone(),
+                    *label,
                     variables.clone(),
                     *position,
                 )
@@ -974,8 +1007,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
             let mut labelled_pctxt = pctxt.clone();
             let labelled_state = labelled_pctxt.mut_state();
             labelled_state.replace_places(|place| place.old(label));
-            self.pctxt_at_label
-                .insert(label.to_string(), labelled_pctxt);
+            self.pctxt_at_label.insert(*label, labelled_pctxt);
         }
 
         // Delete lhs state
@@ -1081,7 +1113,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
         &mut self,
         bcs: Vec<&PathCtxt<'p>>,
     ) -> Result<(Vec<ActionVec>, PathCtxt<'p>), Self::Error> {
-        prepend_join(bcs)
+        let (actions, pctxt) = prepend_join(bcs)?;
+        if self.dump_joins {
+            self.last_join_state = vec![
+                format!("Merged acc:\n{}", pctxt.state().display_acc()),
+                format!("Merged pred:\n{}", pctxt.state().display_pred()),
+            ];
+        }
+        Ok((actions, pctxt))
     }
 
     /// Convert actions to statements and log them.
@@ -1091,6 +1130,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> vir::CfgReplacer<PathCtxt<'p>, ActionVec> for FoldUnf
         block_index: CfgBlockIndex,
         actions: ActionVec,
     ) -> Result<Vec<vir::Stmt>, Self::Error> {
+        if self.dump_joins {
+            self.join_states
+                .insert(block_index.index(), self.last_join_state.clone());
+        }
         let mut stmts = Vec::new();
         for action in actions.0 {
             stmts.push(action.to_stmt());
@@ -1145,7 +1188,7 @@ pub(super) fn prepend_join<'p>(
 
 struct ExprReplacer<'b, 'a: 'b> {
     curr_pctxt: PathCtxt<'a>,
-    pctxt_at_label: &'b FxHashMap<String, PathCtxt<'a>>,
+    pctxt_at_label: &'b FxHashMap<Symbol, PathCtxt<'a>>,
     lhs_pctxt: Option<PathCtxt<'a>>,
     wait_old_expr: bool,
 }
@@ -1153,7 +1196,7 @@ struct ExprReplacer<'b, 'a: 'b> {
 impl<'b, 'a: 'b> ExprReplacer<'b, 'a> {
     pub fn new(
         curr_pctxt: PathCtxt<'a>,
-        pctxt_at_label: &'b FxHashMap<String, PathCtxt<'a>>,
+        pctxt_at_label: &'b FxHashMap<Symbol, PathCtxt<'a>>,
         wait_old_expr: bool,
     ) -> Self {
         ExprReplacer {
@@ -1387,14 +1430,14 @@ impl<'b, 'a: 'b> FallibleExprFolder for ExprReplacer<'b, 'a> {
         } else {
             self.pctxt_at_label
                 .get(&label)
-                .map_or_else(|| Err(FoldUnfoldError::MissingLabel(label.clone())), Ok)?
+                .map_or_else(|| Err(FoldUnfoldError::MissingLabel(label.to_string())), Ok)?
                 .clone()
         };
 
         // Replace old[label] with curr
         tmp_curr_pctxt.mut_state().replace_places(|place| {
             place.map_labels(|opt_label| {
-                if opt_label == label.clone() {
+                if opt_label == label {
                     None
                 } else {
                     Some(opt_label)
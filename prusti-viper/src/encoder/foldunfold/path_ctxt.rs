@@ -13,7 +13,10 @@ use log::{debug, trace};
 use prusti_common::utils::to_string::ToString;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::iter::FromIterator;
-use vir_crate::polymorphic::{self as vir, PermAmount};
+use vir_crate::{
+    common::symbol::Symbol,
+    polymorphic::{self as vir, PermAmount},
+};
 
 /// The fold-unfold context of a CFG path
 #[derive(Debug, Clone)]
@@ -22,7 +25,7 @@ pub struct PathCtxt<'a> {
     /// The definition of the predicates
     predicates: &'a Predicates,
     /// All usages of old expressions to consider
-    old_exprs: &'a FxHashMap<String, Vec<vir::Expr>>,
+    old_exprs: &'a FxHashMap<Symbol, Vec<vir::Expr>>,
     /// A log of some of the relevant actions that lead to this fold-unfold context
     log: EventLog,
 }
@@ -31,7 +34,7 @@ impl<'a> PathCtxt<'a> {
     pub fn new(
         local_vars: Vec<vir::LocalVar>,
         predicates: &'a Predicates,
-        old_exprs: &'a FxHashMap<String, Vec<vir::Expr>>,
+        old_exprs: &'a FxHashMap<Symbol, Vec<vir::Expr>>,
     ) -> Self {
         PathCtxt {
             state: State::new(
@@ -73,7 +76,7 @@ impl<'a> PathCtxt<'a> {
         self.predicates
     }
 
-    pub fn old_exprs(&self) -> &FxHashMap<String, Vec<vir::Expr>> {
+    pub fn old_exprs(&self) -> &FxHashMap<Symbol, Vec<vir::Expr>> {
         self.old_exprs
     }
 
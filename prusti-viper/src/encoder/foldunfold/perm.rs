@@ -6,7 +6,10 @@
 
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::fmt;
-use vir_crate::polymorphic::{Expr, PermAmount, Position, Type};
+use vir_crate::{
+    common::symbol::Symbol,
+    polymorphic::{Expr, PermAmount, Position, Type},
+};
 
 /// An access or predicate permission to a place
 #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -44,7 +47,7 @@ impl Perm {
         self.get_place().get_type()
     }
 
-    pub fn get_label(&self) -> Option<&String> {
+    pub fn get_label(&self) -> Option<&Symbol> {
         self.get_place().get_label()
     }
 
@@ -177,7 +180,7 @@ impl fmt::Display for PermSet {
 
 pub trait PermIterator {
     fn collect_curr(&mut self) -> Vec<Perm>;
-    fn group_by_label(&mut self) -> FxHashMap<Option<String>, Vec<Perm>>;
+    fn group_by_label(&mut self) -> FxHashMap<Option<Symbol>, Vec<Perm>>;
 }
 
 impl<T> PermIterator for T
@@ -188,10 +191,10 @@ where
         self.filter(|perm| perm.is_curr()).collect()
     }
 
-    fn group_by_label(&mut self) -> FxHashMap<Option<String>, Vec<Perm>> {
+    fn group_by_label(&mut self) -> FxHashMap<Option<Symbol>, Vec<Perm>> {
         let mut res_perms = FxHashMap::default();
         for perm in self {
-            let perms: &mut Vec<_> = res_perms.entry(perm.get_label().cloned()).or_default();
+            let perms: &mut Vec<_> = res_perms.entry(perm.get_label().copied()).or_default();
             perms.push(perm.clone());
         }
         res_perms
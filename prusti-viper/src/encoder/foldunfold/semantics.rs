@@ -35,7 +35,7 @@ fn exhale_expr(
             .filter(|p| p.is_curr() || p.is_pred())
             .filter(|p| !(p.is_local() && p.is_acc()))
             // Hack for final exhale of method: do not remove "old[pre](..)" permissions from state
-            .filter(|p| p.get_label() != Some(&"pre".to_string())),
+            .filter(|p| p.get_label().map_or(true, |label| *label != "pre")),
     )
 }
 
@@ -353,7 +353,7 @@ impl ApplyOnState for vir::Stmt {
                                             repl_labelled.base.as_ref(),
                                         ),
                                     ),
-                                    label: repl_labelled.label.clone(),
+                                    label: repl_labelled.label,
                                     position: repl_labelled.position,
                                 });
                             } else {
@@ -134,7 +134,7 @@ impl IntoPolymorphic<vir_poly::AddrOf> for vir_high::expression::AddrOf {
 impl IntoPolymorphic<vir_poly::LabelledOld> for vir_high::expression::LabelledOld {
     fn lower(&self, encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::LabelledOld {
         vir_poly::LabelledOld {
-            label: self.label.clone(),
+            label: self.label.as_str().into(),
             base: self.base.lower(encoder),
             position: self.position.lower(encoder),
         }
@@ -69,7 +69,8 @@ impl IntoPolymorphic<vir_poly::TypeVar> for vir_high::ty::TypeVar {
                 vir_high::ty::TypeVar::LifetimeConst(lifetime_const) => {
                     lifetime_const.get_identifier()
                 }
-            },
+            }
+            .into(),
         }
     }
 }
@@ -82,14 +83,14 @@ impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Tuple {
 
 impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Struct {
     fn lower(&self, encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
-        vir_poly::TypedRef::new(self.name.clone(), self.arguments.lower(encoder))
+        vir_poly::TypedRef::new(self.name.as_str(), self.arguments.lower(encoder))
     }
 }
 
 impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Enum {
     fn lower(&self, encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
         vir_poly::TypedRef {
-            label: self.name.clone(),
+            label: self.name.as_str().into(),
             arguments: self.arguments.lower(encoder),
             variant: self
                 .variant
@@ -102,7 +103,7 @@ impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Enum {
 
 impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Union {
     fn lower(&self, encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
-        vir_poly::TypedRef::new(self.name.clone(), self.arguments.lower(encoder))
+        vir_poly::TypedRef::new(self.name.as_str(), self.arguments.lower(encoder))
     }
 }
 
@@ -135,30 +136,30 @@ impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Pointer {
 
 impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Closure {
     fn lower(&self, _encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
-        vir_poly::TypedRef::new(self.name.clone(), Vec::new())
+        vir_poly::TypedRef::new(self.name.as_str(), Vec::new())
     }
 }
 
 impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::FunctionDef {
     fn lower(&self, _encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
-        vir_poly::TypedRef::new(self.name.clone(), Vec::new())
+        vir_poly::TypedRef::new(self.name.as_str(), Vec::new())
     }
 }
 
 impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Projection {
     fn lower(&self, encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
-        vir_poly::TypedRef::new(self.name.clone(), self.arguments.lower(encoder))
+        vir_poly::TypedRef::new(self.name.as_str(), self.arguments.lower(encoder))
     }
 }
 
 impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Unsupported {
     fn lower(&self, _encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
-        vir_poly::TypedRef::new(self.name.clone(), Vec::new())
+        vir_poly::TypedRef::new(self.name.as_str(), Vec::new())
     }
 }
 
 impl IntoPolymorphic<vir_poly::TypedRef> for vir_high::ty::Trusted {
     fn lower(&self, _encoder: &impl HighTypeEncoderInterfacePrivate) -> vir_poly::TypedRef {
-        vir_poly::TypedRef::new(self.name.clone(), Vec::new())
+        vir_poly::TypedRef::new(self.name.as_str(), Vec::new())
     }
 }
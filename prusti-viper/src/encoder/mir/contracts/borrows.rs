@@ -117,6 +117,49 @@ impl<'tcx> BorrowInfoCollectingVisitor<'tcx> {
         }
     }
 
+    /// Accounts for the lifetime bounds `'long: 'short` of the function. The
+    /// references with the lifetime `'short` may be reborrowed from the
+    /// references with the lifetime `'long`, so the latter stay blocked as
+    /// long as the former are alive.
+    pub fn add_outlives_bounds(&mut self, bounds: &[(ty::Region<'tcx>, ty::Region<'tcx>)]) {
+        let bounds: Vec<_> = bounds
+            .iter()
+            .map(|&(long, short)| {
+                (
+                    self.extract_bound_region(long),
+                    self.extract_bound_region(short),
+                )
+            })
+            .filter(|(long, short)| long != short)
+            .collect();
+        // The bounds are transitive, so propagate the blocked paths until
+        // nothing changes.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (long, short) in &bounds {
+                let Some(long_info) = self.borrow_infos.iter().find(|info| info.region == *long)
+                else {
+                    continue;
+                };
+                let long_blocked_paths = long_info.blocked_paths.clone();
+                let Some(short_info) = self
+                    .borrow_infos
+                    .iter_mut()
+                    .find(|info| info.region == *short)
+                else {
+                    continue;
+                };
+                for path in long_blocked_paths {
+                    if !short_info.blocked_paths.contains(&path) {
+                        short_info.blocked_paths.push(path);
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+
     pub fn get_or_create_borrow_info(
         &mut self,
         region: Option<ty::BoundRegionKind>,
@@ -185,6 +185,22 @@ fn get_procedure_contract<'p, 'v: 'p, 'tcx: 'v>(
         visitor.analyse_arg(*arg, arg_ty)?;
     }
     visitor.analyse_return_ty(return_ty)?;
+    if !env.query.is_closure(proc_def_id) {
+        let outlives_bounds: Vec<_> = env
+            .tcx()
+            .predicates_of(proc_def_id)
+            .instantiate_identity(env.tcx())
+            .predicates
+            .into_iter()
+            .filter_map(|clause| match clause.kind().skip_binder() {
+                ty::ClauseKind::RegionOutlives(ty::OutlivesPredicate(long, short)) => {
+                    Some((long, short))
+                }
+                _ => None,
+            })
+            .collect();
+        visitor.add_outlives_bounds(&outlives_bounds);
+    }
     let borrow_infos: Vec<_> = visitor
         .borrow_infos
         .into_iter()
@@ -1,4 +1,7 @@
-use crate::encoder::errors::{PanicCause, SpannedEncodingResult};
+use crate::{
+    encoder::errors::{PanicCause, SpannedEncodingResult},
+    utils,
+};
 use log::debug;
 use prusti_rustc_interface::span::Span;
 
@@ -18,7 +21,10 @@ impl<'v, 'tcx: 'v> MirPanicsEncoderInterface<'tcx> for super::super::super::Enco
         let macro_names: Vec<String> = macro_backtrace
             .iter()
             .take(lookup_size)
-            .filter_map(|x| x.macro_def_id.map(|y| env_name.get_absolute_item_name(y)))
+            .filter_map(|x| {
+                x.macro_def_id
+                    .map(|y| utils::std_macro_name(env_name.get_absolute_item_name(y)))
+            })
             .collect();
         debug!("macro_names: {:?}", macro_names);
 
@@ -32,15 +38,22 @@ impl<'v, 'tcx: 'v> MirPanicsEncoderInterface<'tcx> for super::super::super::Enco
                 PanicCause::Unreachable
             }
             ["std::unreachable", ..] => PanicCause::Unreachable,
+            ["std::todo", ..] => PanicCause::Unimplemented,
+            ["std::assert_eq" | "std::assert_ne", "std::debug_assert_eq" | "std::debug_assert_ne", ..] => {
+                PanicCause::DebugAssert
+            }
+            ["std::assert_eq" | "std::assert_ne", ..] => PanicCause::Assert,
             ["std::assert", "std::debug_assert", ..] => PanicCause::DebugAssert,
             ["std::assert", ..] => PanicCause::Assert,
-            ["std::panic::panic_2015", "std::panic", "std::debug_assert"] => {
+            ["std::panic::panic_2015" | "core::panic::panic_2015", "std::panic", "std::debug_assert"] => {
                 PanicCause::DebugAssert
             }
             // TODO: assert!(_, "") currently has the same backtrace as panic!()
             // see https://github.com/rust-lang/rust/issues/82157
             //["std::panic::panic_2015", "std::panic", ..] => PanicCause::Assert,
-            ["std::panic::panic_2015", "std::panic", ..] => PanicCause::Panic,
+            ["std::panic::panic_2015" | "core::panic::panic_2015", "std::panic", ..] => {
+                PanicCause::Panic
+            }
             _ => PanicCause::Generic,
         };
         Ok(cause)
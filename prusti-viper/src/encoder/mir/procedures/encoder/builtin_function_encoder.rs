@@ -177,10 +177,20 @@ impl<'p, 'v, 'tcx> BuiltinFuncAppEncoder<'p, 'v, 'tcx> for super::ProcedureEncod
         }
 
         match full_called_function_name.as_str() {
-            "std::rt::begin_panic" | "core::panicking::panic" | "core::panicking::panic_fmt" => {
+            "std::rt::begin_panic"
+            | "core::panicking::panic"
+            | "core::panicking::panic_fmt"
+            | "core::panicking::assert_failed" => {
                 let panic_message = format!("{:?}", args[0]);
                 let panic_cause = self.encoder.encode_panic_cause(span)?;
-                if self.check_panics {
+                let callsite = span.source_callsite();
+                let is_accepted = self
+                    .accepted_panic_spans
+                    .iter()
+                    .any(|accepted_span| accepted_span.contains(callsite));
+                if self.check_panics && is_accepted {
+                    block_builder.add_comment(format!("Rust panic (accepted) - {panic_message}"));
+                } else if self.check_panics {
                     block_builder.add_comment(format!("Rust panic - {panic_message}"));
                     block_builder.add_statement(self.encoder.set_statement_error_ctxt(
                         vir_high::Statement::assert_no_pos(false.into()),
@@ -29,6 +29,7 @@ use prusti_interface::environment::{
     mir_analyses::{
         allocation::{compute_definitely_allocated, DefinitelyAllocatedAnalysisResult},
         initialization::{compute_definitely_initialized, DefinitelyInitializedAnalysisResult},
+        leaks::compute_leaks,
     },
     mir_body::borrowck::{facts::RichLocation, lifetimes::Lifetimes},
     Procedure,
@@ -85,6 +86,7 @@ pub(super) fn encode_procedure<'v, 'tcx: 'v>(
     let specification_blocks =
         SpecificationBlocks::build(encoder.env().query, mir, &procedure, true);
     let initialization = compute_definitely_initialized(def_id, mir, encoder.env().tcx());
+    let leaks = compute_leaks(encoder.env().query, def_id, mir);
     let allocation = compute_definitely_allocated(def_id, mir);
     let lifetime_count = lifetimes.lifetime_count();
     let lifetime_token_permission = None;
@@ -95,6 +97,10 @@ pub(super) fn encode_procedure<'v, 'tcx: 'v>(
         BTreeMap::new();
     let points_to_reborrow: BTreeSet<vir_high::Local> = BTreeSet::new();
     let current_basic_block = None;
+    let accepted_panic_spans = def_id
+        .as_local()
+        .map(|local_def_id| encoder.env().query.get_accepted_panic_spans(local_def_id))
+        .unwrap_or_default();
     let mut procedure_encoder = ProcedureEncoder {
         encoder,
         def_id,
@@ -104,12 +110,14 @@ pub(super) fn encode_procedure<'v, 'tcx: 'v>(
         init_data,
         initialization,
         allocation,
+        leaks,
         lifetimes,
         reachable_blocks: Default::default(),
         specification_blocks,
         specification_block_encoding: Default::default(),
         loop_invariant_encoding: Default::default(),
         check_panics: config::check_panics() && check_mode != CheckMode::CoreProof,
+        accepted_panic_spans,
         locals_without_explicit_allocation,
         used_locals: Default::default(),
         fresh_id_generator: 0,
@@ -135,6 +143,8 @@ struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     init_data: InitializationData<'p, 'tcx>,
     initialization: DefinitelyInitializedAnalysisResult<'tcx>,
     allocation: DefinitelyAllocatedAnalysisResult,
+    /// Locations that might leak a value of a `prusti::must_consume` type.
+    leaks: FxHashSet<mir::Location>,
     lifetimes: Lifetimes,
     /// Blocks that we managed to reach when traversing from the entry block.
     reachable_blocks: FxHashSet<mir::BasicBlock>,
@@ -145,6 +155,8 @@ struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     /// The loop invariant to be inserted at the end of the given basic block.
     loop_invariant_encoding: BTreeMap<mir::BasicBlock, vir_high::Statement>,
     check_panics: bool,
+    /// The spans of the statements annotated with `prusti::accept_panic`.
+    accepted_panic_spans: Vec<Span>,
     /// Locals that are not explicitly allocated or deallocated with
     /// `StorageLive`/`StorageDead`. Such locals are assumed to be alive through
     /// the entire body of the function.
@@ -614,6 +626,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 )?);
             }
             mir::StatementKind::StorageDead(local) => {
+                self.encode_leak_check(block_builder, location)?;
                 self.locals_without_explicit_allocation.remove(local);
                 let memory_block = self
                     .encoder
@@ -1199,7 +1212,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             // TerminatorKind::Abort => {
             //     graph.add_exit_edge(bb, "abort");
             // }
-            TerminatorKind::Return => SuccessorBuilder::exit_return(),
+            TerminatorKind::Return => {
+                self.encode_leak_check(block_builder, location)?;
+                SuccessorBuilder::exit_return()
+            }
             TerminatorKind::Unreachable => {
                 block_builder
                     .add_comment("Target marked as unreachable by the compiler".to_string());
@@ -1401,6 +1417,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             block_builder.add_statement(statement);
         }
 
+        self.encode_leak_check(block_builder, location)?;
+
         // FIXME: Assert that the lifetimes used in type of the place are alive
         // at this point (by exhaling them and inhaling). Do not forget to take
         // into account
@@ -1897,6 +1915,23 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             .into()
     }
 
+    /// Asserts that no value of a `prusti::must_consume` type is leaked at
+    /// `location`, if it might be.
+    fn encode_leak_check(
+        &mut self,
+        block_builder: &mut BasicBlockBuilder,
+        location: mir::Location,
+    ) -> SpannedEncodingResult<()> {
+        if self.leaks.contains(&location) {
+            block_builder.add_statement(self.set_statement_error(
+                location,
+                ErrorCtxt::ResourceLeak,
+                vir_high::Statement::assert_no_pos(false.into()),
+            )?);
+        }
+        Ok(())
+    }
+
     fn set_statement_error(
         &mut self,
         location: mir::Location,
@@ -492,6 +492,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ExpressionBackwardInterpreter<'p, 'v, 'tcx> {
                     "std::rt::begin_panic"
                     | "core::panicking::panic"
                     | "core::panicking::panic_fmt"
+                    | "core::panicking::assert_failed"
                     | "std::rt::panic_fmt" => {
                         // This is called when a Rust assertion fails
                         // args[0]: message
@@ -387,7 +387,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 // Optimization
                                 else_expr
                             } else {
-                                vir::Expr::ite(guard, then_expr.clone(), else_expr)
+                                encode_ite(guard, then_expr.clone(), else_expr)
                             }
                         } else {
                             // Define `final_expr` for the first time
@@ -454,6 +454,99 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 state
                             }
 
+                            "std::mem::discriminant" | "core::mem::discriminant" => {
+                                assert_eq!(args.len(), 1);
+                                let enum_ty = call_substs[0].expect_ty();
+                                let discr_value: vir::Expr = match enum_ty.kind() {
+                                    ty::TyKind::Adt(adt_def, _) if adt_def.is_enum() => {
+                                        if adt_def.variants().len() > 1 {
+                                            let discr_field =
+                                                self.encoder.encode_discriminant_field();
+                                            encoded_args[0].clone().field(discr_field)
+                                        } else {
+                                            0u32.into()
+                                        }
+                                    }
+                                    _ => {
+                                        return Err(SpannedEncodingError::unsupported(
+                                            format!("the discriminant of type {enum_ty:?} is not supported"),
+                                            span,
+                                        ));
+                                    }
+                                };
+                                // The `Discriminant<T>` value is represented by the
+                                // discriminant of the enum.
+                                let mut state = states[&target_block].clone();
+                                state.substitute_value(&encoded_lhs, discr_value);
+                                state
+                            }
+
+                            "std::mem::size_of"
+                            | "core::mem::size_of"
+                            | "std::mem::align_of"
+                            | "core::mem::align_of" => {
+                                assert!(args.is_empty());
+                                let layout_ty = call_substs[0].expect_ty();
+                                // Evaluated at encoding time, like rustc's const
+                                // evaluation of these `const fn`s does.
+                                let layout = tcx
+                                    .layout_of(ty::ParamEnv::reveal_all().and(layout_ty))
+                                    .map_err(|_| {
+                                        SpannedEncodingError::unsupported(
+                                            format!(
+                                                "the layout of type {layout_ty:?} is not known \
+                                                at verification time"
+                                            ),
+                                            span,
+                                        )
+                                    })?;
+                                let value = if full_func_proc_name.ends_with("size_of") {
+                                    layout.size.bytes()
+                                } else {
+                                    layout.align.abi.bytes()
+                                };
+                                let encoded_rhs = self.encoder.encode_int_cast(value.into(), ty);
+                                let lhs_value = self
+                                    .encoder
+                                    .encode_value_expr(encoded_lhs, ty)
+                                    .with_span(span)?;
+                                let mut state = states[&target_block].clone();
+                                state.substitute_value(&lhs_value, encoded_rhs);
+                                state
+                            }
+
+                            "std::cmp::PartialEq::eq" | "core::cmp::PartialEq::eq"
+                                if is_discriminant_ref(
+                                    self.encoder,
+                                    self.mir_encoder.get_operand_ty(&args[0]),
+                                ) =>
+                            {
+                                assert_eq!(args.len(), 2);
+                                let encoded_rhs = vir::Expr::eq_cmp(
+                                    encoded_args[0].clone(),
+                                    encoded_args[1].clone(),
+                                );
+                                let mut state = states[&target_block].clone();
+                                state.substitute_value(&encoded_lhs, encoded_rhs);
+                                state
+                            }
+
+                            "std::cmp::PartialEq::ne" | "core::cmp::PartialEq::ne"
+                                if is_discriminant_ref(
+                                    self.encoder,
+                                    self.mir_encoder.get_operand_ty(&args[0]),
+                                ) =>
+                            {
+                                assert_eq!(args.len(), 2);
+                                let encoded_rhs = vir::Expr::ne_cmp(
+                                    encoded_args[0].clone(),
+                                    encoded_args[1].clone(),
+                                );
+                                let mut state = states[&target_block].clone();
+                                state.substitute_value(&encoded_lhs, encoded_rhs);
+                                state
+                            }
+
                             "std::cmp::PartialEq::eq" | "core::cmp::PartialEq::eq"
                                 if self.encoder.has_structural_eq_impl(
                                     self.mir_encoder.get_operand_ty(&args[0]),
@@ -679,7 +772,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
                                 let error_ctxt = match full_func_proc_name {
                                     "std::rt::begin_panic"
                                     | "core::panicking::panic"
-                                    | "core::panicking::panic_fmt" => {
+                                    | "core::panicking::panic_fmt"
+                                    | "core::panicking::assert_failed" => {
                                         // This is called when a Rust assertion fails
                                         // args[0]: message
                                         // args[1]: position of failing assertions
@@ -880,13 +974,33 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
 
                 match rhs {
                     mir::Rvalue::Use(ref operand) => {
-                        let (encoded_rhs, is_value) = self.encode_operand(operand).with_span(span)?;
-                        if is_value {
-                            if let Some(lhs_value_place) = &opt_lhs_value_place {
-                                state.substitute_value(lhs_value_place, encoded_rhs);
-                            }
+                        let deref_val = match operand {
+                            mir::Operand::Constant(constant) => self
+                                .encoder
+                                .encode_const_deref_expr(ty, constant.literal)
+                                .with_span(span)?,
+                            _ => None,
+                        };
+                        if let Some(deref_val) = deref_val {
+                            // A reference to an immutable static or a promoted constant
+                            let ty::TyKind::Ref(_, target_ty, _) = ty.kind() else {
+                                unreachable!()
+                            };
+                            let target_place = self.encoder.encode_value_expr(
+                                opt_lhs_value_place.unwrap(),
+                                *target_ty,
+                            ).with_span(span)?;
+                            state.substitute_value(&target_place, deref_val);
                         } else {
-                            state.substitute_value(&encoded_lhs, encoded_rhs);
+                            let (encoded_rhs, is_value) =
+                                self.encode_operand(operand).with_span(span)?;
+                            if is_value {
+                                if let Some(lhs_value_place) = &opt_lhs_value_place {
+                                    state.substitute_value(lhs_value_place, encoded_rhs);
+                                }
+                            } else {
+                                state.substitute_value(&encoded_lhs, encoded_rhs);
+                            }
                         }
                     }
 
@@ -1301,3 +1415,46 @@ impl<'p, 'v: 'p, 'tcx: 'v> BackwardMirInterpreter<'tcx>
         Ok(())
     }
 }
+
+/// Encodes `if guard { then_expr } else { else_expr }`. If one of the branches
+/// is a boolean constant, as in the expansion of `matches!`, the conditional is
+/// replaced by the equivalent boolean operation, so that the result is an
+/// exact condition on the discriminants. The short-circuiting operations
+/// evaluate the remaining branch under the same condition as the conditional.
+fn encode_ite(guard: vir::Expr, then_expr: vir::Expr, else_expr: vir::Expr) -> vir::Expr {
+    let as_bool = |expr: &vir::Expr| match expr {
+        vir::Expr::Const(vir::ConstExpr {
+            value: vir::Const::Bool(value),
+            ..
+        }) => Some(*value),
+        _ => None,
+    };
+    match (as_bool(&then_expr), as_bool(&else_expr)) {
+        (Some(true), Some(false)) => guard,
+        (Some(false), Some(true)) => vir::Expr::not(guard),
+        (Some(true), _) => vir::Expr::or(guard, else_expr),
+        (Some(false), _) => vir::Expr::and(vir::Expr::not(guard), else_expr),
+        (_, Some(true)) => vir::Expr::implies(guard, then_expr),
+        (_, Some(false)) => vir::Expr::and(guard, then_expr),
+        _ => vir::Expr::ite(guard, then_expr, else_expr),
+    }
+}
+
+/// Whether `ty` is a reference to a `std::mem::Discriminant<T>`, whose values
+/// are represented by the discriminants of the enums.
+fn is_discriminant_ref<'v, 'tcx: 'v>(encoder: &Encoder<'v, 'tcx>, ty: ty::Ty<'tcx>) -> bool {
+    match ty.kind() {
+        ty::TyKind::Ref(_, inner_ty, _) => match inner_ty.kind() {
+            ty::TyKind::Adt(adt_def, _) => matches!(
+                encoder
+                    .env()
+                    .name
+                    .get_absolute_item_name(adt_def.did())
+                    .as_str(),
+                "std::mem::Discriminant" | "core::mem::Discriminant"
+            ),
+            _ => false,
+        },
+        _ => false,
+    }
+}
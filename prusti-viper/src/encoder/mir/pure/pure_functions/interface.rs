@@ -332,7 +332,8 @@ impl<'v, 'tcx: 'v> PureFunctionEncoderInterface<'v, 'tcx>
             );
 
             let is_bodyless = self.is_trusted(proc_def_id, Some(substs))
-                || !self.env().query.has_body(proc_def_id);
+                || !self.env().query.has_body(proc_def_id)
+                || self.is_assumed_dependency_function(proc_def_id, substs);
 
             let maybe_identifier: SpannedEncodingResult<vir_poly::FunctionIdentifier> = (|| {
                 let proc_kind = self.get_proc_kind(proc_def_id, Some(substs));
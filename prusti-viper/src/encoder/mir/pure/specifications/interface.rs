@@ -285,7 +285,7 @@ impl<'v, 'tcx: 'v> SpecificationEncoderInterface<'tcx> for crate::encoder::Encod
         if let Some(pre_label) = pre_label {
             encoded_assertion = encoded_assertion.map_old_expr_label(|label| {
                 if label == PRECONDITION_LABEL {
-                    pre_label.to_string()
+                    pre_label.into()
                 } else {
                     label
                 }
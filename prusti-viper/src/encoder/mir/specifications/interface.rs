@@ -7,7 +7,10 @@ use prusti_interface::{
     utils::has_spec_only_attr,
 };
 use prusti_rustc_interface::{hir::def_id::DefId, middle::ty::GenericArgsRef, span::Span};
-use std::{cell::RefCell, hash::Hash};
+use std::{
+    cell::{Ref, RefCell},
+    hash::Hash,
+};
 
 pub(crate) struct SpecificationsState<'tcx> {
     specs: RefCell<Specifications<'tcx>>,
@@ -19,6 +22,12 @@ impl<'tcx> SpecificationsState<'tcx> {
             specs: RefCell::new(Specifications::new(user_typed_specs)),
         }
     }
+
+    /// The specifications as collected from the user code, without any
+    /// refinements.
+    pub fn user_typed_specs(&self) -> Ref<'_, DefSpecificationMap> {
+        Ref::map(self.specs.borrow(), Specifications::user_typed_specs)
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -285,6 +294,9 @@ impl<'v, 'tcx: 'v> SpecificationsInterface<'tcx> for super::super::super::Encode
         });
         let mut specs = self.specifications_state.specs.borrow_mut();
         let spec = specs.get_and_refine_proc_spec(self.env(), query)?;
+        if !spec.source.is_local() {
+            self.record_assumed_dependency(spec.source.krate);
+        }
         Some(spec.clone())
     }
 
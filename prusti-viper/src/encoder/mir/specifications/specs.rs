@@ -71,6 +71,10 @@ impl<'tcx> Specifications<'tcx> {
         }
     }
 
+    pub(super) fn user_typed_specs(&self) -> &DefSpecificationMap {
+        &self.user_typed_specs
+    }
+
     #[tracing::instrument(level = "trace", skip(self))]
     pub(super) fn get_loop_spec(&self, def_id: &DefId) -> Option<&LoopSpecification> {
         self.user_typed_specs.get_loop_spec(def_id)
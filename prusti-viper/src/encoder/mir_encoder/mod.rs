@@ -146,7 +146,9 @@ pub trait PlaceEncoder<'v, 'tcx: 'v> {
                         };
                         let field = &variant_def.fields[*field];
                         let field_ty = *proj_field_ty;
-                        if utils::is_reference(field_ty) {
+                        // The loans stored in the fields of enum variants cannot be
+                        // tracked, because the regions of downcasts are unknown.
+                        if utils::is_reference(field_ty) && num_variants != 1 {
                             error_unsupported!("access to reference-typed fields is not supported");
                         }
                         let encoded_field = self
@@ -830,7 +832,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
         let macro_names: Vec<String> = macro_backtrace
             .iter()
             .take(lookup_size)
-            .filter_map(|x| x.macro_def_id.map(|y| env_name.get_absolute_item_name(y)))
+            .filter_map(|x| {
+                x.macro_def_id
+                    .map(|y| utils::std_macro_name(env_name.get_absolute_item_name(y)))
+            })
             .collect();
         debug!("macro_names: {:?}", macro_names);
 
@@ -841,15 +846,22 @@ impl<'p, 'v: 'p, 'tcx: 'v> MirEncoder<'p, 'v, 'tcx> {
             ["core::panic::panic_2015", "core::macros::panic", "std::unreachable"]
             | ["core::panic::unreachable_2015", "std::unreachable", ..]
             | ["std::unreachable", ..] => PanicCause::Unreachable,
+            ["std::todo", ..] => PanicCause::Unimplemented,
+            ["std::assert_eq" | "std::assert_ne", "std::debug_assert_eq" | "std::debug_assert_ne", ..] => {
+                PanicCause::DebugAssert
+            }
+            ["std::assert_eq" | "std::assert_ne", ..] => PanicCause::Assert,
             ["std::assert", "std::debug_assert", ..] => PanicCause::DebugAssert,
             ["std::assert", ..] => PanicCause::Assert,
-            ["std::panic::panic_2015", "std::panic", "std::debug_assert"] => {
+            ["std::panic::panic_2015" | "core::panic::panic_2015", "std::panic", "std::debug_assert"] => {
                 PanicCause::DebugAssert
             }
             // TODO: assert!(_, "") currently has the same backtrace as panic!()
             // see https://github.com/rust-lang/rust/issues/82157
             //["std::panic::panic_2015", "std::panic", ..] => PanicCause::Assert,
-            ["std::panic::panic_2015", "std::panic", ..] => PanicCause::Panic,
+            ["std::panic::panic_2015" | "core::panic::panic_2015", "std::panic", ..] => {
+                PanicCause::Panic
+            }
             _ => PanicCause::Generic,
         }
     }
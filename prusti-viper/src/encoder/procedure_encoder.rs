@@ -44,13 +44,18 @@ use ::log::{debug, trace};
 use prusti_common::{
     config,
     utils::to_string::ToString,
-    vir::{fixes::fix_ghost_vars, ToGraphViz},
+    vir::{fixes::fix_ghost_vars, optimizations, ToGraphViz},
     vir_expr, vir_local, vir_stmt,
 };
 use prusti_interface::{
     data::ProcedureDefId,
     environment::{
         borrowck::{facts, regions::PlaceRegionsError},
+        mir_analyses::{
+            intervals::{compute_bounds_after_blocks, Interval},
+            leaks::compute_leaks,
+            loop_guards::{compute_guard_comparison, GuardComparison, GuardTerm},
+        },
         mir_utils::SliceOrArrayRef,
         polonius_info::{
             LoanPlaces, PoloniusInfo, PoloniusInfoError, ReborrowingDAG, ReborrowingDAGNode,
@@ -62,6 +67,7 @@ use prusti_interface::{
         typed,
         typed::{Pledge, SpecificationItem},
     },
+    unsupported_features::{UnsupportedFeature, UnsupportedPolicy},
     utils, PrustiError,
 };
 use prusti_rustc_interface::{
@@ -99,6 +105,13 @@ pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
     auxiliary_local_vars: FxHashMap<String, vir::Type>,
     mir_encoder: MirEncoder<'p, 'v, 'tcx>,
     check_panics: bool,
+    /// The spans of the statements annotated with `prusti::accept_panic`.
+    accepted_panic_spans: Vec<Span>,
+    /// Whether the arithmetic overflows are checked, see `check_overflows`.
+    check_overflows: bool,
+    /// Whether only the absence of panics is checked, see
+    /// `config::panic_freedom_only`.
+    panic_freedom_only: bool,
     check_foldunfold_state: bool,
     polonius_info: Option<PoloniusInfo<'p, 'tcx>>,
     procedure_contract: Option<ProcedureContract<'tcx>>,
@@ -127,12 +140,23 @@ pub struct ProcedureEncoder<'p, 'v: 'p, 'tcx: 'v> {
         FxHashMap<BasicBlockIndex, FxHashMap<vir::Expr, vir::LocalVar>>,
     /// Information about which places are definitely initialised.
     init_info: InitInfo,
+    /// Locations that might leak a value of a `prusti::must_consume` type.
+    leaks: FxHashSet<mir::Location>,
     /// Mapping from old expressions to ghost variables with which they were replaced.
     old_to_ghost_var: FxHashMap<vir::Expr, vir::Expr>,
     /// Ghost variables used inside package statements.
     old_ghost_vars: FxHashMap<String, vir::Type>,
     /// For each loop head, the block at whose end the loop invariant holds
     cached_loop_invariant_block: FxHashMap<BasicBlockIndex, BasicBlockIndex>,
+    /// For each loop head, the inferred bounds of the integer locals that are
+    /// assigned in the loop, which are added to the loop invariant
+    inferred_loop_bounds: FxHashMap<BasicBlockIndex, Vec<(mir::Local, Interval)>>,
+    /// For each loop head, the comparison checked by the loop guard, which is
+    /// added to the loop invariant
+    inferred_loop_guards: FxHashMap<BasicBlockIndex, GuardComparison<'tcx>>,
+    /// The encodings of the two-phase borrows that are reserved but not yet
+    /// activated, indexed by the local that stores the borrow.
+    pending_two_phase_borrows: FxHashMap<mir::Local, Vec<vir::Stmt>>,
     /// Type substitutions inside this procedure. Most likely identity for the
     /// given proc_def_id.
     substs: GenericArgsRef<'tcx>,
@@ -151,9 +175,14 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let mir_encoder = MirEncoder::new(encoder, mir, proc_def_id);
         let init_info = InitInfo::new(mir, tcx, proc_def_id, &mir_encoder)
             .with_default_span(procedure.get_span())?;
+        let leaks = compute_leaks(encoder.env().query, proc_def_id, mir);
 
         let specification_blocks =
             SpecificationBlocks::build(encoder.env().query, mir, procedure, false);
+        let accepted_panic_spans = proc_def_id
+            .as_local()
+            .map(|local_def_id| encoder.env().query.get_accepted_panic_spans(local_def_id))
+            .unwrap_or_default();
 
         let cfg_method = vir::CfgMethod::new(
             // method name
@@ -179,6 +208,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             auxiliary_local_vars: FxHashMap::default(),
             mir_encoder,
             check_panics: config::check_panics(),
+            accepted_panic_spans,
+            check_overflows: check_overflows(encoder, proc_def_id),
+            panic_freedom_only: proc_def_id.as_local().map_or(false, |local_def_id| {
+                encoder.env().query.is_panic_freedom_only(local_def_id)
+            }),
             check_foldunfold_state: config::check_foldunfold_state(),
             polonius_info: None,
             procedure_contract: None,
@@ -192,13 +226,26 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             procedure_contracts: FxHashMap::default(),
             pure_var_for_preserving_value_map: FxHashMap::default(),
             init_info,
+            leaks,
             old_to_ghost_var: FxHashMap::default(),
             old_ghost_vars: FxHashMap::default(),
             cached_loop_invariant_block: FxHashMap::default(),
+            inferred_loop_bounds: FxHashMap::default(),
+            inferred_loop_guards: FxHashMap::default(),
+            pending_two_phase_borrows: FxHashMap::default(),
             substs,
         })
     }
 
+    /// Whether a panic at `span` is inside a statement annotated with
+    /// `prusti::accept_panic`.
+    fn is_panic_accepted(&self, span: Span) -> bool {
+        let span = span.source_callsite();
+        self.accepted_panic_spans
+            .iter()
+            .any(|accepted_span| accepted_span.contains(span))
+    }
+
     #[tracing::instrument(level = "trace", skip_all)]
     fn encode_specification_blocks(&mut self) -> SpannedEncodingResult<()> {
         // Collect the entry points into the specification blocks.
@@ -280,6 +327,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     Some(spec) => spec,
                     None => return Ok(false),
                 };
+                if self.panic_freedom_only {
+                    return Ok(true);
+                }
 
                 let span = self
                     .encoder
@@ -322,6 +372,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     Some(spec) => spec,
                     None => return Ok(false),
                 };
+                if self.panic_freedom_only {
+                    return Ok(true);
+                }
 
                 let span = self
                     .encoder
@@ -453,6 +506,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 }
             }
         }
+        if config::infer_loop_invariants() {
+            self.inferred_loop_bounds = self.infer_loop_bounds();
+            self.inferred_loop_guards = self.infer_loop_guards();
+        }
 
         // Load Polonius info
         self.polonius_info = Some(
@@ -553,6 +610,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
 
         self.encoder
             .log_vir_program_before_foldunfold(self.cfg_method.to_string());
+        optimizations::dump_vir_pass(&source_filename, &self.cfg_method, "before_foldunfold");
 
         // Dump initial CFG
         if config::dump_debug_info() {
@@ -597,6 +655,11 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
 
         // Fix variable declarations.
         let method_with_fold_unfold = fix_ghost_vars(method_with_fold_unfold);
+        optimizations::dump_vir_pass(
+            &source_filename,
+            &method_with_fold_unfold,
+            "after_foldunfold",
+        );
 
         // Dump final CFG
         if config::dump_debug_info() {
@@ -882,18 +945,9 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let loop_label_prefix = format!("{}loop{}", label_prefix, loop_head.index());
         let loop_depth = loop_info.get_loop_head_depth(loop_head);
 
-        let loop_body: Vec<BasicBlockIndex> = loop_info
-            .get_loop_body(loop_head)
-            .iter()
-            .copied()
-            .filter(|&bb| {
-                self.procedure.is_reachable_block(bb) && !self.procedure.is_spec_block(bb)
-            })
-            .collect();
+        let loop_body = self.get_encoded_loop_body(loop_head);
 
         // Identify important blocks
-        let loop_exit_blocks = loop_info.get_loop_exit_blocks(loop_head);
-        let loop_exit_blocks_set: FxHashSet<_> = loop_exit_blocks.iter().cloned().collect();
         let before_invariant_block: BasicBlockIndex = self.cached_loop_invariant_block[&loop_head];
         let before_inv_block_pos = loop_body
             .iter()
@@ -901,16 +955,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             .position(|bb| bb == before_invariant_block)
             .unwrap();
         let after_inv_block_pos = 1 + before_inv_block_pos;
-        // Find boolean switch exit blocks before the invariant
-        let boolean_exit_blocks_before_inv: Vec<_> = loop_body[0..after_inv_block_pos]
-            .iter()
-            .copied()
-            .filter(|bb| loop_exit_blocks_set.contains(bb))
-            .filter(|&bb| self.procedure.successors(bb).len() == 2)
-            .collect();
-        // HEURISTIC: pick the last boolean exit block before the invariant.
-        // An infinite loop will have no exit blocks, so we have to use an Option here
-        let opt_loop_guard_switch = boolean_exit_blocks_before_inv.last().cloned();
+        let opt_loop_guard_switch =
+            self.get_loop_guard_switch(loop_head, &loop_body[0..after_inv_block_pos]);
         let after_guard_block_pos = opt_loop_guard_switch
             .and_then(|loop_guard_switch| {
                 loop_body
@@ -1325,6 +1371,40 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         Ok((start_block, still_unresolved_edges))
     }
 
+    /// The blocks of a loop that are encoded, in the order of the loop body.
+    fn get_encoded_loop_body(&self, loop_head: BasicBlockIndex) -> Vec<BasicBlockIndex> {
+        self.loop_encoder
+            .loops()
+            .get_loop_body(loop_head)
+            .iter()
+            .copied()
+            .filter(|&bb| {
+                self.procedure.is_reachable_block(bb) && !self.procedure.is_spec_block(bb)
+            })
+            .collect()
+    }
+
+    /// Finds the block that evaluates the loop guard among the blocks of the
+    /// loop body that precede the loop invariant.
+    fn get_loop_guard_switch(
+        &self,
+        loop_head: BasicBlockIndex,
+        loop_body_before_inv: &[BasicBlockIndex],
+    ) -> Option<BasicBlockIndex> {
+        let loop_exit_blocks = self.loop_encoder.loops().get_loop_exit_blocks(loop_head);
+        let loop_exit_blocks_set: FxHashSet<_> = loop_exit_blocks.iter().cloned().collect();
+        // Find boolean switch exit blocks before the invariant
+        let boolean_exit_blocks_before_inv: Vec<_> = loop_body_before_inv
+            .iter()
+            .copied()
+            .filter(|bb| loop_exit_blocks_set.contains(bb))
+            .filter(|&bb| self.procedure.successors(bb).len() == 2)
+            .collect();
+        // HEURISTIC: pick the last boolean exit block before the invariant.
+        // An infinite loop will have no exit blocks, so we have to use an Option here
+        boolean_exit_blocks_before_inv.last().cloned()
+    }
+
     /// Encode a block.
     ///
     /// Returns:
@@ -1487,6 +1567,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let span = self.mir_encoder.get_span_of_location(location);
         let bb_data = &self.mir[location.block];
         let index = location.statement_index;
+        let activated_borrows = self.take_activated_two_phase_borrows(location);
         let stmts_succ_res = if index < bb_data.statements.len() {
             let mir_stmt = &bb_data.statements[index];
             self.encode_statement(mir_stmt, location)
@@ -1501,37 +1582,143 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let (stmts, successor) = match stmts_succ_res {
             Ok(stmts_succ) => stmts_succ,
             Err(err) => {
-                let unsupported_msg = match err.kind() {
+                let (unsupported_msg, is_trusted) = match err.kind() {
+                    EncodingErrorKind::Unsupported(msg)
+                        if UnsupportedFeature::of_message(msg).policy()
+                            == UnsupportedPolicy::Trust =>
+                    {
+                        (msg.to_string(), true)
+                    }
                     EncodingErrorKind::Unsupported(msg)
                         if config::allow_unreachable_unsupported_code() =>
                     {
-                        msg.to_string()
+                        (msg.to_string(), false)
                     }
                     _ => {
                         // Propagate the error
                         return Err(err);
                     }
                 };
-                // TODO: How to combine this with the span of the encoding error?
-                let err_ctxt = ErrorCtxt::Unsupported(unsupported_msg.clone());
-                let pos = self.register_error(span, err_ctxt);
                 let head_stmt = if index < bb_data.statements.len() {
                     format!("[mir] {:?}", &bb_data.statements[index])
                 } else {
                     format!("[mir] {:?}", bb_data.terminator())
                 };
-                let stmts = vec![
-                    vir::Stmt::comment(head_stmt),
-                    vir::Stmt::comment(format!("Unsupported feature: {unsupported_msg}")),
-                    vir::Stmt::Assert(vir::Assert {
-                        expr: false.into(),
-                        position: pos,
-                    }),
-                ];
+                let stmts = if is_trusted {
+                    // The executions that reach the unsupported feature are
+                    // assumed to be correct.
+                    vec![
+                        vir::Stmt::comment(head_stmt),
+                        vir::Stmt::comment(format!(
+                            "Trusted unsupported feature: {unsupported_msg}"
+                        )),
+                        vir::Stmt::Inhale(vir::Inhale { expr: false.into() }),
+                    ]
+                } else {
+                    // TODO: How to combine this with the span of the encoding error?
+                    let err_ctxt = ErrorCtxt::Unsupported(unsupported_msg.clone());
+                    let pos = self.register_error(span, err_ctxt);
+                    vec![
+                        vir::Stmt::comment(head_stmt),
+                        vir::Stmt::comment(format!("Unsupported feature: {unsupported_msg}")),
+                        vir::Stmt::Assert(vir::Assert {
+                            expr: false.into(),
+                            position: pos,
+                        }),
+                    ]
+                };
                 (stmts, Some(MirSuccessor::Kill))
             }
         };
-        Ok((self.set_stmts_default_pos(stmts, span), successor))
+        let mut stmts = self.set_stmts_default_pos(stmts, span);
+        if let Some(borrow_local) = self.two_phase_borrow_reserved_at(location) {
+            if successor.is_none() {
+                let reservation = vir::Stmt::comment(format!(
+                    "Reservation of the two-phase borrow {borrow_local:?}, \
+                    which is encoded when it is activated"
+                ));
+                let borrow_stmts = std::mem::replace(&mut stmts, vec![reservation]);
+                self.pending_two_phase_borrows
+                    .insert(borrow_local, borrow_stmts);
+            }
+        }
+        Ok((
+            activated_borrows.into_iter().chain(stmts).collect(),
+            successor,
+        ))
+    }
+
+    /// The local that stores the two-phase borrow created at `location`, if
+    /// there is one.
+    ///
+    /// Until a two-phase borrow is activated, the borrowed place can still be
+    /// read, for example in `v.push(v.len())`. Its encoding is therefore
+    /// delayed to the first statement or terminator that uses the local,
+    /// which is where the borrow is activated.
+    fn two_phase_borrow_reserved_at(&self, location: mir::Location) -> Option<mir::Local> {
+        let bb_data = &self.mir[location.block];
+        match bb_data.statements.get(location.statement_index)?.kind {
+            mir::StatementKind::Assign(box (
+                lhs,
+                mir::Rvalue::Ref(
+                    _,
+                    mir::BorrowKind::Mut {
+                        kind: mir::MutBorrowKind::TwoPhaseBorrow,
+                    },
+                    _,
+                ),
+            )) => lhs.as_local(),
+            _ => None,
+        }
+    }
+
+    /// Removes and returns the encodings of the pending two-phase borrows
+    /// that are activated by the statement or terminator at `location`.
+    fn take_activated_two_phase_borrows(&mut self, location: mir::Location) -> Vec<vir::Stmt> {
+        if self.pending_two_phase_borrows.is_empty() {
+            return vec![];
+        }
+        struct UsedLocalsCollector {
+            used_locals: FxHashSet<mir::Local>,
+        }
+        impl<'tcx> mir::visit::Visitor<'tcx> for UsedLocalsCollector {
+            fn visit_local(
+                &mut self,
+                local: mir::Local,
+                context: mir::visit::PlaceContext,
+                _location: mir::Location,
+            ) {
+                if context.is_use()
+                    && !context.is_storage_marker()
+                    && !context.is_place_assignment()
+                {
+                    self.used_locals.insert(local);
+                }
+            }
+        }
+        let mut collector = UsedLocalsCollector {
+            used_locals: FxHashSet::default(),
+        };
+        mir::visit::Visitor::visit_location(&mut collector, self.mir, location);
+        let mut used_locals: Vec<_> = collector.used_locals.into_iter().collect();
+        used_locals.sort();
+        used_locals
+            .into_iter()
+            .filter_map(|local| self.pending_two_phase_borrows.remove(&local))
+            .flatten()
+            .collect()
+    }
+
+    /// Encodes the check that no value of a `prusti::must_consume` type is leaked
+    /// at `location`, if it might be.
+    fn encode_leak_check(&self, location: mir::Location, span: Span) -> Option<vir::Stmt> {
+        self.leaks.contains(&location).then(|| {
+            let pos = self.register_error(span, ErrorCtxt::ResourceLeak);
+            vir::Stmt::Assert(vir::Assert {
+                expr: false.into(),
+                position: pos,
+            })
+        })
     }
 
     /// Note: it's better to call `encode_statement_at` instead of this method.
@@ -1545,8 +1732,12 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         let span = self.mir_encoder.get_span_of_location(location);
 
         let encoding_stmts = match stmt.kind {
+            mir::StatementKind::StorageDead(..) => self
+                .encode_leak_check(location, stmt.source_info.span)
+                .into_iter()
+                .collect(),
+
             mir::StatementKind::StorageLive(..)
-            | mir::StatementKind::StorageDead(..)
             | mir::StatementKind::FakeRead(..)
             | mir::StatementKind::AscribeUserType(..)
             | mir::StatementKind::Coverage(..)
@@ -1777,7 +1968,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 let wand_rhs_patched_lhs = wand_rhs.map_old_expr_label(|label| {
                     if label == "lhs" {
                         trace!("{} -> {}", label, new_lhs_label);
-                        new_lhs_label.clone()
+                        (&new_lhs_label).into()
                     } else {
                         label
                     }
@@ -2383,6 +2574,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
 
         let result = match term.kind {
             TerminatorKind::Return => {
+                stmts.extend(self.encode_leak_check(location, term.source_info.span));
                 // Package magic wands, if there is any
                 let postcondition_label = self.cfg_method.get_fresh_label_name();
                 stmts.extend(self.encode_package_end_of_method(
@@ -2559,7 +2751,10 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 (stmts, MirSuccessor::Kill)
             }
 
-            TerminatorKind::Drop { target, .. } => (stmts, MirSuccessor::Goto(target)),
+            TerminatorKind::Drop { target, .. } => {
+                stmts.extend(self.encode_leak_check(location, term.source_info.span));
+                (stmts, MirSuccessor::Goto(target))
+            }
 
             TerminatorKind::FalseEdge { real_target, .. } => {
                 (stmts, MirSuccessor::Goto(real_target))
@@ -2594,7 +2789,8 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     match full_func_proc_name {
                         "std::rt::begin_panic"
                         | "core::panicking::panic"
-                        | "core::panicking::panic_fmt" => {
+                        | "core::panicking::panic_fmt"
+                        | "core::panicking::assert_failed" => {
                             // This is called when a Rust assertion fails
                             // args[0]: message
                             // args[1]: position of failing assertions
@@ -2609,7 +2805,27 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                                 ErrorCtxt::Panic(panic_cause),
                             );
 
-                            if self.check_panics {
+                            if self.check_panics
+                                && panic_cause == PanicCause::Unimplemented
+                                && config::assume_unimplemented()
+                            {
+                                // The statement is assumed to be unreachable, and
+                                // listed at the end of the verification.
+                                stmts.push(vir::Stmt::comment(format!(
+                                    "Rust panic (assumed unreachable) - {panic_message}"
+                                )));
+                                self.encoder
+                                    .record_assumed_unimplemented(term.source_info.span);
+                            } else if self.check_panics
+                                && self.is_panic_accepted(term.source_info.span)
+                            {
+                                // The panic is inside a statement annotated with
+                                // `prusti::accept_panic`, so its reachability is
+                                // not checked.
+                                stmts.push(vir::Stmt::comment(format!(
+                                    "Rust panic (accepted) - {panic_message}"
+                                )));
+                            } else if self.check_panics {
                                 stmts.push(vir::Stmt::comment(format!(
                                     "Rust panic - {panic_message}"
                                 )));
@@ -2872,8 +3088,17 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                     (assert_msg.clone(), ErrorCtxt::AssertTerminator(assert_msg))
                 };
 
+                let is_overflow_check = matches!(
+                    msg,
+                    box mir::AssertKind::Overflow(..) | box mir::AssertKind::OverflowNeg(_)
+                );
                 stmts.push(vir::Stmt::comment(format!("Rust assertion: {assert_msg}")));
-                if self.check_panics {
+                if is_overflow_check && !self.check_overflows {
+                    stmts.push(vir::Stmt::comment(
+                        "Overflows are not checked in this procedure",
+                    ));
+                    stmts.push(vir::Stmt::Inhale(vir::Inhale { expr: viper_guard }));
+                } else if self.check_panics {
                     stmts.push(vir::Stmt::Assert(vir::Assert {
                         expr: viper_guard,
                         position: self.register_error(term.source_info.span, error_ctxt),
@@ -3709,9 +3934,16 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         stmts.push(vir::Stmt::Inhale(vir::Inhale {
             expr: replace_fake_exprs(post_invs_spec),
         }));
-        stmts.push(vir::Stmt::Inhale(vir::Inhale {
-            expr: replace_fake_exprs(post_func_spec),
-        }));
+        // The postcondition of a callee that is verified only for the absence
+        // of panics has not been checked, so it cannot be assumed.
+        let is_callee_panic_freedom_only = called_def_id.as_local().map_or(false, |local_def_id| {
+            self.encoder.env().query.is_panic_freedom_only(local_def_id)
+        });
+        if !is_callee_panic_freedom_only {
+            stmts.push(vir::Stmt::Inhale(vir::Inhale {
+                expr: replace_fake_exprs(post_func_spec),
+            }));
+        }
 
         // Exhale the permissions that were moved into magic wands.
         assert!(!pos.is_default());
@@ -4755,7 +4987,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             ) -> vir::Expr {
                 let base = self.fold_boxed(base);
                 let expr = vir::Expr::LabelledOld(vir::LabelledOld {
-                    label: label.clone(),
+                    label,
                     base,
                     position,
                 });
@@ -4766,7 +4998,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 if self.old_to_ghost_var.contains_key(&expr) {
                     debug!("found={}", self.old_to_ghost_var[&expr]);
                     self.old_to_ghost_var[&expr].clone().set_pos(position)
-                } else if self.label == Some(&label) {
+                } else if self.label == Some(label.as_str()) {
                     let mut counter = 0;
                     let mut name = format!("_old${label}${counter}");
                     while self.old_ghost_vars.contains_key(&name) {
@@ -5090,7 +5322,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             return_cfg_block,
             vir::Stmt::comment("Assert possible strengthening"),
         );
-        if let Some(strengthening_spec) = strengthening_spec {
+        if let Some(strengthening_spec) = strengthening_spec.filter(|_| !self.panic_freedom_only) {
             let patched_strengthening_spec = self
                 .replace_old_places_with_ghost_vars(None, strengthening_spec.refinement_check_expr);
             let pos = self.register_error(
@@ -5111,15 +5343,17 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
             return_cfg_block,
             vir::Stmt::comment("Assert functional specification of postcondition"),
         );
-        let func_pos = self.register_error(self.mir.span, ErrorCtxt::AssertMethodPostcondition);
-        let patched_func_spec = self.replace_old_places_with_ghost_vars(None, func_spec);
-        self.cfg_method.add_stmt(
-            return_cfg_block,
-            vir::Stmt::Assert(vir::Assert {
-                expr: patched_func_spec,
-                position: func_pos,
-            }),
-        );
+        if !self.panic_freedom_only {
+            let func_pos = self.register_error(self.mir.span, ErrorCtxt::AssertMethodPostcondition);
+            let patched_func_spec = self.replace_old_places_with_ghost_vars(None, func_spec);
+            self.cfg_method.add_stmt(
+                return_cfg_block,
+                vir::Stmt::Assert(vir::Assert {
+                    expr: patched_func_spec,
+                    position: func_pos,
+                }),
+            );
+        }
 
         // Assert type invariants
         self.cfg_method.add_stmt(
@@ -5240,7 +5474,7 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
         };
 
         let snap_array = vir::Expr::snap_app(array_base);
-        let old_snap_array = vir::Expr::old(snap_array.clone(), old_label);
+        let old_snap_array = vir::Expr::old(snap_array.clone(), old_label.as_str());
         vir_expr! { [snap_array] == [old_snap_array] }
     }
 
@@ -5591,9 +5825,138 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                 }
             }
         }
+        let mut inferred_specs = vec![];
+        if let Some(bounds) = self.inferred_loop_bounds.get(&loop_head) {
+            for &(local, interval) in bounds {
+                inferred_specs.push(self.encode_local_bounds(local, interval)?);
+            }
+        }
+        if let Some(guard) = self.inferred_loop_guards.get(&loop_head) {
+            inferred_specs.push(self.encode_guard_comparison(loop_head, guard)?);
+        }
+        if encoded_spec_spans.is_empty() && !inferred_specs.is_empty() {
+            encoded_spec_spans.push(self.mir_encoder.get_span_of_basic_block(loop_head));
+        }
+        encoded_specs.extend(inferred_specs);
         Ok((encoded_specs, MultiSpan::from_spans(encoded_spec_spans)))
     }
 
+    /// Infers the bounds of the integer locals that are assigned in each
+    /// loop, at the point where the loop invariant holds.
+    fn infer_loop_bounds(&self) -> FxHashMap<BasicBlockIndex, Vec<(mir::Local, Interval)>> {
+        let loop_inv_blocks: Vec<_> = self.cached_loop_invariant_block.values().copied().collect();
+        let bounds_after_blocks = compute_bounds_after_blocks(
+            self.encoder.env().tcx(),
+            self.proc_def_id,
+            self.mir,
+            &loop_inv_blocks,
+        );
+        let loop_info = self.loop_encoder.loops();
+        self.cached_loop_invariant_block
+            .iter()
+            .filter_map(|(&loop_head, loop_inv_block)| {
+                let mut assigned_locals = FxHashSet::default();
+                for &bb in loop_info.get_loop_body(loop_head) {
+                    let bb_data = &self.mir.basic_blocks[bb];
+                    for stmt in &bb_data.statements {
+                        if let mir::StatementKind::Assign(box (place, _)) = &stmt.kind {
+                            assigned_locals.insert(place.local);
+                        }
+                    }
+                    if let TerminatorKind::Call { destination, .. } = &bb_data.terminator().kind {
+                        assigned_locals.insert(destination.local);
+                    }
+                }
+                let bounds: Vec<_> = bounds_after_blocks
+                    .get(loop_inv_block)?
+                    .iter()
+                    .filter(|(local, _)| assigned_locals.contains(local))
+                    .copied()
+                    .collect();
+                Some((loop_head, bounds))
+            })
+            .collect()
+    }
+
+    /// Infers, for each loop, the comparison checked by the loop guard, which
+    /// still holds where the loop invariant holds.
+    fn infer_loop_guards(&self) -> FxHashMap<BasicBlockIndex, GuardComparison<'tcx>> {
+        let loop_info = self.loop_encoder.loops();
+        self.cached_loop_invariant_block
+            .iter()
+            .filter_map(|(&loop_head, &loop_inv_block)| {
+                let loop_body = self.get_encoded_loop_body(loop_head);
+                let after_inv_block_pos =
+                    1 + loop_body.iter().position(|&bb| bb == loop_inv_block)?;
+                let loop_body_before_inv = &loop_body[0..after_inv_block_pos];
+                let loop_guard_switch =
+                    self.get_loop_guard_switch(loop_head, loop_body_before_inv)?;
+                let guard = compute_guard_comparison(
+                    self.encoder.env().tcx(),
+                    self.mir,
+                    loop_guard_switch,
+                    loop_info.get_loop_body(loop_head),
+                    loop_body_before_inv,
+                )?;
+                Some((loop_head, guard))
+            })
+            .collect()
+    }
+
+    /// Encodes the value of an integer local.
+    fn encode_local_value(&self, local: mir::Local) -> SpannedEncodingResult<vir::Expr> {
+        let ty = self.mir_encoder.get_local_ty(local);
+        let span = self.mir_encoder.get_local_span(local);
+        Ok(vir::Expr::local(self.mir_encoder.encode_local(local)?)
+            .field(self.encoder.encode_value_field(ty).with_span(span)?))
+    }
+
+    /// Encodes the bounds of an integer local inferred by the interval analysis.
+    fn encode_local_bounds(
+        &self,
+        local: mir::Local,
+        interval: Interval,
+    ) -> SpannedEncodingResult<vir::Expr> {
+        let value = self.encode_local_value(local)?;
+        let mut bounds = vec![];
+        if let Some(lo) = interval.lo {
+            bounds.push(vir::Expr::le_cmp(lo.into(), value.clone()));
+        }
+        if let Some(hi) = interval.hi {
+            bounds.push(vir::Expr::le_cmp(value, hi.into()));
+        }
+        Ok(bounds.into_iter().conjoin())
+    }
+
+    /// Encodes a comparison checked by the guard of the loop.
+    fn encode_guard_comparison(
+        &self,
+        loop_head: BasicBlockIndex,
+        guard: &GuardComparison<'tcx>,
+    ) -> SpannedEncodingResult<vir::Expr> {
+        let span = self.mir_encoder.get_span_of_basic_block(loop_head);
+        let encode_term = |term: GuardTerm<'tcx>| match term {
+            GuardTerm::Local(local) => self.encode_local_value(local),
+            GuardTerm::Len(place) => {
+                let (encoded_place, place_ty, _) =
+                    self.mir_encoder.encode_place(place).with_span(span)?;
+                let encoded_place = encoded_place.try_into_expr().with_span(span)?;
+                let sequence_types = self
+                    .encoder
+                    .encode_sequence_types(place_ty)
+                    .with_span(span)?;
+                Ok(sequence_types.len(self.encoder, encoded_place))
+            }
+        };
+        let left = encode_term(guard.left)?;
+        let right = encode_term(guard.right)?;
+        Ok(match guard.op {
+            mir::BinOp::Lt => vir::Expr::lt_cmp(left, right),
+            mir::BinOp::Le => vir::Expr::le_cmp(left, right),
+            op => unreachable!("unexpected loop guard comparison {:?}", op),
+        })
+    }
+
     #[tracing::instrument(level = "trace", skip(self))]
     fn encode_loop_invariant_exhale_stmts(
         &mut self,
@@ -6105,9 +6468,30 @@ impl<'p, 'v: 'p, 'tcx: 'v> ProcedureEncoder<'p, 'v, 'tcx> {
                             vir::AssignKind::Copy,
                             true,
                         )?;
+                        let deref_val = self
+                            .encoder
+                            .encode_const_deref_expr(ty, expr.literal)
+                            .with_span(span)?;
                         // TODO Encoding of string literals is not yet supported,
                         // so do not encode an assignment if the RHS is a string
-                        if !is_str(ty) {
+                        if let Some(deref_val) = deref_val {
+                            // A reference to an immutable static or a promoted
+                            // constant. If `lhs` was freshly allocated,
+                            // `prepare_assign_target` inhaled read permission to
+                            // a new target, whose value we can fix.
+                            if !stmts.is_empty() {
+                                let ty::TyKind::Ref(_, target_ty, _) = ty.kind() else {
+                                    unreachable!()
+                                };
+                                let target_val = self
+                                    .encoder
+                                    .encode_value_expr(lhs.clone().field(field), *target_ty)
+                                    .with_span(span)?;
+                                stmts.push(vir::Stmt::Inhale(vir::Inhale {
+                                    expr: vir::Expr::eq_cmp(target_val, deref_val),
+                                }));
+                            }
+                        } else if !is_str(ty) {
                             // Initialize the constant
                             let const_val = self
                                 .encoder
@@ -7348,6 +7732,51 @@ fn convert_loans_to_borrows(loans: &[facts::Loan]) -> Vec<Borrow> {
 
 /// Check if size of ProcedureContract::borrow_infos is as required
 /// len: Length of borrow_infos
+/// Whether the arithmetic overflows of the procedure are checked. The
+/// `prusti::check_overflows(..)` or `prusti::config(check_overflows = ..)`
+/// attribute of the procedure or of the closest enclosing item that has one,
+/// e.g. a module, overrides `config::check_overflows`. The compiler generates
+/// the overflow checks only if the flag is enabled, so the attribute cannot
+/// enable them otherwise.
+fn check_overflows<'v, 'tcx: 'v>(encoder: &Encoder<'v, 'tcx>, proc_def_id: ProcedureDefId) -> bool {
+    let env = encoder.env();
+    let mut current = Some(proc_def_id);
+    while let Some(def_id) = current {
+        let attrs = env.query.get_attributes(def_id);
+        let value = match utils::read_prusti_attr_bool("check_overflows", attrs) {
+            Some(Some(value)) => Some(value),
+            Some(None) => {
+                PrustiError::incorrect(
+                    "the `prusti::check_overflows` attribute expects a boolean, e.g. \
+                    `prusti::check_overflows(false)`",
+                    env.query.get_def_span(def_id).into(),
+                )
+                .emit(&env.diagnostic);
+                None
+            }
+            None => match utils::read_prusti_config_flag(attrs, "check_overflows") {
+                Some(utils::ItemConfigValue::Bool(value)) => Some(value),
+                _ => None,
+            },
+        };
+        match value {
+            Some(true) if !config::check_overflows() => {
+                PrustiError::warning(
+                    "the `prusti::check_overflows(true)` attribute has no effect if the \
+                    `check_overflows` flag is disabled",
+                    env.query.get_def_span(def_id).into(),
+                )
+                .emit(&env.diagnostic);
+                return false;
+            }
+            Some(value) => return value,
+            None => {}
+        }
+        current = env.tcx().opt_parent(def_id);
+    }
+    config::check_overflows()
+}
+
 fn assert_one_magic_wand(len: usize) -> EncodingResult<()> {
     if len > 1 {
         Err(EncodingError::internal(format!(
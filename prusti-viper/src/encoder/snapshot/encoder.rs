@@ -17,7 +17,7 @@ use crate::{
     },
     error_internal,
 };
-use prusti_common::{vir_expr, vir_local};
+use prusti_common::{config, vir_expr, vir_local};
 
 use prusti_rustc_interface::{
     middle::{ty, ty::ParamEnv},
@@ -288,7 +288,7 @@ impl SnapshotEncoder {
                 })?;
                 variants[*variant_idx]
                     .1
-                    .get(&field.name)
+                    .get(field.name.as_str())
                     .map(|func| func.apply(vec![expr.clone()]))
                     .ok_or_else(|| {
                         EncodingError::internal(format!(
@@ -320,7 +320,7 @@ impl SnapshotEncoder {
             ) => Ok(discriminant_func.apply(vec![expr])),
             (_, Snapshot::Complex { variants, .. }) => variants[0]
                 .1
-                .get(&field.name)
+                .get(field.name.as_str())
                 .map(|func| func.apply(vec![expr.clone()]))
                 .ok_or_else(|| {
                     EncodingError::internal(format!(
@@ -1707,7 +1707,15 @@ impl SnapshotEncoder {
         //     forall args... :: {cons(arg_field, other_args...)}
         //       field(cons(arg_field, other_args...)) == arg_field
         //     ```
+        //
+        // A variant with more than `config::snapshot_large_struct_fields()`
+        // fields gets a single field access axiom that is the conjunction of
+        // the axioms of its fields, and its injectivity axiom is triggered by
+        // groups of `config::snapshot_field_group_size()` field accesses.
+        let large_struct_fields = config::snapshot_large_struct_fields();
+        let field_group_size = config::snapshot_field_group_size().max(1);
         for (variant_idx, variant) in variants.iter().enumerate() {
+            let is_large_variant = variant.fields.len() > large_struct_fields;
             let constructor_name = format!("cons${variant_idx}$");
             let args = variant
                 .fields
@@ -1765,6 +1773,7 @@ impl SnapshotEncoder {
             }
 
             let mut field_access_funcs = FxHashMap::default();
+            let mut field_of_cons_equalities = vec![];
 
             let self_local = vir::LocalVar::new("self", snapshot_type.clone());
             let self_expr = Expr::local(self_local.clone());
@@ -1783,25 +1792,27 @@ impl SnapshotEncoder {
                 field_access_funcs.insert(field.name.to_string(), field_access_func.clone());
 
                 // encode field access axiom
-                domain_axioms.push({
-                    let args = encode_prefixed_args("");
+                let field_of_cons_equality = {
                     let call = encode_constructor_call(&args);
-                    let field_of_cons = field_access_func.apply(vec![call.clone()]);
-
-                    vir::DomainAxiom {
+                    Expr::eq_cmp(
+                        field_access_func.apply(vec![call]),
+                        Expr::local(args[field_idx].clone()),
+                    )
+                };
+                if is_large_variant {
+                    field_of_cons_equalities.push(field_of_cons_equality);
+                } else {
+                    domain_axioms.push(vir::DomainAxiom {
                         comment: None,
                         name: format!("{}${}$field${}$axiom", domain_name, variant_idx, field.name),
                         expr: forall_or_body(
                             args.clone(),
-                            vec![vir::Trigger::new(vec![call.clone()])],
-                            Expr::eq_cmp(
-                                field_of_cons.clone(),
-                                Expr::local(args[field_idx].clone()),
-                            ),
+                            vec![vir::Trigger::new(vec![encode_constructor_call(&args)])],
+                            field_of_cons_equality,
                         ),
                         domain_name: domain_name.to_string(),
-                    }
-                });
+                    });
+                }
 
                 // encode type validity axiom for field
                 // TODO: encode type invariants rather than just integer bounds
@@ -1834,6 +1845,19 @@ impl SnapshotEncoder {
                 }
             }
 
+            if !field_of_cons_equalities.is_empty() {
+                domain_axioms.push(vir::DomainAxiom {
+                    comment: None,
+                    name: format!("{domain_name}${variant_idx}$fields$axiom"),
+                    expr: forall_or_body(
+                        args.clone(),
+                        vec![vir::Trigger::new(vec![encode_constructor_call(&args)])],
+                        field_of_cons_equalities.into_iter().conjoin(),
+                    ),
+                    domain_name: domain_name.to_string(),
+                });
+            }
+
             if !args.is_empty() {
                 let field_access_apps: Vec<_> = variant
                     .fields
@@ -1845,11 +1869,16 @@ impl SnapshotEncoder {
                         field_access_func.apply(vec![self_expr.clone()])
                     })
                     .collect();
+                let trigger_group_size = if is_large_variant {
+                    field_group_size
+                } else {
+                    1
+                };
                 let expr = Expr::forall(
                     vec![self_local.clone()],
                     field_access_apps
-                        .iter()
-                        .map(|e| vir::Trigger::new(vec![e.clone()]))
+                        .chunks(trigger_group_size)
+                        .map(|group| vir::Trigger::new(group.to_vec()))
                         .collect(),
                     Expr::eq_cmp(self_expr.clone(), constructor.apply(field_access_apps)),
                 );
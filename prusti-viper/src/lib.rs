@@ -22,6 +22,9 @@
 #![allow(clippy::needless_lifetimes)]
 #![allow(clippy::needless_pass_by_ref_mut)] // see https://github.com/rust-lang/rust-clippy/issues/11179
 
+pub mod coverage;
 pub mod encoder;
+mod sarif;
+mod semantic_cache;
 mod utils;
 pub mod verifier;
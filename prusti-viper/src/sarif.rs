@@ -0,0 +1,208 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Export of verification results in the SARIF 2.1.0 format, as consumed by
+//! GitHub code scanning. See `config::sarif_path`.
+
+use prusti_common::report::{error_codes::ErrorCode, sarif};
+use prusti_interface::error_report::{ErrorReport, SpanReport, VerificationReport};
+use serde_json::{json, Value};
+use std::{io, path::Path};
+
+/// Writes the results of verifying `crate_name` to the SARIF log at `path`.
+/// Runs of other crates already stored for the log are kept, so that a single
+/// log covers all crates verified by `cargo prusti`. The active `cfg` options
+/// of the crate (e.g. its features and target) are recorded in the run.
+pub fn write_sarif_log(
+    path: &Path,
+    crate_name: &str,
+    prusti_version: &str,
+    cfgs: &[String],
+    reports: &[VerificationReport],
+) -> io::Result<()> {
+    let automation_id = format!("prusti/{crate_name}/");
+    let run = sarif_run(&automation_id, prusti_version, cfgs, reports);
+    sarif::write_run(path, crate_name, &run)
+}
+
+fn sarif_run(
+    automation_id: &str,
+    prusti_version: &str,
+    cfgs: &[String],
+    reports: &[VerificationReport],
+) -> Value {
+    let rules: Vec<Value> = ErrorCode::ALL
+        .iter()
+        .map(|code| {
+            json!({
+                "id": code.code(),
+                "name": code.name(),
+                "shortDescription": { "text": code.summary() },
+                "fullDescription": { "text": code.summary() },
+                "help": { "text": code.explanation(), "markdown": code.explanation() },
+                "defaultConfiguration": { "level": "error" },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = reports
+        .iter()
+        .flat_map(|report| &report.errors)
+        .map(sarif_result)
+        .collect();
+    json!({
+        "tool": {
+            "driver": {
+                "name": "Prusti",
+                "informationUri": "https://github.com/viperproject/prusti-dev",
+                "version": prusti_version,
+                "rules": rules,
+            }
+        },
+        "automationDetails": { "id": automation_id },
+        "columnKind": "unicodeCodePoints",
+        "results": results,
+        "properties": { "cfg": cfgs },
+    })
+}
+
+fn sarif_result(error: &ErrorReport) -> Value {
+    let mut text = error.message.clone();
+    if let Some(help) = &error.help {
+        text = format!("{text}\nhelp: {help}");
+    }
+    let locations: Vec<Value> = error.spans.iter().map(sarif_location).collect();
+    let failing_assertion = error
+        .failing_assertion
+        .iter()
+        .map(|span| (span, "the failing specification or assertion"));
+    let notes = error
+        .notes
+        .iter()
+        .chain(&error.counterexample)
+        .flat_map(|note| note.spans.iter().map(|span| (span, note.message.as_str())));
+    let related_locations: Vec<Value> = failing_assertion
+        .chain(notes)
+        .enumerate()
+        .map(|(id, (span, message))| {
+            let mut location = sarif_location(span);
+            location["id"] = json!(id);
+            location["message"] = json!({ "text": message });
+            location
+        })
+        .collect();
+    let mut result = json!({
+        "level": error.level,
+        "message": { "text": text },
+        "locations": locations,
+    });
+    if let Some(code) = error.code.and_then(ErrorCode::from_code) {
+        result["ruleId"] = json!(code.code());
+        result["ruleIndex"] = json!(ErrorCode::ALL.iter().position(|&c| c == code));
+    }
+    if !related_locations.is_empty() {
+        result["relatedLocations"] = json!(related_locations);
+    }
+    result
+}
+
+fn sarif_location(span: &SpanReport) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": span.file_name.replace('\\', "/") },
+            "region": {
+                "startLine": span.line_start,
+                "startColumn": span.column_start,
+                "endLine": span.line_end,
+                "endColumn": span.column_end,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prusti_interface::error_report::NoteReport;
+
+    fn span(line: usize) -> SpanReport {
+        SpanReport {
+            file_name: "src\\main.rs".to_string(),
+            line_start: line,
+            column_start: 5,
+            line_end: line,
+            column_end: 19,
+        }
+    }
+
+    fn report() -> VerificationReport {
+        let error = ErrorReport {
+            code: Some("P0003"),
+            level: "error",
+            message: "the asserted expression might not hold".to_string(),
+            spans: vec![span(3)],
+            failing_assertion: vec![span(2)],
+            help: Some("add a precondition".to_string()),
+            notes: vec![],
+            counterexample: vec![NoteReport {
+                message: "counterexample for \"x\"".to_string(),
+                spans: vec![span(1)],
+            }],
+        };
+        VerificationReport::new("main".to_string(), 0, vec![error])
+    }
+
+    #[test]
+    fn run_lists_all_error_codes_as_rules() {
+        let run = sarif_run("prusti/foo/", "0.1.0", &[], &[report()]);
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), ErrorCode::ALL.len());
+        assert_eq!(rules[0]["id"], ErrorCode::ALL[0].code());
+        assert_eq!(run["tool"]["driver"]["version"], "0.1.0");
+        assert_eq!(run["automationDetails"]["id"], "prusti/foo/");
+    }
+
+    #[test]
+    fn run_records_the_active_cfgs() {
+        let cfgs = vec!["feature=\"std\"".to_string()];
+        let run = sarif_run("prusti/foo/", "0.1.0", &cfgs, &[]);
+        assert_eq!(run["properties"]["cfg"], json!(["feature=\"std\""]));
+        assert_eq!(run["results"], json!([]));
+    }
+
+    #[test]
+    fn result_refers_to_its_rule_and_related_locations() {
+        let run = sarif_run("prusti/foo/", "0.1.0", &[], &[report()]);
+        let result = &run["results"][0];
+        let code = ErrorCode::from_code("P0003").unwrap();
+        assert_eq!(result["ruleId"], "P0003");
+        assert_eq!(
+            result["ruleIndex"],
+            json!(ErrorCode::ALL.iter().position(|&c| c == code))
+        );
+        assert_eq!(result["level"], "error");
+        assert_eq!(
+            result["message"]["text"],
+            "the asserted expression might not hold\nhelp: add a precondition"
+        );
+        assert_eq!(
+            result["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "src/main.rs" },
+                "region": { "startLine": 3, "startColumn": 5, "endLine": 3, "endColumn": 19 },
+            })
+        );
+        let related = result["relatedLocations"].as_array().unwrap();
+        assert_eq!(related.len(), 2);
+        assert_eq!(related[0]["id"], 0);
+        assert_eq!(
+            related[0]["message"]["text"],
+            "the failing specification or assertion"
+        );
+        assert_eq!(related[0]["physicalLocation"]["region"]["startLine"], 2);
+        assert_eq!(related[1]["id"], 1);
+        assert_eq!(related[1]["message"]["text"], "counterexample for \"x\"");
+    }
+}
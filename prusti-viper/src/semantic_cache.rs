@@ -0,0 +1,106 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Per-crate record of the procedures that verified successfully, keyed by
+//! their semantic hash, see `config::semantic_cache_dir`.
+
+use prusti_common::utils::files::write_atomically;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SemanticCache {
+    /// A hash of the Prusti version and configuration that the procedures were
+    /// verified with.
+    configuration: u64,
+    /// The semantic hash of each procedure that verified successfully, keyed
+    /// by its def path.
+    verified: BTreeMap<String, u64>,
+    /// The outcome of the last verification of the crate, which `cargo prusti`
+    /// reports for crates that it does not verify again.
+    #[serde(default)]
+    summary: VerificationSummary,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct VerificationSummary {
+    /// The number of procedures that were verified successfully.
+    pub verified: usize,
+    /// The number of procedures that were skipped, because they verified in
+    /// an earlier run and did not change since.
+    pub cached: usize,
+    /// The number of procedures that failed to verify or could not be
+    /// encoded.
+    pub failed: usize,
+}
+
+impl SemanticCache {
+    /// Loads the cache of `crate_name` from `dir`. The cache is empty if it
+    /// does not exist, cannot be read, or was written with a different
+    /// `configuration`.
+    pub fn load(dir: &Path, crate_name: &str, configuration: u64) -> Self {
+        fs::read_to_string(dir.join(format!("{crate_name}.json")))
+            .ok()
+            .and_then(|content| serde_json::from_str::<SemanticCache>(&content).ok())
+            .filter(|cache| cache.configuration == configuration)
+            .unwrap_or_else(|| SemanticCache {
+                configuration,
+                verified: BTreeMap::new(),
+                summary: VerificationSummary::default(),
+            })
+    }
+
+    pub fn is_verified(&self, item: &str, hash: u64) -> bool {
+        self.verified.get(item) == Some(&hash)
+    }
+
+    /// Records whether `item` verified successfully, replacing an earlier
+    /// entry of the same item.
+    pub fn record(&mut self, item: String, hash: u64, verified: bool) {
+        if verified {
+            self.verified.insert(item, hash);
+        } else {
+            self.verified.remove(&item);
+        }
+    }
+
+    pub fn set_summary(&mut self, summary: VerificationSummary) {
+        self.summary = summary;
+    }
+
+    /// Writes the cache as `<crate name>.json` into `dir`. The file is
+    /// replaced atomically, so that a crate verified concurrently, e.g. as a
+    /// library and as a test, never reads a partially written cache.
+    pub fn write(&self, dir: &Path, crate_name: &str) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{crate_name}.json"));
+        write_atomically(&path, &serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_is_reloaded_with_the_same_configuration_only() {
+        let dir =
+            std::env::temp_dir().join(format!("prusti-semantic-cache-{}", std::process::id()));
+        let mut cache = SemanticCache::load(&dir, "krate", 1);
+        cache.record("krate::verified".to_string(), 10, true);
+        cache.record("krate::failed".to_string(), 20, false);
+        cache.write(&dir, "krate").unwrap();
+
+        let reloaded = SemanticCache::load(&dir, "krate", 1);
+        assert!(reloaded.is_verified("krate::verified", 10));
+        assert!(!reloaded.is_verified("krate::verified", 11));
+        assert!(!reloaded.is_verified("krate::failed", 20));
+        assert!(!SemanticCache::load(&dir, "krate", 2).is_verified("krate::verified", 10));
+        // No temporary files are left behind.
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -48,3 +48,13 @@ pub fn ty_to_string(typ: &ty::TyKind) -> String {
 pub fn is_reference(base_ty: ty::Ty) -> bool {
     matches!(base_ty.kind(), ty::TyKind::RawPtr(..) | ty::TyKind::Ref(..))
 }
+
+/// Returns the name under which the standard library exports a macro of the
+/// core library, e.g. `std::assert` for `core::assert`. In `#![no_std]` crates
+/// the macros used by the panic classification are only visible through `core`.
+pub fn std_macro_name(name: String) -> String {
+    match name.strip_prefix("core::") {
+        Some(macro_name) if !macro_name.contains("::") => format!("std::{macro_name}"),
+        _ => name,
+    }
+}
@@ -4,31 +4,48 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::encoder::{
-    counterexamples::{counterexample_translation, counterexample_translation_refactored},
-    Encoder,
+use crate::{
+    coverage::{CoverageItem, CoverageReport, CoverageStatus},
+    encoder::{
+        counterexamples::{counterexample_translation, counterexample_translation_refactored},
+        Encoder,
+    },
+    sarif,
+    semantic_cache::{SemanticCache, VerificationSummary},
 };
 use ::log::{debug, error, info};
 use once_cell::sync::Lazy;
 use prusti_common::{
     config,
-    report::log,
+    report::{error_codes::ErrorCode, log, user},
     vir::{optimizations::optimize_program, program::Program},
     Stopwatch,
 };
 use prusti_interface::{
-    data::{VerificationResult, VerificationTask},
+    data::{ProcedureDefId, VerificationResult, VerificationTask},
     environment::Environment,
+    error_report::{SpanReport, VerificationEvent, VerificationReport},
     specs::typed,
+    utils::{read_prusti_attr_int, ItemConfigValue},
     PrustiError,
 };
 use prusti_rustc_interface::span::DUMMY_SP;
 use prusti_server::{
-    process_verification_request, spawn_server_thread, tokio::runtime::Builder, PrustiClient,
-    VerificationRequest, ViperBackendConfig,
+    load_cache, process_verification_request, spawn_server_thread, tokio::runtime::Builder,
+    PrustiClient, ServerPool, VerificationRequest, VerifierPool, ViperBackendConfig,
 };
-use viper::{self, PersistentCache, Viper};
-use vir_crate::common::check_mode::CheckMode;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet},
+    fmt::Write,
+    hash::{Hash, Hasher},
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use viper::{self, PersistentCache, VerificationBackend, Viper};
+use vir_crate::{common::check_mode::CheckMode, polymorphic as vir};
 
 /// A verifier is an object for verifying a single crate, potentially
 /// many times.
@@ -56,6 +73,7 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         if config::dump_debug_info() {
             log::report("config", "prusti", config::dump());
         }
+        self.warn_about_unsupported_backend_features();
 
         for &proc_id in &task.procedures {
             let proc_name = self.env.name.get_absolute_item_name(proc_id);
@@ -64,9 +82,42 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
             info!(" - {} ({})", proc_name, proc_def_path);
             info!("   Source: {:?}", proc_span);
         }
+        let semantic_cache = config::semantic_cache_dir().map(|dir| {
+            let hashes = self.encoder.get_semantic_hashes(&task.procedures);
+            let cache = SemanticCache::load(
+                &dir,
+                &self.env.name.local_crate_name(),
+                self.configuration_hash(),
+            );
+            (dir, cache, hashes)
+        });
+        // Procedures that verified in an earlier run and did not change since.
+        let cached_procedures: FxHashSet<_> = semantic_cache
+            .as_ref()
+            .map(|(_, cache, hashes)| {
+                task.procedures
+                    .iter()
+                    .copied()
+                    .filter(|proc_id| {
+                        cache.is_verified(
+                            &self.env.name.get_item_def_path(*proc_id),
+                            hashes[proc_id],
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !cached_procedures.is_empty() {
+            info!(
+                "Skipping {} procedures that were verified in an earlier run",
+                cached_procedures.len()
+            );
+        }
         for &proc_id in task.procedures.iter().rev() {
             // FIXME: Use the loop above.
-            self.encoder.queue_procedure_encoding(proc_id);
+            if !cached_procedures.contains(&proc_id) {
+                self.encoder.queue_procedure_encoding(proc_id);
+            }
         }
         for &type_id in task.types.iter().rev() {
             // FIXME: Use the loop above.
@@ -75,15 +126,23 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         self.encoder.process_encoding_queue();
 
         let encoding_errors_count = self.encoder.count_encoding_errors();
+        if config::check_only() {
+            stopwatch.finish();
+            return if encoding_errors_count == 0 {
+                VerificationResult::Success
+            } else {
+                VerificationResult::Failure
+            };
+        }
 
         let polymorphic_programs = self.encoder.get_viper_programs();
 
         let mut programs: Vec<Program> = if config::simplify_encoding() {
             stopwatch.start_next("optimizing Viper program");
             let source_file_name = self.encoder.env().name.source_file_name();
-            polymorphic_programs
+            optimize_programs(polymorphic_programs, &source_file_name)
                 .into_iter()
-                .map(|program| Program::Legacy(optimize_program(program, &source_file_name).into()))
+                .map(|program| Program::Legacy(program.into()))
                 .collect()
         } else {
             polymorphic_programs
@@ -93,15 +152,25 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         };
         programs.extend(self.encoder.get_core_proof_programs());
 
+        let procedure_settings = self.procedure_settings(&task.procedures);
+        if let Some(dir) = config::vir_export_dir() {
+            self.export_vir_programs(&dir, &programs, &procedure_settings);
+        }
+        let progress = self.progress_reporter(&task.procedures);
         stopwatch.start_next("verifying Viper program");
-        let verification_results = verify_programs(self.env, programs);
+        let verification_results =
+            verify_programs(self.env, programs, &procedure_settings, &progress);
         stopwatch.finish();
 
         // Group verification results
         let mut verification_errors: Vec<_> = vec![];
         let mut consistency_errors: Vec<_> = vec![];
         let mut java_exceptions: Vec<_> = vec![];
-        for (method_name, result) in verification_results.into_iter() {
+        let mut backend_errors: Vec<_> = vec![];
+        let mut timed_out_methods: Vec<_> = vec![];
+        let mut verification_times: Vec<_> = vec![];
+        for (method_name, result, duration) in verification_results.into_iter() {
+            verification_times.push((method_name.clone(), duration));
             match result {
                 viper::VerificationResult::Success => {}
                 viper::VerificationResult::ConsistencyErrors(errors) => {
@@ -117,14 +186,25 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
                 viper::VerificationResult::JavaException(exception) => {
                     java_exceptions.push((method_name, exception));
                 }
+                viper::VerificationResult::BackendErrors(errors) => {
+                    for error in errors.into_iter() {
+                        backend_errors.push((method_name.clone(), error));
+                    }
+                }
+                viper::VerificationResult::Timeout => {
+                    timed_out_methods.push(method_name);
+                }
             }
         }
 
         // Convert verification results to Prusti errors
         let error_manager = self.encoder.error_manager();
         let mut result = VerificationResult::Success;
+        let mut failed_methods = FxHashSet::default();
+        let mut failed_procedures = FxHashSet::default();
 
         for (method, error) in consistency_errors.into_iter() {
+            failed_methods.insert(method.clone());
             PrustiError::internal(
                 format!("consistency error in {method}: {error}"),
                 DUMMY_SP.into(),
@@ -134,48 +214,73 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
         }
 
         for (method, exception) in java_exceptions.into_iter() {
+            failed_methods.insert(method.clone());
             error!("Java exception: {}", exception.get_stack_trace());
             PrustiError::internal(format!("in {method}: {exception}"), DUMMY_SP.into())
                 .emit(&self.env.diagnostic);
             result = VerificationResult::Failure;
         }
 
+        for (method, error) in backend_errors.into_iter() {
+            failed_methods.insert(method.clone());
+            PrustiError::internal(
+                format!("the verification backend failed in {method}: {error}"),
+                DUMMY_SP.into(),
+            )
+            .emit(&self.env.diagnostic);
+            result = VerificationResult::Failure;
+        }
+
         // Report verification errors
         let mut prusti_errors: Vec<_> = vec![];
+        for method in timed_out_methods {
+            let ProcedureSettings {
+                proc_id,
+                time_limit,
+                ..
+            } = procedure_settings[&method];
+            self.encoder
+                .record_coverage(proc_id, CoverageStatus::TimedOut);
+            failed_methods.insert(method.clone());
+            let prusti_error = PrustiError::verification(
+                format!(
+                    "the verification timed out after {} seconds",
+                    time_limit.unwrap_or_default()
+                ),
+                self.env.query.get_def_span(proc_id).into(),
+            )
+            .set_code(ErrorCode::TimedOut)
+            .set_help("increase the time limit given with `prusti::time_limit`");
+            prusti_errors.push((method, prusti_error));
+        }
         for (method, verification_error) in verification_errors.into_iter() {
             debug!("Verification error in {}: {:?}", method, verification_error);
             let mut prusti_error = error_manager.translate_verification_error(&verification_error);
+            if !prusti_error.is_disabled() {
+                failed_methods.insert(method.clone());
+                failed_procedures.extend(error_manager.get_def_id(&verification_error));
+            }
 
             // annotate with counterexample, if requested
             if config::counterexample() {
-                if config::unsafe_core_proof() {
-                    if let Some(silicon_counterexample) = &verification_error.counterexample {
-                        if let Some(def_id) = error_manager.get_def_id(&verification_error) {
-                            let counterexample =
-                                counterexample_translation_refactored::backtranslate(
-                                    &self.encoder,
-                                    error_manager.position_manager(),
-                                    def_id,
-                                    silicon_counterexample,
-                                );
-                            prusti_error = counterexample.annotate_error(prusti_error);
-                        } else {
-                            prusti_error = prusti_error.add_note(
-                                format!(
-                                    "the verifier produced a counterexample for {method}, but it could not be mapped to source code"
-                                ),
-                                None,
-                            );
-                        }
-                    }
-                } else if let Some(silicon_counterexample) = &verification_error.counterexample {
+                if let Some(silicon_counterexample) = &verification_error.counterexample {
                     if let Some(def_id) = error_manager.get_def_id(&verification_error) {
-                        let counterexample = counterexample_translation::backtranslate(
-                            &self.encoder,
-                            def_id,
-                            silicon_counterexample,
-                        );
-                        prusti_error = counterexample.annotate_error(prusti_error);
+                        prusti_error = if self.encoder.uses_core_proof(def_id) {
+                            counterexample_translation_refactored::backtranslate(
+                                &self.encoder,
+                                error_manager.position_manager(),
+                                def_id,
+                                silicon_counterexample,
+                            )
+                            .annotate_error(prusti_error)
+                        } else {
+                            counterexample_translation::backtranslate(
+                                &self.encoder,
+                                def_id,
+                                silicon_counterexample,
+                            )
+                            .annotate_error(prusti_error)
+                        };
                     } else {
                         prusti_error = prusti_error.add_note(
                             format!(
@@ -187,11 +292,48 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
                 }
             }
 
-            prusti_errors.push(prusti_error);
+            prusti_errors.push((method, prusti_error));
         }
-        prusti_errors.sort();
+        // Several failing Viper assertions may be translated to the same error,
+        // e.g. when a Rust assertion is encoded more than once. Group the errors
+        // by method so that such duplicates are adjacent, then report them in
+        // source order.
+        prusti_errors
+            .sort_by(|(method_a, a), (method_b, b)| method_a.cmp(method_b).then_with(|| a.cmp(b)));
+        prusti_errors.dedup_by(|(method_a, a), (method_b, b)| method_a == method_b && a == b);
+        prusti_errors.sort_by(|(_, a), (_, b)| a.cmp(b));
 
-        for prusti_error in prusti_errors {
+        let sarif_path = config::sarif_path();
+        if config::json_diagnostics() || sarif_path.is_some() {
+            let reports = self.verification_reports(&verification_times, &prusti_errors);
+            if config::json_diagnostics() {
+                for report in &reports {
+                    eprintln!(
+                        "{}",
+                        serde_json::to_string(report)
+                            .expect("failed to serialize verification report")
+                    );
+                }
+            }
+            if let Some(path) = sarif_path {
+                if let Err(error) = sarif::write_sarif_log(
+                    &path,
+                    &self.env.name.local_crate_name(),
+                    self.env.get_prusti_version(),
+                    &self.active_cfgs(),
+                    &reports,
+                ) {
+                    PrustiError::internal(
+                        format!("failed to write SARIF log to {}: {error}", path.display()),
+                        DUMMY_SP.into(),
+                    )
+                    .emit(&self.env.diagnostic);
+                    result = VerificationResult::Failure;
+                }
+            }
+        }
+
+        for (_, prusti_error) in prusti_errors {
             debug!("Prusti error: {:?}", prusti_error);
             if prusti_error.is_disabled() {
                 prusti_error.cancel();
@@ -205,16 +347,466 @@ impl<'v, 'tcx> Verifier<'v, 'tcx> {
             result = VerificationResult::Failure;
         }
 
+        if let Some(dir) = config::flamegraph_dir() {
+            self.write_flamegraph(&dir, &verification_times);
+        }
+
+        if let Some((dir, mut cache, hashes)) = semantic_cache {
+            let mut summary = VerificationSummary::default();
+            for &proc_id in &task.procedures {
+                let status = self.procedure_status(
+                    proc_id,
+                    &cached_procedures,
+                    &failed_methods,
+                    &failed_procedures,
+                );
+                match status {
+                    CoverageStatus::Verified if cached_procedures.contains(&proc_id) => {
+                        summary.cached += 1
+                    }
+                    CoverageStatus::Verified => summary.verified += 1,
+                    CoverageStatus::Failed
+                    | CoverageStatus::TimedOut
+                    | CoverageStatus::Unsupported => summary.failed += 1,
+                    CoverageStatus::Trusted | CoverageStatus::PureOnly => {}
+                }
+                cache.record(
+                    self.env.name.get_item_def_path(proc_id),
+                    hashes[&proc_id],
+                    status == CoverageStatus::Verified,
+                );
+            }
+            cache.set_summary(summary);
+            if let Err(error) = cache.write(&dir, &self.env.name.local_crate_name()) {
+                PrustiError::internal(
+                    format!(
+                        "failed to write semantic cache to {}: {error}",
+                        dir.display()
+                    ),
+                    DUMMY_SP.into(),
+                )
+                .emit(&self.env.diagnostic);
+            }
+        }
+
+        if config::assume_dependency_contracts() {
+            let assumed_dependencies: BTreeSet<_> = self
+                .encoder
+                .get_assumed_dependencies()
+                .iter()
+                .map(|&krate| self.env.name.crate_name(krate))
+                .collect();
+            if !assumed_dependencies.is_empty() {
+                user::message(format!(
+                    "Assumed the contracts of {} dependencies: {}",
+                    assumed_dependencies.len(),
+                    assumed_dependencies
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+
+        if config::assume_unimplemented() {
+            let assumed_unimplemented = self.encoder.get_assumed_unimplemented();
+            for &span in assumed_unimplemented.iter() {
+                PrustiError::warning(
+                    "this statement was assumed to be unreachable, see `assume_unimplemented`",
+                    span.into(),
+                )
+                .emit(&self.env.diagnostic);
+            }
+            if !assumed_unimplemented.is_empty() {
+                user::message(format!(
+                    "Assumed {} todo!() or unimplemented!() statements to be unreachable",
+                    assumed_unimplemented.len()
+                ));
+            }
+        }
+
+        if let Some(dir) = config::coverage_report_dir() {
+            self.report_coverage(
+                &dir,
+                task,
+                &cached_procedures,
+                &failed_methods,
+                &failed_procedures,
+            );
+        }
+
         result
     }
+
+    /// Write each program as JSON to `<dir>/<export name>.json`, see
+    /// `config::vir_export_dir`. Programs that do not encode a procedure are
+    /// exported under their own name.
+    fn export_vir_programs(
+        &self,
+        dir: &Path,
+        programs: &[Program],
+        procedure_settings: &FxHashMap<String, ProcedureSettings>,
+    ) {
+        if let Err(error) = std::fs::create_dir_all(dir) {
+            PrustiError::internal(
+                format!("failed to create {}: {error}", dir.display()),
+                DUMMY_SP.into(),
+            )
+            .emit(&self.env.diagnostic);
+            return;
+        }
+        for program in programs {
+            let export_name = procedure_settings
+                .get(program.get_name())
+                .and_then(|settings| settings.export_name.as_deref())
+                .unwrap_or(program.get_name());
+            let path = dir.join(format!(
+                "{}.json",
+                program.get_export_file_name(export_name)
+            ));
+            let json = serde_json::to_string(program).expect("failed to serialize VIR program");
+            if let Err(error) = std::fs::write(&path, json) {
+                PrustiError::internal(
+                    format!(
+                        "failed to export VIR program to {}: {error}",
+                        path.display()
+                    ),
+                    DUMMY_SP.into(),
+                )
+                .emit(&self.env.diagnostic);
+            }
+        }
+    }
+
+    /// Write the encoding and verification time of each procedure in the
+    /// folded stacks format, see `config::flamegraph_dir`. Each line has the
+    /// form `<crate>;<procedure>;<phase> <microseconds>`.
+    fn write_flamegraph(&self, dir: &Path, verification_times: &[(String, Duration)]) {
+        let crate_name = self.env.name.local_crate_name();
+        // Verification times are keyed by the program name, which is the unique
+        // name of the procedure.
+        let mut frame_names = FxHashMap::default();
+        let mut samples = Vec::new();
+        for (&proc_id, duration) in self.encoder.get_procedure_encoding_times() {
+            let frame_name = self.env.name.get_item_def_path(proc_id);
+            frame_names.insert(
+                self.env.name.get_unique_item_name(proc_id),
+                frame_name.clone(),
+            );
+            samples.push((frame_name, "encoding", *duration));
+        }
+        for (program_name, duration) in verification_times {
+            let frame_name = frame_names
+                .get(program_name)
+                .cloned()
+                .unwrap_or_else(|| program_name.clone());
+            samples.push((frame_name, "verification", *duration));
+        }
+        let folded = folded_stacks(&crate_name, samples);
+        let path = dir.join(format!("{crate_name}.folded"));
+        if let Err(error) =
+            std::fs::create_dir_all(dir).and_then(|()| std::fs::write(&path, folded))
+        {
+            PrustiError::internal(
+                format!(
+                    "failed to write flamegraph data to {}: {error}",
+                    path.display()
+                ),
+                DUMMY_SP.into(),
+            )
+            .emit(&self.env.diagnostic);
+        }
+    }
+
+    /// How far `proc_id` was verified. Procedures that were skipped because
+    /// of the semantic cache count as verified.
+    fn procedure_status(
+        &self,
+        proc_id: ProcedureDefId,
+        cached_procedures: &FxHashSet<ProcedureDefId>,
+        failed_methods: &FxHashSet<String>,
+        failed_procedures: &FxHashSet<ProcedureDefId>,
+    ) -> CoverageStatus {
+        if cached_procedures.contains(&proc_id) {
+            return CoverageStatus::Verified;
+        }
+        match self.encoder.get_procedure_coverage(proc_id) {
+            Some(CoverageStatus::Verified)
+                if failed_procedures.contains(&proc_id)
+                    || failed_methods.contains(&self.env.name.get_unique_item_name(proc_id)) =>
+            {
+                CoverageStatus::Failed
+            }
+            Some(status) => status,
+            None => CoverageStatus::Unsupported,
+        }
+    }
+
+    /// The reporter of the verification progress of `procedures`, see
+    /// `config::report_progress` and `config::json_progress`.
+    fn progress_reporter(&self, procedures: &[ProcedureDefId]) -> ProgressReporter {
+        let mut item_spans = FxHashMap::default();
+        if config::json_progress() {
+            let source_map = self.env.query.codemap();
+            for &proc_id in procedures {
+                item_spans.insert(
+                    self.env.name.get_unique_item_name(proc_id),
+                    SpanReport::from_span(source_map, self.env.query.get_def_span(proc_id)),
+                );
+            }
+        }
+        ProgressReporter { item_spans }
+    }
+
+    /// Warns about the flags that have no effect with the selected backend.
+    fn warn_about_unsupported_backend_features(&self) {
+        let backend = match config::viper_backend().parse() {
+            Ok(backend @ (VerificationBackend::Carbon | VerificationBackend::Native)) => backend,
+            _ => return,
+        };
+        let mut unsupported = vec![];
+        if config::counterexample() {
+            unsupported.push("counterexamples are");
+        }
+        if config::smt_solver() != "z3" {
+            unsupported.push("SMT solvers other than Z3 are");
+        }
+        if config::check_timeout().is_some() && backend == VerificationBackend::Carbon {
+            unsupported.push("the `check_timeout` flag is");
+        }
+        for feature in unsupported {
+            PrustiError::warning(
+                format!("{feature} not supported by the {backend} backend, see `viper_backend`"),
+                DUMMY_SP.into(),
+            )
+            .emit(&self.env.diagnostic);
+        }
+    }
+
+    /// The verifier settings given with attributes on the procedures, and their
+    /// export names, keyed by the name of the program that encodes each
+    /// procedure.
+    fn procedure_settings(
+        &self,
+        procedures: &[ProcedureDefId],
+    ) -> FxHashMap<String, ProcedureSettings> {
+        let mut settings = FxHashMap::default();
+        for &proc_id in procedures {
+            let procedure_settings = ProcedureSettings {
+                proc_id,
+                time_limit: self.read_limit_attr(
+                    proc_id,
+                    "time_limit",
+                    "a positive number of seconds",
+                    1,
+                ),
+                rlimit: self.read_limit_attr(proc_id, "smt_rlimit", "a positive resource limit", 1),
+                random_seed: self.read_limit_attr(proc_id, "smt_random_seed", "a seed", 0),
+                export_name: (config::viper_export_dir().is_some()
+                    || config::vir_export_dir().is_some())
+                .then(|| self.env.name.get_absolute_item_name(proc_id)),
+            };
+            if procedure_settings.time_limit.is_some()
+                && matches!(
+                    config::viper_backend().parse(),
+                    Ok(VerificationBackend::Carbon)
+                )
+            {
+                PrustiError::warning(
+                    "the `prusti::time_limit` attribute is not supported by the Carbon backend \
+                    and is ignored",
+                    self.env.query.get_def_span(proc_id).into(),
+                )
+                .emit(&self.env.diagnostic);
+            }
+            if procedure_settings.time_limit.is_some()
+                || procedure_settings.rlimit.is_some()
+                || procedure_settings.random_seed.is_some()
+                || procedure_settings.export_name.is_some()
+            {
+                settings.insert(
+                    self.env.name.get_unique_item_name(proc_id),
+                    procedure_settings,
+                );
+            }
+        }
+        settings
+    }
+
+    /// Reads the argument of a `prusti::<name>(value)` attribute, which has to
+    /// be at least `min`. Without such an attribute, the value given with
+    /// `prusti::config(<name> = value)` on the procedure or on an enclosing
+    /// item is used.
+    fn read_limit_attr(
+        &self,
+        proc_id: ProcedureDefId,
+        name: &str,
+        expected: &str,
+        min: u64,
+    ) -> Option<u64> {
+        let attrs = self.env.query.get_attributes(proc_id);
+        let value = match read_prusti_attr_int(name, attrs) {
+            Some(value) => value,
+            None => match self.env.query.item_config(proc_id, name)? {
+                ItemConfigValue::Int(value) => Some(value),
+                ItemConfigValue::Bool(_) => None,
+            },
+        };
+        match value {
+            Some(value) if value >= min as u128 && value <= u64::MAX as u128 => Some(value as u64),
+            _ => {
+                PrustiError::incorrect(
+                    format!(
+                        "the `prusti::{name}` attribute expects {expected}, e.g. `prusti::{name}(30)`"
+                    ),
+                    self.env.query.get_def_span(proc_id).into(),
+                )
+                .emit(&self.env.diagnostic);
+                None
+            }
+        }
+    }
+
+    /// A hash of the Prusti version and configuration, which the verdicts
+    /// stored in the semantic cache depend on.
+    fn configuration_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.env.get_prusti_version().hash(&mut hasher);
+        config::dump().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Summarize which procedures of the task were verified and write the
+    /// report to `dir`, see `config::coverage_report_dir`.
+    fn report_coverage(
+        &self,
+        dir: &Path,
+        task: &VerificationTask<'tcx>,
+        cached_procedures: &FxHashSet<ProcedureDefId>,
+        failed_methods: &FxHashSet<String>,
+        failed_procedures: &FxHashSet<ProcedureDefId>,
+    ) {
+        let source_map = self.env.query.codemap();
+        let items = task
+            .procedures
+            .iter()
+            .map(|&proc_id| {
+                let status = self.procedure_status(
+                    proc_id,
+                    cached_procedures,
+                    failed_methods,
+                    failed_procedures,
+                );
+                let span = self.env.query.get_def_span(proc_id);
+                CoverageItem {
+                    item: self.env.name.get_item_def_path(proc_id),
+                    span: (!span.is_dummy()).then(|| SpanReport::from_span(source_map, span)),
+                    status,
+                }
+            })
+            .collect();
+        let report = CoverageReport::new(
+            self.env.name.local_crate_name(),
+            self.env.get_prusti_version().to_string(),
+            items,
+        );
+        user::message(report.summary_line());
+        if let Err(error) = report.write(dir) {
+            PrustiError::internal(
+                format!(
+                    "failed to write coverage report to {}: {error}",
+                    dir.display()
+                ),
+                DUMMY_SP.into(),
+            )
+            .emit(&self.env.diagnostic);
+        }
+    }
+
+    /// The `cfg` options the crate is compiled with, e.g. `feature="std"` or
+    /// `target_os="linux"`, sorted.
+    fn active_cfgs(&self) -> Vec<String> {
+        let mut cfgs: Vec<String> = self
+            .env
+            .tcx()
+            .sess
+            .parse_sess
+            .config
+            .iter()
+            .map(|(name, value)| match value {
+                Some(value) => format!("{name}={:?}", value.as_str()),
+                None => name.to_string(),
+            })
+            .collect();
+        cfgs.sort();
+        cfgs
+    }
+
+    /// Summarize the result of each verified program, see
+    /// `config::json_diagnostics` and `config::sarif_path`.
+    fn verification_reports(
+        &self,
+        verification_times: &[(String, Duration)],
+        prusti_errors: &[(String, PrustiError)],
+    ) -> Vec<VerificationReport> {
+        let source_map = self.env.query.codemap();
+        verification_times
+            .iter()
+            .map(|(method, duration)| {
+                let errors = prusti_errors
+                    .iter()
+                    .filter(|(error_method, error)| error_method == method && !error.is_disabled())
+                    .map(|(_, error)| error.to_report(source_map))
+                    .collect();
+                VerificationReport::new(method.clone(), duration.as_millis(), errors)
+            })
+            .collect()
+    }
+}
+
+/// Formats the `(procedure, phase, duration)` samples of `crate_name` as folded
+/// stacks, one `<crate>;<procedure>;<phase> <microseconds>` line per sample,
+/// sorted. Semicolons and spaces in procedure names are replaced, since they
+/// separate the frames and the count.
+fn folded_stacks(crate_name: &str, mut samples: Vec<(String, &str, Duration)>) -> String {
+    samples.sort();
+    let mut folded = String::new();
+    for (frame_name, phase, duration) in samples {
+        let frame_name = frame_name.replace([';', ' '], "_");
+        writeln!(
+            folded,
+            "{crate_name};{frame_name};{phase} {}",
+            duration.as_micros()
+        )
+        .unwrap();
+    }
+    folded
+}
+
+/// The verifier settings of a procedure given with attributes, see
+/// `Verifier::procedure_settings`.
+#[derive(Clone, Debug)]
+struct ProcedureSettings {
+    proc_id: ProcedureDefId,
+    /// The time limit in seconds, from `prusti::time_limit`.
+    time_limit: Option<u64>,
+    /// The resource limit of Z3, from `prusti::smt_rlimit`.
+    rlimit: Option<u64>,
+    /// The random seed of Z3, from `prusti::smt_random_seed`.
+    random_seed: Option<u64>,
+    /// The name of the exported Viper program, see `config::viper_export_dir`.
+    export_name: Option<String>,
 }
 
 /// Verify a list of programs.
-/// Returns a list of (program_name, verification_result) tuples.
+/// Returns a list of (program_name, verification_result, verification_time)
+/// tuples.
 fn verify_programs(
     env: &Environment,
     programs: Vec<Program>,
-) -> Vec<(String, viper::VerificationResult)> {
+    procedure_settings: &FxHashMap<String, ProcedureSettings>,
+    progress: &ProgressReporter,
+) -> Vec<(String, viper::VerificationResult, Duration)> {
     let source_path = env.name.source_path();
     let rust_program_name = source_path
         .file_name()
@@ -234,38 +826,81 @@ fn verify_programs(
         }
         .parse()
         .unwrap();
+        let mut backend_config = ViperBackendConfig::new(backend);
+        let settings = procedure_settings.get(&program_name);
+        if let Some(settings) = settings {
+            if let Some(seconds) = settings.time_limit {
+                backend_config.set_time_limit(seconds);
+            }
+            if let Some(rlimit) = settings.rlimit {
+                backend_config.set_rlimit(rlimit);
+            }
+            if let Some(seed) = settings.random_seed {
+                backend_config.set_random_seed(seed);
+            }
+        }
         let request = VerificationRequest {
             program,
-            backend_config: ViperBackendConfig::new(backend),
+            backend_config,
+            export_name: settings.and_then(|settings| settings.export_name.clone()),
         };
         (program_name, request)
     });
-    if let Some(server_address) = config::server_address() {
-        let server_address = if server_address == "MOCK" {
-            spawn_server_thread().to_string()
-        } else {
-            server_address
-        };
-        info!("Connecting to Prusti server at {}", server_address);
-        let client = PrustiClient::new(&server_address).unwrap_or_else(|error| {
-            panic!("Could not parse server address ({server_address}) due to {error:?}")
-        });
+    let mut server_addresses = config::server_addresses();
+    if server_addresses.is_empty() {
+        server_addresses.extend(config::server_address());
+    }
+    if !server_addresses.is_empty() {
+        let clients = server_addresses
+            .into_iter()
+            .map(|server_address| {
+                let server_address = if server_address == "MOCK" {
+                    spawn_server_thread().to_string()
+                } else {
+                    server_address
+                };
+                info!("Connecting to Prusti server at {}", server_address);
+                PrustiClient::new(&server_address).unwrap_or_else(|error| {
+                    panic!("Could not parse server address ({server_address}) due to {error:?}")
+                })
+            })
+            .collect();
+        let pool = ServerPool::new(clients);
+        let priority = config::verification_priority()
+            .parse()
+            .unwrap_or_else(|error| panic!("Invalid verification priority: {error}"));
         // Here we construct a Tokio runtime to block until completion of the futures returned by
-        // `client.verify`. However, to report verification errors as early as possible,
-        // `verify_programs` should return an asynchronous stream of verification results.
+        // the pool. The errors are only reported once all programs are verified, but the
+        // progress is reported as soon as the results arrive.
         let runtime = Builder::new_current_thread()
             .thread_name("prusti-viper")
             .enable_all()
             .build()
             .expect("failed to construct Tokio runtime");
-        verification_requests
-            .map(|(program_name, request)| {
-                let remote_result = runtime.block_on(client.verify(request));
-                let result = remote_result.unwrap_or_else(|error| {
-                    panic!("Verification request of program {program_name} failed: {error:?}")
-                });
-                (program_name, result)
-            })
+        let (program_names, requests): (Vec<_>, Vec<_>) = verification_requests.unzip();
+        // The programs are sent to the servers all at once, so their
+        // verification is reported to begin now.
+        for program_name in &program_names {
+            progress.begin(program_name);
+        }
+        let on_result = {
+            let program_names = program_names.clone();
+            let progress = progress.clone();
+            move |index: usize, result: &viper::VerificationResult, duration| {
+                progress.end(&program_names[index], result, duration)
+            }
+        };
+        // Each server gets at most `verification_threads` requests at a time.
+        let results = runtime.block_on(pool.verify(
+            requests,
+            priority,
+            config::verification_threads(),
+            on_result,
+        ));
+        program_names
+            .into_iter()
+            .zip(results)
+            .map(|(program_name, (result, duration))| (program_name, result, duration))
             .collect()
     } else {
         let mut stopwatch = Stopwatch::start("prusti-viper", "JVM startup");
@@ -274,12 +909,199 @@ fn verify_programs(
             Lazy::new(|| Viper::new_with_args(&config::viper_home(), config::extra_jvm_args()));
         let viper_thread = Lazy::new(|| viper.attach_current_thread());
         stopwatch.finish();
-        let mut cache = PersistentCache::load_cache(config::cache_path());
+        let mut cache = load_cache();
+        let threads = config::verification_threads();
+        if threads > 1 {
+            return verify_in_parallel(
+                &viper,
+                cache,
+                verification_requests.collect(),
+                threads,
+                progress,
+            );
+        }
         verification_requests
             .map(|(program_name, request)| {
-                let result = process_verification_request(&viper_thread, request, &mut cache);
-                (program_name, result)
+                progress.begin(&program_name);
+                let start = Instant::now();
+                let result = process_verification_request(
+                    &viper_thread,
+                    request,
+                    &mut cache,
+                    &mut VerifierPool::default(),
+                );
+                let duration = start.elapsed();
+                progress.end(&program_name, &result, duration);
+                (program_name, result, duration)
             })
             .collect()
     }
 }
+
+/// Prints the progress of the verification of each program, see
+/// `config::report_progress` and `config::json_progress`.
+#[derive(Clone, Debug, Default)]
+struct ProgressReporter {
+    /// The spans of the verified procedures, keyed by the name of their
+    /// program. Only filled if `config::json_progress` is enabled.
+    item_spans: FxHashMap<String, SpanReport>,
+}
+
+impl ProgressReporter {
+    /// Reports that the verification of a program begins.
+    fn begin(&self, program_name: &str) {
+        if config::json_progress() {
+            let span = self.item_spans.get(program_name).cloned();
+            print_event(&VerificationEvent::begin(program_name.to_string(), span));
+        }
+    }
+
+    /// Reports the result of a verified program.
+    fn end(&self, program_name: &str, result: &viper::VerificationResult, duration: Duration) {
+        let verdict = match result {
+            viper::VerificationResult::Success => "succeeded",
+            viper::VerificationResult::Failure(_) => "failed",
+            viper::VerificationResult::Timeout => "timed out",
+            viper::VerificationResult::ConsistencyErrors(_)
+            | viper::VerificationResult::JavaException(_)
+            | viper::VerificationResult::BackendErrors(_) => "aborted",
+        };
+        if config::report_progress() {
+            eprintln!(
+                "Verification of {program_name} {verdict} ({:.2}s)",
+                duration.as_secs_f64()
+            );
+        }
+        if config::json_progress() {
+            let span = self.item_spans.get(program_name).cloned();
+            print_event(&VerificationEvent::end(
+                program_name.to_string(),
+                span,
+                verdict,
+                duration.as_millis(),
+            ));
+        }
+    }
+}
+
+fn print_event(event: &VerificationEvent) {
+    eprintln!(
+        "{}",
+        serde_json::to_string(event).expect("failed to serialize verification event")
+    );
+}
+
+/// Optimizes the encoded programs, with `config::optimization_threads()` worker
+/// threads. The programs are returned in their original order.
+fn optimize_programs(programs: Vec<vir::Program>, source_file_name: &str) -> Vec<vir::Program> {
+    let threads = config::optimization_threads();
+    if threads == 1 {
+        return programs
+            .into_iter()
+            .map(|program| optimize_program(program, source_file_name))
+            .collect();
+    }
+    let queue = Mutex::new(programs.into_iter().enumerate());
+    let results = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap().next();
+                let Some((index, program)) = next else {
+                    break;
+                };
+                let program = optimize_program(program, source_file_name);
+                results.lock().unwrap().push((index, program));
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, program)| program).collect()
+}
+
+/// Verifies `requests` with `threads` worker threads. Each worker attaches to
+/// the JVM and creates its own verifiers. The results are returned in the
+/// order of the requests.
+fn verify_in_parallel(
+    viper: &Viper,
+    cache: PersistentCache,
+    requests: Vec<(String, VerificationRequest)>,
+    threads: usize,
+    progress: &ProgressReporter,
+) -> Vec<(String, viper::VerificationResult, Duration)> {
+    let cache = Arc::new(Mutex::new(cache));
+    let queue = Mutex::new(requests.into_iter().enumerate());
+    let results = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let verification_context = Lazy::new(|| viper.attach_current_thread());
+                loop {
+                    let next = queue.lock().unwrap().next();
+                    let Some((index, (program_name, request))) = next else {
+                        break;
+                    };
+                    progress.begin(&program_name);
+                    let start = Instant::now();
+                    let result = process_verification_request(
+                        &verification_context,
+                        request,
+                        &cache,
+                        &mut VerifierPool::default(),
+                    );
+                    let duration = start.elapsed();
+                    progress.end(&program_name, &result, duration);
+                    results
+                        .lock()
+                        .unwrap()
+                        .push((index, (program_name, result, duration)));
+                }
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folded_stacks_have_one_sorted_line_per_sample() {
+        let samples = vec![
+            (
+                "foo::b".to_string(),
+                "verification",
+                Duration::from_millis(3),
+            ),
+            (
+                "foo::b".to_string(),
+                "encoding",
+                Duration::from_micros(1500),
+            ),
+            ("foo::a".to_string(), "encoding", Duration::from_micros(7)),
+        ];
+        assert_eq!(
+            folded_stacks("foo", samples),
+            "foo;foo::a;encoding 7\n\
+             foo;foo::b;encoding 1500\n\
+             foo;foo::b;verification 3000\n"
+        );
+    }
+
+    #[test]
+    fn folded_stacks_escape_frame_separators() {
+        let samples = vec![(
+            "<T as Trait>::f;g".to_string(),
+            "encoding",
+            Duration::from_micros(1),
+        )];
+        assert_eq!(
+            folded_stacks("foo", samples),
+            "foo;<T_as_Trait>::f_g;encoding 1\n"
+        );
+    }
+}
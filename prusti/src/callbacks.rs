@@ -16,8 +16,10 @@ use prusti_rustc_interface::{
         query::{ExternProviders, Providers},
         ty::TyCtxt,
     },
-    session::Session,
+    session::{config::Input, parse::ParseSess, Session},
+    span::{FileName, RealFileName, Symbol},
 };
+use std::{env, fs, path::PathBuf};
 
 #[derive(Default)]
 pub struct PrustiCompilerCalls;
@@ -72,6 +74,60 @@ fn mir_promoted<'tcx>(
     result
 }
 
+/// Appends a module declaration to the crate root for each `.prusti-specs` file
+/// of a dependency (see `config::dependency_specs`), so that the external
+/// specifications that it contains are collected like those of the crate.
+/// The source is only extended at the end, which keeps its spans unchanged.
+fn include_dependency_specs(config: &mut Config) {
+    let Input::File(path) = &config.input else {
+        return;
+    };
+    if config.opts.externs.get("prusti_contracts").is_none() {
+        return;
+    }
+    let modules: Vec<_> = config::dependency_specs()
+        .into_iter()
+        .filter(|(crate_name, _)| config.opts.externs.get(crate_name).is_some())
+        .filter_map(|(crate_name, specs_path)| {
+            let specs_path = fs::canonicalize(specs_path).ok()?;
+            Some(format!(
+                "\n#[path = {:?}]\n#[allow(unused)]\nmod prusti_dependency_specs_{crate_name};\n",
+                specs_path.to_string_lossy()
+            ))
+        })
+        .collect();
+    if modules.is_empty() {
+        return;
+    }
+    let Ok(mut source) = fs::read_to_string(path) else {
+        return;
+    };
+    source.extend(modules);
+    config.input = Input::Str {
+        name: FileName::Real(RealFileName::LocalPath(path.clone())),
+        input: source,
+    };
+}
+
+/// Records the configuration of Prusti as dependencies in the dep-info file of
+/// the crate, so that cargo, and thereby `cargo prusti`, does not skip a crate
+/// whose source did not change when its configuration did.
+fn track_configuration(parse_sess: &mut ParseSess) {
+    let env_depinfo = parse_sess.env_depinfo.get_mut();
+    for name in config::env_var_names() {
+        let value = env::var(&name).ok();
+        env_depinfo.insert((Symbol::intern(&name), value.as_deref().map(Symbol::intern)));
+    }
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    let prusti_toml = PathBuf::from(manifest_dir).join("Prusti.toml");
+    if prusti_toml.exists() {
+        parse_sess
+            .file_depinfo
+            .get_mut()
+            .insert(Symbol::intern(&prusti_toml.to_string_lossy()));
+    }
+}
+
 impl prusti_rustc_interface::driver::Callbacks for PrustiCompilerCalls {
     fn config(&mut self, config: &mut Config) {
         assert!(config.override_queries.is_none());
@@ -81,6 +137,8 @@ impl prusti_rustc_interface::driver::Callbacks for PrustiCompilerCalls {
                 providers.mir_promoted = mir_promoted;
             },
         );
+        include_dependency_specs(config);
+        config.parse_sess_created = Some(Box::new(track_configuration));
     }
     #[tracing::instrument(level = "debug", skip_all)]
     fn after_expansion<'tcx>(
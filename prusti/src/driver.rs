@@ -71,6 +71,22 @@ fn init_loggers() -> Option<FlushGuard> {
     guard
 }
 
+/// Compiles the crate like `rustc`, without running Prusti. Prusti's tool attributes are
+/// still accepted, since the crate may use `prusti-contracts` with the `prusti` feature
+/// enabled by `cargo-prusti`.
+fn run_rustc(mut rustc_args: Vec<String>) -> i32 {
+    rustc_args.push("-Zcrate-attr=feature(stmt_expr_attributes)".to_owned());
+    rustc_args.push("-Zcrate-attr=feature(register_tool)".to_owned());
+    rustc_args.push("-Zcrate-attr=register_tool(prusti)".to_owned());
+    driver::catch_with_exit_code(move || {
+        driver::RunCompiler::new(&rustc_args, &mut RustcCompilerCalls).run()
+    })
+}
+
+struct RustcCompilerCalls;
+
+impl driver::Callbacks for RustcCompilerCalls {}
+
 fn main() {
     driver::install_ice_hook(BUG_REPORT_URL, |handler| {
         let version_info = get_prusti_version_info();
@@ -83,14 +99,24 @@ fn main() {
     // Filter out Prusti's `-P<arg>=<val>` arguments
     let original_rustc_args = config::get_filtered_args();
 
-    // If the environment asks us to actually be rustc, then run `rustc` instead of Prusti,
-    // or if we're building a build script where we can't retrieve MIR bodies.
-    let build_script_build = arg_value(&original_rustc_args, "--crate-name", |val| {
-        val == "build_script_build"
+    // If the environment asks us to actually be rustc, then run `rustc` instead of Prusti.
+    if config::be_rustc() {
+        driver::main();
+    }
+
+    // Build scripts and proc-macro crates run on the host during the build, so there is
+    // nothing to verify and no specifications to export. Cargo names build scripts
+    // `build_script_<file stem>`, e.g. `build_script_build` for `build.rs`.
+    let is_build_script = arg_value(&original_rustc_args, "--crate-name", |val| {
+        val.starts_with("build_script_")
     })
     .is_some();
-    if config::be_rustc() || build_script_build {
-        driver::main();
+    let is_proc_macro = arg_value(&original_rustc_args, "--crate-type", |val| {
+        val == "proc-macro"
+    })
+    .is_some();
+    if is_build_script || is_proc_macro {
+        std::process::exit(run_rustc(original_rustc_args));
     }
 
     // Initialize Prusti and the Rust compiler loggers.
@@ -131,6 +157,15 @@ fn main() {
         }
     }
 
+    // When run through cargo, `cargo-prusti` asks cargo for the test targets
+    // instead. Otherwise, verify test code unless the compiler is only queried.
+    let cargo_invoked = env::var("PRUSTI_CARGO").is_ok();
+    let verify_tests = config::verify_tests()
+        && !cargo_invoked
+        && !rustc_args
+            .iter()
+            .any(|arg| arg == "--test" || arg == "-vV" || arg.starts_with("--print"));
+
     let exit_code = driver::catch_with_exit_code(move || {
         user::message(format!(
             "{}\n{}\n{}\n",
@@ -154,6 +189,12 @@ fn main() {
         rustc_args.push("-Zcrate-attr=feature(register_tool)".to_owned());
         rustc_args.push("-Zcrate-attr=register_tool(prusti)".to_owned());
 
+        if verify_tests {
+            // Build the test harness, so that `#[cfg(test)]` modules and `#[test]`
+            // functions are compiled and verified like any other code.
+            rustc_args.push("--test".to_owned());
+        }
+
         if config::check_overflows() {
             // Some crates might have a `overflow-checks = false` in their `Cargo.toml` to
             // disable integer overflow checks, but we want to override that.
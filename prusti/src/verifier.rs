@@ -3,11 +3,16 @@
 use log::{debug, warn};
 use prusti_common::{config, report::user};
 use prusti_interface::{
-    data::{VerificationResult, VerificationTask},
+    data::{ProcedureDefId, VerificationResult, VerificationTask},
     environment::Environment,
+    error_report::SpanReport,
     specs::typed,
+    trust_audit::collect_trust_assumptions,
+    PrustiError,
 };
+use prusti_rustc_interface::span::{FileName, DUMMY_SP};
 use prusti_viper::verifier::Verifier;
+use std::path::Path;
 
 #[tracing::instrument(name = "prusti::verify", level = "debug", skip(env))]
 pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
@@ -18,9 +23,45 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
         // TODO: can we replace `get_annotated_procedures` with information
         // that is already in `def_spec`?
         let (annotated_procedures, types) = env.get_annotated_procedures_and_types();
-        let verification_task = VerificationTask {
-            procedures: annotated_procedures,
-            types,
+        let selected_path = config::selective_verify();
+        let selected_file = config::selective_verify_file();
+        let verification_task = if selected_path.is_some() || selected_file.is_some() {
+            let procedures: Vec<_> = annotated_procedures
+                .into_iter()
+                .filter(|&proc_id| {
+                    selected_path
+                        .as_ref()
+                        .map_or(true, |path| matches_path(&env, proc_id, path))
+                        && selected_file
+                            .as_ref()
+                            .map_or(true, |file| is_defined_in(&env, proc_id, file))
+                })
+                .collect();
+            if procedures.is_empty() {
+                match (selected_path, selected_file) {
+                    (Some(path), None) => {
+                        user::message(format!("No function matches the selected path `{path}`"))
+                    }
+                    (None, Some(file)) => user::message(format!(
+                        "No function is defined in the selected file `{}`",
+                        file.display()
+                    )),
+                    (Some(path), Some(file)) => user::message(format!(
+                        "No function of the selected file `{}` matches the selected path `{path}`",
+                        file.display()
+                    )),
+                    (None, None) => {}
+                }
+            }
+            VerificationTask {
+                procedures,
+                types: vec![],
+            }
+        } else {
+            VerificationTask {
+                procedures: annotated_procedures,
+                types,
+            }
         };
         debug!("Verification task: {:?}", &verification_task);
 
@@ -29,6 +70,21 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
             verification_task.procedures.len()
         ));
 
+        match config::pure_mir_stage().as_str() {
+            "analysis" => {}
+            "optimized" => user::message(
+                "Pure functions and specifications are encoded from the optimized MIR",
+            ),
+            stage => PrustiError::warning(
+                format!(
+                    "unknown MIR stage `{stage}` in the `pure_mir_stage` flag, expected \
+                    `analysis` or `optimized`; using `analysis`"
+                ),
+                DUMMY_SP.into(),
+            )
+            .emit(&env.diagnostic),
+        }
+
         if config::print_collected_verification_items() {
             println!(
                 "Collected verification items {}:",
@@ -43,6 +99,10 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
             }
         }
 
+        if config::trust_audit() {
+            print_trust_audit(&env, &def_spec, &verification_task.procedures);
+        }
+
         let verification_result =
             if verification_task.procedures.is_empty() && verification_task.types.is_empty() {
                 VerificationResult::Success
@@ -64,6 +124,12 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
                         "Verification result is inconclusive because errors \
                                        were encountered during encoding.",
                     );
+                } else if config::check_only() {
+                    user::message(format!(
+                        "Checked the specifications and the encoding of {} items, \
+                        without verifying them",
+                        verification_task.procedures.len()
+                    ));
                 } else {
                     user::message(format!(
                         "Successful verification of {} items",
@@ -76,10 +142,82 @@ pub fn verify(env: Environment<'_>, def_spec: typed::DefSpecificationMap) {
                 assert!(
                     env.diagnostic.has_errors()
                         || config::internal_errors_as_warnings()
-                        || (config::skip_unsupported_features()
+                        || ((config::skip_unsupported_features()
+                            || !config::unsupported_feature_policy().is_empty())
                             && config::allow_unreachable_unsupported_code())
                 );
             }
         };
     }
 }
+
+/// Whether `path`, e.g. `module::function` or `Type::method`, matches the end
+/// of the full path of `proc_id`.
+fn matches_path(env: &Environment<'_>, proc_id: ProcedureDefId, path: &str) -> bool {
+    let name = env.name.get_absolute_item_name(proc_id);
+    name == path || name.ends_with(&format!("::{path}"))
+}
+
+/// Whether `proc_id` is defined in the source file `file`, given either as the
+/// end of its path, e.g. `src/lib.rs`, or as a path to the same file.
+fn is_defined_in(env: &Environment<'_>, proc_id: ProcedureDefId, file: &Path) -> bool {
+    let span = env.query.get_def_span(proc_id);
+    let FileName::Real(name) = env.query.codemap().span_to_filename(span) else {
+        return false;
+    };
+    let Some(source_path) = name.local_path() else {
+        return false;
+    };
+    source_path.ends_with(file)
+        || matches!(
+            (source_path.canonicalize(), file.canonicalize()),
+            (Ok(source_path), Ok(file)) if source_path == file
+        )
+}
+
+/// Prints the trust assumptions of the function selected by
+/// `TRUST_AUDIT_ENTRY_POINT`, or of all procedures of the crate.
+fn print_trust_audit(
+    env: &Environment<'_>,
+    def_spec: &typed::DefSpecificationMap,
+    procedures: &[ProcedureDefId],
+) {
+    let entry_point = config::trust_audit_entry_point();
+    let entry_points: Vec<_> = procedures
+        .iter()
+        .copied()
+        .filter(|&proc_id| {
+            entry_point
+                .as_ref()
+                .map_or(true, |path| matches_path(env, proc_id, path))
+        })
+        .collect();
+    let target = if let Some(path) = &entry_point {
+        if entry_points.is_empty() {
+            PrustiError::warning(
+                format!("no function matches the trust audit entry point `{path}`"),
+                DUMMY_SP.into(),
+            )
+            .emit(&env.diagnostic);
+            return;
+        }
+        format!("`{path}`")
+    } else {
+        format!("crate `{}`", env.name.local_crate_name())
+    };
+
+    let assumptions = collect_trust_assumptions(env, def_spec, &entry_points);
+    let source_map = env.query.codemap();
+    println!("Trust assumptions of {target} ({}):", assumptions.len());
+    for assumption in assumptions {
+        let location = SpanReport::from_span(source_map, assumption.span);
+        println!(
+            "  {} `{}` at {}:{}:{}",
+            assumption.kind,
+            env.name.get_absolute_item_name(assumption.item),
+            location.file_name,
+            location.line_start,
+            location.column_start,
+        );
+    }
+}
@@ -68,6 +68,7 @@ fn main() {
                 method!("toString"),
             ]),
             java_class!("java.lang.System", vec![
+                method!("gc"),
                 method!("getProperty", "(Ljava/lang/String;)Ljava/lang/String;"),
             ]),
             java_class!("java.lang.Throwable", vec![
@@ -138,6 +139,12 @@ fn main() {
                 constructor!("(Lviper/silver/reporter/Reporter;)V"),
             ]),
             // Silver
+            java_class!("viper.silver.frontend.ViperAstProvider", vec![
+                constructor!("(Lviper/silver/reporter/Reporter;Lch/qos/logback/classic/Logger;Z)V"),
+                method!("execute"),
+                method!("errors"),
+                method!("translationResult"),
+            ]),
             java_class!("viper.silver.frontend.ViperFrontendAPI", vec![
                 method!("initialize"),
                 method!("verify"),
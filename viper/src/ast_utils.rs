@@ -6,6 +6,7 @@
 
 use crate::{ast_factory::Program, jni_utils::JniUtils, JavaException};
 use jni::{objects::JObject, JNIEnv};
+use std::path::Path;
 use viper_sys::wrappers::viper::*;
 
 #[derive(Clone, Copy)]
@@ -48,6 +49,47 @@ impl<'a> AstUtils<'a> {
         )
     }
 
+    /// Parses, type-checks and translates the Viper file at `path`, e.g. a
+    /// hand-written or patched program. Returns the errors reported by Viper if
+    /// the file does not contain a valid program.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn parse_file(&self, path: &Path) -> Result<Program<'a>, Vec<String>> {
+        let reporter = self
+            .jni
+            .unwrap_result(silver::reporter::NoopReporter_object::with(self.env).singleton());
+        let silent_logger_wrapper = silver::logger::SilentLogger_object::with(self.env);
+        let viper_logger = self.jni.unwrap_result(
+            silent_logger_wrapper
+                .call_apply(self.jni.unwrap_result(silent_logger_wrapper.singleton())),
+        );
+        let logger = self
+            .jni
+            .unwrap_result(silver::logger::ViperLogger::with(self.env).call_get(viper_logger));
+        let provider_wrapper = silver::frontend::ViperAstProvider::with(self.env);
+        let provider = self
+            .jni
+            .unwrap_result(provider_wrapper.new(reporter, logger, false));
+        let args = self
+            .jni
+            .new_seq(&[self.jni.new_string(path.to_string_lossy())]);
+        self.jni
+            .unwrap_result(provider_wrapper.call_execute(provider, args));
+        let errors = self.jni.seq_to_vec(
+            self.jni
+                .unwrap_result(provider_wrapper.call_errors(provider)),
+        );
+        if errors.is_empty() {
+            Ok(Program::new(self.jni.unwrap_result(
+                provider_wrapper.call_translationResult(provider),
+            )))
+        } else {
+            Err(errors
+                .into_iter()
+                .map(|error| self.jni.to_string(error))
+                .collect())
+        }
+    }
+
     pub fn to_string(&self, program: Program<'a>) -> String {
         self.jni.to_string(program.to_jobject())
     }
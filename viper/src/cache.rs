@@ -16,22 +16,47 @@ use std::{
 };
 
 pub trait Cache {
-    fn get(&self, request: u64) -> Option<VerificationResult>;
+    fn get(&mut self, request: u64) -> Option<VerificationResult>;
     fn insert(self, request: u64, result: VerificationResult) -> Option<VerificationResult>;
 }
+
+/// The limits of a cache, beyond which the least recently used entries are
+/// evicted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheLimits {
+    pub max_entries: Option<usize>,
+    /// The maximum total size of the serialized results, in bytes.
+    pub max_size: Option<u64>,
+}
+
 // We can change the representation here (e.g. adding fields):
 #[derive(Debug, Clone)]
 pub struct PersistentCache {
     updated: bool,
     load_loc: PathBuf,
-    data: FxHashMap<u64, VerificationResult>,
+    data: FxHashMap<u64, CacheEntry>,
+    /// Incremented on every access, to order the entries by their last use.
+    clock: u64,
+    /// The total size of the entries.
+    size: u64,
+    limits: CacheLimits,
 }
 
-const RESULT_CACHE_VERSION: u64 = 4;
+const RESULT_CACHE_VERSION: u64 = 5;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct CacheEntry {
+    result: VerificationResult,
+    /// The clock of the cache when the entry was last used.
+    last_used: u64,
+    /// The size of the serialized result, in bytes.
+    size: u64,
+}
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct ResultCache {
-    data: FxHashMap<u64, VerificationResult>,
+    data: FxHashMap<u64, CacheEntry>,
+    clock: u64,
     version: u64,
 }
 
@@ -43,7 +68,10 @@ impl From<(PathBuf, ResultCache)> for PersistentCache {
         PersistentCache {
             updated: false,
             load_loc,
+            size: data.values().map(|entry| entry.size).sum(),
             data,
+            clock: rc.clock,
+            limits: CacheLimits::default(),
         }
     }
 }
@@ -52,6 +80,7 @@ impl From<&PersistentCache> for ResultCache {
     fn from(cache: &PersistentCache) -> Self {
         ResultCache {
             data: cache.data.clone(),
+            clock: cache.clock,
             version: RESULT_CACHE_VERSION,
         }
     }
@@ -88,11 +117,105 @@ impl PersistentCache {
                 info!("Cache file doesn't exist or is invalid. Using fresh cache.");
                 ResultCache {
                     data: FxHashMap::default(),
+                    clock: 0,
                     version: RESULT_CACHE_VERSION,
                 }
             }),
         ))
     }
+    /// Sets the limits of the cache, evicting entries if needed.
+    pub fn with_limits(mut self, limits: CacheLimits) -> Self {
+        self.limits = limits;
+        self.evict();
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Adds the entries of a serialized cache, e.g. the content of another
+    /// cache file, that are not yet in this cache. Returns the number of
+    /// added entries.
+    pub fn import(&mut self, serialized: &[u8]) -> Result<usize, String> {
+        let imported: ResultCache =
+            bincode::deserialize(serialized).map_err(|error| error.to_string())?;
+        if imported.version != RESULT_CACHE_VERSION {
+            return Err(format!(
+                "cache version mismatch: expected={} actual={}",
+                RESULT_CACHE_VERSION, imported.version
+            ));
+        }
+        let mut added = 0;
+        for (request, entry) in imported.data {
+            if !self.data.contains_key(&request) {
+                self.insert_entry(request, entry.result);
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    fn insert_entry(
+        &mut self,
+        request: u64,
+        result: VerificationResult,
+    ) -> Option<VerificationResult> {
+        self.updated = true;
+        self.clock += 1;
+        let entry = CacheEntry {
+            size: bincode::serialized_size(&result).unwrap_or(0),
+            result,
+            last_used: self.clock,
+        };
+        self.size += entry.size;
+        let old_entry = self.data.insert(request, entry);
+        if let Some(old_entry) = &old_entry {
+            self.size -= old_entry.size;
+        }
+        self.evict();
+        old_entry.map(|entry| entry.result)
+    }
+
+    fn exceeds_limits(&self) -> bool {
+        self.limits
+            .max_entries
+            .map_or(false, |max_entries| self.data.len() > max_entries)
+            || self
+                .limits
+                .max_size
+                .map_or(false, |max_size| self.size > max_size)
+    }
+
+    /// Removes the least recently used entries until the cache is within its
+    /// limits.
+    fn evict(&mut self) {
+        if !self.exceeds_limits() {
+            return;
+        }
+        let mut entries: Vec<_> = self
+            .data
+            .iter()
+            .map(|(request, entry)| (entry.last_used, *request))
+            .collect();
+        entries.sort_unstable();
+        let mut evicted = 0;
+        for (_, request) in entries {
+            if !self.exceeds_limits() {
+                break;
+            }
+            let entry = self.data.remove(&request).unwrap();
+            self.size -= entry.size;
+            evicted += 1;
+        }
+        self.updated = true;
+        info!("Evicted {} entries from the cache", evicted);
+    }
+
     #[tracing::instrument(level = "debug")]
     pub fn save_cache(&self, cache_loc: &Path) {
         match fs::File::create(cache_loc) {
@@ -114,6 +237,7 @@ impl PersistentCache {
     pub fn save(&mut self) {
         // Save cache to disk, if changed and save path is valid
         if self.updated {
+            self.updated = false;
             if self.load_loc.as_os_str().is_empty() {
                 warn!("Cannot save cache, because the cache path is empty.")
             } else {
@@ -135,17 +259,19 @@ impl Drop for PersistentCache {
 }
 
 impl Cache for &mut PersistentCache {
-    fn get(&self, request: u64) -> Option<VerificationResult> {
-        self.data.get(&request).cloned()
+    fn get(&mut self, request: u64) -> Option<VerificationResult> {
+        self.clock += 1;
+        let entry = self.data.get_mut(&request)?;
+        entry.last_used = self.clock;
+        Some(entry.result.clone())
     }
     fn insert(self, request: u64, result: VerificationResult) -> Option<VerificationResult> {
-        self.updated = true;
-        self.data.insert(request, result)
+        self.insert_entry(request, result)
     }
 }
 
 impl Cache for &Arc<Mutex<PersistentCache>> {
-    fn get(&self, request: u64) -> Option<VerificationResult> {
+    fn get(&mut self, request: u64) -> Option<VerificationResult> {
         let mut cache = self.lock().unwrap();
         cache.deref_mut().get(request)
     }
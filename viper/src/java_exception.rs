@@ -26,6 +26,12 @@ impl JavaException {
     pub fn get_stack_trace(&self) -> &str {
         &self.stack_trace
     }
+
+    /// Whether the JVM ran out of memory, in which case the verification
+    /// might succeed after the memory of the verifier is released.
+    pub fn is_out_of_memory(&self) -> bool {
+        self.message.starts_with("java.lang.OutOfMemoryError")
+    }
 }
 
 impl std::fmt::Display for JavaException {
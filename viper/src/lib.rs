@@ -17,14 +17,16 @@ mod cache;
 mod java_exception;
 pub mod silicon_counterexample;
 pub mod smt_manager;
+mod smt_solver;
 mod verification_backend;
 mod verification_context;
 mod verification_result;
 mod verifier;
 mod viper;
+mod viper_server;
 
 pub use crate::{
     ast_factory::*, ast_utils::*, cache::*, java_exception::*, silicon_counterexample::*,
-    verification_backend::*, verification_context::*, verification_result::*, verifier::*,
-    viper::*,
+    smt_solver::*, verification_backend::*, verification_context::*, verification_result::*,
+    verifier::*, viper::*, viper_server::*,
 };
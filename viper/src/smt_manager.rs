@@ -1,6 +1,8 @@
 use futures::FutureExt;
+use rustc_hash::FxHashMap;
 use std::{
-    path::PathBuf,
+    fmt::Write,
+    path::{Path, PathBuf},
     thread::{self, JoinHandle},
 };
 use tokio::{io::AsyncWriteExt, net::TcpListener, runtime::Builder, sync::oneshot};
@@ -16,10 +18,15 @@ pub struct SmtManager {
     quantifier_instantiations_bound_trace_kind: Option<u64>,
     unique_triggers_bound: Option<u64>,
     unique_triggers_bound_total: Option<u64>,
+    /// If set, how many quantifiers with the most instantiations to report.
+    quantifier_profile_top: Option<usize>,
+    log_path: Option<PathBuf>,
 }
 
 struct Connection {
-    smt_listener: JoinHandle<Vec<String>>,
+    /// Returns the paths of the trace and quantifier profile files of each
+    /// solver instance.
+    smt_listener: JoinHandle<Vec<(String, String)>>,
     termination_shot: oneshot::Sender<()>,
     port: u16,
 }
@@ -36,8 +43,10 @@ impl SmtManager {
         quantifier_instantiations_bound_trace_kind: Option<u64>,
         unique_triggers_bound: Option<u64>,
         unique_triggers_bound_total: Option<u64>,
+        quantifier_profile_top: Option<usize>,
     ) -> Self {
         let log_path = std::fs::canonicalize(log_path).unwrap();
+        let profile_log_path = log_path.clone();
         let (termination_sender, termination_receiver) = oneshot::channel();
         let (port_sender, port_receiver) = std::sync::mpsc::channel();
         let smt_listener = thread::spawn(move || {
@@ -68,13 +77,21 @@ impl SmtManager {
                             let (mut socket, _) = socket.unwrap();
                             let log_file = log_path.join(format!("wrapper_{counter}.log"));
                             let trace_file = log_path.join(format!("trace{counter}.log"));
+                            let qi_profile_file = log_path.join(format!("qi_profile{counter}.log"));
+                            let query_file = log_path.join(format!("query{counter}.smt2"));
                             let log_file = log_file.into_os_string().into_string().unwrap();
                             let trace_file = trace_file.into_os_string().into_string().unwrap();
+                            let qi_profile_file = qi_profile_file.into_os_string().into_string().unwrap();
+                            let query_file = query_file.into_os_string().into_string().unwrap();
                             socket.write_all(log_file.as_bytes()).await.unwrap();
                             socket.write_all(b"\n").await.unwrap();
                             socket.write_all(trace_file.as_bytes()).await.unwrap();
                             socket.write_all(b"\n").await.unwrap();
-                            trace_files.push(trace_file);
+                            socket.write_all(qi_profile_file.as_bytes()).await.unwrap();
+                            socket.write_all(b"\n").await.unwrap();
+                            socket.write_all(query_file.as_bytes()).await.unwrap();
+                            socket.write_all(b"\n").await.unwrap();
+                            trace_files.push((trace_file, qi_profile_file));
                             socket.flush().await.unwrap();
                             socket.shutdown().await.unwrap();
                             counter += 1;
@@ -99,6 +116,8 @@ impl SmtManager {
             quantifier_instantiations_bound_trace_kind,
             unique_triggers_bound,
             unique_triggers_bound_total,
+            quantifier_profile_top,
+            log_path: Some(profile_log_path),
         }
     }
 
@@ -110,7 +129,17 @@ impl SmtManager {
         if let Some(connection) = self.connection.take() {
             connection.termination_shot.send(()).unwrap();
             let trace_files = connection.smt_listener.join().unwrap();
-            for trace_file in trace_files {
+            let mut quantifier_profile = FxHashMap::default();
+            for (trace_file, qi_profile_file) in trace_files {
+                let qi_profile_file = PathBuf::from(qi_profile_file);
+                // A solver instance that was never started does not create
+                // its profile file.
+                if let Ok(content) = std::fs::read_to_string(&qi_profile_file) {
+                    for (quantifier, count) in parse_quantifier_profile(&content) {
+                        *quantifier_profile.entry(quantifier).or_default() += count;
+                    }
+                    std::fs::remove_file(qi_profile_file).unwrap();
+                }
                 let trace_file = PathBuf::from(trace_file);
                 // Since Silicon kills the SMT solver, there is no guarantee
                 // that the scopes will be fully popped.
@@ -136,8 +165,61 @@ impl SmtManager {
                     std::fs::remove_file(trace_file).unwrap();
                 }
             }
+            if let (Some(top), Some(log_path)) = (self.quantifier_profile_top, &self.log_path) {
+                report_quantifier_profile(log_path, quantifier_profile, top);
+            }
+        }
+    }
+}
+
+/// Parses the `[quantifier_instances] <qid> : <instances> : ...` lines printed
+/// by Z3 with `smt.qi.profile=true`. Since Z3 prints cumulative counts
+/// repeatedly, the maximum count of each quantifier is returned.
+fn parse_quantifier_profile(content: &str) -> FxHashMap<String, u64> {
+    let mut profile = FxHashMap::default();
+    for line in content.lines() {
+        let Some(line) = line.strip_prefix("[quantifier_instances]") else {
+            continue;
+        };
+        let mut parts = line.split(" : ");
+        let (Some(quantifier), Some(count)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if let Ok(count) = count.trim().parse::<u64>() {
+            let entry = profile.entry(quantifier.trim().to_string()).or_insert(0);
+            *entry = count.max(*entry);
         }
     }
+    profile
+}
+
+/// Writes the full quantifier profile to `quantifier_profile.csv` in the
+/// method's log directory and prints the `top` quantifiers.
+fn report_quantifier_profile(log_path: &Path, profile: FxHashMap<String, u64>, top: usize) {
+    let mut profile: Vec<_> = profile.into_iter().collect();
+    profile.sort_by(|(a_name, a_count), (b_name, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+    });
+    let mut csv = String::from("quantifier,instantiations\n");
+    for (quantifier, count) in &profile {
+        writeln!(csv, "{quantifier},{count}").unwrap();
+    }
+    std::fs::write(log_path.join("quantifier_profile.csv"), csv).unwrap();
+
+    let method = log_path.file_name().unwrap().to_string_lossy();
+    if profile.is_empty() {
+        println!("No quantifier instantiations were reported for {method}.");
+        return;
+    }
+    let total: u64 = profile.iter().map(|(_, count)| count).sum();
+    println!(
+        "Quantifier instantiations for {method} (total {total}, top {} of {}):",
+        top.min(profile.len()),
+        profile.len()
+    );
+    for (quantifier, count) in profile.iter().take(top) {
+        println!("  {count:>10}  {quantifier}");
+    }
 }
 
 impl Drop for SmtManager {
@@ -145,3 +227,40 @@ impl Drop for SmtManager {
         assert!(self.connection.is_none(), "`stop_and_check` was not called");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_keeps_the_latest_count_of_each_quantifier() {
+        let content = "\
+[quantifier_instances] q1 : 3 : 1 : 2
+[quantifier_instances] q2 : 7 : 1 : 2
+[quantifier_instances] q1 : 5 : 1 : 2
+[mk-app] #1 true
+[quantifier_instances] q3 : many : 1 : 2
+[quantifier_instances] q4
+";
+        let profile = parse_quantifier_profile(content);
+        assert_eq!(profile.len(), 2);
+        assert_eq!(profile["q1"], 5);
+        assert_eq!(profile["q2"], 7);
+    }
+
+    #[test]
+    fn profile_csv_is_sorted_by_count() {
+        let log_path = std::env::temp_dir().join(format!("prusti-smt-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&log_path).unwrap();
+        let profile = parse_quantifier_profile(
+            "[quantifier_instances] b : 4 : 0\n\
+             [quantifier_instances] c : 9 : 0\n\
+             [quantifier_instances] a : 4 : 0\n",
+        );
+        report_quantifier_profile(&log_path, profile, 1);
+
+        let csv = std::fs::read_to_string(log_path.join("quantifier_profile.csv")).unwrap();
+        assert_eq!(csv, "quantifier,instantiations\nc,9\na,4\nb,4\n");
+        std::fs::remove_dir_all(&log_path).unwrap();
+    }
+}
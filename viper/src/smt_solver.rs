@@ -0,0 +1,62 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+
+/// The SMT solver used by Silicon. Carbon always uses Z3 through Boogie.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum SmtSolver {
+    Z3,
+    Cvc5,
+}
+
+#[derive(Clone, Debug)]
+pub struct UnknownSmtSolverError(String);
+
+impl fmt::Display for UnknownSmtSolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid SMT solver: '{}'. Allowed values are 'Z3' and 'cvc5'",
+            self.0
+        )
+    }
+}
+
+impl std::str::FromStr for SmtSolver {
+    type Err = UnknownSmtSolverError;
+    fn from_str(solver: &str) -> Result<Self, Self::Err> {
+        match solver.to_lowercase().as_str() {
+            "z3" => Ok(SmtSolver::Z3),
+            "cvc5" => Ok(SmtSolver::Cvc5),
+            _ => Err(UnknownSmtSolverError(solver.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for SmtSolver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SmtSolver::Z3 => write!(f, "Z3"),
+            SmtSolver::Cvc5 => write!(f, "cvc5"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solver_names_are_parsed_case_insensitively() {
+        assert_eq!("Z3".parse::<SmtSolver>().unwrap(), SmtSolver::Z3);
+        assert_eq!("CVC5".parse::<SmtSolver>().unwrap(), SmtSolver::Cvc5);
+        for solver in [SmtSolver::Z3, SmtSolver::Cvc5] {
+            assert_eq!(solver.to_string().parse::<SmtSolver>().unwrap(), solver);
+        }
+        assert!("yices".parse::<SmtSolver>().is_err());
+    }
+}
@@ -10,6 +10,9 @@ use std::fmt;
 pub enum VerificationBackend {
     Silicon,
     Carbon,
+    /// The experimental verifier of the `native-verifier` crate, which runs
+    /// Z3 directly instead of using Viper.
+    Native,
 }
 
 #[derive(Clone, Debug)]
@@ -19,7 +22,7 @@ impl fmt::Display for UknownBackendError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Invalid verification backend: '{}'. Allowed values are 'Silicon' and 'Carbon'",
+            "Invalid verification backend: '{}'. Allowed values are 'Silicon', 'Carbon' and 'Native'",
             self.0
         )
     }
@@ -31,6 +34,7 @@ impl std::str::FromStr for VerificationBackend {
         match backend.to_lowercase().as_str() {
             "silicon" => Ok(VerificationBackend::Silicon),
             "carbon" => Ok(VerificationBackend::Carbon),
+            "native" => Ok(VerificationBackend::Native),
             _ => Err(UknownBackendError(backend.to_string())),
         }
     }
@@ -41,6 +45,7 @@ impl fmt::Display for VerificationBackend {
         match self {
             VerificationBackend::Silicon => write!(f, "Silicon"),
             VerificationBackend::Carbon => write!(f, "Carbon"),
+            VerificationBackend::Native => write!(f, "Native"),
         }
     }
 }
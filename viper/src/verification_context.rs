@@ -5,7 +5,8 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::{
-    ast_factory::*, ast_utils::*, verification_backend::VerificationBackend, verifier::Verifier,
+    ast_factory::*, ast_utils::*, jni_utils::JniUtils, smt_solver::SmtSolver,
+    verification_backend::VerificationBackend, verifier::Verifier,
 };
 use jni::AttachGuard;
 use log::{debug, info};
@@ -13,6 +14,7 @@ use std::{
     env,
     path::{Path, PathBuf},
 };
+use viper_sys::wrappers::java;
 
 use crate::smt_manager::SmtManager;
 
@@ -33,6 +35,13 @@ impl<'a> VerificationContext<'a> {
         AstUtils::new(&self.env)
     }
 
+    /// Asks the JVM to release the memory of unreachable objects, e.g. of
+    /// dropped verifiers.
+    pub fn collect_garbage(&self) {
+        let jni = JniUtils::new(&self.env);
+        jni.unwrap_result(java::lang::System::with(&self.env).call_gc());
+    }
+
     /// Should be used only by tests.
     pub fn new_verifier_with_default_smt(&self, backend: VerificationBackend) -> Verifier {
         self.new_verifier_with_default_smt_and_extra_args(backend, vec![])
@@ -51,48 +60,82 @@ impl<'a> VerificationContext<'a> {
             backend,
             extra_args,
             None,
+            SmtSolver::Z3,
             z3_exe,
             boogie_exe,
             SmtManager::default(),
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(level = "debug", skip(self, smt_manager))]
     pub fn new_verifier(
         &self,
         backend: VerificationBackend,
         extra_args: Vec<String>,
         report_path: Option<PathBuf>,
-        z3_exe: String,
+        smt_solver: SmtSolver,
+        smt_solver_exe: String,
         boogie_exe: Option<String>,
         smt_manager: SmtManager,
     ) -> Verifier {
-        let mut verifier_args: Vec<String> = vec![];
+        let verifier_args =
+            verifier_args(backend, extra_args, smt_solver, smt_solver_exe, boogie_exe);
+        debug!("Verifier arguments: '{}'", verifier_args.to_vec().join(" "));
 
-        // Set Z3 binary
-        info!("Using Z3 exe: '{}'", &z3_exe);
-        assert!(
-            Path::new(&z3_exe).is_file(),
-            "The Z3_EXE environment variable ({z3_exe:?}) does not point to a valid file."
-        );
-        verifier_args.extend(vec!["--z3Exe".to_string(), z3_exe]);
+        Verifier::new(&self.env, backend, report_path, smt_manager).initialize(&verifier_args)
+    }
+}
 
-        // Set Boogie binary
-        if let VerificationBackend::Carbon = backend {
-            let boogie_exe =
-                boogie_exe.expect("the BOOGIE_EXE environment variable should not be empty");
-            info!("Using BOOGIE exe: '{}'", &boogie_exe);
+/// The arguments of a Viper verifier that select the SMT solver and, for
+/// Carbon, Boogie, followed by `extra_args`.
+pub fn verifier_args(
+    backend: VerificationBackend,
+    extra_args: Vec<String>,
+    smt_solver: SmtSolver,
+    smt_solver_exe: String,
+    boogie_exe: Option<String>,
+) -> Vec<String> {
+    let mut verifier_args: Vec<String> = vec![];
+
+    // Set the SMT solver binary
+    match smt_solver {
+        SmtSolver::Z3 => {
+            info!("Using Z3 exe: '{}'", &smt_solver_exe);
             assert!(
-                Path::new(&boogie_exe).is_file(),
-                "The BOOGIE_EXE environment variable ({boogie_exe:?}) does not point to a valid file."
+                Path::new(&smt_solver_exe).is_file(),
+                "The Z3_EXE environment variable ({smt_solver_exe:?}) does not point to a valid file."
             );
-            verifier_args.extend(vec!["--boogieExe".to_string(), boogie_exe]);
+            verifier_args.extend(vec!["--z3Exe".to_string(), smt_solver_exe]);
         }
+        SmtSolver::Cvc5 => {
+            info!("Using cvc5 exe: '{}'", &smt_solver_exe);
+            assert!(
+                Path::new(&smt_solver_exe).is_file(),
+                "The CVC5_EXE environment variable ({smt_solver_exe:?}) does not point to a valid file."
+            );
+            verifier_args.extend(vec![
+                "--prover".to_string(),
+                "cvc5".to_string(),
+                "--cvc5Exe".to_string(),
+                smt_solver_exe,
+            ]);
+        }
+    }
 
-        verifier_args.extend(extra_args);
+    // Set Boogie binary
+    if let VerificationBackend::Carbon = backend {
+        let boogie_exe =
+            boogie_exe.expect("the BOOGIE_EXE environment variable should not be empty");
+        info!("Using BOOGIE exe: '{}'", &boogie_exe);
+        assert!(
+            Path::new(&boogie_exe).is_file(),
+            "The BOOGIE_EXE environment variable ({boogie_exe:?}) does not point to a valid file."
+        );
+        verifier_args.extend(vec!["--boogieExe".to_string(), boogie_exe]);
+    }
 
-        debug!("Verifier arguments: '{}'", verifier_args.to_vec().join(" "));
+    verifier_args.extend(extra_args);
 
-        Verifier::new(&self.env, backend, report_path, smt_manager).initialize(&verifier_args)
-    }
+    verifier_args
 }
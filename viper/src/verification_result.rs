@@ -17,6 +17,11 @@ pub enum VerificationResult {
     ConsistencyErrors(Vec<String>),
     /// The verification raised a Java exception.
     JavaException(JavaException),
+    /// The verification exceeded the time limit given with `--timeout`.
+    Timeout,
+    /// The verifier reported errors that are neither verification errors nor
+    /// exceptions, e.g. when Carbon cannot run Boogie.
+    BackendErrors(Vec<String>),
 }
 
 impl VerificationResult {
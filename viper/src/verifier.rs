@@ -7,6 +7,7 @@
 use crate::{
     ast_factory::*,
     ast_utils::AstUtils,
+    java_exception::JavaException,
     jni_utils::JniUtils,
     silicon_counterexample::SiliconCounterexample,
     smt_manager::SmtManager,
@@ -56,6 +57,9 @@ impl<'a> Verifier<'a> {
                     VerificationBackend::Carbon => {
                         carbon::CarbonFrontendAPI::with(env).new(reporter)
                     }
+                    VerificationBackend::Native => {
+                        unreachable!("the native backend does not use Viper")
+                    }
                 }
             };
             let frontend_instance = jni.unwrap_result(unwrapped_frontend_instance);
@@ -136,8 +140,16 @@ impl<'a> Verifier<'a> {
                 );
             }
 
+            // E.g. an `OutOfMemoryError` is thrown instead of being reported
+            // as a failure.
             run_timed!("Viper verification", debug,
-                let viper_result = self.jni.unwrap_result(self.frontend_wrapper.call_verify(self.frontend_instance, program.to_jobject()));
+                let viper_result = match self.jni.unwrap_or_exception(self.frontend_wrapper.call_verify(self.frontend_instance, program.to_jobject())) {
+                    Ok(result) => result,
+                    Err(java_exception) => {
+                        self.smt_manager.stop_and_check();
+                        return VerificationResult::JavaException(java_exception);
+                    }
+                };
             );
             debug!(
                 "Viper verification result: {}",
@@ -152,6 +164,7 @@ impl<'a> Verifier<'a> {
 
             if is_failure {
                 let mut errors: Vec<VerificationError> = vec![];
+                let mut other_errors: Vec<String> = vec![];
 
                 let viper_errors = self.jni.seq_to_vec(self.jni.unwrap_result(
                     silver::verifier::Failure::with(self.env).call_errors(viper_result),
@@ -168,6 +181,13 @@ impl<'a> Verifier<'a> {
                 let error_reason_wrapper = silver::verifier::ErrorReason::with(self.env);
 
                 for viper_error in viper_errors {
+                    if self
+                        .jni
+                        .is_instance_of(viper_error, "viper/silver/verifier/TimeoutOccurred")
+                    {
+                        return VerificationResult::Timeout;
+                    }
+
                     let is_verification_error = self
                         .jni
                         .is_instance_of(viper_error, "viper/silver/verifier/VerificationError");
@@ -188,18 +208,24 @@ impl<'a> Verifier<'a> {
                                 "The verification aborted due to the following exception: {}",
                                 stack_trace
                             );
-                        } else {
-                            error!(
-                                "The verifier returned an unhandled error of type {}: {}",
-                                self.jni.class_name(viper_error),
-                                self.jni.to_string(viper_error)
-                            );
+                            return VerificationResult::JavaException(JavaException::new(
+                                self.jni.to_string(exception),
+                                stack_trace,
+                            ));
                         }
-                        unreachable!(
-                            "The verifier returned an unknown error of type {}: {}",
+                        // E.g. Carbon reports a missing or failing Boogie
+                        // this way.
+                        error!(
+                            "The verifier returned an unhandled error of type {}: {}",
                             self.jni.class_name(viper_error),
                             self.jni.to_string(viper_error)
                         );
+                        other_errors.push(format!(
+                            "{}: {}",
+                            self.jni.class_name(viper_error),
+                            self.jni.to_string(viper_error)
+                        ));
+                        continue;
                     };
                     let mut failure_contexts = self.jni.seq_to_vec(self
                     .jni
@@ -328,6 +354,9 @@ impl<'a> Verifier<'a> {
                     ))
                 }
 
+                if !other_errors.is_empty() {
+                    return VerificationResult::BackendErrors(other_errors);
+                }
                 VerificationResult::Failure(errors)
             } else {
                 VerificationResult::Success
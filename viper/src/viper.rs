@@ -96,6 +96,31 @@ impl Viper {
         Viper { jvm }
     }
 
+    /// Returns the used and the maximum size of the heap of the JVM, in bytes.
+    pub fn heap_usage(&self) -> (i64, i64) {
+        let env = self
+            .jvm
+            .attach_current_thread()
+            .expect("failed to attach jvm thread");
+        let jni = JniUtils::new(&env);
+        let runtime = jni.unwrap_result(
+            env.call_static_method(
+                "java/lang/Runtime",
+                "getRuntime",
+                "()Ljava/lang/Runtime;",
+                &[],
+            )
+            .and_then(|value| value.l()),
+        );
+        let call = |method| {
+            jni.unwrap_result(
+                env.call_method(runtime, method, "()J", &[])
+                    .and_then(|value| value.j()),
+            )
+        };
+        (call("totalMemory") - call("freeMemory"), call("maxMemory"))
+    }
+
     pub fn attach_current_thread(&self) -> VerificationContext {
         let env_guard = self
             .jvm
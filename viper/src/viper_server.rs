@@ -0,0 +1,273 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A client of a stand-alone [ViperServer](https://github.com/viperproject/viperserver),
+//! which verifies programs through the HTTP interface of the server instead
+//! of running Viper in the JVM of the current process.
+
+use crate::{
+    verification_backend::VerificationBackend,
+    verification_result::{VerificationError, VerificationResult},
+    JavaException,
+};
+use log::{debug, error, info};
+use serde::Deserialize;
+use std::{io::BufReader, path::Path};
+
+pub struct ViperServerClient {
+    agent: ureq::Agent,
+    server_url: String,
+}
+
+/// A message that the server streams while verifying a program.
+#[derive(Deserialize)]
+struct Message {
+    msg_type: String,
+    #[serde(default)]
+    msg_body: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct ResultDetails {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    errors: Vec<ErrorDetails>,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetails {
+    tag: String,
+    text: String,
+    #[serde(default)]
+    position: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct ExceptionReport {
+    message: String,
+    #[serde(default)]
+    stacktrace: Vec<String>,
+}
+
+impl ViperServerClient {
+    /// Creates a client of the server at `server_address`, e.g.
+    /// `localhost:4000`.
+    pub fn new(server_address: &str) -> Self {
+        let mut server_url = server_address.trim_end_matches('/').to_string();
+        if !server_url.starts_with("http") {
+            server_url = format!("http://{server_url}");
+        }
+        Self {
+            agent: ureq::AgentBuilder::new().build(),
+            server_url,
+        }
+    }
+
+    /// Verifies the Viper program in the file `program_path` with the given
+    /// backend and arguments, e.g. those of `verifier_args`. The server
+    /// reports the source positions of the errors, which `position_id` maps
+    /// from their line and column to the identifiers of the positions.
+    pub fn verify(
+        &self,
+        backend: VerificationBackend,
+        verifier_args: &[String],
+        program_path: &Path,
+        position_id: impl Fn(u32, u32) -> Option<String>,
+    ) -> VerificationResult {
+        let backend_name = match backend {
+            VerificationBackend::Silicon => "silicon",
+            VerificationBackend::Carbon => "carbon",
+            VerificationBackend::Native => {
+                unreachable!("the native backend does not use ViperServer")
+            }
+        };
+        let arg = std::iter::once(backend_name.to_string())
+            .chain(verifier_args.iter().cloned())
+            .chain(std::iter::once(program_path.to_string_lossy().into_owned()))
+            .map(|arg| quote_arg(&arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        debug!("ViperServer arguments: '{}'", arg);
+        match self.request_verification(&arg) {
+            Ok(job_id) => self.wait_for_result(job_id, position_id),
+            Err(error) => VerificationResult::BackendErrors(vec![format!(
+                "could not send the verification request to ViperServer at {}: {error}",
+                self.server_url
+            )]),
+        }
+    }
+
+    /// Starts the verification and returns the identifier of its job.
+    fn request_verification(&self, arg: &str) -> Result<u64, String> {
+        let request = serde_json::json!({ "arg": arg });
+        let response: serde_json::Value = self
+            .agent
+            .post(&format!("{}/verify", self.server_url))
+            .set("Content-Type", "application/json")
+            .send_string(&request.to_string())
+            .map_err(|error| error.to_string())?
+            .into_string()
+            .map_err(|error| error.to_string())
+            .and_then(|body| serde_json::from_str(&body).map_err(|error| error.to_string()))?;
+        let job_id = response["id"]
+            .as_u64()
+            .ok_or_else(|| format!("unexpected response {response}"))?;
+        info!("ViperServer started the verification job {}", job_id);
+        Ok(job_id)
+    }
+
+    /// Reads the messages of the job until its overall result.
+    fn wait_for_result(
+        &self,
+        job_id: u64,
+        position_id: impl Fn(u32, u32) -> Option<String>,
+    ) -> VerificationResult {
+        let response = match self
+            .agent
+            .get(&format!("{}/verify/{job_id}", self.server_url))
+            .call()
+        {
+            Ok(response) => response,
+            Err(error) => {
+                return VerificationResult::BackendErrors(vec![format!(
+                    "could not get the result of the verification job {job_id} from ViperServer: \
+                    {error}"
+                )]);
+            }
+        };
+        let reader = BufReader::new(response.into_reader());
+        for message in serde_json::Deserializer::from_reader(reader).into_iter::<Message>() {
+            let mut message = match message {
+                Ok(message) => message,
+                Err(error) => {
+                    return VerificationResult::BackendErrors(vec![format!(
+                        "could not parse a message of ViperServer: {error}"
+                    )]);
+                }
+            };
+            debug!("ViperServer message of type {}", message.msg_type);
+            match message.msg_type.as_str() {
+                // The results of single methods and functions precede the
+                // overall result, which contains all errors.
+                "verification_result" if message.msg_body["kind"] == "overall" => {
+                    let result: ResultDetails = match serde_json::from_value(
+                        message.msg_body["details"]["result"].take(),
+                    ) {
+                        Ok(result) => result,
+                        Err(error) => {
+                            return VerificationResult::BackendErrors(vec![format!(
+                                "could not parse a verification result of ViperServer: \
+                                    {error}"
+                            )]);
+                        }
+                    };
+                    return convert_result(result, &position_id);
+                }
+                "ast_construction_result" if message.msg_body["status"] == "failure" => {
+                    // The program cannot be parsed or type-checked.
+                    let details = &message.msg_body["details"];
+                    let result = details.get("result").unwrap_or(details);
+                    let errors = result["errors"].as_array().cloned().unwrap_or_default();
+                    return VerificationResult::ConsistencyErrors(
+                        errors
+                            .iter()
+                            .map(|error| error["text"].as_str().unwrap_or_default().to_string())
+                            .collect(),
+                    );
+                }
+                "exception_report" => {
+                    let report: ExceptionReport = serde_json::from_value(message.msg_body)
+                        .unwrap_or_else(|error| ExceptionReport {
+                            message: error.to_string(),
+                            stacktrace: vec![],
+                        });
+                    error!(
+                        "The verification aborted due to the following exception: {}",
+                        report.message
+                    );
+                    return VerificationResult::JavaException(JavaException::new(
+                        report.message,
+                        report.stacktrace.join("\n"),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        VerificationResult::BackendErrors(vec![format!(
+            "ViperServer did not report the result of the verification job {job_id}"
+        )])
+    }
+}
+
+fn convert_result(
+    result: ResultDetails,
+    position_id: &impl Fn(u32, u32) -> Option<String>,
+) -> VerificationResult {
+    if result.kind == "success" {
+        return VerificationResult::Success;
+    }
+    let mut errors = vec![];
+    let mut consistency_errors = vec![];
+    let mut other_errors = vec![];
+    for error in result.errors {
+        match error.tag.as_str() {
+            "timeout.occurred" => return VerificationResult::Timeout,
+            "consistency.error" => consistency_errors.push(error.text),
+            // The identifiers of verification errors have the form
+            // `error:reason`, unlike those of other errors.
+            tag if tag.contains(':') => {
+                let pos_id = error_position(&error.position)
+                    .and_then(|(line, column)| position_id(line, column));
+                if pos_id.is_none() {
+                    debug!(
+                        "ViperServer returned an error whose position has no identifier: {}",
+                        error.text
+                    );
+                }
+                errors.push(VerificationError::new(
+                    error.tag,
+                    pos_id.clone(),
+                    pos_id,
+                    None,
+                    error.text,
+                    None,
+                ));
+            }
+            _ => {
+                error!(
+                    "ViperServer returned an unhandled error of type {}: {}",
+                    error.tag, error.text
+                );
+                other_errors.push(format!("{}: {}", error.tag, error.text));
+            }
+        }
+    }
+    if !consistency_errors.is_empty() {
+        VerificationResult::ConsistencyErrors(consistency_errors)
+    } else if !other_errors.is_empty() {
+        VerificationResult::BackendErrors(other_errors)
+    } else {
+        VerificationResult::Failure(errors)
+    }
+}
+
+/// The line and column of the start of a position such as
+/// `{"file": "program.vpr", "start": "3:5", "end": "3:17"}`.
+fn error_position(position: &serde_json::Value) -> Option<(u32, u32)> {
+    let (line, column) = position["start"].as_str()?.split_once(':')?;
+    Some((line.parse().ok()?, column.parse().ok()?))
+}
+
+/// Quotes an argument that contains whitespace, because the server splits the
+/// arguments at whitespace outside of quotes.
+fn quote_arg(arg: &str) -> String {
+    if arg.contains(char::is_whitespace) {
+        format!("\"{arg}\"")
+    } else {
+        arg.to_string()
+    }
+}
@@ -0,0 +1,58 @@
+use std::{fs, path::PathBuf};
+use viper::*;
+
+/// A cache that is not saved to disk.
+fn in_memory_cache() -> PersistentCache {
+    PersistentCache::load_cache(PathBuf::new())
+}
+
+fn errors(message: &str) -> VerificationResult {
+    VerificationResult::BackendErrors(vec![message.to_string()])
+}
+
+#[test]
+fn evicts_least_recently_used_results() {
+    let mut cache = in_memory_cache().with_limits(CacheLimits {
+        max_entries: Some(2),
+        max_size: None,
+    });
+    (&mut cache).insert(1, VerificationResult::Success);
+    (&mut cache).insert(2, VerificationResult::Timeout);
+    assert_eq!((&mut cache).get(1), Some(VerificationResult::Success));
+    (&mut cache).insert(3, errors("three"));
+    assert_eq!(cache.len(), 2);
+    assert_eq!((&mut cache).get(2), None);
+    assert_eq!((&mut cache).get(1), Some(VerificationResult::Success));
+    assert_eq!((&mut cache).get(3), Some(errors("three")));
+}
+
+#[test]
+fn evicts_results_beyond_the_maximum_size() {
+    let mut cache = in_memory_cache().with_limits(CacheLimits {
+        max_entries: None,
+        max_size: Some(150),
+    });
+    (&mut cache).insert(1, errors(&"a".repeat(40)));
+    (&mut cache).insert(2, errors(&"b".repeat(40)));
+    (&mut cache).insert(3, errors(&"c".repeat(40)));
+    assert_eq!(cache.len(), 2);
+    assert_eq!((&mut cache).get(1), None);
+}
+
+#[test]
+fn imports_results_of_a_cache_file() {
+    let path = std::env::temp_dir().join(format!("prusti-cache-{}.bin", std::process::id()));
+    let mut saved = PersistentCache::load_cache(path.clone());
+    (&mut saved).insert(1, VerificationResult::Success);
+    (&mut saved).insert(2, VerificationResult::Timeout);
+    saved.save();
+    let serialized = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let mut cache = in_memory_cache();
+    (&mut cache).insert(2, errors("two"));
+    assert_eq!(cache.import(&serialized), Ok(1));
+    assert_eq!((&mut cache).get(1), Some(VerificationResult::Success));
+    assert_eq!((&mut cache).get(2), Some(errors("two")));
+    assert!(cache.import(b"not a cache").is_err());
+}
@@ -0,0 +1,62 @@
+use std::{fs, path::PathBuf, sync::Once};
+use viper::*;
+
+static INIT: Once = Once::new();
+
+lazy_static::lazy_static! {
+    static ref VIPER: Viper = Viper::new_for_tests();
+}
+
+/// Setup function that is only run once, even if called multiple times.
+fn setup() {
+    INIT.call_once(|| {
+        env_logger::init();
+    });
+}
+
+/// Writes `content` to a new Viper file in the temporary directory.
+fn viper_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("prusti-{}-{name}.vpr", std::process::id()));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn parsed_programs_are_verified() {
+    setup();
+
+    let verification_context: VerificationContext = VIPER.attach_current_thread();
+    let ast_utils = verification_context.new_ast_utils();
+    let path = viper_file(
+        "valid",
+        "method pass(x: Int) requires x > 0 { assert x >= 0 }\n\
+         method fail(x: Int) { assert x > 0 }\n",
+    );
+    let program = ast_utils.parse_file(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let mut verifier =
+        verification_context.new_verifier_with_default_smt(viper::VerificationBackend::Silicon);
+    match verifier.verify(program) {
+        VerificationResult::Failure(errors) => {
+            assert_eq!(errors.len(), 1, "{errors:?}");
+            assert_eq!(errors[0].full_id, "assert.failed:assertion.false");
+        }
+        result => panic!("unexpected verification result: {result:?}"),
+    }
+}
+
+#[test]
+fn parse_errors_are_returned() {
+    setup();
+
+    let verification_context: VerificationContext = VIPER.attach_current_thread();
+    let ast_utils = verification_context.new_ast_utils();
+    let syntax_error = viper_file("syntax-error", "method foo( { }\n");
+    let type_error = viper_file("type-error", "method foo() { var x: Int := true }\n");
+    for path in [syntax_error, type_error] {
+        let result = ast_utils.parse_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(errors) if !errors.is_empty()));
+    }
+}
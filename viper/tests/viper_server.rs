@@ -0,0 +1,194 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    path::Path,
+    thread,
+};
+use viper::*;
+
+/// Starts a server that answers the verification request and then streams
+/// `messages` as the result. It returns the address of the server and a
+/// handle that returns the body of the verification request.
+fn mock_viper_server(messages: Vec<&'static str>) -> (String, thread::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let handle = thread::spawn(move || {
+        let mut request_body = String::new();
+        for response in [r#"{"id":0}"#.to_string(), messages.join("\n")] {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            if content_length > 0 {
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                request_body = String::from_utf8(body).unwrap();
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                Connection: close\r\n\r\n{response}",
+                response.len()
+            )
+            .unwrap();
+        }
+        request_body
+    });
+    (address, handle)
+}
+
+#[test]
+fn success() {
+    let (address, server) = mock_viper_server(vec![
+        r#"{"msg_type":"copyright","msg_body":{"text":"..."}}"#,
+        r#"{"msg_type":"ast_construction_result","msg_body":{"status":"success","details":{"time":1}}}"#,
+        r#"{"msg_type":"verification_result","msg_body":{"verifier":"silicon","kind":"overall","details":{"result":{"type":"success"},"time":42}}}"#,
+    ]);
+    let client = ViperServerClient::new(&address);
+    let result = client.verify(
+        VerificationBackend::Silicon,
+        &[
+            "--proverConfigArgs".to_string(),
+            "smt.qi.eager_threshold=1000 rlimit=10".to_string(),
+        ],
+        Path::new("program.vpr"),
+        |_, _| None,
+    );
+    assert_eq!(result, VerificationResult::Success);
+    let request: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+    assert_eq!(
+        request["arg"],
+        r#"silicon --proverConfigArgs "smt.qi.eager_threshold=1000 rlimit=10" program.vpr"#
+    );
+}
+
+#[test]
+fn failure_with_positions() {
+    let (address, server) = mock_viper_server(vec![
+        r#"{"msg_type":"verification_result","msg_body":{"verifier":"silicon","kind":"for_entity","details":{"entity":{"type":"method","name":"foo"}}}}"#,
+        r#"{"msg_type":"verification_result","msg_body":{"verifier":"silicon","kind":"overall","details":{"result":{"type":"error","errors":[{"tag":"assert.failed:assertion.false","text":"Assert might fail. Assertion false might not hold.","position":{"file":"program.vpr","start":"3:5","end":"3:17"},"cached":false}]},"time":42}}}"#,
+    ]);
+    let client = ViperServerClient::new(&address);
+    let result = client.verify(
+        VerificationBackend::Silicon,
+        &[],
+        Path::new("program.vpr"),
+        |line, column| (line == 3 && column == 5).then(|| "123".to_string()),
+    );
+    server.join().unwrap();
+    let VerificationResult::Failure(errors) = result else {
+        panic!("the verification should fail, instead found {result:?}");
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].full_id, "assert.failed:assertion.false");
+    assert_eq!(errors[0].offending_pos_id.as_deref(), Some("123"));
+}
+
+#[test]
+fn timeout() {
+    let (address, server) = mock_viper_server(vec![
+        r#"{"msg_type":"verification_result","msg_body":{"verifier":"silicon","kind":"overall","details":{"result":{"type":"error","errors":[{"tag":"timeout.occurred","text":"Timeout occurred after 10 seconds","position":"<no position>"}]}}}}"#,
+    ]);
+    let client = ViperServerClient::new(&address);
+    let result = client.verify(
+        VerificationBackend::Carbon,
+        &[],
+        Path::new("program.vpr"),
+        |_, _| None,
+    );
+    server.join().unwrap();
+    assert_eq!(result, VerificationResult::Timeout);
+}
+
+#[test]
+fn consistency_errors() {
+    let (address, server) = mock_viper_server(vec![
+        r#"{"msg_type":"ast_construction_result","msg_body":{"status":"failure","details":{"result":{"type":"error","errors":[{"tag":"typechecker.error","text":"undeclared identifier y","position":{"file":"program.vpr","start":"2:3","end":"2:4"}}]}}}}"#,
+    ]);
+    let client = ViperServerClient::new(&address);
+    let result = client.verify(
+        VerificationBackend::Silicon,
+        &[],
+        Path::new("program.vpr"),
+        |_, _| None,
+    );
+    server.join().unwrap();
+    assert_eq!(
+        result,
+        VerificationResult::ConsistencyErrors(vec!["undeclared identifier y".to_string()])
+    );
+}
+
+#[test]
+fn exception_report() {
+    let (address, server) = mock_viper_server(vec![
+        r#"{"msg_type":"exception_report","msg_body":{"message":"java.lang.OutOfMemoryError","stacktrace":["at a","at b"]}}"#,
+    ]);
+    let client = ViperServerClient::new(&address);
+    let result = client.verify(
+        VerificationBackend::Silicon,
+        &[],
+        Path::new("program.vpr"),
+        |_, _| None,
+    );
+    server.join().unwrap();
+    let VerificationResult::JavaException(exception) = result else {
+        panic!("the verification should abort, instead found {result:?}");
+    };
+    assert_eq!(exception.get_message(), "java.lang.OutOfMemoryError");
+    assert_eq!(exception.get_stack_trace(), "at a\nat b");
+}
+
+#[test]
+fn missing_overall_result() {
+    let (address, server) = mock_viper_server(vec![
+        r#"{"msg_type":"verification_result","msg_body":{"verifier":"silicon","kind":"for_entity","details":{"entity":{"type":"method","name":"foo"}}}}"#,
+    ]);
+    let client = ViperServerClient::new(&address);
+    let result = client.verify(
+        VerificationBackend::Silicon,
+        &[],
+        Path::new("program.vpr"),
+        |_, _| None,
+    );
+    server.join().unwrap();
+    assert!(
+        matches!(&result, VerificationResult::BackendErrors(errors) if errors.len() == 1),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn unreachable_server() {
+    // Binding and dropping a listener leaves a port on which nothing listens.
+    let address = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    let client = ViperServerClient::new(&address);
+    let result = client.verify(
+        VerificationBackend::Silicon,
+        &[],
+        Path::new("program.vpr"),
+        |_, _| None,
+    );
+    let VerificationResult::BackendErrors(errors) = result else {
+        panic!("the verification should fail to start, instead found {result:?}");
+    };
+    assert!(
+        errors[0].starts_with("could not send the verification request to ViperServer"),
+        "{errors:?}"
+    );
+}
@@ -5,7 +5,9 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use crate::{
-    common::identifier::WithIdentifier, converter::type_substitution::Generic, polymorphic::ast::*,
+    common::{identifier::WithIdentifier, symbol::Symbol},
+    converter::type_substitution::Generic,
+    polymorphic::ast::*,
 };
 use rustc_hash::{FxHashMap, FxHasher};
 use std::{
@@ -289,7 +291,7 @@ impl Type {
         self.substitute(substs)
     }
 
-    pub fn typed_ref<S: Into<String>>(name: S) -> Self {
+    pub fn typed_ref<S: Into<Symbol>>(name: S) -> Self {
         Type::TypedRef(TypedRef {
             label: name.into(),
             arguments: vec![],
@@ -297,7 +299,7 @@ impl Type {
         })
     }
 
-    pub fn typed_ref_with_args<S: Into<String>>(name: S, arguments: Vec<Type>) -> Self {
+    pub fn typed_ref_with_args<S: Into<Symbol>>(name: S, arguments: Vec<Type>) -> Self {
         Type::TypedRef(TypedRef {
             label: name.into(),
             arguments,
@@ -305,7 +307,7 @@ impl Type {
         })
     }
 
-    pub fn domain<S: Into<String>>(name: S) -> Self {
+    pub fn domain<S: Into<Symbol>>(name: S) -> Self {
         Type::Domain(DomainType {
             label: name.into(),
             arguments: vec![],
@@ -313,7 +315,7 @@ impl Type {
         })
     }
 
-    pub fn domain_with_args<S: Into<String>>(name: S, arguments: Vec<Type>) -> Self {
+    pub fn domain_with_args<S: Into<Symbol>>(name: S, arguments: Vec<Type>) -> Self {
         Type::Domain(DomainType {
             label: name.into(),
             arguments,
@@ -321,7 +323,7 @@ impl Type {
         })
     }
 
-    pub fn snapshot<S: Into<String>>(name: S) -> Self {
+    pub fn snapshot<S: Into<Symbol>>(name: S) -> Self {
         Type::Snapshot(SnapshotType {
             label: name.into(),
             arguments: vec![],
@@ -329,7 +331,7 @@ impl Type {
         })
     }
 
-    pub fn snapshot_with_args<S: Into<String>>(name: S, arguments: Vec<Type>) -> Self {
+    pub fn snapshot_with_args<S: Into<Symbol>>(name: S, arguments: Vec<Type>) -> Self {
         Type::Snapshot(SnapshotType {
             label: name.into(),
             arguments,
@@ -343,7 +345,7 @@ impl Type {
         match self {
             Type::TypedRef(typed_ref) => Type::Snapshot(typed_ref.clone().into()),
             Type::TypeVar(_) => Type::Snapshot(SnapshotType {
-                label: self.encode_as_string(),
+                label: self.encode_as_string().into(),
                 arguments: Vec::new(),
                 variant: String::from(""),
             }),
@@ -351,7 +353,7 @@ impl Type {
         }
     }
 
-    pub fn type_var<S: Into<String>>(name: S) -> Self {
+    pub fn type_var<S: Into<Symbol>>(name: S) -> Self {
         Type::TypeVar(TypeVar { label: name.into() })
     }
 
@@ -533,13 +535,13 @@ impl fmt::Display for MapType {
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialOrd, Ord)]
 pub struct TypedRef {
-    pub label: String,
+    pub label: Symbol,
     pub arguments: Vec<Type>,
     pub variant: String,
 }
 
 impl TypedRef {
-    pub fn new<S: Into<String>>(label: S, arguments: Vec<Type>) -> Self {
+    pub fn new<S: Into<Symbol>>(label: S, arguments: Vec<Type>) -> Self {
         Self {
             label: label.into(),
             arguments,
@@ -575,7 +577,7 @@ impl From<SnapshotType> for TypedRef {
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialOrd, Ord)]
 pub struct DomainType {
-    pub label: String,
+    pub label: Symbol,
     pub arguments: Vec<Type>,
     pub variant: String,
 }
@@ -616,7 +618,7 @@ impl From<TypeVar> for DomainType {
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialOrd, Ord)]
 pub struct SnapshotType {
-    pub label: String,
+    pub label: Symbol,
     pub arguments: Vec<Type>,
     pub variant: String,
 }
@@ -659,7 +661,7 @@ impl From<TypeVar> for SnapshotType {
     Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord,
 )]
 pub struct TypeVar {
-    pub label: String,
+    pub label: Symbol,
 }
 
 impl TypeVar {
@@ -716,7 +718,7 @@ impl LocalVar {
 
 #[derive(Clone, Eq, PartialEq, Hash, serde::Serialize, serde::Deserialize, PartialOrd, Ord)]
 pub struct Field {
-    pub name: String,
+    pub name: Symbol,
     pub typ: Type,
 }
 
@@ -733,7 +735,7 @@ impl fmt::Debug for Field {
 }
 
 impl Field {
-    pub fn new<S: Into<String>>(name: S, typ: Type) -> Self {
+    pub fn new<S: Into<Symbol>>(name: S, typ: Type) -> Self {
         Field {
             name: name.into(),
             typ,
@@ -750,6 +752,6 @@ impl Field {
 
 impl WithIdentifier for Field {
     fn get_identifier(&self) -> String {
-        self.name.clone()
+        self.name.to_string()
     }
 }
@@ -5,7 +5,11 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use super::super::borrows::Borrow;
-use crate::{common::display, converter::type_substitution::Generic, polymorphic::ast::*};
+use crate::{
+    common::{display, symbol::Symbol},
+    converter::type_substitution::Generic,
+    polymorphic::ast::*,
+};
 use rustc_hash::FxHashMap;
 use std::{
     fmt,
@@ -239,6 +243,19 @@ impl Expr {
         )
     }
 
+    /// Set the position of the expression to `pos` if it does not have one
+    /// yet. Unlike `set_default_pos`, the subexpressions are left unchanged.
+    /// Used when an expression replaces another one, so that the new
+    /// expression keeps pointing to the same source location.
+    #[must_use]
+    pub fn set_pos_if_default(self, pos: Position) -> Self {
+        if self.pos().is_default() {
+            self.set_pos(pos)
+        } else {
+            self
+        }
+    }
+
     // Replace all Position::default() positions with `pos`
     #[must_use]
     pub fn set_default_pos(self, pos: Position) -> Self {
@@ -294,9 +311,9 @@ impl Expr {
         })
     }
 
-    pub fn labelled_old(label: &str, expr: Expr) -> Self {
+    pub fn labelled_old<S: Into<Symbol>>(label: S, expr: Expr) -> Self {
         Expr::LabelledOld(LabelledOld {
-            label: label.to_string(),
+            label: label.into(),
             base: Box::new(expr),
             position: Position::default(),
         })
@@ -732,7 +749,7 @@ impl Expr {
 
     /// Puts an `old[label](..)` around the expression
     #[must_use]
-    pub fn old<S: fmt::Display + ToString>(self, label: S) -> Self {
+    pub fn old<S: Into<Symbol>>(self, label: S) -> Self {
         match self {
             Expr::Local(..) => {
                 /*
@@ -755,7 +772,7 @@ impl Expr {
                 self
             }
             _ => Expr::LabelledOld(LabelledOld {
-                label: label.to_string(),
+                label: label.into(),
                 base: Box::new(self),
                 position: Position::default(),
             }),
@@ -883,7 +900,7 @@ impl Expr {
         }
     }
 
-    pub fn get_label(&self) -> Option<&String> {
+    pub fn get_label(&self) -> Option<&Symbol> {
         match self {
             Expr::LabelledOld(LabelledOld { label, .. }) => Some(label),
             _ => None,
@@ -1166,12 +1183,12 @@ impl Expr {
     #[must_use]
     pub fn map_labels<F>(self, f: F) -> Self
     where
-        F: Fn(String) -> Option<String>,
+        F: Fn(Symbol) -> Option<Symbol>,
     {
-        struct OldLabelReplacer<T: Fn(String) -> Option<String>> {
+        struct OldLabelReplacer<T: Fn(Symbol) -> Option<Symbol>> {
             f: T,
         }
-        impl<T: Fn(String) -> Option<String>> ExprFolder for OldLabelReplacer<T> {
+        impl<T: Fn(Symbol) -> Option<Symbol>> ExprFolder for OldLabelReplacer<T> {
             fn fold_labelled_old(
                 &mut self,
                 LabelledOld {
@@ -1473,7 +1490,7 @@ impl Expr {
     #[must_use]
     pub fn remove_redundant_old(self) -> Self {
         struct RedundantOldRemover {
-            current_label: Option<String>,
+            current_label: Option<Symbol>,
         }
         impl ExprFolder for RedundantOldRemover {
             fn fold_labelled_old(
@@ -1484,9 +1501,9 @@ impl Expr {
                     position,
                 }: LabelledOld,
             ) -> Expr {
-                let old_current_label = mem::replace(&mut self.current_label, Some(label.clone()));
+                let old_current_label = mem::replace(&mut self.current_label, Some(label));
                 let new_base = default_fold_expr(self, *base);
-                let new_expr = if Some(label.clone()) == old_current_label {
+                let new_expr = if Some(label) == old_current_label {
                     new_base
                 } else {
                     new_base.old(label).set_pos(position)
@@ -1963,7 +1980,7 @@ impl Hash for AddrOf {
 
 #[derive(Debug, Clone, Eq, serde::Serialize, serde::Deserialize, PartialOrd, Ord)]
 pub struct LabelledOld {
-    pub label: String,
+    pub label: Symbol,
     pub base: Box<Expr>,
     pub position: Position,
 }
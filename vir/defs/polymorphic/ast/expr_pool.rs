@@ -0,0 +1,275 @@
+// © 2023, ETH Zurich
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::polymorphic::ast::*;
+use std::mem;
+
+/// The identifier of an expression stored in an [`ExprPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExprId(usize);
+
+#[derive(Debug, Clone)]
+struct ExprNode {
+    /// The expression with its direct subexpressions replaced by
+    /// placeholders.
+    shape: Expr,
+    /// The direct subexpressions, in the order of `direct_subexpressions_mut`.
+    children: Vec<ExprId>,
+}
+
+/// A flat representation of expressions, in which every expression node is
+/// stored once in a vector and refers to its direct subexpressions by their
+/// `ExprId`. Unlike the tree form, the nodes can be traversed and rewritten
+/// with loops instead of recursion, and a node can be shared by several
+/// expressions without cloning it.
+///
+/// The tree form is converted with `insert` and converted back with
+/// `to_expr`.
+#[derive(Debug, Clone, Default)]
+pub struct ExprPool {
+    nodes: Vec<ExprNode>,
+}
+
+impl ExprPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Moves the expression into the pool. The subexpressions are inserted
+    /// before the expression, so their identifiers are smaller.
+    pub fn insert(&mut self, expr: Expr) -> ExprId {
+        enum Task {
+            Visit(Expr),
+            Finish(Expr, usize),
+        }
+        let mut tasks = vec![Task::Visit(expr)];
+        let mut inserted = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(mut expr) => {
+                    let children: Vec<_> = direct_subexpressions_mut(&mut expr)
+                        .into_iter()
+                        .map(|child| mem::replace(child, placeholder()))
+                        .collect();
+                    tasks.push(Task::Finish(expr, children.len()));
+                    tasks.extend(children.into_iter().rev().map(Task::Visit));
+                }
+                Task::Finish(shape, children_count) => {
+                    let children = inserted.split_off(inserted.len() - children_count);
+                    inserted.push(self.insert_node(shape, children));
+                }
+            }
+        }
+        debug_assert_eq!(inserted.len(), 1);
+        inserted.pop().unwrap()
+    }
+
+    /// Adds an expression whose direct subexpressions are already in the
+    /// pool. The subexpressions of `shape` are ignored; they are replaced by
+    /// `children` in the order in which `Expr` lists them.
+    pub fn insert_node(&mut self, mut shape: Expr, children: Vec<ExprId>) -> ExprId {
+        let slots = direct_subexpressions_mut(&mut shape);
+        assert_eq!(
+            slots.len(),
+            children.len(),
+            "the expression {shape:?} has a different number of subexpressions",
+        );
+        for slot in slots {
+            *slot = placeholder();
+        }
+        let id = ExprId(self.nodes.len());
+        self.nodes.push(ExprNode { shape, children });
+        id
+    }
+
+    /// Replaces the node `id` in place, so that all expressions that refer
+    /// to it see the new node.
+    pub fn replace_node(&mut self, id: ExprId, shape: Expr, children: Vec<ExprId>) {
+        let new_id = self.insert_node(shape, children);
+        debug_assert_eq!(new_id.0, self.nodes.len() - 1);
+        self.nodes.swap(id.0, new_id.0);
+        self.nodes.pop();
+    }
+
+    /// The expression `id` without its subexpressions, which are replaced by
+    /// placeholders.
+    pub fn shape(&self, id: ExprId) -> &Expr {
+        &self.nodes[id.0].shape
+    }
+
+    /// The direct subexpressions of the expression `id`.
+    pub fn children(&self, id: ExprId) -> &[ExprId] {
+        &self.nodes[id.0].children
+    }
+
+    /// The expressions reachable from `root`, each listed once and after all
+    /// of its subexpressions.
+    pub fn post_order(&self, root: ExprId) -> Vec<ExprId> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![(root, false)];
+        while let Some((id, children_done)) = stack.pop() {
+            if children_done {
+                order.push(id);
+            } else if !visited[id.0] {
+                visited[id.0] = true;
+                stack.push((id, true));
+                stack.extend(self.children(id).iter().rev().map(|&child| (child, false)));
+            }
+        }
+        order
+    }
+
+    /// Converts the expression `id` back to the tree form.
+    pub fn to_expr(&self, id: ExprId) -> Expr {
+        enum Task {
+            Visit(ExprId),
+            Build(ExprId),
+        }
+        let mut tasks = vec![Task::Visit(id)];
+        let mut built = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(id) => {
+                    tasks.push(Task::Build(id));
+                    tasks.extend(
+                        self.children(id)
+                            .iter()
+                            .rev()
+                            .map(|&child| Task::Visit(child)),
+                    );
+                }
+                Task::Build(id) => {
+                    let mut expr = self.shape(id).clone();
+                    let children = built.split_off(built.len() - self.children(id).len());
+                    for (slot, child) in direct_subexpressions_mut(&mut expr)
+                        .into_iter()
+                        .zip(children)
+                    {
+                        *slot = child;
+                    }
+                    built.push(expr);
+                }
+            }
+        }
+        debug_assert_eq!(built.len(), 1);
+        built.pop().unwrap()
+    }
+}
+
+fn placeholder() -> Expr {
+    Expr::Const(ConstExpr {
+        value: Const::Bool(false),
+        position: Position::default(),
+    })
+}
+
+/// The direct subexpressions of `expr`, in the order in which `ExprWalker`
+/// visits them.
+fn direct_subexpressions_mut(expr: &mut Expr) -> Vec<&mut Expr> {
+    match expr {
+        Expr::Local(_) | Expr::Const(_) => vec![],
+        Expr::Variant(Variant { base, .. })
+        | Expr::Field(FieldExpr { base, .. })
+        | Expr::AddrOf(AddrOf { base, .. })
+        | Expr::LabelledOld(LabelledOld { base, .. })
+        | Expr::FieldAccessPredicate(FieldAccessPredicate { base, .. })
+        | Expr::SnapApp(SnapApp { base, .. })
+        | Expr::Cast(Cast { base, .. }) => vec![&mut **base],
+        Expr::PredicateAccessPredicate(PredicateAccessPredicate { argument, .. })
+        | Expr::UnaryOp(UnaryOp { argument, .. }) => vec![&mut **argument],
+        Expr::MagicWand(MagicWand { left, right, .. })
+        | Expr::BinOp(BinOp { left, right, .. })
+        | Expr::ContainerOp(ContainerOp { left, right, .. }) => vec![&mut **left, &mut **right],
+        Expr::Seq(Seq { elements, .. }) | Expr::Map(Map { elements, .. }) => {
+            elements.iter_mut().collect()
+        }
+        Expr::FuncApp(FuncApp { arguments, .. })
+        | Expr::DomainFuncApp(DomainFuncApp { arguments, .. }) => arguments.iter_mut().collect(),
+        Expr::Unfolding(Unfolding {
+            arguments, base, ..
+        }) => arguments
+            .iter_mut()
+            .chain(std::iter::once(&mut **base))
+            .collect(),
+        Expr::Cond(Cond {
+            guard,
+            then_expr,
+            else_expr,
+            ..
+        }) => vec![&mut **guard, &mut **then_expr, &mut **else_expr],
+        Expr::ForAll(ForAll { triggers, body, .. })
+        | Expr::Exists(Exists { triggers, body, .. }) => triggers
+            .iter_mut()
+            .flat_map(|trigger| trigger.0.iter_mut())
+            .chain(std::iter::once(&mut **body))
+            .collect(),
+        Expr::LetExpr(LetExpr { def, body, .. }) => vec![&mut **def, &mut **body],
+        Expr::InhaleExhale(InhaleExhale {
+            inhale_expr,
+            exhale_expr,
+            ..
+        }) => vec![&mut **inhale_expr, &mut **exhale_expr],
+        Expr::Downcast(DowncastExpr {
+            base, enum_place, ..
+        }) => vec![&mut **base, &mut **enum_place],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(name: &str) -> Expr {
+        Expr::local(LocalVar::new(name, Type::Int))
+    }
+
+    fn sample() -> Expr {
+        let x = LocalVar::new("x", Type::Int);
+        let body = Expr::gt_cmp(Expr::local(x.clone()), local("a"));
+        let trigger = Trigger::new(vec![Expr::add(Expr::local(x.clone()), local("b"))]);
+        Expr::and(
+            Expr::forall(vec![x], vec![trigger], body),
+            Expr::ite(local("c"), local("d"), Expr::minus(local("e"))),
+        )
+    }
+
+    #[test]
+    fn expressions_are_converted_back_to_trees() {
+        let mut pool = ExprPool::new();
+        let id = pool.insert(sample());
+        assert_eq!(pool.to_expr(id), sample());
+        assert_eq!(pool.len(), 13);
+        for node in pool.post_order(id) {
+            assert!(pool.children(node).iter().all(|&child| child < node));
+        }
+    }
+
+    #[test]
+    fn replaced_nodes_are_seen_by_their_parents() {
+        let mut pool = ExprPool::new();
+        let id = pool.insert(Expr::add(local("a"), local("b")));
+        let a = pool.children(id)[0];
+        let c = pool.insert(local("c"));
+        pool.replace_node(a, placeholder(), vec![]);
+        assert_eq!(pool.to_expr(id), Expr::add(placeholder(), local("b")));
+        let minus = pool.insert(Expr::minus(local("d")));
+        let minus = pool.shape(minus).clone();
+        pool.replace_node(a, minus, vec![c]);
+        assert_eq!(
+            pool.to_expr(id),
+            Expr::add(Expr::minus(local("c")), local("b"))
+        );
+    }
+}
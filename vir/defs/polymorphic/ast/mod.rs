@@ -5,14 +5,15 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 pub use self::{
-    backend_type::*, bodyless_method::*, common::*, domain::*, expr::*, expr_transformers::*,
-    function::*, predicate::*, stmt::*, trigger::*,
+    backend_type::*, bodyless_method::*, common::*, domain::*, expr::*, expr_pool::*,
+    expr_transformers::*, function::*, predicate::*, stmt::*, trigger::*,
 };
 
 mod bodyless_method;
 pub mod common;
 mod domain;
 mod expr;
+mod expr_pool;
 mod expr_transformers;
 mod function;
 mod predicate;
@@ -8,7 +8,7 @@ use super::super::{
     borrows::{Borrow, DAG as ReborrowingDAG},
     cfg::CfgBlockIndex,
 };
-use crate::polymorphic::ast::*;
+use crate::{common::symbol::Symbol, polymorphic::ast::*};
 use rustc_hash::FxHashMap;
 use std::fmt::{self, Write};
 
@@ -125,7 +125,7 @@ impl fmt::Display for Comment {
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Label {
-    pub label: String,
+    pub label: Symbol,
 }
 
 impl fmt::Display for Label {
@@ -347,7 +347,7 @@ impl fmt::Display for TransferPerm {
 pub struct PackageMagicWand {
     pub magic_wand: Expr,
     pub package_stmts: Vec<Stmt>,
-    pub label: String,
+    pub label: Symbol,
     pub variables: Vec<LocalVar>,
     pub position: Position,
 }
@@ -461,9 +461,9 @@ impl Stmt {
         })
     }
 
-    pub fn label<S: ToString>(label: S) -> Self {
+    pub fn label<S: Into<Symbol>>(label: S) -> Self {
         Stmt::Label(Label {
-            label: label.to_string(),
+            label: label.into(),
         })
     }
 
@@ -475,11 +475,11 @@ impl Stmt {
         Stmt::Exhale(Exhale { expr, position })
     }
 
-    pub fn package_magic_wand(
+    pub fn package_magic_wand<S: Into<Symbol>>(
         lhs: Expr,
         rhs: Expr,
         stmts: Vec<Stmt>,
-        label: String,
+        label: S,
         vars: Vec<LocalVar>,
         pos: Position,
     ) -> Self {
@@ -491,7 +491,7 @@ impl Stmt {
                 position: pos,
             }),
             package_stmts: stmts,
-            label,
+            label: label.into(),
             variables: vars,
             position: pos,
         })
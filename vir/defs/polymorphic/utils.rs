@@ -6,9 +6,12 @@
 
 //! Various utility methods for working with VIR.
 
-use crate::polymorphic::{
-    self, ast::*, cfg, CfgMethod, ExprFolder, ExprWalker, FallibleStmtFolder, Function, StmtFolder,
-    StmtWalker,
+use crate::{
+    common::symbol::Symbol,
+    polymorphic::{
+        self, ast::*, cfg, CfgMethod, ExprFolder, ExprWalker, FallibleStmtFolder, Function,
+        StmtFolder, StmtWalker,
+    },
 };
 use log::{debug, trace};
 
@@ -68,17 +71,17 @@ impl Expr {
     #[tracing::instrument(name = "Expr::map_old_expr_label", level = "trace", skip_all, fields(self = %self))]
     pub fn map_old_expr_label<F>(self, substitutor: F) -> Self
     where
-        F: Fn(String) -> String,
+        F: Fn(Symbol) -> Symbol,
     {
         struct ExprLabelSubstitutor<T>
         where
-            T: Fn(String) -> String,
+            T: Fn(Symbol) -> Symbol,
         {
             substitutor: T,
         }
         impl<T> ExprFolder for ExprLabelSubstitutor<T>
         where
-            T: Fn(String) -> String,
+            T: Fn(Symbol) -> Symbol,
         {
             fn fold_labelled_old(
                 &mut self,
@@ -8,3 +8,4 @@ pub mod graphviz;
 pub mod identifier;
 pub mod position;
 pub mod positions;
+pub mod symbol;
@@ -0,0 +1,197 @@
+use rustc_hash::FxHashSet;
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::Mutex,
+};
+
+lazy_static::lazy_static! {
+    /// The interned strings. They are never freed, since identifiers are
+    /// shared by all encoded procedures.
+    static ref INTERNER: Mutex<FxHashSet<&'static str>> = Mutex::new(FxHashSet::default());
+}
+
+/// An interned string, used for identifiers that are frequently cloned and
+/// compared, like field names, predicate names and labels.
+///
+/// Cloning and comparing symbols does not look at the characters, because
+/// two symbols are equal if and only if they point to the same interned
+/// string. Hashing and ordering use the characters, so that they do not
+/// depend on the order in which strings are interned.
+#[derive(Clone, Copy)]
+pub struct Symbol(&'static str);
+
+impl Symbol {
+    pub fn intern(string: &str) -> Self {
+        let mut interner = INTERNER.lock().unwrap();
+        if let Some(interned) = interner.get(string) {
+            return Symbol(interned);
+        }
+        let interned: &'static str = Box::leak(string.to_string().into_boxed_str());
+        interner.insert(interned);
+        Symbol(interned)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl Borrow<str> for Symbol {
+    fn borrow(&self) -> &str {
+        self.0
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self == other {
+            Ordering::Equal
+        } else {
+            self.0.cmp(other.0)
+        }
+    }
+}
+
+macro impl_eq_with_strings($($string: ty),*) {
+    $(
+        impl PartialEq<$string> for Symbol {
+            fn eq(&self, other: &$string) -> bool {
+                self.0 == &other[..]
+            }
+        }
+
+        impl PartialEq<Symbol> for $string {
+            fn eq(&self, other: &Symbol) -> bool {
+                &self[..] == other.0
+            }
+        }
+    )*
+}
+
+impl_eq_with_strings!(str, &str, String, &String);
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+impl Default for Symbol {
+    fn default() -> Self {
+        Symbol::intern("")
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(string: &str) -> Self {
+        Symbol::intern(string)
+    }
+}
+
+impl From<&Symbol> for Symbol {
+    fn from(symbol: &Symbol) -> Self {
+        *symbol
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(string: &String) -> Self {
+        Symbol::intern(string)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(string: String) -> Self {
+        Symbol::intern(&string)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> Self {
+        symbol.0.to_string()
+    }
+}
+
+impl serde::Serialize for Symbol {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Symbol {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        Ok(Symbol::intern(&string))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interned_strings_are_shared() {
+        let field = Symbol::from("val_int");
+        assert_eq!(field, Symbol::from(String::from("val_int")));
+        assert!(std::ptr::eq(
+            field.as_str(),
+            Symbol::intern("val_int").as_str()
+        ));
+        assert_ne!(field, Symbol::from("val_bool"));
+        assert_eq!(field, "val_int");
+        assert_eq!(String::from("val_int"), field);
+    }
+
+    #[test]
+    fn symbols_are_ordered_by_their_characters() {
+        let b = Symbol::from("b");
+        let a = Symbol::from("a");
+        assert!(a < b);
+        let mut symbols = vec![b, a, Symbol::from("c")];
+        symbols.sort();
+        assert_eq!(symbols, ["a", "b", "c"]);
+    }
+}
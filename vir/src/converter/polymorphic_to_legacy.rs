@@ -136,7 +136,7 @@ impl From<polymorphic::LocalVar> for legacy::LocalVar {
 impl From<polymorphic::Field> for legacy::Field {
     fn from(field: polymorphic::Field) -> legacy::Field {
         legacy::Field {
-            name: field.name,
+            name: field.name.into(),
             typ: field.typ.into(),
         }
     }
@@ -247,7 +247,7 @@ impl From<polymorphic::Expr> for legacy::Expr {
                 addr_of.position.into(),
             ),
             polymorphic::Expr::LabelledOld(labelled_old) => legacy::Expr::LabelledOld(
-                labelled_old.label,
+                labelled_old.label.into(),
                 Box::new((*labelled_old.base).into()),
                 labelled_old.position.into(),
             ),
@@ -591,7 +591,7 @@ impl From<polymorphic::Stmt> for legacy::Stmt {
     fn from(stmt: polymorphic::Stmt) -> legacy::Stmt {
         match stmt {
             polymorphic::Stmt::Comment(comment) => legacy::Stmt::Comment(comment.comment),
-            polymorphic::Stmt::Label(label) => legacy::Stmt::Label(label.label),
+            polymorphic::Stmt::Label(label) => legacy::Stmt::Label(label.label.into()),
             polymorphic::Stmt::Inhale(inhale) => legacy::Stmt::Inhale(inhale.expr.into()),
             polymorphic::Stmt::Exhale(exhale) => {
                 legacy::Stmt::Exhale(exhale.expr.into(), exhale.position.into())
@@ -661,7 +661,7 @@ impl From<polymorphic::Stmt> for legacy::Stmt {
                         .into_iter()
                         .map(|package_stmt| package_stmt.into())
                         .collect(),
-                    package_magic_wand.label,
+                    package_magic_wand.label.into(),
                     package_magic_wand
                         .variables
                         .into_iter()
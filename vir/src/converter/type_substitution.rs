@@ -918,17 +918,18 @@ impl Generic for Program {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::symbol::Symbol;
 
     lazy_static::lazy_static! {
         static ref SUBSTITUTION_MAP : FxHashMap<TypeVar, Type> = {
             let mut m = FxHashMap::default();
-            m.insert(TypeVar { label: String::from("T") }, Type::Int);
-            m.insert(TypeVar { label: String::from("E") }, Type::Bool);
-            m.insert(TypeVar { label: String::from("F") }, Type::typed_ref("SimpleRef"));
+            m.insert(TypeVar { label: Symbol::from("T") }, Type::Int);
+            m.insert(TypeVar { label: Symbol::from("E") }, Type::Bool);
+            m.insert(TypeVar { label: Symbol::from("F") }, Type::typed_ref("SimpleRef"));
             // Substitution into other type vars that have a mapping
-            m.insert(TypeVar { label: String::from("G") }, Type::typed_ref_with_args("ComplexRef", vec![Type::type_var("T")]));
+            m.insert(TypeVar { label: Symbol::from("G") }, Type::typed_ref_with_args("ComplexRef", vec![Type::type_var("T")]));
             // Subsitutition into the same type vars used for substitution
-            m.insert(TypeVar { label: String::from("H") }, Type::typed_ref_with_args(
+            m.insert(TypeVar { label: Symbol::from("H") }, Type::typed_ref_with_args(
                 "ComplexRef2",
                 vec![
                     Type::type_var("H"),
@@ -1142,11 +1143,11 @@ mod tests {
     // successful substitution within Field
     fn substitution_type_var_field_test() {
         let source = Field {
-            name: String::from("_f1"),
+            name: Symbol::from("_f1"),
             typ: Type::type_var("T"),
         };
         let expected = Field {
-            name: String::from("_f1"),
+            name: Symbol::from("_f1"),
             typ: Type::Int,
         };
         test(source, expected, &SUBSTITUTION_MAP);
@@ -1185,7 +1186,7 @@ mod tests {
                 position,
             })),
             variant_index: Field {
-                name: String::from("_f1"),
+                name: Symbol::from("_f1"),
                 typ: Type::type_var("T"),
             },
             position,
@@ -1199,7 +1200,7 @@ mod tests {
                 position,
             })),
             variant_index: Field {
-                name: String::from("_f1"),
+                name: Symbol::from("_f1"),
                 typ: Type::Int,
             },
             position,
@@ -1216,7 +1217,7 @@ mod tests {
                 position,
             })),
             field: Field {
-                name: String::from("_f1"),
+                name: Symbol::from("_f1"),
                 typ: Type::type_var("T"),
             },
             position,
@@ -1230,7 +1231,7 @@ mod tests {
                 position,
             })),
             field: Field {
-                name: String::from("_f1"),
+                name: Symbol::from("_f1"),
                 typ: Type::Int,
             },
             position,
@@ -1264,7 +1265,7 @@ mod tests {
 
         // LabelledOld
         source = Expr::LabelledOld(LabelledOld {
-            label: String::from("l1"),
+            label: Symbol::from("l1"),
             base: Box::new(Expr::Local(Local {
                 variable: LocalVar {
                     name: String::from("_v1"),
@@ -1275,7 +1276,7 @@ mod tests {
             position,
         });
         expected = Expr::LabelledOld(LabelledOld {
-            label: String::from("l1"),
+            label: Symbol::from("l1"),
             base: Box::new(Expr::Local(Local {
                 variable: LocalVar {
                     name: String::from("_v1"),
@@ -2038,7 +2039,7 @@ mod tests {
                 position,
             })),
             field: Field {
-                name: String::from("f1"),
+                name: Symbol::from("f1"),
                 typ: Type::type_var("E"),
             },
         });
@@ -2058,7 +2059,7 @@ mod tests {
                 position,
             })),
             field: Field {
-                name: String::from("f1"),
+                name: Symbol::from("f1"),
                 typ: Type::Bool,
             },
         });
@@ -2082,10 +2083,10 @@ mod tests {
 
         // Label
         source = Stmt::Label(Label {
-            label: String::from("c1"),
+            label: Symbol::from("c1"),
         });
         expected = Stmt::Label(Label {
-            label: String::from("c1"),
+            label: Symbol::from("c1"),
         });
         test(source, expected, &SUBSTITUTION_MAP);
 
@@ -2446,7 +2447,7 @@ mod tests {
                     position,
                 }),
             ],
-            label: String::from("l1"),
+            label: Symbol::from("l1"),
             variables: vec![
                 LocalVar {
                     name: String::from("_v4"),
@@ -2488,7 +2489,7 @@ mod tests {
                     position,
                 }),
             ],
-            label: String::from("l1"),
+            label: Symbol::from("l1"),
             variables: vec![
                 LocalVar {
                     name: String::from("_v4"),
@@ -2804,7 +2805,7 @@ mod tests {
                 position,
             }),
             field: Field {
-                name: String::from("f1"),
+                name: Symbol::from("f1"),
                 typ: Type::Bool,
             },
         });
@@ -2817,7 +2818,7 @@ mod tests {
                 position,
             }),
             field: Field {
-                name: String::from("f1"),
+                name: Symbol::from("f1"),
                 typ: Type::Bool,
             },
         });
@@ -3261,7 +3262,7 @@ mod tests {
                 typ: Type::type_var("T"),
             },
             discriminant_field: Field {
-                name: String::from("f1"),
+                name: Symbol::from("f1"),
                 typ: Type::type_var("E"),
             },
             discriminant_bounds: Expr::Local(Local {
@@ -3330,7 +3331,7 @@ mod tests {
                 typ: Type::Int,
             },
             discriminant_field: Field {
-                name: String::from("f1"),
+                name: Symbol::from("f1"),
                 typ: Type::Bool,
             },
             discriminant_bounds: Expr::Local(Local {
@@ -3439,7 +3440,7 @@ mod tests {
                 typ: Type::type_var("T"),
             },
             discriminant_field: Field {
-                name: String::from("f1"),
+                name: Symbol::from("f1"),
                 typ: Type::type_var("E"),
             },
             discriminant_bounds: Expr::Local(Local {
@@ -3507,7 +3508,7 @@ mod tests {
                 typ: Type::Int,
             },
             discriminant_field: Field {
-                name: String::from("f1"),
+                name: Symbol::from("f1"),
                 typ: Type::Bool,
             },
             discriminant_bounds: Expr::Local(Local {
@@ -20,6 +20,8 @@ import signal
 import shutil
 import traceback
 import datetime
+import hashlib
+import urllib.request
 
 sys.path.append(os.path.join(os.path.dirname(__file__), 'scripts'))
 import reporting
@@ -153,6 +155,32 @@ def setup(args):
     setup_rustup()
 
 
+VIPER_TOOLS_ARCHIVES = [
+    'ViperToolsLinux.zip',
+    'ViperToolsMac.zip',
+    'ViperToolsWin.zip',
+]
+
+
+def update_viper_checksums():
+    """Pin the SHA-256 checksums of the Viper tools in viper-toolchain.sha256.
+
+    The checksums are checked by the launchers before they extract Viper tools
+    that they downloaded because of the DOWNLOAD_VIPER_TOOLS flag.
+    """
+    lines = []
+    for archive in VIPER_TOOLS_ARCHIVES:
+        url = 'https://github.com/viperproject/viper-ide/releases/download/{}/{}'.format(
+            viper_version(), archive
+        )
+        logging.info(f'Downloading {url}')
+        with urllib.request.urlopen(url) as response:
+            checksum = hashlib.sha256(response.read()).hexdigest()
+        lines.append(f'{checksum}  {archive}\n')
+    with open('viper-toolchain.sha256', 'w') as file:
+        file.writelines(lines)
+
+
 def ide(args):
     """Start VS Code with the given arguments."""
     run_command(['code'] + args)
@@ -229,6 +257,8 @@ def package(mode: str, package_path: str):
         # (source pattern, destination)
         ("rust-toolchain", "."),
         ("viper_tools", "."),
+        # An optional Java runtime, e.g. created with `jlink`.
+        ("jre", "."),
         (f"target/{mode}/prusti-driver*", "."),
         (f"target/{mode}/prusti-server*", "."),
         (f"target/{mode}/prusti-rustc*", "."),
@@ -326,6 +356,9 @@ def main(argv):
         elif arg == 'setup':
             setup(argv[i+1:])
             break
+        elif arg == 'update-viper-checksums':
+            update_viper_checksums()
+            break
         elif arg == 'ide':
             ide(argv[i+1:])
             break